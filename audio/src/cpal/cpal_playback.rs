@@ -25,8 +25,11 @@ pub(crate) struct CpalPlayback {
     resampler: Option<CpalResampler>,
 }
 
+// The resampler works on fixed size chunks, but packets can arrive in any size (the last packet
+// of a file, a speed change, etc), so samples are held in `pending` until a full chunk is ready.
 struct CpalResampler {
     resampler: FftFixedIn<f32>,
+    pending: Vec<f32>,
     input: Vec<Vec<f32>>,
     output: Vec<Vec<f32>>,
     interleaved: Vec<f32>,
@@ -107,6 +110,7 @@ impl OpenOutputStream for CpalPlayback {
 
             Some(CpalResampler {
                 resampler,
+                pending: vec![],
                 input,
                 output,
                 interleaved: vec![],
@@ -138,74 +142,27 @@ impl AudioOutput for CpalPlayback {
             return Ok(());
         }
 
-        let mut buffered_samples;
-        let out_samples = if let Some(resampler) = &mut self.resampler {
-            // If we're in a position where we've not received enough samples (generally caused by
-            // premature termination of the stream / fade out), fill out the sample buffer with
-            // silent samples to complete the expected buffer size.
-            let required_samples = resampler.input[0].capacity() * resampler.input.len();
-            let samples = if samples.len() < required_samples {
-                buffered_samples = Vec::from(samples);
-                buffered_samples.resize(required_samples, 0_f32);
-                buffered_samples.as_slice()
-            } else {
-                samples
-            };
-
-            // So, first problem we run into here, is that our samples are already
-            // interleaved, and our resampler expects them to not be, so lets split them.
-            resampler.input[0] = samples.iter().step_by(2).copied().collect();
-            resampler.input[1] = samples.iter().skip(1).step_by(2).copied().collect();
-
-            // Attempt to perform the resample
-            let result = resampler.resampler.process_into_buffer(
-                &resampler.input,
-                &mut resampler.output,
-                None,
-            );
-
-            match result {
-                Ok(_) => {
-                    // We need to re-interleave the results, channels * channel length
-                    let channels = resampler.output.len();
-
-                    let length = channels * resampler.output[0].len();
-                    if resampler.interleaved.len() != length {
-                        resampler.interleaved.resize(length, 0_f32);
-                    }
-
-                    // Iterate over each frame, and replace the samples
-                    for (i, frame) in resampler.interleaved.chunks_exact_mut(channels).enumerate() {
-                        for (channel, sample) in frame.iter_mut().enumerate() {
-                            *sample = resampler.output[channel][i];
-                        }
-                    }
-
-                    // Send back the result as a slice
-                    resampler.interleaved.as_slice()
-                }
-                Err(err) => {
-                    debug!("Resampling Failed: {}, falling back", err);
-                    samples
+        match &mut self.resampler {
+            Some(resampler) => {
+                resampler.pending.extend_from_slice(samples);
+                while let Some(resampled) = resampler.next_chunk(false) {
+                    write_blocking(&self.buffer_producer, resampled);
                 }
             }
-        } else {
-            // No resampler needed, send back the samples
-            samples
-        };
-
-        let mut position = 0;
-        while let Some(written) = self
-            .buffer_producer
-            .write_blocking(out_samples.split_at(position).1)
-        {
-            position += written;
+            None => write_blocking(&self.buffer_producer, samples),
         }
 
         Ok(())
     }
 
     fn flush(&mut self) {
+        // Push out anything left waiting for the resampler, padded out with silence
+        if let Some(resampler) = &mut self.resampler {
+            while let Some(resampled) = resampler.next_chunk(true) {
+                write_blocking(&self.buffer_producer, resampled);
+            }
+        }
+
         // Make sure the playback buffer is empty, to prevent premature pausing at
         // the end of playback
         while !self.buffer.is_empty() {
@@ -229,3 +186,63 @@ impl AudioOutput for CpalPlayback {
         self.stream.take();
     }
 }
+
+impl CpalResampler {
+    // Resamples the next chunk from the pending samples, if there are enough of them (or if
+    // `pad` is set, in which case a partial chunk is completed with silence).
+    fn next_chunk(&mut self, pad: bool) -> Option<&[f32]> {
+        let channels = self.input.len();
+        let required_samples = self.resampler.input_frames_next() * channels;
+
+        if self.pending.is_empty() || (!pad && self.pending.len() < required_samples) {
+            return None;
+        }
+        if self.pending.len() < required_samples {
+            self.pending.resize(required_samples, 0_f32);
+        }
+
+        // Our samples are interleaved, and the resampler expects them split by channel
+        for (channel, input) in self.input.iter_mut().enumerate() {
+            input.clear();
+            input.extend(
+                self.pending[..required_samples]
+                    .iter()
+                    .skip(channel)
+                    .step_by(channels),
+            );
+        }
+        let chunk: Vec<f32> = self.pending.drain(..required_samples).collect();
+
+        match self
+            .resampler
+            .process_into_buffer(&self.input, &mut self.output, None)
+        {
+            Ok(_) => {
+                // Re-interleave the results, channels * channel length
+                let length = channels * self.output[0].len();
+                if self.interleaved.len() != length {
+                    self.interleaved.resize(length, 0_f32);
+                }
+
+                for (i, frame) in self.interleaved.chunks_exact_mut(channels).enumerate() {
+                    for (channel, sample) in frame.iter_mut().enumerate() {
+                        *sample = self.output[channel][i];
+                    }
+                }
+            }
+            Err(err) => {
+                debug!("Resampling Failed: {}, falling back", err);
+                self.interleaved = chunk;
+            }
+        }
+
+        Some(self.interleaved.as_slice())
+    }
+}
+
+fn write_blocking(producer: &Producer<f32>, samples: &[f32]) {
+    let mut position = 0;
+    while let Some(written) = producer.write_blocking(samples.split_at(position).1) {
+        position += written;
+    }
+}
@@ -1,5 +1,9 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 
+#[cfg(target_os = "linux")]
+pub use crate::pulse::pulse_config::{InputSource, OutputSink, PlaybackStream};
+
+pub mod metadata;
 pub mod player;
 pub mod recorder;
 
@@ -39,6 +43,39 @@ pub fn get_audio_inputs() -> Vec<String> {
     }
 }
 
+// Lists the playback streams of running applications, and moves them between outputs. This is only
+// available via Pulse (or PipeWire's Pulse server), so isn't present on other platforms.
+#[cfg(target_os = "linux")]
+pub fn get_playback_streams() -> anyhow::Result<Vec<PlaybackStream>> {
+    crate::pulse::pulse_config::PulseAudioConfiguration::get_playback_streams()
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_output_sinks() -> anyhow::Result<Vec<OutputSink>> {
+    crate::pulse::pulse_config::PulseAudioConfiguration::get_output_sinks()
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_input_sources() -> anyhow::Result<Vec<InputSource>> {
+    crate::pulse::pulse_config::PulseAudioConfiguration::get_input_sources()
+}
+
+#[cfg(target_os = "linux")]
+pub fn move_playback_stream(stream: u32, sink: u32) -> anyhow::Result<()> {
+    crate::pulse::pulse_config::PulseAudioConfiguration::move_playback_stream(stream, sink)
+}
+
+// Loads (and unloads) a Pulse module, used to create virtual devices. Returns the module index.
+#[cfg(target_os = "linux")]
+pub fn load_module(name: &str, argument: &str) -> anyhow::Result<u32> {
+    crate::pulse::pulse_config::PulseAudioConfiguration::load_module(name, argument)
+}
+
+#[cfg(target_os = "linux")]
+pub fn unload_module(index: u32) -> anyhow::Result<()> {
+    crate::pulse::pulse_config::PulseAudioConfiguration::unload_module(index)
+}
+
 // This is mostly a helper struct for converting between f64 and u64..
 #[derive(Debug)]
 pub struct AtomicF64 {
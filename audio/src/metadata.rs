@@ -0,0 +1,61 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataRevision;
+use symphonia::core::probe::Hint;
+
+// Details about a sample file, read from its headers without decoding the audio.
+#[derive(Debug, Clone, Default)]
+pub struct AudioFileInfo {
+    pub duration_ms: Option<u64>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<usize>,
+    pub tags: BTreeMap<String, String>,
+}
+
+pub fn get_audio_file_info(file: &Path) -> Result<AudioFileInfo> {
+    let mut hint = Hint::new();
+    if let Some(extension) = file.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+
+    let media_source = Box::new(File::open(file).context("Unable to open Audio File")?);
+    let stream = MediaSourceStream::new(media_source, Default::default());
+    let mut probe = symphonia::default::get_probe()
+        .format(&hint, stream, &Default::default(), &Default::default())
+        .context("Unable to read Audio File")?;
+
+    let mut info = AudioFileInfo::default();
+    if let Some(track) = probe.format.default_track() {
+        let params = &track.codec_params;
+        info.sample_rate = params.sample_rate;
+        info.channels = params.channels.map(|channels| channels.count());
+        if let (Some(frames), Some(rate)) = (params.n_frames, params.sample_rate) {
+            info.duration_ms = Some(frames * 1000 / rate as u64);
+        }
+    }
+
+    // Tags can be found either before the container (ID3 on MP3s), or inside it..
+    if let Some(metadata) = probe.metadata.get() {
+        if let Some(revision) = metadata.current() {
+            add_tags(&mut info.tags, revision);
+        }
+    }
+    if let Some(revision) = probe.format.metadata().current() {
+        add_tags(&mut info.tags, revision);
+    }
+
+    Ok(info)
+}
+
+fn add_tags(tags: &mut BTreeMap<String, String>, revision: &MetadataRevision) {
+    for tag in revision.tags() {
+        let key = match tag.std_key {
+            Some(key) => format!("{:?}", key),
+            None => tag.key.clone(),
+        };
+        tags.insert(key, tag.value.to_string());
+    }
+}
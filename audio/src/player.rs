@@ -6,7 +6,7 @@ use log::debug;
 use std::fs::File;
 use std::io::ErrorKind::UnexpectedEof;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
 use std::sync::{Arc, Mutex};
 
 use crate::audio::{get_output, AudioSpecification};
@@ -18,6 +18,9 @@ use symphonia::core::io::MediaSourceStream;
 use symphonia::core::probe::{Hint, ProbeResult};
 use symphonia::default::get_codecs;
 
+// The loudness (in LUFS) that sample gains are calculated against.
+pub const NORMALIZATION_TARGET: f64 = -23.0;
+
 pub struct Player {
     file: PathBuf,
     probe: ProbeResult,
@@ -31,8 +34,10 @@ pub struct Player {
     start_pct: Option<f64>,
     stop_pct: Option<f64>,
     gain: Option<f64>,
+    speed: Option<f64>,
 
     progress: Arc<AtomicU8>,
+    position: Arc<AtomicU64>,
     error: Arc<Mutex<Option<String>>>,
 
     // Used for processing Gain..
@@ -49,6 +54,7 @@ impl Player {
         start_pct: Option<f64>,
         stop_pct: Option<f64>,
         gain: Option<f64>,
+        speed: Option<f64>,
     ) -> Result<Self> {
         let probe_result = Player::load_file(file);
         if probe_result.is_err() {
@@ -64,6 +70,7 @@ impl Player {
             force_stop: Arc::new(AtomicBool::new(false)),
 
             progress: Arc::new(AtomicU8::new(0)),
+            position: Arc::new(AtomicU64::new(0)),
             error: Arc::new(Mutex::new(None)),
 
             device,
@@ -71,6 +78,7 @@ impl Player {
             start_pct,
             stop_pct,
             gain,
+            speed,
 
             process_only: false,
             normalized_gain: Arc::new(AtomicF64::new(1.0)),
@@ -198,6 +206,9 @@ impl Player {
         // Prepare the Sample Buffer..
         let mut sample_buffer = None;
 
+        // Only needed if the speed has been changed..
+        let mut resampler = None;
+
         // Start the Processed Sample Count..
         let mut samples_processed = if let Some(frame) = first_frame {
             let seek_time = SeekTo::TimeStamp {
@@ -213,6 +224,10 @@ impl Player {
             0
         };
 
+        // Track the position separately in frames, so it's unaffected by mono duplication..
+        let mut frames_played = samples_processed / channels as u64;
+        self.position.store(0, Ordering::Relaxed);
+
         let mut break_playback = false;
         let mut mono_playback = false;
 
@@ -232,6 +247,8 @@ impl Player {
 
             match decoder.decode(&packet) {
                 Ok(decoded) => {
+                    let packet_frames = decoded.frames() as u64;
+
                     // Is this the first decoded packet?
                     if audio_output.is_none() && sample_buffer.is_none() {
                         let spec = *decoded.spec();
@@ -255,6 +272,11 @@ impl Player {
                             };
 
                             audio_output.replace(get_output(audio_spec)?);
+
+                            if let Some(speed) = self.speed {
+                                let channels = output_spec.channels.count();
+                                resampler = Some(SpeedResampler::new(speed, channels));
+                            }
                         }
                     }
 
@@ -329,11 +351,27 @@ impl Player {
 
                         // Flush the samples to the Audio Stream..
                         if let Some(audio_output) = &mut audio_output {
-                            audio_output.write(&samples).unwrap()
+                            let written = match &mut resampler {
+                                Some(resampler) => audio_output.write(&resampler.process(&samples)),
+                                None => audio_output.write(&samples),
+                            };
+
+                            // If the output has gone away there's nothing to play to, so stop here.
+                            if let Err(error) = written {
+                                audio_output.stop();
+                                bail!("Unable to write to the Audio Output: {}", error);
+                            }
                         }
 
                         samples_processed += samples.len() as u64;
 
+                        // Update the playback position (in milliseconds)..
+                        frames_played += packet_frames;
+                        if let Some(rate) = sample_rate {
+                            let position = frames_played * 1000 / rate as u64;
+                            self.position.store(position, Ordering::Relaxed);
+                        }
+
                         // Calculate the Current Processing Percent..
                         let progress = Player::processed(frames, samples_processed, channels);
                         if self.progress.load(Ordering::Relaxed) != progress {
@@ -374,8 +412,7 @@ impl Player {
                 debug!("Unable to Obtain loudness in Mode M, Setting Default..");
                 self.normalized_gain.store(1.0, Ordering::Relaxed);
             } else {
-                let target = -23.0;
-                let gain_db = target - loudness;
+                let gain_db = NORMALIZATION_TARGET - loudness;
                 let value = f64::powf(10., gain_db / 20.);
 
                 self.normalized_gain.store(value, Ordering::Relaxed);
@@ -418,6 +455,7 @@ impl Player {
             stopping: self.stopping.clone(),
             force_stop: self.force_stop.clone(),
             progress: self.progress.clone(),
+            position: self.position.clone(),
             error: self.error.clone(),
             calculated_gain: self.normalized_gain.clone(),
         }
@@ -431,8 +469,59 @@ pub struct PlayerState {
 
     // These are generally read only from the outside..
     pub progress: Arc<AtomicU8>,
+    pub position: Arc<AtomicU64>,
     pub error: Arc<Mutex<Option<String>>>,
 
     // Specifically for calculating the gain..
     pub calculated_gain: Arc<AtomicF64>,
 }
+
+/*
+A naive linear resampler used to change the playback speed of a sample, the pitch changes along
+with the speed (much like speeding up a tape). The last frame of each packet is kept so the
+interpolation carries across packet boundaries.
+ */
+struct SpeedResampler {
+    speed: f64,
+    channels: usize,
+    position: f64,
+    last_frame: Vec<f32>,
+}
+
+impl SpeedResampler {
+    fn new(speed: f64, channels: usize) -> Self {
+        Self {
+            speed,
+            channels,
+            position: 1.0,
+            last_frame: vec![0.0; channels],
+        }
+    }
+
+    fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        let frames = input.len() / self.channels;
+        let mut output = Vec::with_capacity((frames as f64 / self.speed) as usize * self.channels);
+
+        // Position 0 is the last frame of the previous packet, 1.. are the frames in this one.
+        while self.position < frames as f64 {
+            let index = self.position.floor() as usize;
+            let fraction = (self.position - index as f64) as f32;
+
+            for channel in 0..self.channels {
+                let from = match index {
+                    0 => self.last_frame[channel],
+                    _ => input[(index - 1) * self.channels + channel],
+                };
+                let to = input[index * self.channels + channel];
+                output.push(from + (to - from) * fraction);
+            }
+            self.position += self.speed;
+        }
+
+        if frames > 0 {
+            self.position -= frames as f64;
+            self.last_frame = input[(frames - 1) * self.channels..frames * self.channels].to_vec();
+        }
+        output
+    }
+}
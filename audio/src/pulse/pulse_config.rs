@@ -2,12 +2,36 @@ use std::cell::RefCell;
 use std::ops::Deref;
 use std::rc::Rc;
 
+use anyhow::{anyhow, bail, Result};
 use libpulse_binding as pulse;
 use libpulse_binding::callbacks::ListResult;
-use libpulse_binding::context::introspect::{SinkInfo, SourceInfo};
+use libpulse_binding::context::introspect::{SinkInfo, SinkInputInfo, SourceInfo};
 use libpulse_binding::context::{Context, FlagSet, State};
 use libpulse_binding::mainloop::standard::{IterateResult, Mainloop};
-use libpulse_binding::proplist::Proplist;
+use libpulse_binding::proplist::{properties, Proplist};
+
+// An application's playback stream (a 'Sink Input' in Pulse terms)
+#[derive(Debug, Clone)]
+pub struct PlaybackStream {
+    pub index: u32,
+    pub sink: u32,
+    pub process: Option<String>,
+    pub application: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputSink {
+    pub index: u32,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InputSource {
+    pub index: u32,
+    pub name: String,
+    pub description: Option<String>,
+}
 
 pub(crate) struct PulseAudioConfiguration;
 pub struct PulseRuntime {
@@ -17,32 +41,34 @@ pub struct PulseRuntime {
 
 impl PulseRuntime {
     fn connect() -> Self {
+        Self::try_connect().expect("Failed to Connect to Pulse Audio!")
+    }
+
+    // As above, but for things which run regularly, where failing to connect shouldn't be fatal.
+    fn try_connect() -> Result<Self> {
         // Connect to the PulseAudio Server..
         let app_name: &str = env!("CARGO_PKG_NAME");
 
-        let mut proplist = Proplist::new().unwrap();
+        let mut proplist = Proplist::new().ok_or_else(|| anyhow!("Unable to create Proplist"))?;
         proplist
-            .set_str(pulse::proplist::properties::APPLICATION_NAME, app_name)
-            .unwrap();
+            .set_str(properties::APPLICATION_NAME, app_name)
+            .map_err(|_| anyhow!("Unable to set Application Name"))?;
 
         let main_loop = Rc::new(RefCell::new(
-            Mainloop::new().expect("Failed to create MainLoop"),
+            Mainloop::new().ok_or_else(|| anyhow!("Failed to create MainLoop"))?,
         ));
         let context = Rc::new(RefCell::new(
             Context::new_with_proplist(main_loop.borrow().deref(), app_name, &proplist)
-                .expect("Unable to create context"),
+                .ok_or_else(|| anyhow!("Unable to create context"))?,
         ));
 
-        context
-            .borrow_mut()
-            .connect(None, FlagSet::NOFLAGS, None)
-            .expect("Failed to connect context");
+        context.borrow_mut().connect(None, FlagSet::NOFLAGS, None)?;
 
         loop {
             match main_loop.borrow_mut().iterate(true) {
                 IterateResult::Success(_) => {}
                 IterateResult::Quit(_) | IterateResult::Err(_) => {
-                    panic!("Failed to Connect to Pulse Audio!");
+                    bail!("Failed to Connect to Pulse Audio!");
                 }
             }
 
@@ -54,13 +80,20 @@ impl PulseRuntime {
                 State::Ready => {
                     break;
                 }
-                State::Failed => {}
-                State::Terminated => {}
+                State::Failed | State::Terminated => {
+                    bail!("Failed to Connect to Pulse Audio!");
+                }
             }
         }
 
         // At this point, we're connected and ready to go :)
-        PulseRuntime { main_loop, context }
+        Ok(PulseRuntime { main_loop, context })
+    }
+
+    fn wait_for<T: ?Sized>(&self, op: &pulse::operation::Operation<T>) {
+        while op.get_state() == pulse::operation::State::Running {
+            self.main_loop.borrow_mut().iterate(true);
+        }
     }
 
     fn disconnect(&self) {
@@ -141,4 +174,151 @@ impl PulseAudioConfiguration {
         let unwrapped = wrapped.deref().borrow().clone();
         unwrapped
     }
+
+    pub(crate) fn get_playback_streams() -> Result<Vec<PlaybackStream>> {
+        let found: Vec<PlaybackStream> = vec![];
+        let wrapped = Rc::new(RefCell::new(found));
+        let insider = wrapped.clone();
+
+        let pulse = PulseRuntime::try_connect()?;
+        let op = pulse
+            .context
+            .borrow_mut()
+            .introspect()
+            .get_sink_input_info_list(move |input_list: ListResult<&SinkInputInfo>| {
+                if let ListResult::Item(item) = input_list {
+                    insider.borrow_mut().push(PlaybackStream {
+                        index: item.index,
+                        sink: item.sink,
+                        process: item
+                            .proplist
+                            .get_str(properties::APPLICATION_PROCESS_BINARY),
+                        application: item.proplist.get_str(properties::APPLICATION_NAME),
+                    });
+                }
+            });
+        pulse.wait_for(&op);
+
+        pulse.disconnect();
+        let unwrapped = wrapped.deref().borrow().clone();
+        Ok(unwrapped)
+    }
+
+    pub(crate) fn get_output_sinks() -> Result<Vec<OutputSink>> {
+        let found: Vec<OutputSink> = vec![];
+        let wrapped = Rc::new(RefCell::new(found));
+        let insider = wrapped.clone();
+
+        let pulse = PulseRuntime::try_connect()?;
+        let op = pulse.context.borrow_mut().introspect().get_sink_info_list(
+            move |sink_list: ListResult<&SinkInfo>| {
+                if let ListResult::Item(item) = sink_list {
+                    if let Some(name) = &item.name {
+                        insider.borrow_mut().push(OutputSink {
+                            index: item.index,
+                            name: name.to_string(),
+                            description: item.description.as_ref().map(|d| d.to_string()),
+                        });
+                    }
+                }
+            },
+        );
+        pulse.wait_for(&op);
+
+        pulse.disconnect();
+        let unwrapped = wrapped.deref().borrow().clone();
+        Ok(unwrapped)
+    }
+
+    pub(crate) fn get_input_sources() -> Result<Vec<InputSource>> {
+        let found: Vec<InputSource> = vec![];
+        let wrapped = Rc::new(RefCell::new(found));
+        let insider = wrapped.clone();
+
+        let pulse = PulseRuntime::try_connect()?;
+        let op = pulse
+            .context
+            .borrow_mut()
+            .introspect()
+            .get_source_info_list(move |source_list: ListResult<&SourceInfo>| {
+                if let ListResult::Item(item) = source_list {
+                    if let Some(name) = &item.name {
+                        insider.borrow_mut().push(InputSource {
+                            index: item.index,
+                            name: name.to_string(),
+                            description: item.description.as_ref().map(|d| d.to_string()),
+                        });
+                    }
+                }
+            });
+        pulse.wait_for(&op);
+
+        pulse.disconnect();
+        let unwrapped = wrapped.deref().borrow().clone();
+        Ok(unwrapped)
+    }
+
+    pub(crate) fn move_playback_stream(stream: u32, sink: u32) -> Result<()> {
+        let result = Rc::new(RefCell::new(false));
+        let insider = result.clone();
+
+        let pulse = PulseRuntime::try_connect()?;
+        let op = pulse
+            .context
+            .borrow_mut()
+            .introspect()
+            .move_sink_input_by_index(
+                stream,
+                sink,
+                Some(Box::new(move |success| *insider.borrow_mut() = success)),
+            );
+        pulse.wait_for(&op);
+        pulse.disconnect();
+
+        if !*result.borrow() {
+            bail!("Unable to move stream {} to sink {}", stream, sink);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn load_module(name: &str, argument: &str) -> Result<u32> {
+        let result = Rc::new(RefCell::new(u32::MAX));
+        let insider = result.clone();
+
+        let pulse = PulseRuntime::try_connect()?;
+        let op =
+            pulse
+                .context
+                .borrow_mut()
+                .introspect()
+                .load_module(name, argument, move |index| *insider.borrow_mut() = index);
+        pulse.wait_for(&op);
+        pulse.disconnect();
+
+        // Pulse returns an invalid index if the module couldn't be loaded..
+        let index = *result.borrow();
+        if index == u32::MAX {
+            bail!("Unable to load module {} {}", name, argument);
+        }
+        Ok(index)
+    }
+
+    pub(crate) fn unload_module(index: u32) -> Result<()> {
+        let result = Rc::new(RefCell::new(false));
+        let insider = result.clone();
+
+        let pulse = PulseRuntime::try_connect()?;
+        let op = pulse
+            .context
+            .borrow_mut()
+            .introspect()
+            .unload_module(index, move |success| *insider.borrow_mut() = success);
+        pulse.wait_for(&op);
+        pulse.disconnect();
+
+        if !*result.borrow() {
+            bail!("Unable to unload module {}", index);
+        }
+        Ok(())
+    }
 }
@@ -17,8 +17,8 @@ use log::{debug, error, info, warn};
 use rb::{Producer, RbConsumer, RbProducer, SpscRb, RB};
 use symphonia::core::audio::{Layout, SignalSpec};
 
-use crate::audio::{get_input, AudioInput, AudioSpecification};
-use crate::get_audio_inputs;
+use crate::audio::{get_input, get_output, AudioInput, AudioSpecification};
+use crate::{get_audio_inputs, AtomicF64};
 
 static NEXT_ID: AtomicU32 = AtomicU32::new(0);
 static READ_TIMEOUT: Duration = Duration::from_millis(100);
@@ -40,6 +40,9 @@ pub struct RingProducer {
 #[derive(Debug, Clone)]
 pub struct RecorderState {
     pub stop: Arc<AtomicBool>,
+
+    // The peak level (in dBFS) of the most recently received audio..
+    pub level: Arc<AtomicF64>,
 }
 
 impl Debug for BufferedRecorder {
@@ -228,6 +231,10 @@ impl BufferedRecorder {
             {
                 // Read these out into a vec..
                 let samples: Vec<f32> = Vec::from(&read_buffer[0..samples]);
+                state
+                    .level
+                    .store(get_peak_level(&samples), Ordering::Relaxed);
+
                 match self.handle_samples(samples, &mut ebu_r128, writing, &mut writer) {
                     Ok(result) => writing = result,
                     Err(error) => {
@@ -255,6 +262,43 @@ impl BufferedRecorder {
         Ok(())
     }
 
+    // Plays the live input to another output until the state is stopped, so a recording can be
+    // heard while it's being captured.
+    pub fn monitor(&self, device: String, state: RecorderState) -> Result<()> {
+        let spec = AudioSpecification {
+            device: Some(device),
+            spec: SignalSpec::new_with_layout(48000, Layout::Stereo),
+            buffer: 4800,
+        };
+        let mut output = get_output(spec)?;
+
+        let ring_buf = SpscRb::<f32>::new(48000 * 2);
+        let (ring_buf_producer, ring_buf_consumer) = (ring_buf.producer(), ring_buf.consumer());
+
+        let producer_id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        self.add_producer(RingProducer {
+            id: producer_id,
+            producer: ring_buf_producer,
+        });
+
+        // 50ms at a time, to keep the delay between input and output down..
+        let mut read_buffer: [f32; 4800] = [0.0; 4800];
+        while !state.stop.load(Ordering::Relaxed) {
+            if let Ok(Some(samples)) =
+                ring_buf_consumer.read_blocking_timeout(&mut read_buffer, READ_TIMEOUT)
+            {
+                if let Err(error) = output.write(&read_buffer[0..samples]) {
+                    warn!("Error Monitoring Recording: {}", error);
+                    break;
+                }
+            }
+        }
+
+        output.stop();
+        self.del_producer(producer_id);
+        Ok(())
+    }
+
     fn handle_samples(
         &self,
         samples: Vec<f32>,
@@ -323,3 +367,13 @@ impl Drop for BufferedRecorder {
         self.stop.store(true, Ordering::Relaxed);
     }
 }
+
+fn get_peak_level(samples: &[f32]) -> f64 {
+    let peak = samples
+        .iter()
+        .fold(0_f32, |peak, sample| peak.max(sample.abs()));
+    if peak <= 0.0 {
+        return -100.;
+    }
+    (20. * (peak as f64).log10()).max(-100.)
+}
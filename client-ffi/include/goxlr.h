@@ -0,0 +1,42 @@
+#ifndef GOXLR_H
+#define GOXLR_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct GoXLRHandle GoXLRHandle;
+
+typedef enum GoXLRResult {
+    GOXLR_OK = 0,
+    GOXLR_INVALID_ARGUMENT = 1,
+    GOXLR_CONNECTION_FAILED = 2,
+    GOXLR_PROTOCOL_MISMATCH = 3,
+    GOXLR_DAEMON_ERROR = 4,
+    GOXLR_COMMUNICATION_ERROR = 5,
+} GoXLRResult;
+
+/* Connects to the daemon, returns NULL on failure. */
+GoXLRHandle *goxlr_connect(void);
+void goxlr_disconnect(GoXLRHandle *handle);
+
+/* Returns the daemon status as JSON, free with goxlr_free_string. */
+char *goxlr_get_status(GoXLRHandle *handle);
+
+/* Commands are JSON, for example: {"SetVolume": ["Music", 128]} */
+GoXLRResult goxlr_send_command(GoXLRHandle *handle, const char *serial, const char *command);
+GoXLRResult goxlr_send_daemon_command(GoXLRHandle *handle, const char *command);
+
+/* Once subscribed, goxlr_next_event blocks until a status change arrives, as a JSON Patch. */
+GoXLRResult goxlr_subscribe_events(GoXLRHandle *handle);
+char *goxlr_next_event(GoXLRHandle *handle);
+
+/* Owned by the library, valid until the next failing call on this thread. */
+const char *goxlr_last_error(void);
+void goxlr_free_string(char *value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,295 @@
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use goxlr_client_lib::{ClientError, DaemonCommand, GoXLRClient, GoXLRCommand};
+use tokio::runtime::{Builder, Runtime};
+
+/*
+A small C API over the client library, allowing things like OBS plugins, or Python scripts (via
+ctypes / cffi) to control the daemon without needing to implement the IPC protocol. The matching
+header can be found in include/goxlr.h.
+
+All calls are blocking, and a handle should only be used from one thread at a time. Commands,
+statuses and events are passed as JSON strings, in the same format used by the HTTP API, for
+example:
+
+GoXLRHandle *handle = goxlr_connect();
+goxlr_send_command(handle, "S210000000AAA", "{\"SetVolume\": [\"Music\", 128]}");
+goxlr_disconnect(handle);
+
+Strings returned by the library must be freed with goxlr_free_string. When a call fails, a
+description of the error can be retrieved with goxlr_last_error.
+ */
+pub struct GoXLRHandle {
+    runtime: Runtime,
+    client: GoXLRClient,
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GoXLRResult {
+    Ok = 0,
+    InvalidArgument = 1,
+    ConnectionFailed = 2,
+    ProtocolMismatch = 3,
+    DaemonError = 4,
+    CommunicationError = 5,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(error: impl ToString) {
+    let message = CString::new(error.to_string()).unwrap_or_default();
+    LAST_ERROR.with(|last| last.replace(Some(message)));
+}
+
+fn handle_error(error: ClientError) -> GoXLRResult {
+    let result = match error {
+        ClientError::ConnectionFailed(_) => GoXLRResult::ConnectionFailed,
+        ClientError::ProtocolMismatch { .. } => GoXLRResult::ProtocolMismatch,
+        ClientError::Daemon(_) => GoXLRResult::DaemonError,
+        ClientError::UnexpectedResponse | ClientError::Transport(_) => {
+            GoXLRResult::CommunicationError
+        }
+    };
+    set_last_error(error);
+    result
+}
+
+unsafe fn read_string(value: *const c_char) -> Option<String> {
+    if value.is_null() {
+        set_last_error("Required argument was NULL");
+        return None;
+    }
+    match CStr::from_ptr(value).to_str() {
+        Ok(value) => Some(value.to_owned()),
+        Err(error) => {
+            set_last_error(error);
+            None
+        }
+    }
+}
+
+fn to_c_string(value: String) -> *mut c_char {
+    match CString::new(value) {
+        Ok(value) => value.into_raw(),
+        Err(error) => {
+            set_last_error(error);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Connects to the daemon, returning NULL on failure.
+#[no_mangle]
+pub extern "C" fn goxlr_connect() -> *mut GoXLRHandle {
+    let runtime = match Builder::new_current_thread().enable_all().build() {
+        Ok(runtime) => runtime,
+        Err(error) => {
+            set_last_error(error);
+            return ptr::null_mut();
+        }
+    };
+
+    match runtime.block_on(GoXLRClient::connect()) {
+        Ok(client) => Box::into_raw(Box::new(GoXLRHandle { runtime, client })),
+        Err(error) => {
+            handle_error(error);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Closes the connection and frees the handle.
+///
+/// # Safety
+/// The handle must have been returned by goxlr_connect, and not already disconnected.
+#[no_mangle]
+pub unsafe extern "C" fn goxlr_disconnect(handle: *mut GoXLRHandle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Returns the current daemon status as JSON, or NULL on failure.
+///
+/// # Safety
+/// The handle must be a valid handle returned by goxlr_connect.
+#[no_mangle]
+pub unsafe extern "C" fn goxlr_get_status(handle: *mut GoXLRHandle) -> *mut c_char {
+    let handle = match handle.as_mut() {
+        Some(handle) => handle,
+        None => {
+            set_last_error("Handle was NULL");
+            return ptr::null_mut();
+        }
+    };
+
+    match handle.runtime.block_on(handle.client.status()) {
+        Ok(status) => match serde_json::to_string(&status) {
+            Ok(status) => to_c_string(status),
+            Err(error) => {
+                set_last_error(error);
+                ptr::null_mut()
+            }
+        },
+        Err(error) => {
+            handle_error(error);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Runs a GoXLRCommand, provided as JSON, against the device with the given serial.
+///
+/// # Safety
+/// The handle must be a valid handle returned by goxlr_connect, and the strings must be valid
+/// NULL terminated UTF-8.
+#[no_mangle]
+pub unsafe extern "C" fn goxlr_send_command(
+    handle: *mut GoXLRHandle,
+    serial: *const c_char,
+    command: *const c_char,
+) -> GoXLRResult {
+    let handle = match handle.as_mut() {
+        Some(handle) => handle,
+        None => {
+            set_last_error("Handle was NULL");
+            return GoXLRResult::InvalidArgument;
+        }
+    };
+
+    let (serial, command) = match (read_string(serial), read_string(command)) {
+        (Some(serial), Some(command)) => (serial, command),
+        _ => return GoXLRResult::InvalidArgument,
+    };
+
+    let command: GoXLRCommand = match serde_json::from_str(&command) {
+        Ok(command) => command,
+        Err(error) => {
+            set_last_error(error);
+            return GoXLRResult::InvalidArgument;
+        }
+    };
+
+    match handle
+        .runtime
+        .block_on(handle.client.send_command(&serial, command))
+    {
+        Ok(()) => GoXLRResult::Ok,
+        Err(error) => handle_error(error),
+    }
+}
+
+/// Runs a DaemonCommand, provided as JSON.
+///
+/// # Safety
+/// The handle must be a valid handle returned by goxlr_connect, and the string must be valid
+/// NULL terminated UTF-8.
+#[no_mangle]
+pub unsafe extern "C" fn goxlr_send_daemon_command(
+    handle: *mut GoXLRHandle,
+    command: *const c_char,
+) -> GoXLRResult {
+    let handle = match handle.as_mut() {
+        Some(handle) => handle,
+        None => {
+            set_last_error("Handle was NULL");
+            return GoXLRResult::InvalidArgument;
+        }
+    };
+
+    let command = match read_string(command) {
+        Some(command) => command,
+        None => return GoXLRResult::InvalidArgument,
+    };
+
+    let command: DaemonCommand = match serde_json::from_str(&command) {
+        Ok(command) => command,
+        Err(error) => {
+            set_last_error(error);
+            return GoXLRResult::InvalidArgument;
+        }
+    };
+
+    match handle
+        .runtime
+        .block_on(handle.client.send_daemon_command(command))
+    {
+        Ok(()) => GoXLRResult::Ok,
+        Err(error) => handle_error(error),
+    }
+}
+
+/// Asks the daemon to start sending status changes, which can be read with goxlr_next_event.
+///
+/// # Safety
+/// The handle must be a valid handle returned by goxlr_connect.
+#[no_mangle]
+pub unsafe extern "C" fn goxlr_subscribe_events(handle: *mut GoXLRHandle) -> GoXLRResult {
+    let handle = match handle.as_mut() {
+        Some(handle) => handle,
+        None => {
+            set_last_error("Handle was NULL");
+            return GoXLRResult::InvalidArgument;
+        }
+    };
+
+    match handle.runtime.block_on(handle.client.subscribe_events()) {
+        Ok(()) => GoXLRResult::Ok,
+        Err(error) => handle_error(error),
+    }
+}
+
+/// Blocks until the next status change, returning it as a JSON Patch, or NULL on failure.
+///
+/// # Safety
+/// The handle must be a valid handle returned by goxlr_connect.
+#[no_mangle]
+pub unsafe extern "C" fn goxlr_next_event(handle: *mut GoXLRHandle) -> *mut c_char {
+    let handle = match handle.as_mut() {
+        Some(handle) => handle,
+        None => {
+            set_last_error("Handle was NULL");
+            return ptr::null_mut();
+        }
+    };
+
+    match handle.runtime.block_on(handle.client.next_event()) {
+        Ok(patch) => match serde_json::to_string(&patch) {
+            Ok(patch) => to_c_string(patch),
+            Err(error) => {
+                set_last_error(error);
+                ptr::null_mut()
+            }
+        },
+        Err(error) => {
+            handle_error(error);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns a description of the last error on this thread, or NULL if there hasn't been one.
+/// The string is owned by the library, and is valid until the next failing call on this thread.
+#[no_mangle]
+pub extern "C" fn goxlr_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| match last.borrow().as_ref() {
+        Some(error) => error.as_ptr(),
+        None => ptr::null(),
+    })
+}
+
+/// Frees a string returned by the library.
+///
+/// # Safety
+/// The string must have been returned by this library, and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn goxlr_free_string(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
@@ -0,0 +1,19 @@
+use goxlr_ipc::DaemonError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ClientError {
+    #[error("Unable to connect to the GoXLR daemon: {0}")]
+    ConnectionFailed(#[from] std::io::Error),
+
+    #[error("Daemon Protocol Version {daemon} does not match Client Version {client}")]
+    ProtocolMismatch { daemon: u32, client: u32 },
+
+    #[error("{0}")]
+    Daemon(#[from] DaemonError),
+
+    #[error("Unexpected response from the GoXLR daemon")]
+    UnexpectedResponse,
+
+    #[error("Communication with the GoXLR daemon failed: {0}")]
+    Transport(#[from] anyhow::Error),
+}
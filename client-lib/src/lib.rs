@@ -0,0 +1,158 @@
+use goxlr_ipc::clients::ipc::ipc_client::IPCClient;
+use goxlr_ipc::clients::ipc::ipc_socket::Socket;
+use goxlr_ipc::{
+    DaemonRequest, DaemonResponse, DaemonStatus, IpcRequest, IpcResponse, PROTOCOL_VERSION,
+};
+use interprocess::local_socket::tokio::LocalSocketStream;
+
+mod error;
+
+pub use error::ClientError;
+pub use goxlr_ipc::{
+    ButtonStates, DaemonCommand, DaemonError, DaemonEvent, DaemonInfo, GoXLRCommand, IpcEncoding,
+};
+pub use json_patch::Patch;
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+pub use goxlr_ipc::clients::ipc::{default_socket_path, socket_name, NAMED_PIPE, SOCKET_ENV};
+
+/*
+A small async wrapper around the daemon's IPC socket, so tools written in Rust don't need to deal
+with the framing, request IDs or the protocol handshake themselves. A basic example:
+
+let mut client = GoXLRClient::connect().await?;
+let status = client.status().await?;
+for serial in status.mixers.keys() {
+    client.send_command(serial, GoXLRCommand::SetVolume(ChannelName::Music, 128)).await?;
+}
+
+Events (as JSON Patches against the status) can be received by calling subscribe_events, and then
+next_event in a loop. Raw button presses are available in the same way via next_hardware_event.
+ */
+#[derive(Debug)]
+pub struct GoXLRClient {
+    client: IPCClient,
+}
+
+impl GoXLRClient {
+    /// Connects to the daemon on the default socket for this platform, or GOXLR_SOCKET if set.
+    pub async fn connect() -> Result<Self> {
+        Self::connect_to(&socket_name()).await
+    }
+
+    /// Connects to the daemon on a specific socket path (or named pipe on Windows).
+    pub async fn connect_to(name: &str) -> Result<Self> {
+        let connection = LocalSocketStream::connect(name).await?;
+        let socket: Socket<IpcResponse, IpcRequest> = Socket::new(connection);
+
+        let mut client = Self {
+            client: IPCClient::new(socket),
+        };
+
+        match client
+            .request(DaemonRequest::Hello(PROTOCOL_VERSION))
+            .await?
+        {
+            DaemonResponse::Hello(version) if version == PROTOCOL_VERSION => Ok(client),
+            DaemonResponse::Hello(version) => Err(ClientError::ProtocolMismatch {
+                daemon: version,
+                client: PROTOCOL_VERSION,
+            }),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn status(&mut self) -> Result<DaemonStatus> {
+        match self.request(DaemonRequest::GetStatus).await? {
+            DaemonResponse::Status(status) => Ok(status),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    /// Returns diagnostic information about the daemon (version, uptime, clients and devices).
+    pub async fn daemon_info(&mut self) -> Result<DaemonInfo> {
+        match self.request(DaemonRequest::GetDaemonStatus).await? {
+            DaemonResponse::DaemonInfo(info) => Ok(info),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    /// Returns the currently held buttons and encoder positions for a device.
+    pub async fn button_states(&mut self, serial: &str) -> Result<ButtonStates> {
+        match self
+            .request(DaemonRequest::GetButtonStates(serial.to_owned()))
+            .await?
+        {
+            DaemonResponse::ButtonStates(states) => Ok(states),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn send_command(&mut self, serial: &str, command: GoXLRCommand) -> Result<()> {
+        self.expect_ok(DaemonRequest::Command(serial.to_owned(), command))
+            .await
+    }
+
+    /// Runs a list of commands on a device in order, stopping at the first failure. On failure the
+    /// device's profile and mic profile are rolled back to how they were before the batch.
+    pub async fn send_commands(
+        &mut self,
+        serial: &str,
+        commands: Vec<GoXLRCommand>,
+    ) -> Result<DaemonStatus> {
+        match self
+            .request(DaemonRequest::Batch(serial.to_owned(), commands))
+            .await?
+        {
+            DaemonResponse::Status(status) => Ok(status),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn send_daemon_command(&mut self, command: DaemonCommand) -> Result<()> {
+        self.expect_ok(DaemonRequest::Daemon(command)).await
+    }
+
+    /// Asks the daemon to start sending status changes, these can be read via next_event.
+    pub async fn subscribe_events(&mut self) -> Result<()> {
+        self.expect_ok(DaemonRequest::Subscribe).await
+    }
+
+    /// Waits for the next status change from the daemon, subscribe_events must be called first.
+    pub async fn next_event(&mut self) -> Result<Patch> {
+        Ok(self.client.next_patch().await?)
+    }
+
+    /// Waits for the next hardware event (button presses and releases), subscribe_events must be
+    /// called first.
+    pub async fn next_hardware_event(&mut self) -> Result<DaemonEvent> {
+        Ok(self.client.next_event().await?)
+    }
+
+    /// Switches the connection to a different encoding, Cbor is more compact than the default
+    /// Json, which helps when following a busy event stream.
+    pub async fn set_encoding(&mut self, encoding: IpcEncoding) -> Result<()> {
+        Ok(self.client.set_encoding(encoding).await?)
+    }
+
+    /// Returns the underlying IPCClient, for use with the goxlr_ipc Client trait.
+    pub fn into_inner(self) -> IPCClient {
+        self.client
+    }
+
+    async fn expect_ok(&mut self, request: DaemonRequest) -> Result<()> {
+        match self.request(request).await? {
+            DaemonResponse::Ok => Ok(()),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    async fn request(&mut self, request: DaemonRequest) -> Result<DaemonResponse> {
+        let id = self.client.submit(request).await?;
+        match self.client.wait_for(id).await? {
+            DaemonResponse::Error(error) => Err(ClientError::Daemon(error.code)),
+            response => Ok(response),
+        }
+    }
+}
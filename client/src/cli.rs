@@ -1,14 +1,15 @@
 use clap::{ArgAction, Args, Parser, Subcommand};
 
 use goxlr_types::{
-    AnimationMode, Button, ButtonColourGroups, ButtonColourOffStyle, ChannelName,
-    CompressorAttackTime, CompressorRatio, CompressorReleaseTime, EchoStyle, EffectBankPresets,
-    EncoderColourTargets, EqFrequencies, FaderDisplayStyle, FaderName, GateTimes, GenderStyle,
-    HardTuneSource, HardTuneStyle, InputDevice, MegaphoneStyle, MiniEqFrequencies, Mix,
-    MuteFunction, MuteState, OutputDevice, PitchStyle, ReverbStyle, RobotRange, RobotStyle,
-    SampleBank, SampleButtons, SamplePlayOrder, SamplePlaybackMode, SimpleColourTargets,
-    WaterfallDirection,
+    AnimationMode, Button, ButtonColourGroups, ButtonColourHoldStyle, ButtonColourOffStyle,
+    ChannelName, CompressorAttackTime, CompressorRatio, CompressorReleaseTime, EchoStyle,
+    EffectBankPresets, EncoderColourTargets, EncoderName, EqFrequencies, FaderDisplayStyle,
+    FaderName, GateTimes, GenderStyle, HardTuneSource, HardTuneStyle, InputDevice, MegaphoneStyle,
+    MiniEqFrequencies, Mix, MuteFunction, MuteState, OutputDevice, PitchStyle, ReverbStyle,
+    RobotRange, RobotStyle, SampleBank, SampleButtons, SamplePlayOrder, SamplePlaybackMode,
+    ScribbleAlignment, SimpleColourTargets, VolumeLevel, WaterfallDirection,
 };
+use std::path::PathBuf;
 use std::str::FromStr;
 
 #[derive(Parser, Debug)]
@@ -17,7 +18,7 @@ use std::str::FromStr;
 pub struct Cli {
     /// The specific device's serial number to execute commands on.
     /// This field is optional if you have exactly one GoXLR, but required if you have more.
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub device: Option<String>,
 
     /// Display the device information after any subcommands have been executed.
@@ -35,6 +36,22 @@ pub struct Cli {
     #[arg(long, num_args=0..=1, default_missing_value="http://localhost:14564")]
     pub use_http: Option<String>,
 
+    /// The path of the daemon's IPC socket, overrides GOXLR_SOCKET
+    #[arg(long)]
+    pub socket_path: Option<PathBuf>,
+
+    /// Retry connecting to the daemon this many times (waiting longer between each) before giving up
+    #[arg(long, default_value = "0")]
+    pub retry: u8,
+
+    /// Start the daemon if it can't be reached, then wait for it to come up
+    #[arg(long)]
+    pub start_daemon: bool,
+
+    /// Start the daemon via this systemd user unit (with --start-daemon), rather than running it directly
+    #[arg(long, requires = "start_daemon", conflicts_with = "socket_path")]
+    pub daemon_unit: Option<String>,
+
     #[command(flatten, next_help_heading = "Microphone controls")]
     pub microphone_controls: MicrophoneControls,
 
@@ -81,9 +98,9 @@ pub enum SubCommands {
         #[arg(value_enum)]
         channel: ChannelName,
 
-        /// The new volume as a percentage [0 - 100]
-        #[arg(value_parser=percent_value)]
-        volume_percent: u8,
+        /// The new volume as a percentage [0 - 100] or in dB (eg. -12dB)
+        #[arg(allow_hyphen_values = true)]
+        volume: VolumeLevel,
     },
 
     Submix {
@@ -125,6 +142,18 @@ pub enum SubCommands {
         enabled: bool,
     },
 
+    /// Save and load named sets of routes
+    RoutingPreset {
+        #[command(subcommand)]
+        command: RoutingPresetCommands,
+    },
+
+    /// Save and load named sets of channel volumes and mutes
+    VolumePreset {
+        #[command(subcommand)]
+        command: VolumePresetCommands,
+    },
+
     /// Commands to control the GoXLR lighting
     Lighting {
         #[command(subcommand)]
@@ -141,6 +170,73 @@ pub enum SubCommands {
         #[clap[subcommand]]
         command: SamplerCommands,
     },
+
+    /// Manage the GoXLR Firmware
+    Firmware {
+        #[command(subcommand)]
+        command: FirmwareCommands,
+    },
+
+    /// Backup and Restore the device's profiles and daemon settings (the GoXLR can't report its
+    /// on-board settings, so these are what the daemon sends to it)
+    Backup {
+        #[command(subcommand)]
+        command: BackupCommands,
+    },
+
+    /// Cut everything from the stream, while leaving the headphones alone
+    StreamMute {
+        /// Is the stream muted? [true | false]
+        #[arg(value_parser, action = ArgAction::Set)]
+        muted: bool,
+    },
+
+    /// Open the mic while push to talk is enabled
+    PushToTalk {
+        /// Is the mic open? [true | false]
+        #[arg(value_parser, action = ArgAction::Set)]
+        active: bool,
+    },
+
+    /// Revert the last change made to the device
+    Undo,
+
+    /// Re-apply the last change which was undone
+    Redo,
+
+    /// Hold the current mixer state in memory, and return to it later
+    Snapshot {
+        #[command(subcommand)]
+        command: SnapshotCommands,
+    },
+
+    /// List the GoXLR devices known to the Daemon
+    Devices,
+
+    /// List the channels which can be assigned to a fader, with their current volumes
+    Channels,
+
+    /// Run a set of read-only checks against the device, and print a report for bug reports
+    Diagnostics,
+
+    /// Commands relating to the Daemon itself
+    Daemon {
+        #[command(subcommand)]
+        command: DaemonCommands,
+    },
+
+    /// Print a shell completion script to stdout
+    Completions {
+        /// The shell to generate completions for
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+
+    /// Write man pages for the client and all its subcommands
+    Manpages {
+        /// The directory to write the pages to
+        directory: PathBuf,
+    },
 }
 
 fn percent_value(s: &str) -> Result<u8, String> {
@@ -156,6 +252,20 @@ fn percent_value(s: &str) -> Result<u8, String> {
     Ok(value)
 }
 
+fn playback_speed_value(s: &str) -> Result<f32, String> {
+    let value = f32::from_str(s);
+    if value.is_err() {
+        return Err(String::from("Value must be between 0.25 and 4.0"));
+    }
+
+    let value = value.unwrap();
+    if !(0.25..=4.0).contains(&value) {
+        return Err(String::from("Value must be between 0.25 and 4.0"));
+    }
+
+    Ok(value)
+}
+
 fn percent_value_float(s: &str) -> Result<f32, String> {
     let value = f32::from_str(s);
     if value.is_err() {
@@ -213,6 +323,16 @@ pub enum ProfileAction {
         profile_name: String,
     },
 
+    /// Load a profile from a file, rather than the profile directory
+    LoadFile {
+        /// The path to the profile file
+        path: PathBuf,
+
+        /// Copy the profile into the profile directory, and use it by default
+        #[arg(long)]
+        save: bool,
+    },
+
     /// Load a Profiles Colours Only
     LoadColours {
         /// The name of the profile to load colours from
@@ -227,6 +347,12 @@ pub enum ProfileAction {
         /// The new Profile Name
         profile_name: String,
     },
+
+    /// Save a copy of the current state as a new profile, without switching to it
+    Export {
+        /// The new Profile Name
+        profile_name: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -261,6 +387,17 @@ pub enum MicrophoneCommands {
         #[arg(value_parser=percent_value)]
         level: u8,
     },
+
+    /// Measure the mic level while speaking normally, and suggest a gain
+    Calibrate {
+        /// How many seconds to listen for (up to 30)
+        #[arg(default_value = "10")]
+        seconds: u8,
+
+        /// Apply the suggested gain once complete
+        #[arg(value_parser, action = ArgAction::Set, default_value = "false")]
+        apply: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -275,9 +412,9 @@ pub enum SubmixCommands {
         #[arg(value_enum)]
         channel: ChannelName,
 
-        /// The new volume as a percentage [0 - 100]
-        #[arg(value_parser=percent_value)]
-        volume_percent: u8,
+        /// The new volume as a percentage [0 - 100] or in dB (eg. -12dB)
+        #[arg(allow_hyphen_values = true)]
+        volume: VolumeLevel,
     },
 
     Linked {
@@ -418,9 +555,8 @@ pub enum FaderCommands {
         #[arg(value_enum)]
         fader: FaderName,
 
-        /// The New Channel Name
-        #[arg(value_enum)]
-        channel: ChannelName,
+        /// The New Channel Name, use 'channels' to list those available on the device
+        channel: String,
     },
 
     /// Change the behaviour of a Fader Mute Button
@@ -477,6 +613,18 @@ pub enum Scribbles {
         #[arg(value_parser, action = ArgAction::Set)]
         inverted: bool,
     },
+
+    /// Sets the font and alignment used for the scribble text
+    Style {
+        #[arg(value_enum)]
+        fader: FaderName,
+
+        #[arg(value_enum)]
+        alignment: ScribbleAlignment,
+
+        /// The path to a TTF font, uses the built in font if not set
+        font: Option<PathBuf>,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -501,6 +649,12 @@ pub enum LightingCommands {
     /// Sets the Global GoXLR Colour
     Global { colour: String },
 
+    /// Generates a colour scheme from a primary, and optional accent colour [RRGGBB]
+    Theme {
+        primary: String,
+        accent: Option<String>,
+    },
+
     /// Configure Lighting for a specific fader
     Fader {
         #[command(subcommand)]
@@ -626,6 +780,16 @@ pub enum ButtonLightingCommands {
         #[arg(value_enum)]
         off_style: ButtonColourOffStyle,
     },
+
+    HoldStyle {
+        /// The Button to change
+        #[arg(value_enum)]
+        button: Button,
+
+        /// How the button should be presented while held down
+        #[arg(value_enum)]
+        hold_style: ButtonColourHoldStyle,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -693,14 +857,35 @@ pub enum EffectsCommands {
     LoadEffectPreset {
         name: String,
     },
+    /// Load a preset from the library into a specific effect bank
+    LoadEffectPresetToBank {
+        name: String,
+
+        #[arg(value_enum)]
+        bank: EffectBankPresets,
+    },
     RenameActivePreset {
         name: String,
     },
     SaveActivePreset,
+    /// Rename the active effect bank and save it to the preset library
+    SaveActivePresetAs {
+        name: String,
+    },
+    /// List the presets available in the preset library
+    ListPresets,
     SetActivePreset {
         #[arg(value_enum)]
         preset: EffectBankPresets,
     },
+    /// Turn an effect encoder to a specific position
+    Encoder {
+        #[arg(value_enum)]
+        encoder: EncoderName,
+
+        #[arg(allow_hyphen_values = true)]
+        value: i8,
+    },
     Reverb {
         #[command(subcommand)]
         command: Reverb,
@@ -754,31 +939,55 @@ pub enum Reverb {
     Decay { decay: u16 },
 
     /// Set the Reverb Early Level
-    EarlyLevel { level: i8 },
+    EarlyLevel {
+        #[arg(allow_hyphen_values = true)]
+        level: i8,
+    },
 
     /// Set the Reverb Tail Level
-    TailLevel { level: i8 },
+    TailLevel {
+        #[arg(allow_hyphen_values = true)]
+        level: i8,
+    },
 
     /// Set the Reverb Pre-Delay
     PreDelay { delay: u8 },
 
     /// Set the Reverb Low 'Colour'
-    LowColour { colour: i8 },
+    LowColour {
+        #[arg(allow_hyphen_values = true)]
+        colour: i8,
+    },
 
     /// Set the Reverb High 'Colour'
-    HighColour { colour: i8 },
+    HighColour {
+        #[arg(allow_hyphen_values = true)]
+        colour: i8,
+    },
 
     /// Set the Reverb High Factor
-    HighFactor { factor: i8 },
+    HighFactor {
+        #[arg(allow_hyphen_values = true)]
+        factor: i8,
+    },
 
     /// Set the Reverb Diffuse Level
-    Diffuse { diffuse: i8 },
+    Diffuse {
+        #[arg(allow_hyphen_values = true)]
+        diffuse: i8,
+    },
 
     /// Set the Reverb Mod Speed
-    ModSpeed { speed: i8 },
+    ModSpeed {
+        #[arg(allow_hyphen_values = true)]
+        speed: i8,
+    },
 
     /// Set the Reverb Mod Depth
-    ModDepth { depth: i8 },
+    ModDepth {
+        #[arg(allow_hyphen_values = true)]
+        depth: i8,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -799,12 +1008,18 @@ pub enum Echo {
     /// Set the Echo Tempo (only valid if 'Style' is 'ClassicSlap')
     Tempo { tempo: u16 },
 
-    /// Set the Reverb Left Delay (only valid if 'Style' is not 'ClassicSlap')
+    /// Set the Echo Left Delay (only valid if 'Style' is not 'ClassicSlap')
     DelayLeft { delay: u16 },
 
-    /// Set the Reverb Right Delay (only valid if 'Style' is not 'ClassicSlap')
+    /// Set the Echo Right Delay (only valid if 'Style' is not 'ClassicSlap')
     DelayRight { delay: u16 },
 
+    /// Set the Echo Left Feedback Level
+    FeedbackLeft { feedback: u8 },
+
+    /// Set the Echo Right Feedback Level
+    FeedbackRight { feedback: u8 },
+
     /// Set the Echo XFB from Left to Right
     FeedbackXFBLtoR { feedback: u8 },
 
@@ -822,7 +1037,10 @@ pub enum Pitch {
     },
 
     /// Set the pitch Amount
-    Amount { amount: i8 },
+    Amount {
+        #[arg(allow_hyphen_values = true)]
+        amount: i8,
+    },
 
     /// Set the Pitch Character
     Character { character: u8 },
@@ -831,11 +1049,15 @@ pub enum Pitch {
 #[derive(Subcommand, Debug)]
 #[command(arg_required_else_help = true)]
 pub enum Gender {
+    /// Set the Gender Style
     Style {
         #[arg(value_enum)]
         style: GenderStyle,
     },
+
+    /// Set the Gender Amount
     Amount {
+        #[arg(allow_hyphen_values = true)]
         amount: i8,
     },
 }
@@ -853,7 +1075,10 @@ pub enum Megaphone {
     Amount { amount: u8 },
 
     /// Set the Post Processing Gain
-    PostGain { gain: i8 },
+    PostGain {
+        #[arg(allow_hyphen_values = true)]
+        gain: i8,
+    },
 
     /// Sets the State of the Megaphone Button
     Enabled {
@@ -878,6 +1103,7 @@ pub enum Robot {
         range: RobotRange,
 
         /// The Gain Value
+        #[arg(allow_hyphen_values = true)]
         gain: i8,
     },
 
@@ -904,10 +1130,16 @@ pub enum Robot {
     PulseWidth { width: u8 },
 
     /// Sets the Robot Activation Threshold
-    Threshold { threshold: i8 },
+    Threshold {
+        #[arg(allow_hyphen_values = true)]
+        threshold: i8,
+    },
 
     /// Sets the Robot Dry Mix
-    DryMix { dry_mix: i8 },
+    DryMix {
+        #[arg(allow_hyphen_values = true)]
+        dry_mix: i8,
+    },
 
     /// Sets the Current state of the Robot Button
     Enabled {
@@ -960,6 +1192,20 @@ pub enum SamplerCommands {
         file: String,
     },
 
+    /// Copy a file into the samples directory and add it, reusing any identical file already there
+    Import {
+        #[arg(value_enum)]
+        bank: SampleBank,
+
+        #[arg(value_enum)]
+        button: SampleButtons,
+
+        file: PathBuf,
+    },
+
+    /// Search the samples directory for any samples which have been moved
+    Relink,
+
     RemoveByIndex {
         #[arg(value_enum)]
         bank: SampleBank,
@@ -996,6 +1242,15 @@ pub enum SamplerCommands {
         button: SampleButtons,
     },
 
+    /// Stop anything playing on a button and remove all of its samples
+    Clear {
+        #[arg(value_enum)]
+        bank: SampleBank,
+
+        #[arg(value_enum)]
+        button: SampleButtons,
+    },
+
     PlaybackMode {
         #[arg(value_enum)]
         bank: SampleBank,
@@ -1043,4 +1298,156 @@ pub enum SamplerCommands {
         #[arg(value_parser=percent_value_float)]
         stop_position: f32,
     },
+
+    /// Change the playback speed of a sample (the pitch changes with it), 1.0 is normal speed
+    PlaybackSpeed {
+        #[arg(value_enum)]
+        bank: SampleBank,
+
+        #[arg(value_enum)]
+        button: SampleButtons,
+
+        sample_id: usize,
+
+        #[arg(value_parser=playback_speed_value)]
+        speed: f32,
+    },
+
+    /// The loudness (in LUFS) samples are normalised to, the default is -23
+    NormalizationTarget {
+        #[arg(allow_hyphen_values = true)]
+        target: f32,
+    },
+
+    /// The longest a recording can run (in seconds) before it's stopped, 0 for no limit
+    MaxRecordingLength { seconds: u16 },
+
+    /// How new recordings are named, supports {date}, {bank}, {button} and {counter}
+    RecordingTemplate { template: String },
+
+    /// Queue the next track when a 'Play Next' button is pressed during playback
+    QueueNext {
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// Send the sampler input to the monitor device while recording
+    MonitorRecording {
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// Also play samples to a local output device, leave empty to disable
+    MonitorDevice { device: Option<String> },
+
+    /// Switch the active Sampler Bank
+    ActiveBank {
+        #[arg(value_enum)]
+        bank: SampleBank,
+    },
+
+    /// List any samples in the profile which can't be found in the samples directory
+    Validate,
+
+    /// Show the length, format and tags of a file in the samples directory
+    Info { file: String },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum FirmwareCommands {
+    /// Upload a firmware file to the GoXLR, this will reboot the device once complete
+    Update {
+        /// The path to the firmware file
+        file: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum BackupCommands {
+    /// Copy the current profile, mic profile and device settings into a backup
+    Create {
+        /// The name of the backup, kept in the daemon's backups directory
+        name: String,
+    },
+
+    /// Restore the profiles and device settings from a backup
+    Restore {
+        /// The name of the backup to restore
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum RoutingPresetCommands {
+    /// Store the current routing table under a name, replacing any existing preset
+    Save {
+        /// The name of the preset
+        name: String,
+    },
+
+    /// Apply all the routes in a preset
+    Load {
+        /// The name of the preset
+        name: String,
+    },
+
+    /// Delete a preset
+    Remove {
+        /// The name of the preset
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum VolumePresetCommands {
+    /// Store the current channel volumes and fader mutes under a name, replacing any existing preset
+    Save {
+        /// The name of the preset
+        name: String,
+    },
+
+    /// Apply the volumes and mutes in a preset
+    Load {
+        /// The name of the preset
+        name: String,
+    },
+
+    /// Delete a preset
+    Remove {
+        /// The name of the preset
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum SnapshotCommands {
+    /// Store the current state under a name, replacing any existing snapshot with that name
+    Take {
+        /// The name of the snapshot
+        name: String,
+    },
+
+    /// Return the device to a previously taken snapshot
+    Restore {
+        /// The name of the snapshot
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum DaemonCommands {
+    /// Show diagnostic information about the daemon, useful when reporting issues
+    Status,
+
+    /// Check the connection to the daemon, and whether the devices are still being polled
+    Ping,
+
+    /// Collect the logs, settings and device information into a zip to attach to issues
+    SupportBundle,
 }
@@ -0,0 +1,134 @@
+use anyhow::{anyhow, bail, Context, Result};
+use goxlr_client_lib::{ClientError, GoXLRClient};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tokio::time::sleep;
+use which::which;
+
+/*
+The daemon may not be running yet (for example, the first command after login could arrive
+before the daemon's autostart has kicked in), so connecting can optionally be retried with an
+increasing delay between attempts, and the daemon can be started if the first attempt fails.
+
+The daemon can be started either through a systemd user unit (if one is provided), or by
+running the binary directly, found in the same way the launcher does.
+ */
+
+static DAEMON_NAME: &str = "goxlr-daemon";
+
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(250);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(4);
+
+// Starting the daemon and opening its socket takes a moment, so give it a few attempts at least
+const DAEMON_START_RETRIES: u8 = 8;
+
+pub struct ConnectOptions {
+    pub socket_path: Option<PathBuf>,
+    pub retries: u8,
+    pub start_daemon: bool,
+    pub daemon_unit: Option<String>,
+}
+
+pub async fn connect(options: &ConnectOptions) -> Result<GoXLRClient> {
+    let mut retries = options.retries;
+    let mut delay = INITIAL_RETRY_DELAY;
+    let mut daemon_started = false;
+
+    loop {
+        let connection = match &options.socket_path {
+            Some(path) => GoXLRClient::connect_to(&path.to_string_lossy()).await,
+            None => GoXLRClient::connect().await,
+        };
+
+        // Only a failure to reach the socket is worth retrying, anything else won't fix itself
+        let error = match connection {
+            Ok(client) => return Ok(client),
+            Err(error @ ClientError::ConnectionFailed(_)) => error,
+            Err(error) => {
+                return Err(error).context("Unable to connect to the GoXLR daemon Process")
+            }
+        };
+
+        if options.start_daemon && !daemon_started {
+            daemon_started = true;
+            start_daemon(options)?;
+            retries = retries.max(DAEMON_START_RETRIES);
+        }
+
+        if retries == 0 {
+            return Err(error).context("Unable to connect to the GoXLR daemon Process");
+        }
+        retries -= 1;
+
+        sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+    }
+}
+
+// A unit's socket path comes from its own configuration, so the command line prevents a custom
+// socket path being combined with --daemon-unit.
+fn start_daemon(options: &ConnectOptions) -> Result<()> {
+    if let Some(unit) = &options.daemon_unit {
+        let status = Command::new("systemctl")
+            .args(["--user", "start", unit])
+            .status()
+            .context("Unable to run systemctl")?;
+        if !status.success() {
+            bail!("Unable to start the {} systemd unit ({})", unit, status);
+        }
+        return Ok(());
+    }
+
+    let path =
+        locate_daemon_binary().ok_or_else(|| anyhow!("Unable to Locate GoXLR Daemon Binary"))?;
+    let mut command = Command::new(&path);
+    command.stdin(Stdio::null());
+    command.stdout(Stdio::null());
+    command.stderr(Stdio::null());
+
+    // Make sure the daemon listens where we're going to look for it
+    if let Some(socket_path) = &options.socket_path {
+        command.arg("--socket-path").arg(socket_path);
+    }
+
+    if let Some(parent) = path.parent() {
+        command.current_dir(parent);
+    }
+
+    // Keep the daemon out of our process group, so it isn't taken down with the terminal
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    command
+        .spawn()
+        .with_context(|| format!("Unable to Launch {}", path.to_string_lossy()))?;
+    Ok(())
+}
+
+fn locate_daemon_binary() -> Option<PathBuf> {
+    let bin_name = get_daemon_binary_name();
+
+    // Prefer a daemon sitting alongside the client, otherwise look on $PATH
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(parent) = exe.parent() {
+            let bin = parent.join(&bin_name);
+            if bin.exists() {
+                return Some(bin);
+            }
+        }
+    }
+
+    which(bin_name).ok()
+}
+
+fn get_daemon_binary_name() -> String {
+    if cfg!(windows) {
+        format!("{DAEMON_NAME}.exe")
+    } else {
+        String::from(DAEMON_NAME)
+    }
+}
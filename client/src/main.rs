@@ -1,78 +1,178 @@
 mod cli;
+mod daemon;
 mod microphone;
 
 use crate::cli::{
-    AnimationCommands, ButtonGroupLightingCommands, ButtonLightingCommands, CompressorCommands,
-    CoughButtonBehaviours, Echo, EffectsCommands, EqualiserCommands, EqualiserMiniCommands,
-    FaderCommands, FaderLightingCommands, FadersAllLightingCommands, Gender, HardTune,
-    LightingCommands, Megaphone, MicrophoneCommands, NoiseGateCommands, Pitch, ProfileAction,
-    ProfileType, Reverb, Robot, SamplerCommands, Scribbles, SubCommands, SubmixCommands,
+    AnimationCommands, BackupCommands, ButtonGroupLightingCommands, ButtonLightingCommands,
+    CompressorCommands, CoughButtonBehaviours, DaemonCommands, Echo, EffectsCommands,
+    EqualiserCommands, EqualiserMiniCommands, FaderCommands, FaderLightingCommands,
+    FadersAllLightingCommands, FirmwareCommands, Gender, HardTune, LightingCommands, Megaphone,
+    MicrophoneCommands, NoiseGateCommands, Pitch, ProfileAction, ProfileType, Reverb, Robot,
+    RoutingPresetCommands, SamplerCommands, Scribbles, SnapshotCommands, SubCommands,
+    SubmixCommands, VolumePresetCommands,
 };
+use crate::daemon::{connect, ConnectOptions};
 use crate::microphone::apply_microphone_controls;
 use anyhow::{anyhow, Context, Result};
-use clap::Parser;
+use clap::{CommandFactory, Parser, ValueEnum};
+use clap_complete::generate;
 use cli::Cli;
 use goxlr_ipc::client::Client;
-use goxlr_ipc::clients::ipc::ipc_client::IPCClient;
-use goxlr_ipc::clients::ipc::ipc_socket::Socket;
 use goxlr_ipc::clients::web::web_client::WebClient;
 use goxlr_ipc::GoXLRCommand;
-use goxlr_ipc::{DaemonRequest, DaemonResponse, DeviceType, MixerStatus, UsbProductInformation};
-use goxlr_types::{ChannelName, FaderName, InputDevice, MicrophoneType, OutputDevice};
-use interprocess::local_socket::tokio::LocalSocketStream;
-use interprocess::local_socket::NameTypeSupport;
+use goxlr_ipc::{
+    DaemonInfo, DeviceType, DiagnosticReport, FirmwareUpdateStage, MixerStatus, PingInfo,
+    SampleInfo, ScribbleStyle, UsbProductInformation,
+};
+use goxlr_types::{ChannelName, FaderName, InputDevice, MicrophoneType, OutputDevice, VolumeLevel};
+use std::fs::create_dir_all;
+use std::io;
+use std::time::{Duration, Instant};
 use strum::IntoEnumIterator;
-
-static SOCKET_PATH: &str = "/tmp/goxlr.socket";
-static NAMED_PIPE: &str = "@goxlr.socket";
+use tokio::time::sleep;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli: Cli = Cli::parse();
 
+    // These are generated from the definitions in cli.rs, so don't need the daemon..
+    match &cli.subcommands {
+        Some(SubCommands::Completions { shell }) => {
+            generate(
+                *shell,
+                &mut Cli::command(),
+                "goxlr-client",
+                &mut io::stdout(),
+            );
+            return Ok(());
+        }
+        Some(SubCommands::Manpages { directory }) => {
+            create_dir_all(directory)?;
+            clap_mangen::generate_to(Cli::command(), directory)?;
+            println!("Man pages written to {}", directory.to_string_lossy());
+            return Ok(());
+        }
+        _ => {}
+    }
+
     let mut client: Box<dyn Client>;
 
     if let Some(url) = cli.use_http {
         client = Box::new(WebClient::new(format!("{}/api/command", url)));
+        client.handshake().await?;
     } else {
-        let connection = LocalSocketStream::connect(match NameTypeSupport::query() {
-            NameTypeSupport::OnlyPaths | NameTypeSupport::Both => SOCKET_PATH,
-            NameTypeSupport::OnlyNamespaced => NAMED_PIPE,
-        })
-        .await
-        .context("Unable to connect to the GoXLR daemon Process")?;
-
-        let socket: Socket<DaemonResponse, DaemonRequest> = Socket::new(connection);
-        client = Box::new(IPCClient::new(socket));
+        let options = ConnectOptions {
+            socket_path: cli.socket_path.clone(),
+            retries: cli.retry,
+            start_daemon: cli.start_daemon,
+            daemon_unit: cli.daemon_unit.clone(),
+        };
+        let connection = connect(&options).await?;
+        client = Box::new(connection.into_inner());
     }
 
     client.poll_status().await?;
 
+    // Daemon commands don't need a device, so handle them before looking for one..
+    if let Some(SubCommands::Daemon { command }) = &cli.subcommands {
+        match command {
+            DaemonCommands::Status => {
+                let info = client.daemon_info().await?;
+                if cli.status_json {
+                    println!("{}", serde_json::to_string_pretty(&info)?);
+                } else {
+                    print_daemon_info(&info);
+                }
+            }
+            DaemonCommands::Ping => {
+                let start = Instant::now();
+                let info = client.ping().await?;
+                let latency = start.elapsed();
+                if cli.status_json {
+                    println!("{}", serde_json::to_string_pretty(&info)?);
+                } else {
+                    print_ping_info(&info, latency);
+                }
+            }
+            DaemonCommands::SupportBundle => {
+                let path = client.support_bundle().await?;
+                println!("Support bundle written to {}", path.to_string_lossy());
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Devices) = &cli.subcommands {
+        if cli.status_json {
+            let devices: Vec<_> = client
+                .status()
+                .mixers
+                .values()
+                .map(device_summary)
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&devices)?);
+        } else if client.status().mixers.is_empty() {
+            println!("No GoXLR Devices Found");
+        } else {
+            print_device_list(client.status().mixers.values());
+        }
+        return Ok(());
+    }
+
+    // Offline devices can't be controlled, so only consider connected ones when picking a default
+    let connected: Vec<&MixerStatus> = client
+        .status()
+        .mixers
+        .values()
+        .filter(|mixer| mixer.connected)
+        .collect();
+
     let serial = if let Some(serial) = &cli.device {
+        if !client.status().mixers.contains_key(serial) {
+            return Err(anyhow!(
+                "Device {} was not found, use 'devices' to list available devices",
+                serial
+            ));
+        }
         serial.to_owned()
-    } else if client.status().mixers.is_empty() {
+    } else if connected.is_empty() {
         return Err(anyhow!("No GoXLR Devices are Connected."));
-    } else if client.status().mixers.len() == 1 {
-        client.status().mixers.keys().next().unwrap().to_owned()
+    } else if connected.len() == 1 {
+        connected[0].hardware.serial_number.to_owned()
     } else {
-        for mixer in client.status().mixers.values() {
-            println!(
-                "{} - {} on bus {}, address {}",
-                mixer.hardware.serial_number,
-                match mixer.hardware.device_type {
-                    DeviceType::Unknown => "Unknown device",
-                    DeviceType::Full => "Regular GoXLR",
-                    DeviceType::Mini => "Mini GoXLR",
-                },
-                mixer.hardware.usb_device.bus_number,
-                mixer.hardware.usb_device.address
-            );
-        }
+        print_device_list(connected.into_iter());
         return Err(anyhow!(
-            "Multiple GoXLR devices are connected, please specify which one to control"
+            "Multiple GoXLR devices are connected, please specify which one to control with --device"
         ));
     };
 
+    if let Some(SubCommands::Channels) = &cli.subcommands {
+        let mixer = &client.status().mixers[&serial];
+        if cli.status_json {
+            let channels: Vec<_> = mixer
+                .capabilities
+                .fader_channels()
+                .into_iter()
+                .map(|channel| channel_summary(mixer, channel))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&channels)?);
+        } else {
+            print_channels(mixer);
+        }
+        return Ok(());
+    }
+
+    if let Some(SubCommands::Diagnostics) = &cli.subcommands {
+        let info = client.daemon_info().await?;
+        let report = client.diagnostics(&serial).await?;
+        if cli.status_json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            print_diagnostics(&info, client.status().mixers.get(&serial), &report);
+        }
+        return Ok(());
+    }
+
     apply_microphone_controls(&cli.microphone_controls, &mut client, &serial)
         .await
         .context("Could not apply microphone controls")?;
@@ -171,11 +271,21 @@ async fn main() -> Result<()> {
                             .command(&serial, GoXLRCommand::SetDeeser(*level))
                             .await?;
                     }
+                    MicrophoneCommands::Calibrate { seconds, apply } => {
+                        client
+                            .command(&serial, GoXLRCommand::CalibrateMicGain(*seconds, *apply))
+                            .await
+                            .context("Unable to Start Mic Calibration")?;
+                        println!("Calibrating for {} seconds, speak normally..", seconds);
+                        wait_for_mic_calibration(&mut client, &serial).await?;
+                    }
                 },
                 SubCommands::Faders { fader } => match fader {
                     FaderCommands::Channel { fader, channel } => {
+                        let channel = find_channel(&client.status().mixers[&serial], channel)?;
+
                         client
-                            .command(&serial, GoXLRCommand::SetFader(*fader, *channel))
+                            .command(&serial, GoXLRCommand::SetFader(*fader, channel))
                             .await?;
                     }
                     FaderCommands::MuteBehaviour {
@@ -227,6 +337,19 @@ async fn main() -> Result<()> {
                                 )
                                 .await?;
                         }
+                        Scribbles::Style {
+                            fader,
+                            alignment,
+                            font,
+                        } => {
+                            let style = ScribbleStyle {
+                                font: font.clone(),
+                                alignment: *alignment,
+                            };
+                            client
+                                .command(&serial, GoXLRCommand::SetScribbleStyle(*fader, style))
+                                .await?;
+                        }
                     },
                 },
                 SubCommands::Router {
@@ -238,14 +361,9 @@ async fn main() -> Result<()> {
                         .command(&serial, GoXLRCommand::SetRouter(*input, *output, *enabled))
                         .await?;
                 }
-                SubCommands::Volume {
-                    channel,
-                    volume_percent,
-                } => {
-                    let value = (255 * *volume_percent as u16) / 100;
-
+                SubCommands::Volume { channel, volume } => {
                     client
-                        .command(&serial, GoXLRCommand::SetVolume(*channel, value as u8))
+                        .command(&serial, GoXLRCommand::SetVolume(*channel, volume.raw))
                         .await?;
                 }
                 SubCommands::CoughButton { command } => match command {
@@ -300,6 +418,14 @@ async fn main() -> Result<()> {
                             .command(&serial, GoXLRCommand::SetGlobalColour(colour.to_string()))
                             .await?;
                     }
+                    LightingCommands::Theme { primary, accent } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetColourTheme(primary.to_string(), accent.clone()),
+                            )
+                            .await?;
+                    }
                     LightingCommands::Fader { command } => match command {
                         FaderLightingCommands::Display { fader, display } => {
                             client
@@ -370,6 +496,14 @@ async fn main() -> Result<()> {
                                 )
                                 .await?;
                         }
+                        ButtonLightingCommands::HoldStyle { button, hold_style } => {
+                            client
+                                .command(
+                                    &serial,
+                                    GoXLRCommand::SetButtonHoldStyle(*button, *hold_style),
+                                )
+                                .await?;
+                        }
                     },
                     LightingCommands::ButtonGroup { command } => match command {
                         ButtonGroupLightingCommands::Colour {
@@ -450,6 +584,15 @@ async fn main() -> Result<()> {
                                 .await
                                 .context("Unable to Load Profile")?;
                         }
+                        ProfileAction::LoadFile { path, save } => {
+                            let path = path
+                                .canonicalize()
+                                .context("Unable to locate the Profile")?;
+                            client
+                                .command(&serial, GoXLRCommand::LoadProfileFromFile(path, *save))
+                                .await
+                                .context("Unable to Load Profile")?;
+                        }
                         ProfileAction::LoadColours { profile_name } => {
                             client
                                 .command(
@@ -474,6 +617,15 @@ async fn main() -> Result<()> {
                                 .await
                                 .context("Unable to Save Profile")?;
                         }
+                        ProfileAction::Export { profile_name } => {
+                            client
+                                .command(
+                                    &serial,
+                                    GoXLRCommand::ExportProfile(profile_name.to_string()),
+                                )
+                                .await
+                                .context("Unable to Export Profile")?;
+                        }
                     },
                     ProfileType::Microphone { command } => match command {
                         ProfileAction::New { profile_name } => {
@@ -494,6 +646,15 @@ async fn main() -> Result<()> {
                                 .await
                                 .context("Unable to Load Microphone Profile")?;
                         }
+                        ProfileAction::LoadFile { path, save } => {
+                            let path = path
+                                .canonicalize()
+                                .context("Unable to locate the Microphone Profile")?;
+                            client
+                                .command(&serial, GoXLRCommand::LoadMicProfileFromFile(path, *save))
+                                .await
+                                .context("Unable to Load Microphone Profile")?;
+                        }
                         ProfileAction::LoadColours { .. } => {
                             return Err(anyhow!("Not supported for Microphone"));
                         }
@@ -512,6 +673,15 @@ async fn main() -> Result<()> {
                                 .await
                                 .context("Unable to Save Microphone Profile")?;
                         }
+                        ProfileAction::Export { profile_name } => {
+                            client
+                                .command(
+                                    &serial,
+                                    GoXLRCommand::ExportMicProfile(profile_name.to_string()),
+                                )
+                                .await
+                                .context("Unable to Export Microphone Profile")?;
+                        }
                     },
                 },
                 SubCommands::Effects { command } => match command {
@@ -522,6 +692,29 @@ async fn main() -> Result<()> {
                             .context("Unable to Load Preset")?;
                     }
 
+                    EffectsCommands::LoadEffectPresetToBank { name, bank } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::LoadEffectPresetToBank(name.to_string(), *bank),
+                            )
+                            .await
+                            .context("Unable to Load Preset")?;
+                    }
+
+                    EffectsCommands::SaveActivePresetAs { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::SaveActivePresetAs(name.to_string()))
+                            .await
+                            .context("Unable to Save Preset")?;
+                    }
+
+                    EffectsCommands::ListPresets => {
+                        for preset in &client.status().files.presets {
+                            println!("{}", preset);
+                        }
+                    }
+
                     EffectsCommands::SetActivePreset { preset } => {
                         client
                             .command(&serial, GoXLRCommand::SetActiveEffectPreset(*preset))
@@ -529,6 +722,13 @@ async fn main() -> Result<()> {
                             .context("Unable to set the Active Preset")?;
                     }
 
+                    EffectsCommands::Encoder { encoder, value } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetEncoderValue(*encoder, *value))
+                            .await
+                            .context("Unable to set the Encoder Value")?;
+                    }
+
                     EffectsCommands::RenameActivePreset { name } => {
                         client
                             .command(&serial, GoXLRCommand::RenameActivePreset(name.to_string()))
@@ -654,6 +854,18 @@ async fn main() -> Result<()> {
                                 .await
                                 .context("Unable to Set Echo Delay Right")?;
                         }
+                        Echo::FeedbackLeft { feedback } => {
+                            client
+                                .command(&serial, GoXLRCommand::SetEchoFeedbackLeft(*feedback))
+                                .await
+                                .context("Unable to Set Echo Feedback Left")?;
+                        }
+                        Echo::FeedbackRight { feedback } => {
+                            client
+                                .command(&serial, GoXLRCommand::SetEchoFeedbackRight(*feedback))
+                                .await
+                                .context("Unable to Set Echo Feedback Right")?;
+                        }
                         Echo::FeedbackXFBLtoR { feedback } => {
                             client
                                 .command(&serial, GoXLRCommand::SetEchoFeedbackXFBLtoR(*feedback))
@@ -834,6 +1046,24 @@ async fn main() -> Result<()> {
                             .await
                             .context("Unable to add Sample File")?;
                     }
+                    SamplerCommands::Import { bank, button, file } => {
+                        // The daemon does the copying, so it needs the full path..
+                        let file = if file.is_absolute() {
+                            file.clone()
+                        } else {
+                            std::env::current_dir()?.join(file)
+                        };
+                        client
+                            .command(&serial, GoXLRCommand::ImportSample(*bank, *button, file))
+                            .await
+                            .context("Unable to import Sample File")?;
+                    }
+                    SamplerCommands::Relink => {
+                        client
+                            .command(&serial, GoXLRCommand::RelinkSamples())
+                            .await
+                            .context("Unable to relink Samples")?;
+                    }
                     SamplerCommands::RemoveByIndex {
                         bank,
                         button,
@@ -865,12 +1095,98 @@ async fn main() -> Result<()> {
                             .command(&serial, GoXLRCommand::PlayNextSample(*bank, *button))
                             .await?;
                     }
+                    SamplerCommands::ActiveBank { bank } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetActiveSamplerBank(*bank))
+                            .await
+                            .context("Unable to Set the Active Sampler Bank")?;
+                    }
+                    SamplerCommands::Info { file } => {
+                        let info = client
+                            .sample_info(file)
+                            .await
+                            .context("Unable to read Sample Info")?;
+                        if cli.status_json {
+                            println!("{}", serde_json::to_string_pretty(&info)?);
+                        } else {
+                            print_sample_info(&info);
+                        }
+                    }
+                    SamplerCommands::Validate => {
+                        let missing = client
+                            .validate_samples(&serial)
+                            .await
+                            .context("Unable to Validate Samples")?;
+                        if missing.is_empty() {
+                            println!("All samples found.");
+                        }
+                        for sample in missing {
+                            println!(
+                                "Missing: {} {} - {}",
+                                sample.bank, sample.button, sample.track
+                            );
+                        }
+                    }
                     SamplerCommands::StopPlayback { bank, button } => {
                         client
                             .command(&serial, GoXLRCommand::StopSamplePlayback(*bank, *button))
                             .await
                             .context("Unable to Stop Sample Playback")?;
                     }
+                    SamplerCommands::NormalizationTarget { target } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetSamplerNormalizationTarget(*target),
+                            )
+                            .await
+                            .context("Unable to set Normalization Target")?;
+                    }
+                    SamplerCommands::MaxRecordingLength { seconds } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetSamplerMaxRecordingLength(*seconds),
+                            )
+                            .await
+                            .context("Unable to set Maximum Recording Length")?;
+                    }
+                    SamplerCommands::RecordingTemplate { template } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetSamplerRecordingTemplate(template.clone()),
+                            )
+                            .await
+                            .context("Unable to set Recording Name Template")?;
+                    }
+                    SamplerCommands::QueueNext { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetSamplerQueueNext(*enabled))
+                            .await
+                            .context("Unable to set Sample Queueing")?;
+                    }
+                    SamplerCommands::MonitorRecording { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetSamplerMonitorRecording(*enabled))
+                            .await
+                            .context("Unable to set Recording Monitoring")?;
+                    }
+                    SamplerCommands::MonitorDevice { device } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetSamplerMonitorDevice(device.clone()),
+                            )
+                            .await
+                            .context("Unable to set Sampler Monitor Device")?;
+                    }
+                    SamplerCommands::Clear { bank, button } => {
+                        client
+                            .command(&serial, GoXLRCommand::ClearSampleButton(*bank, *button))
+                            .await
+                            .context("Unable to Clear Sample Button")?;
+                    }
                     SamplerCommands::PlaybackMode { bank, button, mode } => {
                         client
                             .command(
@@ -931,6 +1247,22 @@ async fn main() -> Result<()> {
                             .await
                             .context("Unable to set Stop Percent")?;
                     }
+                    SamplerCommands::PlaybackSpeed {
+                        bank,
+                        button,
+                        sample_id,
+                        speed,
+                    } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetSamplePlaybackSpeed(
+                                    *bank, *button, *sample_id, *speed,
+                                ),
+                            )
+                            .await
+                            .context("Unable to set Sample Playback Speed")?;
+                    }
                 },
                 SubCommands::Submix { command } => match command {
                     SubmixCommands::Enabled { enabled } => {
@@ -938,16 +1270,9 @@ async fn main() -> Result<()> {
                             .command(&serial, GoXLRCommand::SetSubMixEnabled(*enabled))
                             .await?;
                     }
-                    SubmixCommands::Volume {
-                        channel,
-                        volume_percent,
-                    } => {
-                        let value = (255 * *volume_percent as u16) / 100;
+                    SubmixCommands::Volume { channel, volume } => {
                         client
-                            .command(
-                                &serial,
-                                GoXLRCommand::SetSubMixVolume(*channel, value as u8),
-                            )
+                            .command(&serial, GoXLRCommand::SetSubMixVolume(*channel, volume.raw))
                             .await?;
                     }
                     SubmixCommands::Linked { channel, linked } => {
@@ -966,6 +1291,112 @@ async fn main() -> Result<()> {
                             .await?;
                     }
                 },
+                SubCommands::Firmware { command } => match command {
+                    FirmwareCommands::Update { file } => {
+                        // The daemon reads the file, so it needs the full path..
+                        let path = file
+                            .canonicalize()
+                            .context("Unable to locate the Firmware File")?;
+                        client
+                            .command(&serial, GoXLRCommand::UpdateFirmware(path))
+                            .await
+                            .context("Unable to start the Firmware Update")?;
+                        wait_for_firmware_update(&mut client, &serial).await?;
+                    }
+                },
+                SubCommands::StreamMute { muted } => {
+                    let command = match muted {
+                        true => GoXLRCommand::MuteAllToStream(),
+                        false => GoXLRCommand::RestoreStreamAudio(),
+                    };
+                    client.command(&serial, command).await?;
+                }
+                SubCommands::PushToTalk { active } => {
+                    client
+                        .command(&serial, GoXLRCommand::SetPushToTalkActive(*active))
+                        .await?;
+                }
+                SubCommands::Undo => {
+                    client
+                        .command(&serial, GoXLRCommand::Undo())
+                        .await
+                        .context("Unable to Undo")?;
+                }
+                SubCommands::Redo => {
+                    client
+                        .command(&serial, GoXLRCommand::Redo())
+                        .await
+                        .context("Unable to Redo")?;
+                }
+                SubCommands::Daemon { .. }
+                | SubCommands::Devices
+                | SubCommands::Channels
+                | SubCommands::Diagnostics
+                | SubCommands::Completions { .. }
+                | SubCommands::Manpages { .. } => {}
+                SubCommands::Backup { command } => match command {
+                    BackupCommands::Create { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::CreateBackup(name.clone()))
+                            .await
+                            .context("Unable to Create Backup")?;
+                    }
+                    BackupCommands::Restore { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::RestoreBackup(name.clone()))
+                            .await
+                            .context("Unable to Restore Backup")?;
+                    }
+                },
+                SubCommands::RoutingPreset { command } => match command {
+                    RoutingPresetCommands::Save { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::SaveRoutingPreset(name.clone()))
+                            .await?;
+                    }
+                    RoutingPresetCommands::Load { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::LoadRoutingPreset(name.clone()))
+                            .await
+                            .context("Unable to Load Routing Preset")?;
+                    }
+                    RoutingPresetCommands::Remove { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetRoutingPreset(name.clone(), None))
+                            .await?;
+                    }
+                },
+                SubCommands::VolumePreset { command } => match command {
+                    VolumePresetCommands::Save { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::SaveVolumePreset(name.clone()))
+                            .await?;
+                    }
+                    VolumePresetCommands::Load { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::LoadVolumePreset(name.clone()))
+                            .await
+                            .context("Unable to Load Volume Preset")?;
+                    }
+                    VolumePresetCommands::Remove { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetVolumePreset(name.clone(), None))
+                            .await?;
+                    }
+                },
+                SubCommands::Snapshot { command } => match command {
+                    SnapshotCommands::Take { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::TakeSnapshot(name.clone()))
+                            .await?;
+                    }
+                    SnapshotCommands::Restore { name } => {
+                        client
+                            .command(&serial, GoXLRCommand::RestoreSnapshot(name.clone()))
+                            .await
+                            .context("Unable to Restore Snapshot")?;
+                    }
+                },
             }
         }
     }
@@ -1001,6 +1432,274 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+fn print_ping_info(info: &PingInfo, latency: Duration) {
+    println!("Latency: {}ms", latency.as_millis());
+    if info.devices.is_empty() {
+        println!("No Devices Connected");
+    }
+    for device in &info.devices {
+        match (device.last_poll, device.responsive) {
+            (Some(last_poll), true) => {
+                println!("Device {}: Responsive ({}ms)", device.serial, last_poll)
+            }
+            (Some(last_poll), false) => println!(
+                "Device {}: Not Responding, last polled {}ms ago",
+                device.serial, last_poll
+            ),
+            (None, _) => println!("Device {}: Not Yet Polled", device.serial),
+        }
+    }
+}
+
+fn print_sample_info(info: &SampleInfo) {
+    println!("File: {}", info.file);
+    if let Some(duration) = info.duration_ms {
+        println!("Duration: {}.{:03}s", duration / 1000, duration % 1000);
+    }
+    if let Some(rate) = info.sample_rate {
+        println!("Sample Rate: {}Hz", rate);
+    }
+    if let Some(channels) = info.channels {
+        println!("Channels: {}", channels);
+    }
+    for (key, value) in &info.tags {
+        println!("{}: {}", key, value);
+    }
+}
+
+fn print_diagnostics(info: &DaemonInfo, mixer: Option<&MixerStatus>, report: &DiagnosticReport) {
+    println!("GoXLR Utility Diagnostics");
+    println!("-------------------------");
+    println!("Client version: {}", env!("CARGO_PKG_VERSION"));
+    print_daemon_info(info);
+    println!();
+
+    println!("Device: {}", report.serial);
+    if let Some(mixer) = mixer {
+        println!("Device type: {:?}", mixer.hardware.device_type);
+        print_usb_info(&mixer.hardware.usb_device);
+    }
+    println!();
+
+    for check in &report.checks {
+        let result = if check.passed { "PASS" } else { "FAIL" };
+        println!("[{}] {}: {}", result, check.name, check.detail);
+    }
+
+    let failed = report.checks.iter().filter(|check| !check.passed).count();
+    println!();
+    if failed == 0 {
+        println!("All checks passed.");
+    } else {
+        println!(
+            "{} check(s) failed, please include this report when filing a bug.",
+            failed
+        );
+    }
+}
+
+fn print_daemon_info(info: &DaemonInfo) {
+    match &info.git_hash {
+        Some(hash) => println!("Daemon version: {} ({})", info.version, hash),
+        None => println!("Daemon version: {}", info.version),
+    }
+    println!("Protocol version: {}", info.protocol_version);
+    println!("Uptime: {}s", info.uptime);
+    println!("Socket: {}", info.socket_path);
+    if info.http_settings.enabled {
+        println!(
+            "HTTP: {}:{}",
+            info.http_settings.bind_address, info.http_settings.port
+        );
+    } else {
+        println!("HTTP: Disabled");
+    }
+    println!(
+        "Clients: {} IPC, {} Websocket",
+        info.ipc_clients, info.websocket_clients
+    );
+
+    if info.devices.is_empty() {
+        println!("No Devices Found");
+    }
+    for device in &info.devices {
+        let state = match (device.connected, device.uptime) {
+            (false, _) => String::from("Offline"),
+            (true, Some(uptime)) => format!("Connected for {}s", uptime),
+            (true, None) => String::from("Connected"),
+        };
+        println!(
+            "Device {} ({:?}): Firmware {}, {}",
+            device.serial, device.device_type, device.firmware, state
+        );
+        if let Some(stage) = device.firmware_update {
+            println!("  Firmware Update: {:?}", stage);
+        }
+    }
+}
+
+async fn wait_for_mic_calibration(client: &mut Box<dyn Client>, serial: &str) -> Result<()> {
+    loop {
+        sleep(Duration::from_millis(250)).await;
+        client.poll_status().await?;
+
+        let mic_status = match client.status().mixers.get(serial) {
+            Some(mixer) => &mixer.mic_status,
+            None => return Err(anyhow!("Device disconnected during calibration")),
+        };
+        if mic_status.calibrating {
+            continue;
+        }
+
+        let result = match &mic_status.last_calibration {
+            Some(result) => result,
+            None => return Err(anyhow!("Mic Calibration did not complete")),
+        };
+        if !result.speech_detected {
+            println!("No speech was detected, gain unchanged.");
+            return Ok(());
+        }
+        println!(
+            "Peak: {:.1}dB, Average: {:.1}dB",
+            result.peak_db, result.average_db
+        );
+        if result.applied {
+            println!(
+                "Gain changed from {}dB to {}dB",
+                result.previous_gain, result.suggested_gain
+            );
+        } else {
+            println!(
+                "Suggested Gain: {}dB (currently {}dB)",
+                result.suggested_gain, result.previous_gain
+            );
+        }
+        return Ok(());
+    }
+}
+
+async fn wait_for_firmware_update(client: &mut Box<dyn Client>, serial: &str) -> Result<()> {
+    let mut last_status = None;
+    loop {
+        sleep(Duration::from_millis(250)).await;
+        client.poll_status().await?;
+
+        let status = client
+            .status()
+            .mixers
+            .get(serial)
+            .and_then(|mixer| mixer.firmware_update.clone());
+
+        // If the device has gone away before we saw the result, it's rebooting..
+        let status = match status {
+            Some(status) => status,
+            None => {
+                println!("Device Rebooting..");
+                return Ok(());
+            }
+        };
+
+        let current = (status.stage, status.progress);
+        if last_status != Some(current) {
+            println!("{:?}: {}%", status.stage, status.progress);
+            last_status = Some(current);
+        }
+
+        match status.stage {
+            FirmwareUpdateStage::Complete => {
+                println!("Firmware Update Complete, the GoXLR will now reboot.");
+                return Ok(());
+            }
+            FirmwareUpdateStage::Failed => {
+                return Err(anyhow!(
+                    "Firmware Update Failed: {}",
+                    status.error.unwrap_or_default()
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn print_device_list<'a>(mixers: impl Iterator<Item = &'a MixerStatus>) {
+    for mixer in mixers {
+        println!(
+            "{} - {} on bus {}, address {}{}",
+            mixer.hardware.serial_number,
+            device_type_name(&mixer.hardware.device_type),
+            mixer.hardware.usb_device.bus_number,
+            mixer.hardware.usb_device.address,
+            if mixer.connected { "" } else { " (Offline)" }
+        );
+    }
+}
+
+fn device_summary(mixer: &MixerStatus) -> serde_json::Value {
+    serde_json::json!({
+        "serial": mixer.hardware.serial_number,
+        "device_type": mixer.hardware.device_type,
+        "connected": mixer.connected,
+        "profile": mixer.profile_name,
+        "mic_profile": mixer.mic_profile_name,
+    })
+}
+
+fn channel_summary(mixer: &MixerStatus, channel: ChannelName) -> serde_json::Value {
+    serde_json::json!({
+        "channel": channel,
+        "volume": VolumeLevel::from_raw(mixer.get_channel_volume(channel)),
+        "fader": channel_fader(mixer, channel),
+    })
+}
+
+fn channel_fader(mixer: &MixerStatus, channel: ChannelName) -> Option<FaderName> {
+    FaderName::iter().find(|fader| mixer.get_fader_status(*fader).channel == channel)
+}
+
+fn print_channels(mixer: &MixerStatus) {
+    for channel in mixer.capabilities.fader_channels() {
+        let volume = VolumeLevel::from_raw(mixer.get_channel_volume(channel));
+        match channel_fader(mixer, channel) {
+            Some(fader) => println!("{}: {} (Fader {})", channel, volume, fader),
+            None => println!("{}: {}", channel, volume),
+        }
+    }
+}
+
+// Channels are checked against those the device can put on a fader (the same check the daemon
+// makes), accepting either the name shown by 'channels' or the one used elsewhere on the command
+// line (eg. 'line-in').
+fn find_channel(mixer: &MixerStatus, name: &str) -> Result<ChannelName> {
+    let channels = mixer.capabilities.fader_channels();
+    let found = channels.iter().find(|channel| {
+        channel.to_string().eq_ignore_ascii_case(name)
+            || channel
+                .to_possible_value()
+                .is_some_and(|value| value.matches(name, true))
+    });
+
+    match found {
+        Some(channel) => Ok(*channel),
+        None => {
+            let available: Vec<String> =
+                channels.iter().map(|channel| channel.to_string()).collect();
+            Err(anyhow!(
+                "The {} channel can't be assigned to a fader on this device, available channels: {}",
+                name,
+                available.join(", ")
+            ))
+        }
+    }
+}
+
+fn device_type_name(device_type: &DeviceType) -> &'static str {
+    match device_type {
+        DeviceType::Unknown => "Unknown device",
+        DeviceType::Full => "Regular GoXLR",
+        DeviceType::Mini => "Mini GoXLR",
+    }
+}
+
 fn print_device(device: &MixerStatus) {
     println!(
         "Device type: {}",
@@ -1027,6 +1726,12 @@ fn print_usb_info(usb: &UsbProductInformation) {
         "USB Address: bus {}, address {}",
         usb.bus_number, usb.address
     );
+
+    let features = usb.dcp_features;
+    println!(
+        "DCP Categories: Peaks: {}, Router: {}, Mixer: {}, NVM: {}",
+        features.peaks, features.router, features.mixer, features.nvm
+    );
 }
 
 fn print_mixer_info(mixer: &MixerStatus) {
@@ -1050,8 +1755,8 @@ fn print_mixer_info(mixer: &MixerStatus) {
     }
 
     for channel in ChannelName::iter() {
-        let pct = (mixer.get_channel_volume(channel) as f32 / 255.0) * 100.0;
-        println!("{channel} volume: {pct:.0}%");
+        let volume = VolumeLevel::from_raw(mixer.get_channel_volume(channel));
+        println!("{channel} volume: {volume}");
     }
 
     for microphone in MicrophoneType::iter() {
@@ -4,6 +4,7 @@ use std::env;
 use std::fs::File;
 use std::io::Error;
 use std::path::Path;
+use std::process::Command;
 
 #[cfg(target_os = "windows")]
 use windres::Build;
@@ -16,6 +17,17 @@ fn main() -> Result<(), Error> {
         Build::new().compile("resources/goxlr-daemon.rc").unwrap();
     }
 
+    // Record the commit we're built from, so it can be included in diagnostics..
+    if let Ok(output) = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+    {
+        if output.status.success() {
+            let hash = String::from_utf8_lossy(&output.stdout);
+            println!("cargo:rustc-env=GIT_HASH={}", hash.trim());
+        }
+    }
+
     let outdir = match env::var_os("OUT_DIR") {
         None => return Ok(()),
         Some(outdir) => outdir,
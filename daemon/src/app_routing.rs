@@ -0,0 +1,157 @@
+use crate::settings::SettingsHandle;
+use crate::shutdown::Shutdown;
+use log::{debug, info};
+
+#[cfg(target_os = "linux")]
+use {
+    crate::settings::AppRoutingRule,
+    crate::virtual_channels::VIRTUAL_SINK_PREFIX,
+    anyhow::Result,
+    goxlr_audio::{get_output_sinks, get_playback_streams, move_playback_stream, OutputSink},
+    goxlr_types::ChannelName,
+    log::warn,
+    std::collections::HashSet,
+    std::time::Duration,
+    tokio::time::sleep,
+};
+
+/*
+Under Windows, applications can be pointed at a specific GoXLR channel from the sound settings,
+but on Linux there's no equivalent, so this service moves application streams to the correct
+GoXLR output based on rules in the settings file, for example:
+
+"app_routing": [
+    { "process": "spotify", "channel": "Music" },
+    { "process": "Discord", "channel": "Chat" }
+]
+
+The process is compared (ignoring case) against both the binary name and the name the application
+reports. Each stream is only moved once when it first matches a rule, so it can still be moved
+manually afterwards. The rules are re-read on every check, so changes to them apply without a
+restart. This talks to the Pulse server, so works with both PulseAudio and PipeWire (via
+pipewire-pulse).
+ */
+#[cfg(target_os = "linux")]
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+#[cfg(target_os = "linux")]
+pub async fn spawn_app_routing_service(settings: SettingsHandle, mut shutdown: Shutdown) {
+    info!("Starting Application Routing Service..");
+    let mut handled = HashSet::new();
+    loop {
+        tokio::select! {
+            () = shutdown.recv() => {
+                info!("Shutting down Application Routing Service");
+                return;
+            },
+            () = sleep(CHECK_INTERVAL) => {
+                let rules = settings.get_app_routing().await;
+                if rules.is_empty() {
+                    continue;
+                }
+
+                // Talking to Pulse blocks, so keep it off the runtime..
+                let task = tokio::task::spawn_blocking(move || {
+                    let result = route_streams(&rules, &mut handled);
+                    (handled, result)
+                });
+
+                match task.await {
+                    Ok((returned, result)) => {
+                        handled = returned;
+                        if let Err(error) = result {
+                            warn!("Unable to Route Application Streams: {}", error);
+                        }
+                    }
+                    Err(error) => {
+                        warn!("Application Routing Task Failed: {}", error);
+                        handled = HashSet::new();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn route_streams(rules: &[AppRoutingRule], handled: &mut HashSet<u32>) -> Result<()> {
+    let streams = get_playback_streams()?;
+
+    // Forget about any streams which have gone away..
+    handled.retain(|index| streams.iter().any(|stream| stream.index == *index));
+
+    // Streams without a matching rule are left unhandled, in case a rule for them turns up later
+    let matched: Vec<_> = streams
+        .iter()
+        .filter(|stream| !handled.contains(&stream.index))
+        .filter_map(|stream| {
+            let names = [&stream.process, &stream.application];
+            let rule = rules.iter().find(|rule| {
+                names
+                    .iter()
+                    .filter_map(|name| name.as_ref())
+                    .any(|name| name.eq_ignore_ascii_case(&rule.process))
+            });
+            rule.map(|rule| (stream, rule))
+        })
+        .collect();
+    if matched.is_empty() {
+        return Ok(());
+    }
+
+    let sinks = get_output_sinks()?;
+    for (stream, rule) in matched {
+        let sink = match find_channel_sink(&sinks, rule.channel) {
+            Some(sink) => sink,
+            None => {
+                debug!("Unable to find GoXLR output for {}", rule.channel);
+                continue;
+            }
+        };
+
+        if stream.sink != sink.index {
+            info!(
+                "Moving {} to the GoXLR {} channel",
+                rule.process, rule.channel
+            );
+
+            // Leave the stream unhandled if this fails, so it's tried again on the next pass
+            if let Err(error) = move_playback_stream(stream.index, sink.index) {
+                warn!("Unable to Move {}: {}", rule.process, error);
+                continue;
+            }
+        }
+        handled.insert(stream.index);
+    }
+    Ok(())
+}
+
+// The GoXLR's outputs are named after the channels (for example 'GoXLR Music' or
+// 'alsa_output.usb-TC-Helicon_GoXLR-00.HiFi__Music__sink'), so match on both.
+#[cfg(target_os = "linux")]
+pub(crate) fn find_channel_sink(sinks: &[OutputSink], channel: ChannelName) -> Option<&OutputSink> {
+    let channel = channel.to_string().to_lowercase();
+    sinks.iter().find(|sink| {
+        // Don't match any of the outputs we've created ourselves..
+        if sink.name.starts_with(VIRTUAL_SINK_PREFIX) {
+            return false;
+        }
+
+        let name = format!(
+            "{} {}",
+            sink.name,
+            sink.description.clone().unwrap_or_default()
+        )
+        .to_lowercase();
+        name.contains("goxlr") && name.contains(&channel)
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub async fn spawn_app_routing_service(settings: SettingsHandle, _: Shutdown) {
+    if !settings.get_app_routing().await.is_empty() {
+        info!("Application Routing Configured, but is only supported on Linux.");
+        return;
+    }
+    debug!("Application Routing is not supported on this platform.");
+}
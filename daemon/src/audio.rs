@@ -6,22 +6,30 @@ use goxlr_audio::get_audio_inputs;
 use goxlr_audio::player::{Player, PlayerState};
 use goxlr_audio::recorder::BufferedRecorder;
 use goxlr_audio::recorder::RecorderState;
+use goxlr_audio::AtomicF64;
+use goxlr_ipc::{ActiveSample, PlaybackProgress};
 use goxlr_types::SampleBank;
 use goxlr_types::SampleButtons;
 use log::{debug, error, info, warn};
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::thread::JoinHandle;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use strum::IntoEnumIterator;
 
 #[derive(Debug)]
 pub struct AudioHandler {
     output_device: Option<String>,
 
+    // An optional second output, samples are played here as well as the GoXLR
+    monitor_device: Option<String>,
+
+    // Whether the input should also be sent to the monitor while recording
+    monitor_recording: bool,
+
     buffered_input: Option<Arc<BufferedRecorder>>,
 
     last_device_check: Option<Instant>,
@@ -35,6 +43,7 @@ pub struct AudioFile {
     pub(crate) gain: Option<f64>,
     pub(crate) start_pct: Option<f64>,
     pub(crate) stop_pct: Option<f64>,
+    pub(crate) speed: Option<f64>,
     pub(crate) fade_on_stop: bool,
 }
 
@@ -65,6 +74,9 @@ struct StateManager {
     pub(crate) stream_type: StreamType,
     pub(crate) recording: Option<AudioRecordingState>,
     pub(crate) playback: Option<AudioPlaybackState>,
+    pub(crate) monitor: Option<AudioPlaybackState>,
+    pub(crate) track: String,
+    pub(crate) started: SystemTime,
 }
 
 #[derive(Debug, PartialEq)]
@@ -105,6 +117,8 @@ impl AudioHandler {
         // Find the Input Device..
         let mut handler = Self {
             output_device: None,
+            monitor_device: None,
+            monitor_recording: false,
 
             buffered_input: None,
 
@@ -201,6 +215,24 @@ impl AudioHandler {
         patterns
     }
 
+    // Looks for the sampler's output and input, without changing the devices in use.
+    pub fn find_sampler_devices(&self) -> (Option<String>, Option<String>) {
+        let find = |devices: Vec<String>, patterns: Vec<Regex>| {
+            devices.into_iter().find(|device| {
+                patterns
+                    .iter()
+                    .any(|pattern| pattern.is_match(device).unwrap_or(false))
+            })
+        };
+
+        let output = find(
+            goxlr_audio::get_audio_outputs(),
+            self.get_output_device_patterns(),
+        );
+        let input = find(get_audio_inputs(), self.get_input_device_patterns());
+        (output, input)
+    }
+
     fn find_device(&mut self, is_output: bool) {
         debug!("Attempting to Find Device..");
         if let Some(last_check) = self.last_device_check {
@@ -243,6 +275,48 @@ impl AudioHandler {
         }
     }
 
+    pub fn reset_devices(&mut self) {
+        debug!("Clearing cached Audio Devices");
+        self.output_device = None;
+        self.last_device_check = None;
+    }
+
+    pub fn set_monitor_device(&mut self, device: Option<String>) {
+        self.monitor_device = device;
+    }
+
+    pub fn get_monitor_device(&self) -> Option<String> {
+        self.monitor_device.clone()
+    }
+
+    pub fn set_monitor_recording(&mut self, enabled: bool) {
+        self.monitor_recording = enabled;
+    }
+
+    pub fn is_monitor_recording(&self) -> bool {
+        self.monitor_recording
+    }
+
+    pub fn get_recording_duration(
+        &self,
+        bank: SampleBank,
+        button: SampleButtons,
+    ) -> Option<Duration> {
+        let stream = self.active_streams[bank][button].as_ref()?;
+        if stream.stream_type != StreamType::Recording {
+            return None;
+        }
+        Some(stream.started.elapsed().unwrap_or_default())
+    }
+
+    pub fn get_recording_level(&self, bank: SampleBank, button: SampleButtons) -> Option<f64> {
+        let stream = self.active_streams[bank][button].as_ref()?;
+        stream
+            .recording
+            .as_ref()
+            .map(|recording| recording.state.level.load(Ordering::Relaxed))
+    }
+
     pub async fn check_playing(&mut self) -> bool {
         let mut state_changed = false;
 
@@ -289,6 +363,44 @@ impl AudioHandler {
         false
     }
 
+    pub fn get_active_sample(
+        &self,
+        bank: SampleBank,
+        button: SampleButtons,
+    ) -> Option<ActiveSample> {
+        self.active_streams[bank][button]
+            .as_ref()
+            .map(|stream| ActiveSample {
+                track: stream.track.clone(),
+                recording: stream.stream_type == StreamType::Recording,
+                started: stream
+                    .started
+                    .duration_since(UNIX_EPOCH)
+                    .map(|time| time.as_millis() as u64)
+                    .unwrap_or(0),
+            })
+    }
+
+    pub fn get_sample_progress(
+        &self,
+        bank: SampleBank,
+        button: SampleButtons,
+    ) -> Option<PlaybackProgress> {
+        let stream = self.active_streams[bank][button].as_ref()?;
+        if let Some(playback) = &stream.playback {
+            return Some(PlaybackProgress {
+                percent: Some(playback.state.progress.load(Ordering::Relaxed)),
+                position_ms: playback.state.position.load(Ordering::Relaxed),
+            });
+        }
+
+        let elapsed = stream.started.elapsed().unwrap_or_default();
+        Some(PlaybackProgress {
+            percent: None,
+            position_ms: elapsed.as_millis() as u64,
+        })
+    }
+
     pub fn is_sample_recording(&self) -> bool {
         for bank in SampleBank::iter() {
             for button in SampleButtons::iter() {
@@ -329,43 +441,24 @@ impl AudioHandler {
         }
 
         if let Some(output_device) = &self.output_device {
-            let fade_duration = match audio.fade_on_stop {
-                true => Some(0.5),
-                false => None,
-            };
-
-            // Ok, we need to grab and configure the player..
-            let mut player = Player::new(
-                &audio.file,
-                Some(output_device.clone()),
-                fade_duration,
-                audio.start_pct,
-                audio.stop_pct,
-                audio.gain,
-            )?;
-
-            let state = player.get_state();
-            let handler = thread::spawn(move || {
-                if !loop_track {
-                    let result = player.play();
-                    if let Err(error) = result {
-                        warn!("Playback Error: {}", error);
-                    }
-                } else {
-                    let result = player.play_loop();
-                    if let Err(error) = result {
-                        warn!("Loop Playback Error: {}", error);
-                    }
+            let playback = spawn_player(&audio, output_device.clone(), loop_track)?;
+
+            // Failing to play to the monitor shouldn't prevent playback on the GoXLR..
+            let mut monitor = None;
+            if let Some(monitor_device) = &self.monitor_device {
+                match spawn_player(&audio, monitor_device.clone(), loop_track) {
+                    Ok(state) => monitor = Some(state),
+                    Err(error) => warn!("Unable to play Sample to Monitor: {}", error),
                 }
-            });
+            }
 
             self.active_streams[bank][button] = Some(StateManager {
                 stream_type: StreamType::Playback,
                 recording: None,
-                playback: Some(AudioPlaybackState {
-                    handle: Some(handler),
-                    state,
-                }),
+                playback: Some(playback),
+                monitor,
+                track: get_track_name(&audio.file),
+                started: SystemTime::now(),
             });
         } else {
             return Err(anyhow!("Unable to play Sample, Output device not found"));
@@ -386,6 +479,14 @@ impl AudioHandler {
                 return Err(anyhow!("Attempted to Stop Playback on Recording Stream.."));
             }
 
+            // The monitor follows whatever happens to the main playback..
+            if let Some(monitor) = &player.monitor {
+                if force || monitor.state.stopping.load(Ordering::Relaxed) {
+                    monitor.state.force_stop.store(true, Ordering::Relaxed);
+                }
+                monitor.state.stopping.store(true, Ordering::Relaxed);
+            }
+
             if let Some(playback_state) = &mut player.playback {
                 if playback_state.state.stopping.load(Ordering::Relaxed) {
                     // We should be stopping already, force the shutdown.
@@ -436,8 +537,23 @@ impl AudioHandler {
 
             let state = RecorderState {
                 stop: Arc::new(AtomicBool::new(false)),
+                level: Arc::new(AtomicF64::new(-100.)),
             };
 
+            // Let the user hear what's being recorded, if they've asked for it..
+            if self.monitor_recording {
+                if let Some(device) = self.monitor_device.clone() {
+                    let inner_recorder = recorder.clone();
+                    let inner_state = state.clone();
+                    thread::spawn(move || {
+                        if let Err(error) = inner_recorder.monitor(device, inner_state) {
+                            warn!("Unable to Monitor Recording: {}", error);
+                        }
+                    });
+                }
+            }
+
+            let track = get_track_name(&path);
             let inner_recorder = recorder.clone();
             let inner_path = path.clone();
             let inner_state = state.clone();
@@ -457,6 +573,9 @@ impl AudioHandler {
                     state,
                 }),
                 playback: None,
+                monitor: None,
+                track,
+                started: SystemTime::now(),
             });
         } else {
             bail!("No valid Input Device was Found");
@@ -469,7 +588,7 @@ impl AudioHandler {
         &mut self,
         bank: SampleBank,
         button: SampleButtons,
-    ) -> Result<Option<String>> {
+    ) -> Result<Option<PathBuf>> {
         let mut filename = None;
 
         if let Some(player) = &mut self.active_streams[bank][button] {
@@ -483,11 +602,7 @@ impl AudioHandler {
 
                 // Recording Complete, check the file was made...
                 if recording_state.file.exists() {
-                    if let Some(file_name) = recording_state.file.file_name() {
-                        filename.replace(String::from(file_name.to_string_lossy()));
-                    } else {
-                        bail!("Unable to Extract Filename from Path! (This shouldn't be possible!)")
-                    }
+                    filename.replace(recording_state.file.clone());
                 }
             }
         } else {
@@ -510,7 +625,7 @@ impl AudioHandler {
         }
 
         // Create the player..
-        let mut player = Player::new(&path, None, None, None, None, None)?;
+        let mut player = Player::new(&path, None, None, None, None, None, None)?;
 
         // Grab the State..
         let state = player.get_state();
@@ -610,3 +725,47 @@ pub struct CalculationResult {
     pub button: SampleButtons,
     pub gain: f64,
 }
+
+fn spawn_player(audio: &AudioFile, device: String, loop_track: bool) -> Result<AudioPlaybackState> {
+    let fade_duration = match audio.fade_on_stop {
+        true => Some(0.5),
+        false => None,
+    };
+
+    // Ok, we need to grab and configure the player..
+    let mut player = Player::new(
+        &audio.file,
+        Some(device),
+        fade_duration,
+        audio.start_pct,
+        audio.stop_pct,
+        audio.gain,
+        audio.speed,
+    )?;
+
+    let state = player.get_state();
+    let handler = thread::spawn(move || {
+        if !loop_track {
+            let result = player.play();
+            if let Err(error) = result {
+                warn!("Playback Error: {}", error);
+            }
+        } else {
+            let result = player.play_loop();
+            if let Err(error) = result {
+                warn!("Loop Playback Error: {}", error);
+            }
+        }
+    });
+
+    Ok(AudioPlaybackState {
+        handle: Some(handler),
+        state,
+    })
+}
+
+fn get_track_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
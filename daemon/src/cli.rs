@@ -25,6 +25,10 @@ pub struct Cli {
     #[arg(long)]
     pub http_enable_cors: bool,
 
+    /// Expose daemon metrics in the Prometheus format at /metrics on the HTTP Server
+    #[arg(long)]
+    pub http_enable_metrics: bool,
+
     /// Set the HTTP Bind Address (0.0.0.0 for all interfaces)
     #[arg(long)]
     pub http_bind_address: Option<String>,
@@ -48,6 +52,18 @@ pub struct Cli {
     /// Force regular expression to use when finding the Sampler Output
     #[arg(long)]
     pub override_sample_output_device: Option<String>,
+
+    /// The path of the IPC socket (or named pipe on Windows), overrides GOXLR_SOCKET
+    #[arg(long)]
+    pub socket_path: Option<PathBuf>,
+
+    /// Share the IPC socket with members of this group (Unix only)
+    #[arg(long)]
+    pub socket_group: Option<String>,
+
+    /// Attach a simulated GoXLR, for development without hardware (can be used multiple times)
+    #[arg(long, value_enum)]
+    pub simulate_device: Vec<SimulatedDevice>,
 }
 
 fn default_config_location() -> PathBuf {
@@ -73,3 +89,9 @@ pub enum LevelFilter {
     /// Corresponds to the `Trace` log level.
     Trace,
 }
+
+#[derive(ValueEnum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum SimulatedDevice {
+    Full,
+    Mini,
+}
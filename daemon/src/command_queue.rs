@@ -0,0 +1,97 @@
+use crate::primary_worker::DeviceCommand;
+use goxlr_ipc::GoXLRCommand;
+use goxlr_types::{
+    Button, ButtonColourGroups, ChannelName, EncoderColourTargets, FaderName, SamplerColourTargets,
+    SimpleColourTargets,
+};
+use std::collections::VecDeque;
+
+/*
+When a UI drags a slider (or a colour picker), it can send dozens of commands a second, each of
+which results in a USB request. Commands which arrive while the device is busy are held here, and
+if a newer command would simply overwrite the value set by an older one, the older one is dropped
+(and reported as successful, as its value has been superseded).
+
+An older command is only dropped if everything queued after it is also a 'setter' of this kind,
+so something like a profile save will always see the values sent before it.
+ */
+#[derive(Default)]
+pub struct CommandQueue {
+    queue: VecDeque<DeviceCommand>,
+}
+
+impl CommandQueue {
+    pub fn push(&mut self, command: DeviceCommand) {
+        if let DeviceCommand::RunDeviceCommand(serial, new_command, _) = &command {
+            if let Some(key) = CoalesceKey::from_command(new_command) {
+                if let Some(index) = self.find_superseded(serial, key) {
+                    if let Some(DeviceCommand::RunDeviceCommand(_, _, sender)) =
+                        self.queue.remove(index)
+                    {
+                        let _ = sender.send(Ok(()));
+                    }
+                }
+            }
+        }
+        self.queue.push_back(command);
+    }
+
+    pub fn pop(&mut self) -> Option<DeviceCommand> {
+        self.queue.pop_front()
+    }
+
+    fn find_superseded(&self, serial: &str, key: CoalesceKey) -> Option<usize> {
+        for (index, queued) in self.queue.iter().enumerate().rev() {
+            let (queued_serial, queued_key) = match queued {
+                DeviceCommand::RunDeviceCommand(queued_serial, command, _) => {
+                    match CoalesceKey::from_command(command) {
+                        Some(queued_key) => (queued_serial, queued_key),
+                        None => return None,
+                    }
+                }
+                _ => return None,
+            };
+
+            if queued_serial == serial && queued_key == key {
+                return Some(index);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum CoalesceKey {
+    Volume(ChannelName),
+    SubMixVolume(ChannelName),
+    GlobalColour,
+    GlobalBrightness,
+    FaderColours(FaderName),
+    AllFaderColours,
+    ButtonColours(Button),
+    ButtonGroupColours(ButtonColourGroups),
+    SimpleColour(SimpleColourTargets),
+    EncoderColour(EncoderColourTargets),
+    SampleColour(SamplerColourTargets),
+}
+
+impl CoalesceKey {
+    fn from_command(command: &GoXLRCommand) -> Option<Self> {
+        match command {
+            GoXLRCommand::SetVolume(channel, _) => Some(Self::Volume(*channel)),
+            GoXLRCommand::SetSubMixVolume(channel, _) => Some(Self::SubMixVolume(*channel)),
+            GoXLRCommand::SetGlobalColour(_) => Some(Self::GlobalColour),
+            GoXLRCommand::SetGlobalBrightness(_) => Some(Self::GlobalBrightness),
+            GoXLRCommand::SetFaderColours(fader, _, _) => Some(Self::FaderColours(*fader)),
+            GoXLRCommand::SetAllFaderColours(_, _) => Some(Self::AllFaderColours),
+            GoXLRCommand::SetButtonColours(button, _, _) => Some(Self::ButtonColours(*button)),
+            GoXLRCommand::SetButtonGroupColours(group, _, _) => {
+                Some(Self::ButtonGroupColours(*group))
+            }
+            GoXLRCommand::SetSimpleColour(target, _) => Some(Self::SimpleColour(*target)),
+            GoXLRCommand::SetEncoderColour(target, _, _, _) => Some(Self::EncoderColour(*target)),
+            GoXLRCommand::SetSampleColour(target, _, _, _) => Some(Self::SampleColour(*target)),
+            _ => None,
+        }
+    }
+}
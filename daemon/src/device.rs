@@ -1,56 +1,143 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::fs::create_dir_all;
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::{anyhow, bail, Result};
-use chrono::Local;
+use anyhow::{anyhow, bail, Context, Result};
 use enum_map::EnumMap;
 use enumset::EnumSet;
-use log::{debug, error, info};
+use json_patch::{diff, Patch};
+use log::{debug, error, info, warn};
 use ritelinked::LinkedHashSet;
+use serde_json::Value;
 use strum::IntoEnumIterator;
+use tokio::sync::mpsc;
 use tokio::sync::mpsc::Sender;
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
 
+use goxlr_audio::player::NORMALIZATION_TARGET;
 use goxlr_ipc::{
-    DeviceType, Display, FaderStatus, GoXLRCommand, HardwareStatus, Levels, MicSettings,
-    MixerStatus, SampleProcessState, Settings,
+    ButtonStates as InputStates, DaemonError, DaemonEvent, DeviceType, DiagnosticCheck,
+    DiagnosticReport, Display, Ducking, EncoderPositions, FaderMeter, FaderStatus, GoXLRCommand,
+    HardwareStatus, IdleBehaviour, IdleLighting, Levels, MicCalibrationResult, MicSettings,
+    MissingSample, MixerStatus, PushToTalk, RoutingPreset, SampleProcessState, Settings,
+    VoiceActivity, VolumePreset,
 };
 use goxlr_profile_loader::components::mute::MuteFunction;
 use goxlr_types::{
-    Button, ChannelName, DisplayModeComponents, EffectBankPresets, EffectKey, EncoderName,
-    FaderName, HardTuneSource, InputDevice as BasicInputDevice, MicrophoneParamKey, Mix, MuteState,
-    OutputDevice as BasicOutputDevice, RobotRange, SampleBank, SampleButtons, SamplePlaybackMode,
-    VersionNumber, WaterfallDirection,
+    Button, ChannelName, DeviceCapabilities, DisplayModeComponents, EffectBankPresets, EffectKey,
+    EncoderName, FaderName, HardTuneSource, InputDevice as BasicInputDevice, MicrophoneParamKey,
+    Mix, MuteState, OutputDevice as BasicOutputDevice, RobotRange, SampleBank, SampleButtons,
+    SamplePlaybackMode, VersionNumber, VolumeLevel, WaterfallDirection,
 };
 use goxlr_usb::animation::{AnimationMode, WaterFallDir};
 use goxlr_usb::buttonstate::{ButtonStates, Buttons};
 use goxlr_usb::channelstate::ChannelState;
 use goxlr_usb::channelstate::ChannelState::{Muted, Unmuted};
 use goxlr_usb::device::base::FullGoXLRDevice;
+use goxlr_usb::device::simulator::device::{create_detached_device, SimulatedDeviceType};
 use goxlr_usb::routing::{InputDevice, OutputDevice};
 
 use crate::audio::{AudioFile, AudioHandler};
 use crate::events::EventTriggers;
 use crate::events::EventTriggers::TTSMessage;
-use crate::files::find_file_in_path;
+use crate::files::{
+    check_recording_template, create_path, find_sample_file, get_backup_path, get_recording_path,
+    get_sample_track_name,
+};
+use crate::firmware::FirmwareUpdate;
+use crate::history::{CommandHistory, DeviceSnapshot, HistoryEntry};
+use crate::metrics::METRICS;
+use crate::mic_calibration::{level_to_db, MicCalibration};
 use crate::mic_profile::{MicProfileAdapter, DEFAULT_MIC_PROFILE_NAME};
+use crate::platform;
 use crate::profile::{
-    usb_to_standard_button, version_newer_or_equal_to, ProfileAdapter, DEFAULT_PROFILE_NAME,
+    get_fader_colour_positions, usb_to_standard_button, validate_route, version_newer_or_equal_to,
+    ProfileAdapter, SamplerSettings, DEFAULT_PROFILE_NAME,
 };
+use crate::sample_hashes::{relink_tracks, RelinkResult, RelinkTrack, SampleHashes};
+use crate::settings::ButtonMacro;
 use crate::SettingsHandle;
 
+// Written alongside the profiles when backing up a device's settings.
+const BACKUP_SETTINGS_FILE: &str = "settings.json";
+
+// How often progress events are sent for playing or recording samples
+const SAMPLE_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+// How long a fader has to be still before its new volume is announced
+const VOLUME_ANNOUNCE_DELAY: Duration = Duration::from_millis(500);
+
+// How long the watchdog will wait for the device to respond to a probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+// How often the live volumes and mutes are checked, and stored if they've changed
+const STATE_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(30);
+
+// Recordings won't start (and will be stopped) when the disk has less than this available
+const MIN_RECORDING_SPACE: u64 = 100 * 1024 * 1024;
+
+// Volumes rising by more than this on profile load are faded in, rather than set directly
+const PROFILE_FADE_THRESHOLD: u8 = 25;
+const MAX_PROFILE_VOLUME_FADE: u16 = 5000;
+
+// How often (in milliseconds) the fader meter can be redrawn, the lower bound is the update loop..
+const MIN_FADER_METER_REFRESH: u16 = 50;
+const MAX_FADER_METER_REFRESH: u16 = 1000;
+
+// The fader meter shows the mic level between this and 0dBFS, and is rounded to a number of
+// steps so the colour map isn't resent for changes that wouldn't be visible.
+const FADER_METER_FLOOR: f64 = -60.;
+const FADER_METER_STEPS: u8 = 20;
+
 pub struct Device<'a> {
     goxlr: Box<dyn FullGoXLRDevice>,
     hardware: HardwareStatus,
     last_buttons: EnumSet<Buttons>,
     button_states: EnumMap<Buttons, ButtonState>,
+    events: Vec<DaemonEvent>,
+    active_samples: EnumMap<SampleBank, EnumMap<SampleButtons, bool>>,
+    queued_samples: EnumMap<SampleBank, EnumMap<SampleButtons, VecDeque<AudioFile>>>,
+    missing_samples: Vec<MissingSample>,
+    sample_hashes: SampleHashes,
+    sample_relink: Option<JoinHandle<(SampleHashes, Vec<RelinkResult>)>>,
+    last_sample_progress: Instant,
     fader_last_seen: EnumMap<FaderName, u8>,
     fader_pause_until: EnumMap<FaderName, PauseUntil>,
+    volume_announcements: EnumMap<FaderName, Option<Instant>>,
+    last_state_snapshot: Instant,
+    history: CommandHistory,
+    snapshots: HashMap<String, DeviceSnapshot>,
+    stream_muted: bool,
     profile: ProfileAdapter,
     mic_profile: MicProfileAdapter,
     audio_handler: Option<AudioHandler>,
     hold_time: u16,
+    button_hold_times: HashMap<Button, u16>,
     vc_mute_also_mute_cm: bool,
+    ducking: Ducking,
+    ducking_state: DuckingState,
+    push_to_talk: PushToTalk,
+    push_to_talk_active: bool,
+    push_to_talk_release: Option<Instant>,
+    voice_activity: VoiceActivity,
+    mic_active: bool,
+    mic_last_active: u128,
+    fader_meter: FaderMeter,
+    fader_meter_level: Option<u8>,
+    fader_meter_updated: Instant,
+    volume_fades: EnumMap<ChannelName, Option<VolumeFade>>,
+    brightness: u8,
+    idle_lighting: IdleLighting,
+    last_interaction: u128,
+    is_idle: bool,
+    connected: bool,
+    firmware_update: Option<FirmwareUpdate>,
+    mic_calibration: Option<MicCalibration>,
+    last_mic_calibration: Option<MicCalibrationResult>,
     settings: &'a SettingsHandle,
     global_events: Sender<EventTriggers>,
 
@@ -63,12 +150,52 @@ struct PauseUntil {
     until: u8,
 }
 
+#[derive(Debug, Copy, Clone)]
+struct VolumeFade {
+    from: u8,
+    to: u8,
+    start: u128,
+    duration: u128,
+}
+
+#[derive(Debug, Default, Clone)]
+struct DuckingState {
+    // How far through the duck we are, 0 is normal volume, 1 is fully ducked.
+    level: f32,
+    last_active: u128,
+    last_update: u128,
+
+    // The volume last sent for each ducked channel, and the volume it had before the duck started.
+    // The profile keeps the un-ducked volume, so ducked levels are never saved or compounded.
+    applied: EnumMap<ChannelName, Option<u8>>,
+    base: EnumMap<ChannelName, Option<u8>>,
+}
+
 #[derive(Debug, Default, Copy, Clone)]
 struct ButtonState {
     press_time: u128,
     hold_handled: bool,
 }
 
+// The Device's own state which commands can change outside of the profiles, held aside during
+// a dry run and put back afterwards.
+struct DryRunState {
+    events: Vec<DaemonEvent>,
+    last_buttons: EnumSet<Buttons>,
+    button_states: EnumMap<Buttons, ButtonState>,
+    volume_fades: EnumMap<ChannelName, Option<VolumeFade>>,
+    fader_last_seen: EnumMap<FaderName, u8>,
+    fader_pause_until: EnumMap<FaderName, PauseUntil>,
+    volume_announcements: EnumMap<FaderName, Option<Instant>>,
+    ducking_state: DuckingState,
+    stream_muted: bool,
+    push_to_talk_active: bool,
+    push_to_talk_release: Option<Instant>,
+    mic_active: bool,
+    last_interaction: u128,
+    is_idle: bool,
+}
+
 // Used when loading profiles to provide the previous
 // profile's settings for comparison.
 #[derive(Default)]
@@ -105,7 +232,26 @@ impl<'a> Device<'a> {
             device_type, profile, mic_profile
         );
 
-        let profile = ProfileAdapter::from_named_or_default(profile, profile_directory);
+        let mut profile = ProfileAdapter::from_named_or_default(profile, profile_directory);
+
+        // Put back any volumes and mutes from before the daemon was last stopped..
+        if settings_handle
+            .get_device_restore_runtime_state(&hardware.serial_number)
+            .await
+        {
+            let state = settings_handle
+                .get_device_runtime_state(&hardware.serial_number)
+                .await;
+            if let Some(state) = state {
+                if state.profile == profile.name() {
+                    debug!("Restoring Runtime State..");
+                    if let Err(error) = profile.apply_runtime_state(&state) {
+                        warn!("Unable to Restore Runtime State: {}", error);
+                    }
+                }
+            }
+        }
+
         let mic_profile =
             MicProfileAdapter::from_named_or_default(mic_profile, mic_profile_directory);
 
@@ -122,8 +268,16 @@ impl<'a> Device<'a> {
                 error!("Error Running Script: {}", e);
             }
 
-            if let Ok(audio) = audio_loader {
+            if let Ok(mut audio) = audio_loader {
                 debug!("Audio Handler Loaded OK..");
+                let monitor = settings_handle
+                    .get_device_sampler_monitor_device(&hardware.serial_number)
+                    .await;
+                let monitor_recording = settings_handle
+                    .get_device_sampler_monitor_recording(&hardware.serial_number)
+                    .await;
+                audio.set_monitor_device(monitor);
+                audio.set_monitor_recording(monitor_recording);
                 audio_handler.replace(audio);
             }
         } else {
@@ -133,21 +287,77 @@ impl<'a> Device<'a> {
         let hold_time = settings_handle
             .get_device_hold_time(&hardware.serial_number)
             .await;
+        let button_hold_times = settings_handle
+            .get_device_button_hold_times(&hardware.serial_number)
+            .await;
         let vc_mute_also_mute_cm = settings_handle
             .get_device_chat_mute_mutes_mic_to_chat(&hardware.serial_number)
             .await;
 
+        let ducking = settings_handle
+            .get_device_ducking(&hardware.serial_number)
+            .await;
+        let push_to_talk = settings_handle
+            .get_device_push_to_talk(&hardware.serial_number)
+            .await;
+        let voice_activity = settings_handle
+            .get_device_voice_activity(&hardware.serial_number)
+            .await;
+        let fader_meter = settings_handle
+            .get_device_fader_meter(&hardware.serial_number)
+            .await;
+
+        let brightness = settings_handle
+            .get_device_brightness(&hardware.serial_number)
+            .await;
+        let idle_lighting = settings_handle
+            .get_device_idle_lighting(&hardware.serial_number)
+            .await;
+
         let mut device = Self {
             profile,
             mic_profile,
             goxlr,
             hardware,
             hold_time,
+            button_hold_times,
             vc_mute_also_mute_cm,
+            ducking,
+            ducking_state: Default::default(),
+            push_to_talk,
+            push_to_talk_active: false,
+            push_to_talk_release: None,
+            voice_activity,
+            mic_active: false,
+            mic_last_active: 0,
+            fader_meter,
+            fader_meter_level: None,
+            fader_meter_updated: Instant::now(),
+            volume_fades: EnumMap::default(),
+            brightness,
+            idle_lighting,
+            last_interaction: 0,
+            is_idle: false,
+            connected: true,
+            firmware_update: None,
+            mic_calibration: None,
+            last_mic_calibration: None,
             last_buttons: EnumSet::empty(),
             button_states: EnumMap::default(),
+            events: vec![],
+            active_samples: EnumMap::default(),
+            queued_samples: EnumMap::default(),
+            missing_samples: vec![],
+            sample_hashes: SampleHashes::load(),
+            sample_relink: None,
+            last_sample_progress: Instant::now(),
             fader_last_seen: EnumMap::default(),
             fader_pause_until: EnumMap::default(),
+            volume_announcements: EnumMap::default(),
+            last_state_snapshot: Instant::now(),
+            history: CommandHistory::default(),
+            snapshots: HashMap::new(),
+            stream_muted: false,
             audio_handler,
             settings: settings_handle,
             global_events,
@@ -165,6 +375,65 @@ impl<'a> Device<'a> {
         &self.hardware.serial_number
     }
 
+    pub fn is_connected(&self) -> bool {
+        self.connected
+    }
+
+    // Called when the device has gone away, we keep hold of the Device (and its profiles) so
+    // that if it comes back, it can carry on where it left off.
+    pub fn set_disconnected(&mut self) {
+        if !self.connected {
+            return;
+        }
+
+        warn!("[{}] Device is offline", self.serial());
+        self.connected = false;
+        self.goxlr.shutdown();
+        self.volume_fades = EnumMap::default();
+    }
+
+    // Firmware updates stop the polling, so the device won't be responding to anything else.
+    pub fn is_updating_firmware(&self) -> bool {
+        self.firmware_update.is_some()
+    }
+
+    // Makes a simple request to the device, to check it's still able to respond.
+    pub async fn probe(&mut self) -> bool {
+        let request = self.goxlr.get_button_states();
+        matches!(timeout(PROBE_TIMEOUT, request).await, Ok(Ok(_)))
+    }
+
+    // Forces the sampler to look up its audio devices again, they may have changed name or
+    // order while the system was asleep.
+    pub fn reset_audio_devices(&mut self) {
+        if let Some(audio_handler) = &mut self.audio_handler {
+            audio_handler.reset_devices();
+        }
+    }
+
+    pub async fn reconnect(
+        &mut self,
+        goxlr: Box<dyn FullGoXLRDevice>,
+        hardware: HardwareStatus,
+    ) -> Result<()> {
+        info!("[{}] Device is back online, restoring state", self.serial());
+        self.goxlr = goxlr;
+        self.hardware = hardware;
+        self.connected = true;
+
+        // Reset anything tracking the physical state of the device..
+        self.last_buttons = EnumSet::empty();
+        self.button_states = EnumMap::default();
+        self.fader_last_seen = EnumMap::default();
+        self.fader_pause_until = EnumMap::default();
+        self.ducking_state = DuckingState::default();
+        self.is_idle = false;
+        self.firmware_update = None;
+
+        self.apply_profile(None).await?;
+        self.apply_mic_profile().await
+    }
+
     pub async fn status(&self) -> MixerStatus {
         let mut fader_map: EnumMap<FaderName, FaderStatus> = Default::default();
         for name in FaderName::iter() {
@@ -172,9 +441,11 @@ impl<'a> Device<'a> {
         }
 
         let mut button_states: EnumMap<Button, bool> = Default::default();
+        let mut button_held: EnumMap<Button, bool> = Default::default();
         for (button, state) in self.button_states.iter() {
             if state.press_time > 0 {
                 button_states[usb_to_standard_button(button)] = true;
+                button_held[usb_to_standard_button(button)] = state.hold_handled;
             }
         }
 
@@ -188,10 +459,28 @@ impl<'a> Device<'a> {
             .get_device_shutdown_commands(self.serial())
             .await;
 
-        let sampler_prerecord = self
-            .settings
-            .get_device_sampler_pre_buffer(self.serial())
-            .await;
+        let sampler_settings = SamplerSettings {
+            pre_record: self
+                .settings
+                .get_device_sampler_pre_buffer(self.serial())
+                .await,
+            normalization_target: self
+                .settings
+                .get_device_sampler_normalization_target(self.serial())
+                .await,
+            max_recording_length: self
+                .settings
+                .get_device_sampler_max_recording(self.serial())
+                .await,
+            queue_next: self
+                .settings
+                .get_device_sampler_queue_next(self.serial())
+                .await,
+            recording_template: self
+                .settings
+                .get_device_sampler_recording_template(self.serial())
+                .await,
+        };
 
         let submix_supported = self.device_supports_submixes();
 
@@ -212,20 +501,31 @@ impl<'a> Device<'a> {
 
         let is_mini = self.hardware.device_type == DeviceType::Mini;
 
+        let mut snapshots: Vec<String> = self.snapshots.keys().cloned().collect();
+        snapshots.sort();
+
         MixerStatus {
             hardware: self.hardware.clone(),
+            capabilities: self.hardware.device_type.capabilities(),
+            connected: self.connected,
+            firmware_update: self.firmware_update.as_ref().map(|update| update.status()),
             shutdown_commands,
             fader_status: fader_map,
-            cough_button: self.profile.get_cough_status(),
+            cough_button: self.profile.get_cough_status(button_states[Button::Cough]),
+            bleep_button: self.profile.get_bleep_status(button_states[Button::Bleep]),
             levels: Levels {
                 submix_supported: self.device_supports_submixes(),
                 output_monitor: self.profile.get_monitoring_mix(),
                 volumes,
+                volume_levels: EnumMap::from_fn(|channel| VolumeLevel::from_raw(volumes[channel])),
                 submix: self.profile.get_submixes_ipc(submix_supported),
                 bleep: self.mic_profile.bleep_level(),
                 deess: self.mic_profile.get_deesser(),
             },
             router: self.profile.create_router(),
+            stream_muted: self.stream_muted,
+            push_to_talk_active: self.push_to_talk_active || self.push_to_talk_release.is_some(),
+            mic_active: self.mic_active,
             mic_status: MicSettings {
                 mic_type: self.mic_profile.mic_type(),
                 mic_gains: self.mic_profile.mic_gains(),
@@ -233,6 +533,8 @@ impl<'a> Device<'a> {
                 equaliser: self.mic_profile.equalizer_ipc(),
                 equaliser_mini: self.mic_profile.equalizer_mini_ipc(),
                 compressor: self.mic_profile.compressor_ipc(),
+                calibrating: self.mic_calibration.is_some(),
+                last_calibration: self.last_mic_calibration.clone(),
             },
             lighting: self
                 .profile
@@ -241,11 +543,12 @@ impl<'a> Device<'a> {
             sampler: self.profile.get_sampler_ipc(
                 is_mini,
                 &self.audio_handler,
-                sampler_prerecord,
+                sampler_settings,
                 SampleProcessState {
                     progress: sample_progress,
                     last_error: sample_error,
                 },
+                &self.missing_samples,
             ),
             settings: Settings {
                 display: Display {
@@ -255,16 +558,48 @@ impl<'a> Device<'a> {
                     equaliser_fine: self.mic_profile.get_eq_fine_display_mode(),
                 },
                 mute_hold_duration: self.hold_time,
+                button_hold_durations: self.button_hold_times.clone(),
                 vc_mute_also_mute_cm: self.vc_mute_also_mute_cm,
+                restore_runtime_state: self
+                    .settings
+                    .get_device_restore_runtime_state(self.serial())
+                    .await,
+                profile_volume_fade: self
+                    .settings
+                    .get_device_profile_volume_fade(self.serial())
+                    .await,
+                ducking: self.ducking.clone(),
+                push_to_talk: self.push_to_talk.clone(),
+                voice_activity: self.voice_activity.clone(),
+                fader_meter: self.fader_meter.clone(),
+                idle_lighting: self.idle_lighting.clone(),
+                brightness: self.brightness,
+                schedules: self.settings.get_device_schedules(self.serial()).await,
+                channel_templates: self
+                    .settings
+                    .get_device_channel_templates(self.serial())
+                    .await,
+                scribble_styles: self
+                    .settings
+                    .get_device_scribble_styles(self.serial())
+                    .await,
+                routing_presets: self
+                    .settings
+                    .get_device_routing_presets(self.serial())
+                    .await,
+                volume_presets: self.settings.get_device_volume_presets(self.serial()).await,
             },
             button_down: button_states,
+            button_held,
             profile_name: self.profile.name().to_owned(),
             mic_profile_name: self.mic_profile.name().to_owned(),
+            snapshots,
         }
     }
 
     pub async fn shutdown(&mut self) {
         debug!("Shutting Down Device: {}", self.hardware.serial_number);
+        self.store_runtime_state().await;
 
         let commands = self
             .settings
@@ -288,6 +623,11 @@ impl<'a> Device<'a> {
     }
 
     pub async fn update_state(&mut self) -> Result<bool> {
+        // While updating firmware, the device shouldn't be asked to do anything else..
+        if let Some(update) = &mut self.firmware_update {
+            return Ok(update.step(&mut self.goxlr).await);
+        }
+
         let mut state_updated = false;
         let mut refresh_colour_map = false;
 
@@ -307,11 +647,15 @@ impl<'a> Device<'a> {
                     let bank = result.bank;
                     let button = result.button;
 
-                    let filename = result.file.file_name().unwrap();
-                    let filename = filename.to_string_lossy().to_string();
+                    // Keep any bank / button folders, so the sample can be found again later..
+                    let sample_path = self.settings.get_samples_directory().await;
+                    let filename = get_sample_track_name(&sample_path, &result.file);
+                    let hash = self.sample_hashes.get_hash(&result.file).ok();
+                    self.sample_hashes.save();
 
                     let track = self.profile.add_sample_file(bank, button, filename);
                     track.normalized_gain = result.gain;
+                    track.hash = hash;
 
                     refresh_colour_map = true;
                 }
@@ -328,24 +672,80 @@ impl<'a> Device<'a> {
                 state_updated = true;
             }
 
+            if let Err(error) = self.play_queued_samples().await {
+                error!("Error Playing Queued Sample: {}", error);
+            }
+
+            self.sync_sample_events();
+
+            if let Err(error) = self.check_recording_limits().await {
+                error!("Error Checking Recording Limits: {}", error);
+            }
+
             if self.sync_sample_lighting().await? && !state_updated {
                 state_updated = true;
             };
 
             if refresh_colour_map {
-                self.load_colour_map()?;
+                self.load_colour_map().await?;
             }
         }
 
+        match self.update_sample_relink().await {
+            Ok(updated) => state_updated = state_updated || updated,
+            Err(error) => error!("Error Relinking Samples: {}", error),
+        }
+
+        match self.update_volume_fades().await {
+            Ok(updated) => state_updated = state_updated || updated,
+            Err(error) => error!("Error Updating Volume Fades: {}", error),
+        }
+
+        if let Err(error) = self.update_ducking().await {
+            error!("Error Updating Ducking: {}", error);
+        }
+
+        match self.update_push_to_talk().await {
+            Ok(updated) => state_updated = state_updated || updated,
+            Err(error) => error!("Error Updating Push to Talk: {}", error),
+        }
+
+        match self.update_voice_activity().await {
+            Ok(updated) => state_updated = state_updated || updated,
+            Err(error) => error!("Error Updating Voice Activity: {}", error),
+        }
+
+        if let Err(error) = self.update_fader_meter().await {
+            error!("Error Updating Fader Meter: {}", error);
+        }
+
+        match self.update_mic_calibration().await {
+            Ok(updated) => state_updated = state_updated || updated,
+            Err(error) => error!("Error Updating Mic Calibration: {}", error),
+        }
+
+        self.announce_volumes().await;
+
+        if self.last_state_snapshot.elapsed() >= STATE_SNAPSHOT_INTERVAL {
+            self.last_state_snapshot = Instant::now();
+            self.store_runtime_state().await;
+        }
+
+        if let Err(error) = self.check_idle().await {
+            error!("Error Updating Idle Lighting: {}", error);
+        }
+
         // Find any buttons that have been held, and action if needed.
         for button in self.last_buttons {
             if !self.button_states[button].hold_handled {
                 let now = self.get_epoch_ms();
-                if (now - self.button_states[button].press_time) > self.hold_time.into() {
+                let hold_time = self.get_button_hold_time(button);
+                if (now - self.button_states[button].press_time) > hold_time.into() {
                     if let Err(error) = self.on_button_hold(button).await {
                         error!("{}", error);
                     }
                     self.button_states[button].hold_handled = true;
+                    state_updated = true;
                 }
             }
         }
@@ -353,8 +753,380 @@ impl<'a> Device<'a> {
         Ok(state_updated)
     }
 
+    // Reads the mic level while calibrating, and handles the result once it's complete.
+    async fn update_mic_calibration(&mut self) -> Result<bool> {
+        if self.mic_calibration.is_none() {
+            return Ok(false);
+        }
+
+        let level = self.goxlr.get_microphone_level().await?;
+        let calibration = match &mut self.mic_calibration {
+            Some(calibration) => calibration,
+            None => return Ok(false),
+        };
+        calibration.add_level(level);
+        if !calibration.is_complete() {
+            return Ok(false);
+        }
+
+        let result = calibration.result();
+        self.mic_calibration = None;
+
+        info!(
+            "Mic Calibration Complete, Peak: {:.1}dB, Average: {:.1}dB, Suggested Gain: {}dB",
+            result.peak_db, result.average_db, result.suggested_gain
+        );
+        if result.applied {
+            self.mic_profile
+                .set_mic_gain(result.mic_type, result.suggested_gain)?;
+            self.apply_mic_gain().await?;
+        }
+
+        let serial = self.serial().to_string();
+        self.events
+            .push(DaemonEvent::MicCalibrationComplete(serial, result.clone()));
+        self.last_mic_calibration = Some(result);
+        Ok(true)
+    }
+
+    async fn update_volume_fades(&mut self) -> Result<bool> {
+        let now = self.get_epoch_ms();
+        let mut updated = false;
+
+        for channel in ChannelName::iter() {
+            let fade = match self.volume_fades[channel] {
+                Some(fade) => fade,
+                None => continue,
+            };
+
+            let elapsed = now - fade.start;
+            let volume = if elapsed >= fade.duration {
+                self.volume_fades[channel] = None;
+                fade.to
+            } else {
+                let progress = elapsed as f32 / fade.duration as f32;
+                let difference = fade.to as f32 - fade.from as f32;
+                (fade.from as f32 + (difference * progress)).round() as u8
+            };
+
+            if volume != self.profile.get_channel_volume(channel) {
+                self.set_channel_volume(channel, volume).await?;
+                updated = true;
+            }
+        }
+        Ok(updated)
+    }
+
+    async fn set_channel_volume(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
+        // While submixes are active the Mic Monitor is held at 100% (see load_submix_settings),
+        // so only store the level, it'll be applied when submixes are turned off.
+        let submix_active = self.device_supports_submixes() && self.profile.is_submix_enabled();
+        if channel != ChannelName::MicMonitor || !submix_active {
+            self.goxlr.set_volume(channel, volume).await?;
+        }
+        self.profile.set_channel_volume(channel, volume)?;
+
+        // If this channel is being ducked, the duck needs reapplying on the next update.
+        self.ducking_state.applied[channel] = None;
+        self.ducking_state.base[channel] = None;
+
+        // Update the Submix when volume changes via IPC
+        self.update_submix_for(channel, volume).await?;
+
+        if let Some(fader) = self.profile.get_fader_from_channel(channel) {
+            self.fader_pause_until[fader].paused = true;
+            self.fader_pause_until[fader].until = volume;
+        }
+        Ok(())
+    }
+
+    async fn update_ducking(&mut self) -> Result<()> {
+        let now = self.get_epoch_ms();
+        let elapsed = now - self.ducking_state.last_update;
+        self.ducking_state.last_update = now;
+
+        if !self.ducking.enabled && self.ducking_state.level == 0. {
+            return Ok(());
+        }
+
+        // We only duck if the mic is actually audible, and loud enough..
+        let mic_muted = self.mic_muted_by_cough() || self.mic_muted_by_fader();
+        if self.ducking.enabled
+            && !mic_muted
+            && self.goxlr.get_microphone_level().await? >= self.ducking.threshold
+        {
+            self.ducking_state.last_active = now;
+        }
+
+        let active =
+            self.ducking.enabled && now - self.ducking_state.last_active < self.ducking.hold.into();
+
+        // Move the level towards where it should be, based on the attack / release times.
+        let level = self.ducking_state.level;
+        let (target, time) = if active {
+            (1., self.ducking.attack)
+        } else {
+            (0., self.ducking.release)
+        };
+
+        let step = if time == 0 {
+            1.
+        } else {
+            elapsed as f32 / time as f32
+        };
+
+        let new_level = if target > level {
+            (level + step).min(target)
+        } else {
+            (level - step).max(target)
+        };
+        self.ducking_state.level = new_level;
+
+        // Work out the volumes, and only send them if something has changed..
+        let reduction = 1. - ((self.ducking.amount.min(100) as f32 / 100.) * new_level);
+        for channel in self.ducking.channels.clone() {
+            // Nothing's been ducked, and nothing needs to be, so leave the channel alone.
+            if new_level == 0. && self.ducking_state.applied[channel].is_none() {
+                continue;
+            }
+
+            let base = match self.ducking_state.base[channel] {
+                Some(base) => base,
+                None => {
+                    let volume = self.profile.get_channel_volume(channel);
+                    self.ducking_state.base[channel] = Some(volume);
+                    volume
+                }
+            };
+
+            let ducked = (base as f32 * reduction).round() as u8;
+            if self.ducking_state.applied[channel] != Some(ducked) {
+                self.set_ducked_volume(channel, ducked).await?;
+                self.ducking_state.applied[channel] = Some(ducked);
+            }
+
+            if new_level == 0. {
+                // The original volume has been restored, reset for the next duck.
+                self.ducking_state.applied[channel] = None;
+                self.ducking_state.base[channel] = None;
+            }
+        }
+        Ok(())
+    }
+
+    async fn set_ducked_volume(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
+        self.goxlr.set_volume(channel, volume).await?;
+
+        // On the Full the fader will physically move, make sure that isn't seen as a human
+        // moving it (see update_volumes_to), otherwise the ducked level ends up in the profile.
+        if let Some(fader) = self.profile.get_fader_from_channel(channel) {
+            self.fader_pause_until[fader].paused = true;
+            self.fader_pause_until[fader].until = volume;
+        }
+        Ok(())
+    }
+
+    // Returns true if the fader is sat at the level ducking has moved it to.
+    fn is_fader_ducked_to(&self, channel: ChannelName, volume: u8) -> bool {
+        match self.ducking_state.applied[channel] {
+            Some(ducked) => volume.abs_diff(ducked) <= 5,
+            None => false,
+        }
+    }
+
+    async fn set_ducking(&mut self, ducking: Ducking) -> Result<()> {
+        if ducking.amount > 100 {
+            bail!("Ducking amount must be a percentage");
+        }
+
+        // Restore the volumes of any channels which are no longer being ducked..
+        for channel in self.ducking.channels.clone() {
+            if !ducking.channels.contains(&channel) {
+                if self.ducking_state.applied[channel].is_some() {
+                    let volume = self.profile.get_channel_volume(channel);
+                    self.set_ducked_volume(channel, volume).await?;
+                }
+                self.ducking_state.applied[channel] = None;
+                self.ducking_state.base[channel] = None;
+            }
+        }
+
+        self.ducking = ducking.clone();
+        self.settings
+            .set_device_ducking(self.serial(), ducking)
+            .await;
+        self.settings.save().await;
+        Ok(())
+    }
+
+    // Tracks whether the mic is audible and picking up sound, returns true if that has changed.
+    async fn update_voice_activity(&mut self) -> Result<bool> {
+        let active = if self.voice_activity.enabled {
+            let now = self.get_epoch_ms();
+            let muted = self.mic_muted_by_cough()
+                || self.mic_muted_by_fader()
+                || self.is_push_to_talk_closed();
+
+            if !muted && self.goxlr.get_microphone_level().await? >= self.voice_activity.threshold {
+                self.mic_last_active = now;
+            }
+            !muted && now - self.mic_last_active < self.voice_activity.hold.into()
+        } else {
+            false
+        };
+
+        if active == self.mic_active {
+            return Ok(false);
+        }
+
+        self.mic_active = active;
+        self.events
+            .push(DaemonEvent::MicActivity(self.serial().to_owned(), active));
+        Ok(true)
+    }
+
+    // Samples the mic level at the meter's refresh rate, and redraws the fader LEDs when the
+    // displayed level changes.
+    async fn update_fader_meter(&mut self) -> Result<()> {
+        if !self.fader_meter.enabled {
+            return Ok(());
+        }
+
+        let refresh_rate = Duration::from_millis(self.fader_meter.refresh_rate.into());
+        if self.fader_meter_updated.elapsed() < refresh_rate {
+            return Ok(());
+        }
+        self.fader_meter_updated = Instant::now();
+
+        let db = level_to_db(self.goxlr.get_microphone_level().await?);
+        let level = ((db - FADER_METER_FLOOR) / -FADER_METER_FLOOR * FADER_METER_STEPS as f64)
+            .round()
+            .clamp(0., FADER_METER_STEPS as f64) as u8;
+
+        if self.fader_meter_level == Some(level) {
+            return Ok(());
+        }
+        self.fader_meter_level = Some(level);
+
+        // Only the mic level is known, so there's nothing to redraw unless it's on a fader..
+        if FaderName::iter()
+            .any(|fader| self.profile.get_fader_assignment(fader) == ChannelName::Mic)
+        {
+            self.load_colour_map().await?;
+        }
+        Ok(())
+    }
+
+    async fn set_fader_meter(&mut self, fader_meter: FaderMeter) -> Result<()> {
+        if !(MIN_FADER_METER_REFRESH..=MAX_FADER_METER_REFRESH).contains(&fader_meter.refresh_rate)
+        {
+            bail!(
+                "Refresh rate must be between {}ms and {}ms",
+                MIN_FADER_METER_REFRESH,
+                MAX_FADER_METER_REFRESH
+            );
+        }
+
+        self.fader_meter = fader_meter.clone();
+        self.fader_meter_level = None;
+        self.settings
+            .set_device_fader_meter(self.serial(), fader_meter)
+            .await;
+        self.settings.save().await;
+
+        // Put the fader colours back to normal, the meter will take over on the next update..
+        self.load_colour_map().await
+    }
+
+    async fn set_voice_activity(&mut self, voice_activity: VoiceActivity) -> Result<()> {
+        self.voice_activity = voice_activity.clone();
+        self.settings
+            .set_device_voice_activity(self.serial(), voice_activity)
+            .await;
+        self.settings.save().await;
+        Ok(())
+    }
+
+    // While push to talk is enabled, the mic is only open when the trigger is held, or while
+    // the release delay is running (so the end of a sentence doesn't get cut off).
+    fn is_push_to_talk_closed(&self) -> bool {
+        self.push_to_talk.enabled
+            && !self.push_to_talk_active
+            && self.push_to_talk_release.is_none()
+    }
+
+    fn is_push_to_talk_button(&self, button: Buttons) -> bool {
+        self.push_to_talk.enabled
+            && self.push_to_talk.button == Some(usb_to_standard_button(button))
+    }
+
+    async fn set_channel_state(&mut self, channel: ChannelName, state: ChannelState) -> Result<()> {
+        let state = if channel == ChannelName::Mic && self.is_push_to_talk_closed() {
+            Muted
+        } else {
+            state
+        };
+        self.goxlr.set_channel_state(channel, state).await
+    }
+
+    async fn set_push_to_talk(&mut self, push_to_talk: PushToTalk) -> Result<()> {
+        self.push_to_talk = push_to_talk.clone();
+        self.push_to_talk_active = false;
+        self.push_to_talk_release = None;
+
+        self.settings
+            .set_device_push_to_talk(self.serial(), push_to_talk)
+            .await;
+        self.settings.save().await;
+
+        self.apply_push_to_talk_state().await
+    }
+
+    async fn set_push_to_talk_active(&mut self, active: bool) -> Result<()> {
+        if !self.push_to_talk.enabled {
+            bail!("Push to Talk is not enabled");
+        }
+
+        if active {
+            // Pressing again during the release delay simply keeps the mic open..
+            self.push_to_talk_release = None;
+            if !self.push_to_talk_active {
+                self.push_to_talk_active = true;
+                self.apply_push_to_talk_state().await?;
+            }
+        } else if self.push_to_talk_active {
+            self.push_to_talk_active = false;
+            let delay = Duration::from_millis(self.push_to_talk.release_delay.into());
+            self.push_to_talk_release = Some(Instant::now() + delay);
+            self.update_push_to_talk().await?;
+        }
+        Ok(())
+    }
+
+    // Closes the mic once the release delay has passed, returns true if it was closed.
+    async fn update_push_to_talk(&mut self) -> Result<bool> {
+        if let Some(release) = self.push_to_talk_release {
+            if Instant::now() >= release {
+                self.push_to_talk_release = None;
+                self.apply_push_to_talk_state().await?;
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    async fn apply_push_to_talk_state(&mut self) -> Result<()> {
+        // When opening, the mic still needs to respect any mutes set in the profile..
+        let state = if self.mic_muted_by_cough() || self.mic_muted_by_fader() {
+            Muted
+        } else {
+            Unmuted
+        };
+        self.set_channel_state(ChannelName::Mic, state).await
+    }
+
     pub async fn monitor_inputs(&mut self) -> Result<bool> {
-        let state = self.goxlr.get_button_states()?;
+        let state = self.goxlr.get_button_states().await?;
         let mut changed = self.update_volumes_to(state.volumes).await?;
         let result = self.update_encoders_to(state.encoders).await?;
         if !changed {
@@ -362,6 +1134,11 @@ impl<'a> Device<'a> {
             changed = result;
         }
 
+        // If any pressed or released buttons have a hold style, the states will need refreshing..
+        let refresh_hold_states = (state.pressed ^ self.last_buttons)
+            .iter()
+            .any(|button| self.profile.get_button_hold_state(button).is_some());
+
         let pressed_buttons = state.pressed.difference(self.last_buttons);
         for button in pressed_buttons {
             // This is a new press, store it in the states..
@@ -369,12 +1146,27 @@ impl<'a> Device<'a> {
                 press_time: self.get_epoch_ms(),
                 hold_handled: false,
             };
+            self.events.push(DaemonEvent::ButtonDown(
+                self.serial().to_owned(),
+                usb_to_standard_button(button),
+            ));
+            changed = true;
 
-            if let Err(error) = self.on_button_down(button).await {
-                error!("{}", error);
+            // The push to talk button replaces the button's normal behaviour, including holds..
+            if self.is_push_to_talk_button(button) {
+                self.button_states[button].hold_handled = true;
+                if let Err(error) = self.set_push_to_talk_active(true).await {
+                    error!("{}", error);
+                }
+                continue;
             }
 
-            changed = true;
+            // Buttons with a macro assigned are handled on hold or release..
+            if !self.has_button_macro(button).await {
+                if let Err(error) = self.on_button_down(button).await {
+                    error!("{}", error);
+                }
+            }
         }
 
         let released_buttons = self.last_buttons.difference(state.pressed);
@@ -382,7 +1174,12 @@ impl<'a> Device<'a> {
             let button_state = self.button_states[button];
 
             // Output errors, but don't throw them up the stack!
-            if let Err(error) = self.on_button_up(button, &button_state).await {
+            let result = if self.is_push_to_talk_button(button) {
+                self.set_push_to_talk_active(false).await
+            } else {
+                self.on_button_up(button, &button_state).await
+            };
+            if let Err(error) = result {
                 error!("{}", error);
             }
 
@@ -390,14 +1187,256 @@ impl<'a> Device<'a> {
                 press_time: 0,
                 hold_handled: false,
             };
+            self.events.push(DaemonEvent::ButtonUp(
+                self.serial().to_owned(),
+                usb_to_standard_button(button),
+            ));
 
             changed = true;
         }
 
         self.last_buttons = state.pressed;
+        if refresh_hold_states {
+            self.update_button_states().await?;
+        }
+
+        if changed {
+            self.last_interaction = self.get_epoch_ms();
+            if self.is_idle {
+                debug!("Device Interaction, restoring lighting..");
+                self.is_idle = false;
+                self.load_colour_map().await?;
+                self.load_animation(true).await?;
+            }
+        }
         Ok(changed)
     }
 
+    // Compares the active samples against the last check, and raises events for any playback or
+    // recording which has started or stopped since. Progress for running streams is sent at most
+    // every SAMPLE_PROGRESS_INTERVAL, recording levels are sent on every check to drive meters.
+    fn sync_sample_events(&mut self) {
+        let audio_handler = match &self.audio_handler {
+            Some(audio_handler) => audio_handler,
+            None => return,
+        };
+
+        let send_progress = self.last_sample_progress.elapsed() >= SAMPLE_PROGRESS_INTERVAL;
+        if send_progress {
+            self.last_sample_progress = Instant::now();
+        }
+
+        for bank in SampleBank::iter() {
+            for button in SampleButtons::iter() {
+                if let Some(level) = audio_handler.get_recording_level(bank, button) {
+                    let serial = self.serial().to_string();
+                    self.events.push(DaemonEvent::SampleRecordingLevel(
+                        serial, bank, button, level,
+                    ));
+                }
+
+                let active = audio_handler.get_active_sample(bank, button);
+                if active.is_some() == self.active_samples[bank][button] {
+                    if send_progress {
+                        if let Some(progress) = audio_handler.get_sample_progress(bank, button) {
+                            let serial = self.serial().to_string();
+                            self.events
+                                .push(DaemonEvent::SampleProgress(serial, bank, button, progress));
+                        }
+                    }
+                    continue;
+                }
+
+                self.active_samples[bank][button] = active.is_some();
+                let serial = self.serial().to_string();
+                self.events.push(match active {
+                    Some(active) => DaemonEvent::SampleStarted(serial, bank, button, active),
+                    None => DaemonEvent::SampleStopped(serial, bank, button),
+                });
+            }
+        }
+    }
+
+    // Returns any button events raised since the last call, for sending to subscribed clients.
+    pub fn take_events(&mut self) -> Vec<DaemonEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn button_states(&self) -> InputStates {
+        InputStates {
+            pressed: self
+                .last_buttons
+                .iter()
+                .map(usb_to_standard_button)
+                .collect(),
+            encoders: EncoderPositions {
+                pitch: self.profile.get_pitch_encoder_position(),
+                gender: self.profile.get_gender_value(),
+                reverb: self.profile.get_reverb_value(),
+                echo: self.profile.get_echo_value(),
+            },
+        }
+    }
+
+    // Runs a set of read-only queries against the device, for diagnosing problems.
+    pub async fn run_diagnostics(&mut self) -> DiagnosticReport {
+        let mut checks = vec![];
+
+        let (passed, detail) = match self.goxlr.get_firmware_version().await {
+            Ok(versions) => (
+                true,
+                format!("Firmware {}, DICE {}", versions.firmware, versions.dice),
+            ),
+            Err(error) => (false, error.to_string()),
+        };
+        checks.push(diagnostic_check("Firmware Version", passed, detail));
+
+        let (passed, detail) = match self.goxlr.get_serial_number().await {
+            Ok((serial, date)) => (
+                serial == self.hardware.serial_number,
+                format!("{} (Manufactured {})", serial, date),
+            ),
+            Err(error) => (false, error.to_string()),
+        };
+        checks.push(diagnostic_check("Serial Number", passed, detail));
+
+        let categories = self.goxlr.get_supported_dcp_categories().await;
+        checks.push(diagnostic_check(
+            "DCP Categories",
+            !categories.is_empty(),
+            format!("{:?}", categories),
+        ));
+
+        let (passed, detail) = match self.goxlr.get_button_states().await {
+            Ok(state) => (
+                true,
+                format!(
+                    "Faders {:?}, Encoders {:?}, {} Buttons Pressed",
+                    state.volumes,
+                    state.encoders,
+                    state.pressed.len()
+                ),
+            ),
+            Err(error) => (false, error.to_string()),
+        };
+        checks.push(diagnostic_check("Status Report", passed, detail));
+
+        let (passed, detail) = match self.goxlr.get_microphone_level().await {
+            Ok(level) => (true, format!("{}", level)),
+            Err(error) => (false, error.to_string()),
+        };
+        checks.push(diagnostic_check("Microphone Level", passed, detail));
+
+        match &self.audio_handler {
+            Some(audio_handler) => {
+                let (output, input) = audio_handler.find_sampler_devices();
+                for (name, device) in [("Sampler Output", output), ("Sampler Input", input)] {
+                    let passed = device.is_some();
+                    let detail = device.unwrap_or_else(|| String::from("Not Found"));
+                    checks.push(diagnostic_check(name, passed, detail));
+                }
+            }
+            None => checks.push(diagnostic_check(
+                "Sampler Audio",
+                true,
+                String::from("Sampler Audio is not in use for this device"),
+            )),
+        }
+
+        DiagnosticReport {
+            serial: self.hardware.serial_number.clone(),
+            checks,
+        }
+    }
+
+    async fn check_idle(&mut self) -> Result<()> {
+        if self.is_idle || self.idle_lighting.timeout == 0 {
+            return Ok(());
+        }
+
+        let now = self.get_epoch_ms();
+        if self.last_interaction == 0 {
+            // First run, start counting from here..
+            self.last_interaction = now;
+            return Ok(());
+        }
+
+        let timeout = u128::from(self.idle_lighting.timeout) * 60 * 1000;
+        if now - self.last_interaction < timeout {
+            return Ok(());
+        }
+
+        debug!("Device Idle, changing lighting..");
+        self.is_idle = true;
+        self.apply_idle_lighting().await
+    }
+
+    async fn apply_idle_lighting(&mut self) -> Result<()> {
+        let supports_animations = self.device_supports_animations();
+        if self.idle_lighting.behaviour == IdleBehaviour::Rainbow && supports_animations {
+            self.goxlr
+                .set_animation_mode(
+                    true,
+                    AnimationMode::RainbowDark,
+                    self.profile.get_animation_mod1(),
+                    self.profile.get_animation_mod2(),
+                    WaterFallDir::Off,
+                )
+                .await?;
+            return Ok(());
+        }
+
+        // Make sure an existing animation doesn't override the dimmed colours..
+        if supports_animations {
+            self.goxlr
+                .set_animation_mode(false, AnimationMode::None, 0, 0, WaterFallDir::Off)
+                .await?;
+        }
+        self.load_colour_map().await
+    }
+
+    async fn set_idle_lighting(&mut self, idle: IdleLighting) -> Result<()> {
+        if idle.brightness > 100 {
+            bail!("Idle Brightness must be a percentage");
+        }
+
+        self.idle_lighting = idle.clone();
+        if self.is_idle {
+            // Reapply the lighting with the new settings..
+            self.is_idle = false;
+            self.load_colour_map().await?;
+            self.load_animation(true).await?;
+        }
+        self.last_interaction = self.get_epoch_ms();
+
+        self.settings
+            .set_device_idle_lighting(self.serial(), idle)
+            .await;
+        self.settings.save().await;
+        Ok(())
+    }
+
+    // Returns the percentage brightness the colour map should be sent at.
+    fn get_lighting_brightness(&self) -> u8 {
+        if !self.is_idle {
+            return self.brightness;
+        }
+
+        let idle_brightness = match self.idle_lighting.behaviour {
+            IdleBehaviour::Off => 0,
+            IdleBehaviour::Dim | IdleBehaviour::Rainbow => self.idle_lighting.brightness,
+        };
+        (self.brightness as u16 * idle_brightness as u16 / 100) as u8
+    }
+
+    fn get_button_hold_time(&self, button: Buttons) -> u16 {
+        let button = usb_to_standard_button(button);
+        match self.button_hold_times.get(&button) {
+            Some(hold_time) => *hold_time,
+            None => self.hold_time,
+        }
+    }
+
     async fn on_button_down(&mut self, button: Buttons) -> Result<()> {
         debug!("Handling Button Down: {:?}", button);
 
@@ -426,13 +1465,21 @@ impl<'a> Device<'a> {
             }
             _ => {}
         }
-        self.update_button_states()?;
+        self.update_button_states().await?;
         Ok(())
     }
 
     async fn on_button_hold(&mut self, button: Buttons) -> Result<()> {
         debug!("Handling Button Hold: {:?}", button);
 
+        let standard_button = usb_to_standard_button(button);
+        if let Some(button_macro) = self.get_button_macro(standard_button).await {
+            if let Some(name) = button_macro.hold {
+                self.run_macro(&name).await?;
+            }
+            return Ok(());
+        }
+
         // Fader mute buttons maintain their own state check, so it can be programmatically called.
         match button {
             Buttons::Fader1Mute => {
@@ -456,7 +1503,7 @@ impl<'a> Device<'a> {
             }
             _ => {}
         }
-        self.update_button_states()?;
+        self.update_button_states().await?;
         Ok(())
     }
 
@@ -465,6 +1512,17 @@ impl<'a> Device<'a> {
             "Handling Button Release: {:?}, Has Long Press Handled: {:?}",
             button, state.hold_handled
         );
+
+        let standard_button = usb_to_standard_button(button);
+        if let Some(button_macro) = self.get_button_macro(standard_button).await {
+            // If there's no hold macro, the tap macro is run regardless of how long we were held.
+            if !state.hold_handled || button_macro.hold.is_none() {
+                if let Some(name) = button_macro.tap {
+                    self.run_macro(&name).await?;
+                }
+            }
+            return Ok(());
+        }
         match button {
             Buttons::Fader1Mute => {
                 if !state.hold_handled {
@@ -535,15 +1593,15 @@ impl<'a> Device<'a> {
 
             Buttons::SamplerSelectA => {
                 self.load_sample_bank(SampleBank::A).await?;
-                self.load_colour_map()?;
+                self.load_colour_map().await?;
             }
             Buttons::SamplerSelectB => {
                 self.load_sample_bank(SampleBank::B).await?;
-                self.load_colour_map()?;
+                self.load_colour_map().await?;
             }
             Buttons::SamplerSelectC => {
                 self.load_sample_bank(SampleBank::C).await?;
-                self.load_colour_map()?;
+                self.load_colour_map().await?;
             }
 
             Buttons::SamplerBottomLeft => {
@@ -563,10 +1621,13 @@ impl<'a> Device<'a> {
                     .await?;
             }
             Buttons::SamplerClear => {
-                self.handle_sample_clear().await?;
+                // If Clear was held to erase a button, it shouldn't also toggle the clear mode.
+                if !state.hold_handled {
+                    self.handle_sample_clear().await?;
+                }
             }
         }
-        self.update_button_states()?;
+        self.update_button_states().await?;
         Ok(())
     }
 
@@ -633,13 +1694,13 @@ impl<'a> Device<'a> {
 
             if mute_function == MuteFunction::All {
                 // In this scenario, we should just set cough_button_on and mute the channel.
-                self.goxlr.set_channel_state(ChannelName::Mic, Muted)?;
+                self.set_channel_state(ChannelName::Mic, Muted).await?;
             }
 
             let message = format!("Mic Muted{}", target);
             let _ = self.global_events.send(TTSMessage(message)).await;
 
-            self.apply_routing(BasicInputDevice::Microphone)?;
+            self.apply_routing(BasicInputDevice::Microphone).await?;
             return Ok(());
         }
 
@@ -657,8 +1718,8 @@ impl<'a> Device<'a> {
             let message = "Mic Muted".to_string();
             let _ = self.global_events.send(TTSMessage(message)).await;
 
-            self.goxlr.set_channel_state(ChannelName::Mic, Muted)?;
-            self.apply_routing(BasicInputDevice::Microphone)?;
+            self.set_channel_state(ChannelName::Mic, Muted).await?;
+            self.apply_routing(BasicInputDevice::Microphone).await?;
             return Ok(());
         }
 
@@ -676,12 +1737,12 @@ impl<'a> Device<'a> {
                     if (muted_to_all || (muted_to_x && mute_function == MuteFunction::All))
                         && !self.mic_muted_by_fader()
                     {
-                        self.goxlr.set_channel_state(ChannelName::Mic, Unmuted)?;
+                        self.set_channel_state(ChannelName::Mic, Unmuted).await?;
                     }
 
                     let message = "Mic Unmuted".to_string();
                     let _ = self.global_events.send(TTSMessage(message)).await;
-                    self.apply_routing(BasicInputDevice::Microphone)?;
+                    self.apply_routing(BasicInputDevice::Microphone).await?;
                     return Ok(());
                 }
 
@@ -689,30 +1750,121 @@ impl<'a> Device<'a> {
                 self.profile.set_mute_chat_button_on(true);
 
                 if mute_function == MuteFunction::All {
-                    self.goxlr.set_channel_state(ChannelName::Mic, Muted)?;
+                    self.set_channel_state(ChannelName::Mic, Muted).await?;
                 }
 
                 let message = format!("Mic Muted{}", target);
                 let _ = self.global_events.send(TTSMessage(message)).await;
 
-                // Update the transient routing..
-                self.apply_routing(BasicInputDevice::Microphone)?;
-                return Ok(());
-            }
+                // Update the transient routing..
+                self.apply_routing(BasicInputDevice::Microphone).await?;
+                return Ok(());
+            }
+
+            self.profile.set_mute_chat_button_on(false);
+            if mute_function == MuteFunction::All && !self.mic_muted_by_fader() {
+                self.set_channel_state(ChannelName::Mic, Unmuted).await?;
+            }
+
+            let message = "Mic Unmuted".to_string();
+            let _ = self.global_events.send(TTSMessage(message)).await;
+
+            // Disable button and refresh transient routing
+            self.apply_routing(BasicInputDevice::Microphone).await?;
+            return Ok(());
+        }
+
+        Ok(())
+    }
+
+    async fn set_cough_mute_state(&mut self, state: MuteState) -> Result<()> {
+        // This is the API equivalent of pressing the cough button, we don't care about hold or
+        // toggle behaviours here, just move directly to the requested state.
+        let (_, muted_to_x, muted_to_all, mute_function) =
+            self.profile.get_mute_chat_button_state();
+
+        let target = tts_target(mute_function);
+        match state {
+            MuteState::Unmuted => {
+                if !muted_to_x && !muted_to_all {
+                    return Ok(());
+                }
+
+                self.profile.set_mute_chat_button_on(false);
+                self.profile.set_mute_chat_button_blink(false);
+
+                if (muted_to_all || mute_function == MuteFunction::All)
+                    && !self.mic_muted_by_fader()
+                {
+                    self.set_channel_state(ChannelName::Mic, Unmuted).await?;
+                }
+
+                let message = "Mic Unmuted".to_string();
+                let _ = self.global_events.send(TTSMessage(message)).await;
+            }
+            MuteState::MutedToX => {
+                if muted_to_all {
+                    bail!("Unable to Transition from MutedToAll to MutedToX");
+                }
+                if muted_to_x {
+                    return Ok(());
+                }
+
+                self.profile.set_mute_chat_button_on(true);
+                if mute_function == MuteFunction::All {
+                    self.set_channel_state(ChannelName::Mic, Muted).await?;
+                }
+
+                let message = format!("Mic Muted{}", target);
+                let _ = self.global_events.send(TTSMessage(message)).await;
+            }
+            MuteState::MutedToAll => {
+                if muted_to_all {
+                    return Ok(());
+                }
+
+                self.profile.set_mute_chat_button_on(true);
+                self.profile.set_mute_chat_button_blink(true);
+                self.set_channel_state(ChannelName::Mic, Muted).await?;
+
+                let message = "Mic Muted".to_string();
+                let _ = self.global_events.send(TTSMessage(message)).await;
+            }
+        }
+
+        // Refresh the transient routing, and the button lighting..
+        self.apply_routing(BasicInputDevice::Microphone).await?;
+        self.update_button_states().await?;
+        Ok(())
+    }
+
+    async fn get_button_macro(&self, button: Button) -> Option<ButtonMacro> {
+        self.settings
+            .get_device_button_macro(self.serial(), button)
+            .await
+    }
+
+    async fn has_button_macro(&self, button: Buttons) -> bool {
+        let button = usb_to_standard_button(button);
+        self.get_button_macro(button).await.is_some()
+    }
+
+    async fn run_macro(&mut self, name: &str) -> Result<()> {
+        let Some(commands) = self.settings.get_device_macro(self.serial(), name).await else {
+            bail!("Macro {} not found", name);
+        };
 
-            self.profile.set_mute_chat_button_on(false);
-            if mute_function == MuteFunction::All && !self.mic_muted_by_fader() {
-                self.goxlr.set_channel_state(ChannelName::Mic, Unmuted)?;
+        debug!("Running Macro: {}", name);
+        for command in commands {
+            if let GoXLRCommand::RunMacro(_) = command {
+                // Prevent a macro from (potentially infinitely) calling other macros..
+                warn!("Macros cannot run other Macros, skipping..");
+                continue;
             }
 
-            let message = "Mic Unmuted".to_string();
-            let _ = self.global_events.send(TTSMessage(message)).await;
-
-            // Disable button and refresh transient routing
-            self.apply_routing(BasicInputDevice::Microphone)?;
-            return Ok(());
+            // perform_command is what called us, so we need to box the recursion..
+            Box::pin(self.perform_command(command)).await?;
         }
-
         Ok(())
     }
 
@@ -746,9 +1898,9 @@ impl<'a> Device<'a> {
         let input = self.get_basic_input_from_channel(channel);
         self.profile.set_mute_button_on(fader, true)?;
         if input.is_some() {
-            self.apply_routing(input.unwrap())?;
+            self.apply_routing(input.unwrap()).await?;
         }
-        self.update_button_states()?;
+        self.update_button_states().await?;
         Ok(())
     }
 
@@ -765,8 +1917,8 @@ impl<'a> Device<'a> {
         if !(muted_to_x && mute_function == MuteFunction::All) {
             let volume = self.profile.get_channel_volume(channel);
             self.profile.set_mute_previous_volume(fader, volume)?;
-            self.goxlr.set_volume(channel, 0)?;
-            self.goxlr.set_channel_state(channel, Muted)?;
+            self.goxlr.set_volume(channel, 0).await?;
+            self.set_channel_state(channel, Muted).await?;
             self.profile.set_mute_button_on(fader, true)?;
         }
 
@@ -781,14 +1933,14 @@ impl<'a> Device<'a> {
 
         // If we're Chat, we may need to transiently route the Microphone..
         if channel == ChannelName::Chat {
-            self.apply_routing(BasicInputDevice::Microphone)?;
+            self.apply_routing(BasicInputDevice::Microphone).await?;
         }
 
         if channel == ChannelName::Mic {
-            self.apply_routing(BasicInputDevice::Microphone)?;
+            self.apply_routing(BasicInputDevice::Microphone).await?;
         }
 
-        self.update_button_states()?;
+        self.update_button_states().await?;
         Ok(())
     }
 
@@ -812,33 +1964,33 @@ impl<'a> Device<'a> {
             if channel != ChannelName::Mic
                 || (channel == ChannelName::Mic && !self.mic_muted_by_cough())
             {
-                self.goxlr.set_channel_state(channel, Unmuted)?;
+                self.set_channel_state(channel, Unmuted).await?;
             }
 
-            self.goxlr.set_volume(channel, previous_volume)?;
+            self.goxlr.set_volume(channel, previous_volume).await?;
             self.profile.set_channel_volume(channel, previous_volume)?;
 
             // As before, we might need transient Mic Routing..
             if channel == ChannelName::Chat {
-                self.apply_routing(BasicInputDevice::Microphone)?;
+                self.apply_routing(BasicInputDevice::Microphone).await?;
             }
 
             if channel == ChannelName::Mic {
-                self.apply_routing(BasicInputDevice::Microphone)?;
+                self.apply_routing(BasicInputDevice::Microphone).await?;
             }
         }
 
         // Always do a Transient Routing update, just in case we went from Mute to X -> Mute to All
         let input = self.get_basic_input_from_channel(channel);
         if mute_function != MuteFunction::All && input.is_some() {
-            self.apply_routing(input.unwrap())?;
+            self.apply_routing(input.unwrap()).await?;
         }
 
         let name = self.profile.get_fader_assignment(fader);
         let message = format!("{} unmuted", name);
         let _ = self.global_events.send(TTSMessage(message)).await;
 
-        self.update_button_states()?;
+        self.update_button_states().await?;
         Ok(())
     }
 
@@ -880,24 +2032,221 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
+    // Missing samples are left in the profile (the file may come back, or be relocated by the
+    // user), but are flagged in the status and skipped during playback.
     pub async fn validate_sampler(&mut self) -> Result<()> {
+        let missing = self.find_missing_samples().await;
+        for sample in &missing {
+            if !self.missing_samples.contains(sample) {
+                self.sample_missing(sample.bank, sample.button, sample.track.clone());
+            }
+        }
+        self.missing_samples = missing;
+        Ok(())
+    }
+
+    // Looks for moved samples (and hashes any which haven't been yet) in the background, this
+    // happens when a profile is loaded, or when requested. The results are picked up by
+    // update_state once it's finished.
+    async fn relink_samples(&mut self) {
+        let mut tracks = vec![];
+        for bank in SampleBank::iter() {
+            for button in SampleButtons::iter() {
+                for track in self.profile.get_sample_bank(bank, button).iter() {
+                    tracks.push(RelinkTrack {
+                        bank,
+                        button,
+                        track: track.track.clone(),
+                        hash: track.hash.clone(),
+                    });
+                }
+            }
+        }
+
+        // Anything still running was for an older profile, so it's no longer needed
+        if let Some(task) = self.sample_relink.take() {
+            task.abort();
+        }
+        if tracks.is_empty() {
+            return;
+        }
+
+        let sample_path = self.settings.get_samples_directory().await;
+        let hashes = self.sample_hashes.clone();
+        self.sample_relink = Some(tokio::task::spawn_blocking(move || {
+            relink_tracks(hashes, &sample_path, tracks)
+        }));
+    }
+
+    // Picks up the results of a finished relink, returns true if any tracks were changed.
+    async fn update_sample_relink(&mut self) -> Result<bool> {
+        let finished = match &self.sample_relink {
+            Some(task) => task.is_finished(),
+            None => false,
+        };
+        if !finished {
+            return Ok(false);
+        }
+
+        let task = match self.sample_relink.take() {
+            Some(task) => task,
+            None => return Ok(false),
+        };
+        let (hashes, results) = task.await?;
+        self.sample_hashes.merge(hashes);
+
+        if !self.apply_relink(results) {
+            return Ok(false);
+        }
+        self.validate_sampler().await?;
+        Ok(true)
+    }
+
+    fn apply_relink(&mut self, results: Vec<RelinkResult>) -> bool {
+        let mut changed = false;
+        for result in results {
+            let bank = self.profile.get_sample_bank(result.bank, result.button);
+            if let Some(track) = bank.iter_mut().find(|track| track.track == result.from) {
+                track.track = result.track;
+                track.hash = result.hash;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn sample_missing(&mut self, bank: SampleBank, button: SampleButtons, track: String) {
+        warn!("Sample {} on {} {} could not be found", track, bank, button);
+
+        let serial = self.serial().to_string();
+        self.events.push(DaemonEvent::SampleMissing(
+            serial,
+            bank,
+            button,
+            track.clone(),
+        ));
+
+        let sample = MissingSample {
+            bank,
+            button,
+            track,
+        };
+        if !self.missing_samples.contains(&sample) {
+            self.missing_samples.push(sample);
+        }
+    }
+
+    // Returns the next track on a button which can be found, skipping over any which are missing.
+    async fn get_next_available_track(
+        &mut self,
+        bank: SampleBank,
+        button: SampleButtons,
+    ) -> Result<Option<AudioFile>> {
+        let sample_path = self.settings.get_samples_directory().await;
+        let count = self.profile.get_sample_bank(bank, button).len();
+
+        for _ in 0..count {
+            let audio = self.profile.get_track_by_bank_button(bank, button)?;
+            let track = audio.file.to_string_lossy().to_string();
+            if find_sample_file(&sample_path, bank, button, &track).is_some() {
+                return Ok(Some(audio));
+            }
+            self.sample_missing(bank, button, track);
+        }
+        Ok(None)
+    }
+
+    // Returns any tracks in the profile which can't be found under the samples directory.
+    pub async fn find_missing_samples(&mut self) -> Vec<MissingSample> {
         let sample_path = self.settings.get_samples_directory().await;
+
+        let mut missing = vec![];
         for bank in SampleBank::iter() {
             for button in SampleButtons::iter() {
-                let tracks = self.profile.get_sample_bank(bank, button);
-                tracks.retain(|track| {
-                    let file = PathBuf::from(track.track.clone());
+                for track in self.profile.get_sample_bank(bank, button).iter() {
+                    if find_sample_file(&sample_path, bank, button, &track.track).is_none() {
+                        missing.push(MissingSample {
+                            bank,
+                            button,
+                            track: track.track.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        missing
+    }
 
-                    // Simply, if this returns None, the file isn't present.
-                    find_file_in_path(sample_path.clone(), file).is_some()
-                });
+    async fn add_sample(
+        &mut self,
+        bank: SampleBank,
+        button: SampleButtons,
+        filename: &str,
+    ) -> Result<()> {
+        let path = self.get_path_for_sample(bank, button, filename).await?;
+
+        // If we have an audio handler, try to calcuate the Gain..
+        if let Some(audio_handler) = &mut self.audio_handler {
+            if audio_handler.is_calculating() {
+                bail!("Gain Calculation already in progress..");
             }
+
+            // V2 Here, this technically still blocks in it's current state, however, it
+            // doesn't have to anymore.
+            audio_handler.calculate_gain_thread(path, bank, button)?;
+        }
+
+        // Update the lighting..
+        self.load_colour_map().await
+    }
+
+    // Copies a sample into the button's folder, unless a file with the same contents is already
+    // in the samples directory, in which case that's used instead.
+    async fn import_sample_file(
+        &mut self,
+        bank: SampleBank,
+        button: SampleButtons,
+        source: &Path,
+    ) -> Result<String> {
+        if !source.is_file() {
+            bail!("Sample file not found: {}", source.to_string_lossy());
         }
 
-        // Because we may have removed the 'last' sample on a button, we need to refresh
-        // the states to make sure everything is correctly updated.
-        self.load_colour_map()?;
-        self.update_button_states()
+        let sample_path = self.settings.get_samples_directory().await;
+        let hash = self.sample_hashes.get_hash(source)?;
+
+        let file = match self.sample_hashes.find_by_hash(&sample_path, &hash) {
+            Some(existing) => {
+                info!(
+                    "{} is already in the samples directory as {}, using the existing file",
+                    source.to_string_lossy(),
+                    existing.to_string_lossy()
+                );
+                existing
+            }
+            None => {
+                let folder = sample_path.join(bank.to_string()).join(button.to_string());
+                create_path(&folder)?;
+
+                let name = source.file_name().context("Invalid sample file name")?;
+                let mut target = folder.join(name);
+
+                // A different file with the same name is already here, so number this one..
+                let stem = source.file_stem().unwrap_or(name).to_string_lossy();
+                let extension = source.extension().unwrap_or_default().to_string_lossy();
+                let mut counter = 1;
+                while target.exists() {
+                    target = folder.join(format!("{} ({}).{}", stem, counter, extension));
+                    counter += 1;
+                }
+
+                fs::copy(source, &target).context("Unable to copy the sample file")?;
+                target
+            }
+        };
+        self.sample_hashes.save();
+
+        Ok(get_sample_track_name(&sample_path, &file))
     }
 
     async fn handle_sample_button_down(&mut self, button: SampleButtons) -> Result<()> {
@@ -906,6 +2255,14 @@ impl<'a> Device<'a> {
             self.profile.is_sample_clear_active()
         );
 
+        // Holding Clear while pressing a sample button erases it straight away..
+        if self.last_buttons.contains(Buttons::SamplerClear) {
+            self.button_states[Buttons::SamplerClear].hold_handled = true;
+
+            let bank = self.profile.get_active_sample_bank();
+            return self.clear_sample_button(bank, button).await;
+        }
+
         // We don't do anything if clear is flashing..
         if self.profile.is_sample_clear_active() {
             debug!("Sample Clear is Active, ignoring..");
@@ -922,10 +2279,7 @@ impl<'a> Device<'a> {
         let sample_bank = self.profile.get_active_sample_bank();
 
         if !self.profile.current_sample_bank_has_samples(button) {
-            let file_date = Local::now().format("%Y-%m-%dT%H%M%S").to_string();
-            let full_name = format!("Recording_{file_date}.wav");
-
-            self.record_audio_file(button, full_name).await?;
+            self.record_audio_file(button).await?;
             return Ok(());
         }
 
@@ -938,9 +2292,27 @@ impl<'a> Device<'a> {
             SamplePlaybackMode::PlayNext
             | SamplePlaybackMode::StopOnRelease
             | SamplePlaybackMode::FadeOnRelease => {
+                // If queueing is enabled, pressing Play Next while a sample is running lines up
+                // the next track to start once the current one finishes.
+                if mode == SamplePlaybackMode::PlayNext
+                    && self.is_sample_active(sample_bank, button)
+                    && self
+                        .settings
+                        .get_device_sampler_queue_next(self.serial())
+                        .await
+                {
+                    if let Some(audio) = self.get_next_available_track(sample_bank, button).await? {
+                        self.queued_samples[sample_bank][button].push_back(audio);
+                    }
+                    return Ok(());
+                }
+
                 // In all three of these cases, we will always play audio on button down.
                 //let file = self.profile.get_sample_file(button);
-                let mut audio = self.profile.get_next_track(button)?;
+                let mut audio = match self.get_next_available_track(sample_bank, button).await? {
+                    Some(audio) => audio,
+                    None => return Ok(()),
+                };
                 if mode == SamplePlaybackMode::FadeOnRelease {
                     audio.fade_on_stop = true;
                 }
@@ -963,7 +2335,11 @@ impl<'a> Device<'a> {
                     Ok(())
                 } else {
                     // Play the next file.
-                    let mut audio = self.profile.get_next_track(button)?;
+                    let mut audio = match self.get_next_available_track(sample_bank, button).await?
+                    {
+                        Some(audio) => audio,
+                        None => return Ok(()),
+                    };
 
                     if mode == SamplePlaybackMode::PlayFade {
                         audio.fade_on_stop = true;
@@ -980,6 +2356,7 @@ impl<'a> Device<'a> {
     }
 
     async fn stop_all_samples(&mut self) -> Result<()> {
+        self.queued_samples = EnumMap::default();
         if let Some(audio) = &mut self.audio_handler {
             for bank in SampleBank::iter() {
                 for button in SampleButtons::iter() {
@@ -1014,23 +2391,8 @@ impl<'a> Device<'a> {
         let active_bank = self.profile.get_active_sample_bank();
         // If clear is flashing, remove all samples from the button, disable the clearer and return..
         if self.profile.is_sample_clear_active() {
-            debug!("Stopping any playing samples..");
-            if let Some(handler) = &mut self.audio_handler {
-                // Force stop of anything playing back on this button.
-                handler.stop_playback(active_bank, button, true).await?;
-            }
-
-            debug!("Clearing Samples on Button..");
-            self.profile.clear_all_samples(button);
-
-            debug!("Cleared samples..");
             self.profile.set_sample_clear_active(false)?;
-
-            debug!("Disabled Buttons..");
-            self.load_colour_map()?;
-
-            debug!("Reset Colours");
-            return Ok(());
+            return self.clear_sample_button(active_bank, button).await;
         }
 
         // We only need to either a) Stop recording, or b) Handle Stop On Release..
@@ -1048,19 +2410,11 @@ impl<'a> Device<'a> {
                 .unwrap()
                 .sample_recording(sample_bank, button)
             {
-                let file_name = self
-                    .audio_handler
-                    .as_mut()
-                    .unwrap()
-                    .stop_record(sample_bank, button)?;
-
-                if let Some(file_name) = file_name {
-                    self.profile.add_sample_file(sample_bank, button, file_name);
-                }
+                return self.finish_recording(sample_bank, button).await;
             }
             // In all cases, we should stop the colour flashing.
             self.profile.set_sample_button_blink(button, false)?;
-            self.load_colour_map()?;
+            self.load_colour_map().await?;
 
             return Ok(());
         }
@@ -1081,6 +2435,33 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
+    // Stops anything running on the button, removes its samples and saves the profile.
+    async fn clear_sample_button(&mut self, bank: SampleBank, button: SampleButtons) -> Result<()> {
+        self.queued_samples[bank][button].clear();
+        if let Some(audio_handler) = &mut self.audio_handler {
+            debug!("Stopping any playing samples..");
+            if audio_handler.sample_recording(bank, button) {
+                audio_handler.stop_record(bank, button)?;
+            }
+            if audio_handler.is_sample_playing(bank, button) {
+                audio_handler.stop_playback(bank, button, true).await?;
+            }
+        }
+
+        debug!("Clearing Samples on Button..");
+        self.profile.clear_all_samples(bank, button);
+        if bank == self.profile.get_active_sample_bank() {
+            self.profile.set_sample_button_state(button, false)?;
+            self.profile.set_sample_button_blink(button, false)?;
+        }
+
+        let profile_directory = self.settings.get_profile_directory().await;
+        self.profile.save(&profile_directory, true)?;
+
+        self.load_colour_map().await?;
+        self.update_button_states().await
+    }
+
     /// A Simple Method that simply starts playback on the Sampler Channel..
     async fn play_audio_file(
         &mut self,
@@ -1089,10 +2470,30 @@ impl<'a> Device<'a> {
         mut audio: AudioFile,
         loop_track: bool,
     ) -> Result<()> {
-        // Fill out the path..
-        let sample_path = self.get_path_for_sample(audio.file).await?;
+        // Fill out the path, if the file has gone missing, skip it rather than failing..
+        let track = audio.file.to_string_lossy().to_string();
+        let sample_path = match self.get_path_for_sample(bank, button, &track).await {
+            Ok(path) => path,
+            Err(_) => {
+                self.sample_missing(bank, button, track);
+                return Ok(());
+            }
+        };
         audio.file = sample_path;
 
+        // Gains are calculated against the default target, so adjust them to the configured one
+        // (samples without a calculated gain aren't being normalised, so are left alone)
+        let target = self
+            .settings
+            .get_device_sampler_normalization_target(self.serial())
+            .await as f64;
+        if let Some(gain) = audio.gain {
+            if target != NORMALIZATION_TARGET {
+                let offset = f64::powf(10., (target - NORMALIZATION_TARGET) / 20.);
+                audio.gain = Some(gain * offset);
+            }
+        }
+
         if let Some(audio_handler) = &mut self.audio_handler {
             audio_handler.stop_playback(bank, button, true).await?;
 
@@ -1101,6 +2502,7 @@ impl<'a> Device<'a> {
                 .await;
 
             if result.is_ok() {
+                METRICS.sample_played();
                 self.profile.set_sample_button_state(button, true)?;
             } else {
                 error!("{}", result.err().unwrap());
@@ -1114,6 +2516,7 @@ impl<'a> Device<'a> {
         bank: SampleBank,
         button: SampleButtons,
     ) -> Result<()> {
+        self.queued_samples[bank][button].clear();
         if let Some(audio_handler) = &mut self.audio_handler {
             audio_handler.stop_playback(bank, button, false).await?;
         }
@@ -1121,13 +2524,57 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
-    async fn record_audio_file(&mut self, button: SampleButtons, file_name: String) -> Result<()> {
+    fn is_sample_active(&self, bank: SampleBank, button: SampleButtons) -> bool {
+        match &self.audio_handler {
+            Some(audio_handler) => {
+                audio_handler.is_sample_playing(bank, button)
+                    && !audio_handler.is_sample_stopping(bank, button)
+            }
+            None => false,
+        }
+    }
+
+    // Starts the next queued track on any button whose previous sample has finished.
+    async fn play_queued_samples(&mut self) -> Result<()> {
+        for bank in SampleBank::iter() {
+            for button in SampleButtons::iter() {
+                if self.queued_samples[bank][button].is_empty()
+                    || self.is_sample_active(bank, button)
+                {
+                    continue;
+                }
+
+                if let Some(audio) = self.queued_samples[bank][button].pop_front() {
+                    self.play_audio_file(bank, button, audio, false).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn record_audio_file(&mut self, button: SampleButtons) -> Result<()> {
         let sample_bank = self.profile.get_active_sample_bank();
 
         // Create the full Path..
         let mut sample_path = self.settings.get_samples_directory().await;
-        sample_path = sample_path.join("Recorded");
-        sample_path = sample_path.join(file_name);
+        if let Some(free) = platform::get_free_space(&sample_path) {
+            if free < MIN_RECORDING_SPACE {
+                bail!("Not enough free disk space to start a recording");
+            }
+        }
+        let template = self
+            .settings
+            .get_device_sampler_recording_template(self.serial())
+            .await;
+        sample_path = get_recording_path(
+            &sample_path.join("Recorded"),
+            &template,
+            sample_bank,
+            button,
+        )?;
+        if let Some(parent) = sample_path.parent() {
+            create_path(parent)?;
+        }
 
         if let Some(audio_handler) = &mut self.audio_handler {
             let result = audio_handler.record_for_button(sample_path, sample_bank, button);
@@ -1139,9 +2586,81 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
-    async fn get_path_for_sample(&mut self, part: PathBuf) -> Result<PathBuf> {
+    // Stops a recording, and adds the result (if anything was captured) to the button.
+    async fn finish_recording(&mut self, bank: SampleBank, button: SampleButtons) -> Result<()> {
+        if let Some(audio_handler) = &mut self.audio_handler {
+            if let Some(file) = audio_handler.stop_record(bank, button)? {
+                let sample_path = self.settings.get_samples_directory().await;
+                let name = get_sample_track_name(&sample_path, &file);
+                self.profile.add_sample_file(bank, button, name);
+
+                let serial = self.serial().to_string();
+                self.events
+                    .push(DaemonEvent::RecordingStopped(serial, bank, button, file));
+            }
+        }
+
+        // In all cases, we should stop the colour flashing.
+        self.profile.set_sample_button_blink(button, false)?;
+        self.load_colour_map().await
+    }
+
+    // Stops any recording which has run past the maximum length, or is close to filling the
+    // disk, keeping whatever was recorded up to that point.
+    async fn check_recording_limits(&mut self) -> Result<()> {
+        let audio_handler = match &self.audio_handler {
+            Some(audio_handler) => audio_handler,
+            None => return Ok(()),
+        };
+
+        if !audio_handler.is_sample_recording() {
+            return Ok(());
+        }
+
+        let max_length = self
+            .settings
+            .get_device_sampler_max_recording(self.serial())
+            .await as u64;
+        let sample_path = self.settings.get_samples_directory().await;
+        let low_space = platform::get_free_space(&sample_path)
+            .map(|free| free < MIN_RECORDING_SPACE)
+            .unwrap_or(false);
+
+        let mut expired = vec![];
+        for bank in SampleBank::iter() {
+            for button in SampleButtons::iter() {
+                if let Some(duration) = audio_handler.get_recording_duration(bank, button) {
+                    if low_space || (max_length > 0 && duration.as_secs() >= max_length) {
+                        expired.push((bank, button));
+                    }
+                }
+            }
+        }
+
+        for (bank, button) in expired {
+            match low_space {
+                true => warn!(
+                    "Low on Disk Space, Stopping Recording on {} {}",
+                    bank, button
+                ),
+                false => warn!(
+                    "Maximum Length Reached, Stopping Recording on {} {}",
+                    bank, button
+                ),
+            }
+            self.finish_recording(bank, button).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_path_for_sample(
+        &mut self,
+        bank: SampleBank,
+        button: SampleButtons,
+        track: &str,
+    ) -> Result<PathBuf> {
         let sample_path = self.settings.get_samples_directory().await;
-        if let Some(file) = find_file_in_path(sample_path, part) {
+        if let Some(file) = find_sample_file(&sample_path, bank, button, track) {
             return Ok(file);
         }
         bail!("Sample Not Found");
@@ -1168,7 +2687,7 @@ impl<'a> Device<'a> {
         }
 
         if changed {
-            self.update_button_states()?;
+            self.update_button_states().await?;
         }
 
         Ok(changed)
@@ -1181,16 +2700,23 @@ impl<'a> Device<'a> {
         let _ = self.global_events.send(TTSMessage(tts_message)).await;
 
         self.profile.load_effect_bank(preset)?;
-        self.load_encoder_effects()?;
-        self.set_pitch_mode()?;
-
-        self.apply_effects(self.mic_profile.get_reverb_keyset())?;
-        self.apply_effects(self.mic_profile.get_megaphone_keyset())?;
-        self.apply_effects(self.mic_profile.get_robot_keyset())?;
-        self.apply_effects(self.mic_profile.get_hardtune_keyset())?;
-        self.apply_effects(self.mic_profile.get_echo_keyset())?;
-        self.apply_effects(self.mic_profile.get_pitch_keyset())?;
-        self.apply_effects(self.mic_profile.get_gender_keyset())?;
+        self.load_encoder_effects().await?;
+        self.set_pitch_mode().await?;
+
+        self.apply_effects(self.mic_profile.get_reverb_keyset())
+            .await?;
+        self.apply_effects(self.mic_profile.get_megaphone_keyset())
+            .await?;
+        self.apply_effects(self.mic_profile.get_robot_keyset())
+            .await?;
+        self.apply_effects(self.mic_profile.get_hardtune_keyset())
+            .await?;
+        self.apply_effects(self.mic_profile.get_echo_keyset())
+            .await?;
+        self.apply_effects(self.mic_profile.get_pitch_keyset())
+            .await?;
+        self.apply_effects(self.mic_profile.get_gender_keyset())
+            .await?;
 
         Ok(())
     }
@@ -1201,7 +2727,8 @@ impl<'a> Device<'a> {
         let _ = self.global_events.send(TTSMessage(tts_message)).await;
 
         self.profile.set_megaphone(enabled)?;
-        self.apply_effects(LinkedHashSet::from_iter([EffectKey::MegaphoneEnabled]))?;
+        self.apply_effects(LinkedHashSet::from_iter([EffectKey::MegaphoneEnabled]))
+            .await?;
         Ok(())
     }
 
@@ -1211,7 +2738,8 @@ impl<'a> Device<'a> {
         let _ = self.global_events.send(TTSMessage(tts_message)).await;
 
         self.profile.set_robot(enabled)?;
-        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotEnabled]))?;
+        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotEnabled]))
+            .await?;
         Ok(())
     }
 
@@ -1221,14 +2749,18 @@ impl<'a> Device<'a> {
         let _ = self.global_events.send(TTSMessage(tts_message)).await;
 
         self.profile.set_hardtune(enabled)?;
-        self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneEnabled]))?;
-        self.set_pitch_mode()?;
+        self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneEnabled]))
+            .await?;
+        self.set_pitch_mode().await?;
 
         // When changing the Hard Tune amount, we need to update the pitch encoder..
         let pitch = self.profile.get_pitch_encoder_position();
-        self.goxlr.set_encoder_value(EncoderName::Pitch, pitch)?;
+        self.goxlr
+            .set_encoder_value(EncoderName::Pitch, pitch)
+            .await?;
         self.profile.set_pitch_knob_position(pitch)?;
-        self.apply_effects(LinkedHashSet::from_iter([EffectKey::PitchAmount]))?;
+        self.apply_effects(LinkedHashSet::from_iter([EffectKey::PitchAmount]))
+            .await?;
         Ok(())
     }
 
@@ -1249,7 +2781,7 @@ impl<'a> Device<'a> {
         key_updates.insert(EffectKey::MegaphoneEnabled);
         key_updates.insert(EffectKey::HardTuneEnabled);
         key_updates.insert(EffectKey::RobotEnabled);
-        self.apply_effects(key_updates)?;
+        self.apply_effects(key_updates).await?;
 
         Ok(())
     }
@@ -1273,6 +2805,47 @@ impl<'a> Device<'a> {
         muted_to_all || (muted_to_x && mute_function == MuteFunction::All)
     }
 
+    async fn store_runtime_state(&mut self) {
+        if !self
+            .settings
+            .get_device_restore_runtime_state(self.serial())
+            .await
+        {
+            return;
+        }
+
+        let state = self.profile.get_runtime_state();
+        let previous = self.settings.get_device_runtime_state(self.serial()).await;
+        if previous.as_ref() != Some(&state) {
+            debug!("Storing Runtime State..");
+            self.settings
+                .set_device_runtime_state(self.serial(), state)
+                .await;
+            self.settings.save().await;
+        }
+    }
+
+    // Once a fader has settled after being moved by hand, announce its new volume..
+    async fn announce_volumes(&mut self) {
+        for fader in FaderName::iter() {
+            let moved = match self.volume_announcements[fader] {
+                Some(moved) => moved,
+                None => continue,
+            };
+            if moved.elapsed() < VOLUME_ANNOUNCE_DELAY {
+                continue;
+            }
+            self.volume_announcements[fader] = None;
+
+            let channel = self.profile.get_fader_assignment(fader);
+            let volume = self.profile.get_channel_volume(channel);
+            let percent = (volume as f32 / 255. * 100.).round();
+
+            let message = format!("{} volume {}%", channel, percent);
+            let _ = self.global_events.send(TTSMessage(message)).await;
+        }
+    }
+
     async fn update_volumes_to(&mut self, volumes: [u8; 4]) -> Result<bool> {
         let mut value_changed = false;
 
@@ -1308,6 +2881,12 @@ impl<'a> Device<'a> {
             let channel = self.profile.get_fader_assignment(fader);
             let old_volume = self.profile.get_channel_volume(channel);
 
+            // The fader has been moved by ducking, the profile keeps the un-ducked volume.
+            let motorised = self.hardware.device_type != DeviceType::Mini;
+            if motorised && self.is_fader_ducked_to(channel, new_volume) {
+                continue;
+            }
+
             if new_volume != old_volume {
                 debug!(
                     "Updating {} volume from {} to {} as a human moved the fader",
@@ -1328,15 +2907,20 @@ impl<'a> Device<'a> {
 
                 value_changed = true;
                 self.profile.set_channel_volume(channel, new_volume)?;
+                self.volume_announcements[fader] = Some(Instant::now());
+
+                // A human move during a duck becomes the new base, and is ducked from there.
+                self.ducking_state.applied[channel] = None;
+                self.ducking_state.base[channel] = None;
 
                 // Update the Submix..
-                self.update_submix_for(channel, new_volume)?;
+                self.update_submix_for(channel, new_volume).await?;
             }
         }
         Ok(value_changed)
     }
 
-    fn update_submix_for(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
+    async fn update_submix_for(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
         if self.device_supports_submixes() && self.profile.is_submix_enabled() {
             if let Some(mix) = self.profile.get_submix_from_channel(channel) {
                 if !self.profile.submix_linked(mix) {
@@ -1350,7 +2934,7 @@ impl<'a> Device<'a> {
 
                 if linked_volume != mix_current_volume {
                     self.profile.set_submix_volume(mix, linked_volume)?;
-                    self.goxlr.set_sub_volume(mix, linked_volume)?;
+                    self.goxlr.set_sub_volume(mix, linked_volume).await?;
                 }
             }
         }
@@ -1372,7 +2956,8 @@ impl<'a> Device<'a> {
             );
             value_changed = true;
             self.profile.set_pitch_knob_position(encoders[0])?;
-            self.apply_effects(LinkedHashSet::from_iter([EffectKey::PitchAmount]))?;
+            self.apply_effects(LinkedHashSet::from_iter([EffectKey::PitchAmount]))
+                .await?;
 
             let user_value = self
                 .mic_profile
@@ -1400,7 +2985,8 @@ impl<'a> Device<'a> {
                 .get_effect_value(EffectKey::GenderAmount, self.profile());
 
             if new_value != current_value {
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GenderAmount]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GenderAmount]))
+                    .await?;
                 let message = format!("Gender {}", new_value);
                 let _ = self.global_events.send(TTSMessage(message)).await;
             }
@@ -1420,7 +3006,8 @@ impl<'a> Device<'a> {
                 .mic_profile
                 .get_effect_value(EffectKey::ReverbAmount, self.profile());
 
-            self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbAmount]))?;
+            self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbAmount]))
+                .await?;
 
             let percent = 100 - ((new_value as f32 / -36.) * 100.) as i32;
             let message = format!("Reverb {} percent", percent);
@@ -1435,7 +3022,8 @@ impl<'a> Device<'a> {
             );
             value_changed = true;
             self.profile.set_echo_value(encoders[3])?;
-            self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoAmount]))?;
+            self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoAmount]))
+                .await?;
 
             let mut user_value = self
                 .mic_profile
@@ -1449,32 +3037,140 @@ impl<'a> Device<'a> {
     }
 
     pub async fn perform_command(&mut self, command: GoXLRCommand) -> Result<()> {
+        if !is_command_supported(&command, &self.hardware.device_type.capabilities()) {
+            bail!(DaemonError::CommandNotSupportedOnMini);
+        }
+
+        if self.firmware_update.is_some() {
+            bail!("A Firmware Update is in progress on this device");
+        }
+
+        match command {
+            GoXLRCommand::Undo() => return self.undo().await,
+            GoXLRCommand::Redo() => return self.redo().await,
+            _ => {}
+        }
+
+        // Grab the state before the change, so it can be put back later..
+        let mut previous = None;
+        if !self.history.is_continuation(&command) {
+            previous = Some((command.clone(), self.take_snapshot()?));
+        }
+        let profile_names = (
+            self.profile.name().to_owned(),
+            self.mic_profile.name().to_owned(),
+        );
+
+        self.execute_command(command).await?;
+
+        // Undoing into a different profile would be confusing, so history starts again on load.
+        if profile_names.0 != self.profile.name() || profile_names.1 != self.mic_profile.name() {
+            self.history.clear();
+        } else if let Some((command, snapshot)) = previous {
+            if snapshot != self.take_snapshot()? {
+                self.history.record(HistoryEntry { command, snapshot });
+            }
+        }
+        Ok(())
+    }
+
+    async fn execute_command(&mut self, command: GoXLRCommand) -> Result<()> {
         match command {
             GoXLRCommand::SetShutdownCommands(commands) => {
                 self.settings
-                    .set_device_shutdown_commands(self.serial(), commands)
+                    .set_device_shutdown_commands(self.serial(), commands)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetSamplerPreBufferDuration(duration) => {
+                if duration > 30000 {
+                    bail!("Buffer must be below 30seconds");
+                }
+
+                self.settings
+                    .set_device_sampler_pre_buffer(self.serial(), duration)
+                    .await;
+                self.settings.save().await;
+
+                // Reload the Audio Handler..
+                self.stop_all_samples().await?;
+
+                // Drop the Audio Handler..
+                let mut new_handler = AudioHandler::new(duration)?;
+                let monitor = self
+                    .settings
+                    .get_device_sampler_monitor_device(self.serial())
+                    .await;
+                let monitor_recording = self
+                    .settings
+                    .get_device_sampler_monitor_recording(self.serial())
+                    .await;
+                new_handler.set_monitor_device(monitor);
+                new_handler.set_monitor_recording(monitor_recording);
+                self.audio_handler = Some(new_handler);
+            }
+            GoXLRCommand::SetSamplerQueueNext(enabled) => {
+                self.settings
+                    .set_device_sampler_queue_next(self.serial(), enabled)
+                    .await;
+                self.settings.save().await;
+
+                if !enabled {
+                    self.queued_samples = EnumMap::default();
+                }
+            }
+            GoXLRCommand::SetSamplerRecordingTemplate(template) => {
+                check_recording_template(&template)?;
+                self.settings
+                    .set_device_sampler_recording_template(self.serial(), template)
                     .await;
                 self.settings.save().await;
             }
-            GoXLRCommand::SetSamplerPreBufferDuration(duration) => {
-                if duration > 30000 {
-                    bail!("Buffer must be below 30seconds");
+            GoXLRCommand::SetSamplerMaxRecordingLength(seconds) => {
+                self.settings
+                    .set_device_sampler_max_recording(self.serial(), seconds)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetSamplerMonitorRecording(enabled) => {
+                self.settings
+                    .set_device_sampler_monitor_recording(self.serial(), enabled)
+                    .await;
+                self.settings.save().await;
+
+                if let Some(audio_handler) = &mut self.audio_handler {
+                    audio_handler.set_monitor_recording(enabled);
+                }
+            }
+            GoXLRCommand::SetSamplerNormalizationTarget(target) => {
+                if !(-60.0..=0.0).contains(&target) {
+                    bail!("Normalization Target must be between -60 and 0 LUFS");
                 }
 
                 self.settings
-                    .set_device_sampler_pre_buffer(self.serial(), duration)
+                    .set_device_sampler_normalization_target(self.serial(), target)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetSamplerMonitorDevice(device) => {
+                self.settings
+                    .set_device_sampler_monitor_device(self.serial(), device.clone())
                     .await;
                 self.settings.save().await;
 
-                // Reload the Audio Handler..
-                self.stop_all_samples().await?;
-
-                // Drop the Audio Handler..
-                let new_handler = AudioHandler::new(duration)?;
-                self.audio_handler = Some(new_handler);
+                if let Some(audio_handler) = &mut self.audio_handler {
+                    audio_handler.set_monitor_device(device);
+                }
             }
 
             GoXLRCommand::SetFader(fader, channel) => {
+                let capabilities = self.hardware.device_type.capabilities();
+                if !capabilities.has_channel(channel) {
+                    bail!(DaemonError::ChannelNotAvailable(channel));
+                }
+                if !capabilities.is_fader_channel(channel) {
+                    bail!("The {} channel can't be assigned to a fader", channel);
+                }
                 self.set_fader(fader, channel).await?;
             }
             GoXLRCommand::SetFaderMuteFunction(fader, behaviour) => {
@@ -1489,16 +3185,51 @@ impl<'a> Device<'a> {
             }
 
             GoXLRCommand::SetVolume(channel, volume) => {
-                self.goxlr.set_volume(channel, volume)?;
-                self.profile.set_channel_volume(channel, volume)?;
-
-                // Update the Submix when volume changes via IPC
-                self.update_submix_for(channel, volume)?;
-
-                if let Some(fader) = self.profile.get_fader_from_channel(channel) {
-                    self.fader_pause_until[fader].paused = true;
-                    self.fader_pause_until[fader].until = volume;
+                // Setting a volume directly cancels any fade in progress..
+                self.volume_fades[channel] = None;
+                self.set_channel_volume(channel, volume).await?;
+            }
+            GoXLRCommand::FadeChannelVolume(channel, volume, duration) => {
+                self.volume_fades[channel] = Some(VolumeFade {
+                    from: self.profile.get_channel_volume(channel),
+                    to: volume,
+                    start: self.get_epoch_ms(),
+                    duration: duration.into(),
+                });
+            }
+            GoXLRCommand::SetVolumePreset(name, preset) => {
+                if let Some(preset) = &preset {
+                    self.validate_volume_preset(preset)?;
+                }
+                self.settings
+                    .set_device_volume_preset(self.serial(), &name, preset)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SaveVolumePreset(name) => {
+                let capabilities = self.hardware.device_type.capabilities();
+                let mut preset = VolumePreset::default();
+                for channel in ChannelName::iter() {
+                    if capabilities.has_channel(channel) {
+                        let volume = self.profile.get_channel_volume(channel);
+                        preset.volumes.insert(channel, volume);
+                    }
+                }
+                for fader in FaderName::iter() {
+                    if capabilities.has_fader(fader) {
+                        let channel = self.profile.get_fader_assignment(fader);
+                        preset
+                            .mutes
+                            .insert(channel, self.get_fader_mute_state(fader));
+                    }
                 }
+                self.settings
+                    .set_device_volume_preset(self.serial(), &name, Some(preset))
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::LoadVolumePreset(name) => {
+                self.load_volume_preset(&name).await?;
             }
 
             GoXLRCommand::SetCoughMuteFunction(mute_function) => {
@@ -1517,24 +3248,62 @@ impl<'a> Device<'a> {
             }
             GoXLRCommand::SetSwearButtonVolume(volume) => {
                 self.mic_profile.set_bleep_level(volume)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::BleepLevel]))?;
-                self.apply_mic_params(HashSet::from([MicrophoneParamKey::BleepLevel]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::BleepLevel]))
+                    .await?;
+                self.apply_mic_params(HashSet::from([MicrophoneParamKey::BleepLevel]))
+                    .await?;
             }
             GoXLRCommand::SetMicrophoneType(mic_type) => {
                 self.mic_profile.set_mic_type(mic_type)?;
-                self.apply_mic_gain()?;
+                self.apply_mic_gain().await?;
             }
             GoXLRCommand::SetMicrophoneGain(mic_type, gain) => {
                 self.mic_profile.set_mic_type(mic_type)?;
                 self.mic_profile.set_mic_gain(mic_type, gain)?;
-                self.apply_mic_gain()?;
+                self.apply_mic_gain().await?;
+            }
+            GoXLRCommand::CalibrateMicGain(seconds, apply) => {
+                let mic_type = self.mic_profile.mic_type();
+                let gain = self.mic_profile.mic_gains()[mic_type];
+                self.mic_calibration = Some(MicCalibration::new(seconds, apply, mic_type, gain)?);
+                info!("Starting Mic Calibration for {} seconds", seconds);
             }
             GoXLRCommand::SetRouter(input, output, enabled) => {
                 debug!("Setting Routing: {:?} {:?} {}", input, output, enabled);
                 self.profile.set_routing(input, output, enabled)?;
 
                 // Apply the change..
-                self.apply_routing(input)?;
+                self.apply_routing(input).await?;
+            }
+            GoXLRCommand::SetRoutingPreset(name, preset) => {
+                if let Some(preset) = &preset {
+                    for (input, outputs) in &preset.routes {
+                        for output in outputs.keys() {
+                            validate_route(*input, *output)?;
+                        }
+                    }
+                }
+                self.settings
+                    .set_device_routing_preset(self.serial(), &name, preset)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SaveRoutingPreset(name) => {
+                let mut preset = RoutingPreset::default();
+                for (input, outputs) in self.profile.create_router() {
+                    let outputs = outputs
+                        .into_iter()
+                        .filter(|(output, _)| validate_route(input, *output).is_ok())
+                        .collect();
+                    preset.routes.insert(input, outputs);
+                }
+                self.settings
+                    .set_device_routing_preset(self.serial(), &name, Some(preset))
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::LoadRoutingPreset(name) => {
+                self.load_routing_preset(&name).await?;
             }
 
             GoXLRCommand::SetElementDisplayMode(element, display) => match element {
@@ -1556,79 +3325,101 @@ impl<'a> Device<'a> {
             // Equaliser
             GoXLRCommand::SetEqMiniGain(gain, value) => {
                 let param = self.mic_profile.set_mini_eq_gain(gain, value)?;
-                self.apply_mic_params(HashSet::from([param]))?;
+                self.apply_mic_params(HashSet::from([param])).await?;
             }
             GoXLRCommand::SetEqMiniFreq(freq, value) => {
                 let param = self.mic_profile.set_mini_eq_freq(freq, value)?;
-                self.apply_mic_params(HashSet::from([param]))?;
+                self.apply_mic_params(HashSet::from([param])).await?;
             }
             GoXLRCommand::SetEqGain(gain, value) => {
                 let param = self.mic_profile.set_eq_gain(gain, value)?;
-                self.apply_effects(LinkedHashSet::from_iter([param]))?;
+                self.apply_effects(LinkedHashSet::from_iter([param]))
+                    .await?;
             }
             GoXLRCommand::SetEqFreq(freq, value) => {
                 let param = self.mic_profile.set_eq_freq(freq, value)?;
-                self.apply_effects(LinkedHashSet::from_iter([param]))?;
+                self.apply_effects(LinkedHashSet::from_iter([param]))
+                    .await?;
             }
             GoXLRCommand::SetGateThreshold(value) => {
                 self.mic_profile.set_gate_threshold(value)?;
-                self.apply_mic_params(HashSet::from([MicrophoneParamKey::GateThreshold]))?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GateThreshold]))?;
+                self.apply_mic_params(HashSet::from([MicrophoneParamKey::GateThreshold]))
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GateThreshold]))
+                    .await?;
             }
 
             // Noise Gate
             GoXLRCommand::SetGateAttenuation(percentage) => {
                 self.mic_profile.set_gate_attenuation(percentage)?;
-                self.apply_mic_params(HashSet::from([MicrophoneParamKey::GateAttenuation]))?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GateAttenuation]))?;
+                self.apply_mic_params(HashSet::from([MicrophoneParamKey::GateAttenuation]))
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GateAttenuation]))
+                    .await?;
             }
             GoXLRCommand::SetGateAttack(attack_time) => {
                 self.mic_profile.set_gate_attack(attack_time)?;
-                self.apply_mic_params(HashSet::from([MicrophoneParamKey::GateAttack]))?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GateAttack]))?;
+                self.apply_mic_params(HashSet::from([MicrophoneParamKey::GateAttack]))
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GateAttack]))
+                    .await?;
             }
             GoXLRCommand::SetGateRelease(release_time) => {
                 self.mic_profile.set_gate_release(release_time)?;
-                self.apply_mic_params(HashSet::from([MicrophoneParamKey::GateRelease]))?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GateRelease]))?;
+                self.apply_mic_params(HashSet::from([MicrophoneParamKey::GateRelease]))
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GateRelease]))
+                    .await?;
             }
             GoXLRCommand::SetGateActive(active) => {
                 self.mic_profile.set_gate_active(active)?;
 
                 // GateEnabled appears to only be an effect key.
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GateEnabled]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GateEnabled]))
+                    .await?;
             }
 
             // Compressor
             GoXLRCommand::SetCompressorThreshold(value) => {
                 self.mic_profile.set_compressor_threshold(value)?;
-                self.apply_mic_params(HashSet::from([MicrophoneParamKey::CompressorThreshold]))?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::CompressorThreshold]))?;
+                self.apply_mic_params(HashSet::from([MicrophoneParamKey::CompressorThreshold]))
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::CompressorThreshold]))
+                    .await?;
             }
             GoXLRCommand::SetCompressorRatio(ratio) => {
                 self.mic_profile.set_compressor_ratio(ratio)?;
-                self.apply_mic_params(HashSet::from([MicrophoneParamKey::CompressorRatio]))?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::CompressorRatio]))?;
+                self.apply_mic_params(HashSet::from([MicrophoneParamKey::CompressorRatio]))
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::CompressorRatio]))
+                    .await?;
             }
             GoXLRCommand::SetCompressorAttack(value) => {
                 self.mic_profile.set_compressor_attack(value)?;
-                self.apply_mic_params(HashSet::from([MicrophoneParamKey::CompressorAttack]))?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::CompressorAttack]))?;
+                self.apply_mic_params(HashSet::from([MicrophoneParamKey::CompressorAttack]))
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::CompressorAttack]))
+                    .await?;
             }
             GoXLRCommand::SetCompressorReleaseTime(value) => {
                 self.mic_profile.set_compressor_release(value)?;
-                self.apply_mic_params(HashSet::from([MicrophoneParamKey::CompressorRelease]))?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::CompressorRelease]))?;
+                self.apply_mic_params(HashSet::from([MicrophoneParamKey::CompressorRelease]))
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::CompressorRelease]))
+                    .await?;
             }
             GoXLRCommand::SetCompressorMakeupGain(value) => {
                 self.mic_profile.set_compressor_makeup(value)?;
-                self.apply_mic_params(HashSet::from([MicrophoneParamKey::CompressorMakeUpGain]))?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::CompressorMakeUpGain]))?;
+                self.apply_mic_params(HashSet::from([MicrophoneParamKey::CompressorMakeUpGain]))
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::CompressorMakeUpGain]))
+                    .await?;
             }
 
             GoXLRCommand::SetDeeser(percentage) => {
                 self.mic_profile.set_deesser(percentage)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::DeEsser]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::DeEsser]))
+                    .await?;
             }
 
             // Colouring..
@@ -1638,7 +3429,7 @@ impl<'a> Device<'a> {
                 }
 
                 self.profile.set_animation_mode(mode)?;
-                self.load_animation(false)?;
+                self.load_animation(false).await?;
             }
             GoXLRCommand::SetAnimationMod1(value) => {
                 if !self.device_supports_animations() {
@@ -1646,7 +3437,7 @@ impl<'a> Device<'a> {
                 }
 
                 self.profile.set_animation_mod1(value)?;
-                self.load_animation(false)?;
+                self.load_animation(false).await?;
             }
             GoXLRCommand::SetAnimationMod2(value) => {
                 if !self.device_supports_animations() {
@@ -1654,7 +3445,7 @@ impl<'a> Device<'a> {
                 }
 
                 self.profile.set_animation_mod2(value)?;
-                self.load_animation(false)?;
+                self.load_animation(false).await?;
             }
             GoXLRCommand::SetAnimationWaterfall(direction) => {
                 if !self.device_supports_animations() {
@@ -1662,23 +3453,41 @@ impl<'a> Device<'a> {
                 }
 
                 self.profile.set_animation_waterfall(direction)?;
-                self.load_animation(false)?;
+                self.load_animation(false).await?;
             }
 
+            GoXLRCommand::SetGlobalBrightness(brightness) => {
+                if brightness > 100 {
+                    bail!("Brightness must be a percentage");
+                }
+                self.brightness = brightness;
+                self.load_colour_map().await?;
+
+                self.settings
+                    .set_device_brightness(self.serial(), brightness)
+                    .await;
+                self.settings.save().await;
+            }
             GoXLRCommand::SetGlobalColour(colour) => {
                 self.profile.set_global_colour(colour)?;
-                self.load_colour_map()?;
-                self.update_button_states()?;
-                self.set_all_fader_display_from_profile()?;
+                self.load_colour_map().await?;
+                self.update_button_states().await?;
+                self.set_all_fader_display_from_profile().await?;
+            }
+            GoXLRCommand::SetColourTheme(primary, accent) => {
+                self.profile.set_colour_theme(primary, accent)?;
+                self.load_colour_map().await?;
+                self.update_button_states().await?;
+                self.set_all_fader_display_from_profile().await?;
             }
             GoXLRCommand::SetFaderDisplayStyle(fader, display) => {
                 self.profile.set_fader_display(fader, display)?;
-                self.set_fader_display_from_profile(fader)?;
+                self.set_fader_display_from_profile(fader).await?;
             }
             GoXLRCommand::SetFaderColours(fader, top, bottom) => {
                 // Need to get the fader colour map, and set values..
                 self.profile.set_fader_colours(fader, top, bottom)?;
-                self.load_colour_map()?;
+                self.load_colour_map().await?;
             }
             GoXLRCommand::SetAllFaderColours(top, bottom) => {
                 // I considered this as part of SetFaderColours, but spamming a new colour map
@@ -1688,12 +3497,12 @@ impl<'a> Device<'a> {
                     self.profile
                         .set_fader_colours(fader, top.to_owned(), bottom.to_owned())?;
                 }
-                self.load_colour_map()?;
+                self.load_colour_map().await?;
             }
             GoXLRCommand::SetAllFaderDisplayStyle(display_style) => {
                 for fader in FaderName::iter() {
                     self.profile.set_fader_display(fader, display_style)?;
-                    self.set_fader_display_from_profile(fader)?;
+                    self.set_fader_display_from_profile(fader).await?;
                 }
             }
             GoXLRCommand::SetButtonColours(target, colour, colour2) => {
@@ -1701,61 +3510,77 @@ impl<'a> Device<'a> {
                     .set_button_colours(target, colour, colour2.as_ref())?;
 
                 // Reload the colour map and button states..
-                self.load_colour_map()?;
-                self.update_button_states()?;
+                self.load_colour_map().await?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::SetButtonOffStyle(target, off_style) => {
                 self.profile.set_button_off_style(target, off_style)?;
 
-                self.load_colour_map()?;
-                self.update_button_states()?;
+                self.load_colour_map().await?;
+                self.update_button_states().await?;
+            }
+            GoXLRCommand::SetButtonHoldStyle(target, hold_style) => {
+                self.profile.set_button_hold_style(target, hold_style)?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::SetButtonGroupColours(target, colour, colour_2) => {
                 self.profile
                     .set_group_button_colours(target, colour, colour_2)?;
 
-                self.load_colour_map()?;
-                self.update_button_states()?;
+                self.load_colour_map().await?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::SetButtonGroupOffStyle(target, off_style) => {
                 self.profile.set_group_button_off_style(target, off_style)?;
-                self.load_colour_map()?;
-                self.update_button_states()?;
+                self.load_colour_map().await?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::SetSimpleColour(target, colour) => {
                 self.profile.set_simple_colours(target, colour)?;
-                self.load_colour_map()?;
-                self.update_button_states()?;
+                self.load_colour_map().await?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::SetEncoderColour(target, colour, colour_2, colour_3) => {
                 self.profile
                     .set_encoder_colours(target, colour, colour_2, colour_3)?;
-                self.load_colour_map()?;
+                self.load_colour_map().await?;
             }
             GoXLRCommand::SetSampleColour(target, colour, colour_2, colour_3) => {
                 self.profile
                     .set_sampler_colours(target, colour, colour_2, colour_3)?;
                 self.profile.sync_sample_if_active(target)?;
-                self.load_colour_map()?;
+                self.load_colour_map().await?;
             }
             GoXLRCommand::SetSampleOffStyle(target, style) => {
                 self.profile.set_sampler_off_style(target, style)?;
-                self.load_colour_map()?;
-                self.update_button_states()?;
+                self.load_colour_map().await?;
+                self.update_button_states().await?;
             }
 
             // Effects
             GoXLRCommand::LoadEffectPreset(name) => {
                 let presets_directory = self.settings.get_presets_directory().await;
-                self.profile.load_preset(name, vec![&presets_directory])?;
-
                 let current_effect_bank = self.profile.get_active_effect_bank();
+                self.profile
+                    .load_preset(name, vec![&presets_directory], current_effect_bank)?;
 
                 // Force a reload of this effect bank..
                 // TODO: This is slightly sloppy, as it will make unneeded changes.
                 // TODO: Loading a profile should be separate from an 'event'.
                 self.load_effect_bank(current_effect_bank).await?;
-                self.update_button_states()?;
+                self.update_button_states().await?;
+            }
+
+            GoXLRCommand::LoadEffectPresetToBank(name, bank) => {
+                let presets_directory = self.settings.get_presets_directory().await;
+                self.profile
+                    .load_preset(name, vec![&presets_directory], bank)?;
+
+                // Only the active bank is live on the device, others are applied when selected.
+                if bank == self.profile.get_active_effect_bank() {
+                    self.load_effect_bank(bank).await?;
+                    self.update_button_states().await?;
+                }
             }
 
             GoXLRCommand::RenameActivePreset(name) => {
@@ -1787,10 +3612,29 @@ impl<'a> Device<'a> {
                 self.profile.write_preset(name, &preset_directory)?;
             }
 
+            GoXLRCommand::SaveActivePresetAs(name) => {
+                let preset_directory = self.settings.get_presets_directory().await;
+                let current = self
+                    .profile
+                    .profile()
+                    .settings()
+                    .context()
+                    .selected_effects();
+                self.profile
+                    .profile_mut()
+                    .settings_mut()
+                    .effects_mut(current)
+                    .set_name(name.clone())?;
+
+                self.profile
+                    .write_preset(name.replace(' ', "_"), &preset_directory)?;
+            }
+
             // Reverb
             GoXLRCommand::SetReverbStyle(style) => {
                 self.profile.set_reverb_style(style)?;
-                self.apply_effects(self.mic_profile.get_reverb_keyset())?;
+                self.apply_effects(self.mic_profile.get_reverb_keyset())
+                    .await?;
             }
             GoXLRCommand::SetReverbAmount(amount) => {
                 self.profile
@@ -1799,74 +3643,87 @@ impl<'a> Device<'a> {
 
                 let encoder_value = self.profile.get_reverb_value();
                 self.goxlr
-                    .set_encoder_value(EncoderName::Reverb, encoder_value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbAmount]))?;
+                    .set_encoder_value(EncoderName::Reverb, encoder_value)
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbAmount]))
+                    .await?;
             }
             GoXLRCommand::SetReverbDecay(value) => {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_decay_millis(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbDecay]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbDecay]))
+                    .await?;
             }
             GoXLRCommand::SetReverbEarlyLevel(value) => {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_early_level(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbEarlyLevel]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbEarlyLevel]))
+                    .await?;
             }
             GoXLRCommand::SetReverbTailLevel(value) => {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_tail_level(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbTailLevel]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbTailLevel]))
+                    .await?;
             }
             GoXLRCommand::SetReverbPreDelay(value) => {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_predelay(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbPredelay]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbPredelay]))
+                    .await?;
             }
             GoXLRCommand::SetReverbLowColour(value) => {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_low_color(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbLowColor]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbLowColor]))
+                    .await?;
             }
             GoXLRCommand::SetReverbHighColour(value) => {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_hi_color(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbHighColor]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbHighColor]))
+                    .await?;
             }
             GoXLRCommand::SetReverbHighFactor(value) => {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_hi_factor(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbHighFactor]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbHighFactor]))
+                    .await?;
             }
             GoXLRCommand::SetReverbDiffuse(value) => {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_diffuse(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbDiffuse]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbDiffuse]))
+                    .await?;
             }
             GoXLRCommand::SetReverbModSpeed(value) => {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_mod_speed(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbModSpeed]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbModSpeed]))
+                    .await?;
             }
             GoXLRCommand::SetReverbModDepth(value) => {
                 self.profile
                     .get_active_reverb_profile_mut()
                     .set_mod_depth(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbModDepth]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::ReverbModDepth]))
+                    .await?;
             }
 
             // Echo..
             GoXLRCommand::SetEchoStyle(value) => {
                 self.profile.set_echo_style(value)?;
-                self.apply_effects(self.mic_profile.get_echo_keyset())?;
+                self.apply_effects(self.mic_profile.get_echo_keyset())
+                    .await?;
             }
             GoXLRCommand::SetEchoAmount(value) => {
                 self.profile
@@ -1875,68 +3732,80 @@ impl<'a> Device<'a> {
 
                 let encoder_value = self.profile.get_echo_value();
                 self.goxlr
-                    .set_encoder_value(EncoderName::Echo, encoder_value)?;
+                    .set_encoder_value(EncoderName::Echo, encoder_value)
+                    .await?;
 
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoAmount]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoAmount]))
+                    .await?;
             }
             GoXLRCommand::SetEchoFeedback(value) => {
                 self.profile
                     .get_active_echo_profile_mut()
                     .set_feedback(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoFeedback]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoFeedback]))
+                    .await?;
             }
             GoXLRCommand::SetEchoTempo(value) => {
                 self.profile
                     .get_active_echo_profile_mut()
                     .set_tempo(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoTempo]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoTempo]))
+                    .await?;
             }
             GoXLRCommand::SetEchoDelayLeft(value) => {
                 self.profile
                     .get_active_echo_profile_mut()
                     .set_time_left(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoDelayL]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoDelayL]))
+                    .await?;
             }
             GoXLRCommand::SetEchoDelayRight(value) => {
                 self.profile
                     .get_active_echo_profile_mut()
                     .set_time_right(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoDelayR]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoDelayR]))
+                    .await?;
             }
             GoXLRCommand::SetEchoFeedbackLeft(value) => {
                 self.profile
                     .get_active_echo_profile_mut()
                     .set_feedback_left(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoFeedbackL]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoFeedbackL]))
+                    .await?;
             }
             GoXLRCommand::SetEchoFeedbackRight(value) => {
                 self.profile
                     .get_active_echo_profile_mut()
                     .set_feedback_right(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoFeedbackR]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoFeedbackR]))
+                    .await?;
             }
             GoXLRCommand::SetEchoFeedbackXFBRtoL(value) => {
                 self.profile
                     .get_active_echo_profile_mut()
                     .set_xfb_r_to_l(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoXFBRtoL]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoXFBRtoL]))
+                    .await?;
             }
             GoXLRCommand::SetEchoFeedbackXFBLtoR(value) => {
                 self.profile
                     .get_active_echo_profile_mut()
                     .set_xfb_l_to_r(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoXFBLtoR]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::EchoXFBLtoR]))
+                    .await?;
             }
 
             // Pitch
             GoXLRCommand::SetPitchStyle(value) => {
                 self.profile.set_pitch_style(value)?;
-                self.set_pitch_mode()?;
+                self.set_pitch_mode().await?;
 
                 // Force set the encoder position, when going from Wide -> Narrow, the encoder
                 // will still return it's 'Wide' value during polls which error out otherwise.
                 let value = self.profile.get_pitch_encoder_position();
-                self.goxlr.set_encoder_value(EncoderName::Pitch, value)?;
+                self.goxlr
+                    .set_encoder_value(EncoderName::Pitch, value)
+                    .await?;
             }
             GoXLRCommand::SetPitchAmount(value) => {
                 let hard_tune_enabled = self.profile.is_hardtune_enabled(true);
@@ -1945,67 +3814,82 @@ impl<'a> Device<'a> {
                     .set_knob_position(value, hard_tune_enabled)?;
 
                 let value = self.profile.get_pitch_encoder_position();
-                self.goxlr.set_encoder_value(EncoderName::Pitch, value)?;
+                self.goxlr
+                    .set_encoder_value(EncoderName::Pitch, value)
+                    .await?;
 
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::PitchAmount]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::PitchAmount]))
+                    .await?;
             }
             GoXLRCommand::SetPitchCharacter(value) => {
                 self.profile
                     .get_active_pitch_profile_mut()
                     .set_inst_ratio(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::PitchCharacter]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::PitchCharacter]))
+                    .await?;
             }
 
             // Gender
             GoXLRCommand::SetGenderStyle(value) => {
                 self.profile.set_gender_style(value)?;
-                self.apply_effects(self.mic_profile.get_gender_keyset())?;
+                self.apply_effects(self.mic_profile.get_gender_keyset())
+                    .await?;
             }
             GoXLRCommand::SetGenderAmount(value) => {
                 self.profile
                     .get_active_gender_profile_mut()
                     .set_amount(value)?;
                 let value = self.profile.get_gender_value();
-                self.goxlr.set_encoder_value(EncoderName::Gender, value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GenderAmount]))?;
+                self.goxlr
+                    .set_encoder_value(EncoderName::Gender, value)
+                    .await?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::GenderAmount]))
+                    .await?;
             }
 
             GoXLRCommand::SetMegaphoneStyle(value) => {
                 self.profile.set_megaphone_style(value)?;
-                self.apply_effects(self.mic_profile.get_megaphone_keyset())?;
+                self.apply_effects(self.mic_profile.get_megaphone_keyset())
+                    .await?;
             }
             GoXLRCommand::SetMegaphoneAmount(value) => {
                 self.profile
                     .get_active_megaphone_profile_mut()
                     .set_trans_dist_amt(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::MegaphoneAmount]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::MegaphoneAmount]))
+                    .await?;
             }
             GoXLRCommand::SetMegaphonePostGain(value) => {
                 self.profile
                     .get_active_megaphone_profile_mut()
                     .set_trans_postgain(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::MegaphonePostGain]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::MegaphonePostGain]))
+                    .await?;
             }
 
             // Robot
             GoXLRCommand::SetRobotStyle(value) => {
                 self.profile.set_robot_style(value)?;
-                self.apply_effects(self.mic_profile.get_robot_keyset())?;
+                self.apply_effects(self.mic_profile.get_robot_keyset())
+                    .await?;
             }
             GoXLRCommand::SetRobotGain(range, value) => {
                 let profile = self.profile.get_active_robot_profile_mut();
                 match range {
                     RobotRange::Low => {
                         profile.set_vocoder_low_gain(value)?;
-                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotLowGain]))?;
+                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotLowGain]))
+                            .await?;
                     }
                     RobotRange::Medium => {
                         profile.set_vocoder_mid_gain(value)?;
-                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotMidGain]))?;
+                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotMidGain]))
+                            .await?;
                     }
                     RobotRange::High => {
                         profile.set_vocoder_high_gain(value)?;
-                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotHiGain]))?;
+                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotHiGain]))
+                            .await?;
                     }
                 }
             }
@@ -2014,15 +3898,18 @@ impl<'a> Device<'a> {
                 match range {
                     RobotRange::Low => {
                         profile.set_vocoder_low_freq(value)?;
-                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotLowFreq]))?;
+                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotLowFreq]))
+                            .await?;
                     }
                     RobotRange::Medium => {
                         profile.set_vocoder_mid_freq(value)?;
-                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotMidFreq]))?;
+                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotMidFreq]))
+                            .await?;
                     }
                     RobotRange::High => {
                         profile.set_vocoder_high_freq(value)?;
-                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotHiFreq]))?;
+                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotHiFreq]))
+                            .await?;
                     }
                 }
             }
@@ -2031,15 +3918,18 @@ impl<'a> Device<'a> {
                 match range {
                     RobotRange::Low => {
                         profile.set_vocoder_low_bw(value)?;
-                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotLowWidth]))?;
+                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotLowWidth]))
+                            .await?;
                     }
                     RobotRange::Medium => {
                         profile.set_vocoder_mid_bw(value)?;
-                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotMidWidth]))?;
+                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotMidWidth]))
+                            .await?;
                     }
                     RobotRange::High => {
                         profile.set_vocoder_high_bw(value)?;
-                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotHiWidth]))?;
+                        self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotHiWidth]))
+                            .await?;
                     }
                 }
             }
@@ -2047,49 +3937,74 @@ impl<'a> Device<'a> {
                 self.profile
                     .get_active_robot_profile_mut()
                     .set_synthosc_waveform(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotWaveform]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotWaveform]))
+                    .await?;
             }
             GoXLRCommand::SetRobotPulseWidth(value) => {
                 self.profile
                     .get_active_robot_profile_mut()
                     .set_synthosc_pulse_width(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotPulseWidth]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotPulseWidth]))
+                    .await?;
             }
             GoXLRCommand::SetRobotThreshold(value) => {
                 self.profile
                     .get_active_robot_profile_mut()
                     .set_vocoder_gate_threshold(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotThreshold]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotThreshold]))
+                    .await?;
             }
             GoXLRCommand::SetRobotDryMix(value) => {
                 self.profile
                     .get_active_robot_profile_mut()
                     .set_dry_mix(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotDryMix]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::RobotDryMix]))
+                    .await?;
             }
 
             // Hard Tune
             GoXLRCommand::SetHardTuneStyle(value) => {
                 self.profile.set_hardtune_style(value)?;
-                self.apply_effects(self.mic_profile.get_hardtune_keyset())?;
+                self.apply_effects(self.mic_profile.get_hardtune_keyset())
+                    .await?;
             }
             GoXLRCommand::SetHardTuneAmount(value) => {
                 self.profile
                     .get_active_hardtune_profile_mut()
                     .set_amount(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneAmount]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneAmount]))
+                    .await?;
             }
             GoXLRCommand::SetHardTuneRate(value) => {
                 self.profile
                     .get_active_hardtune_profile_mut()
                     .set_rate(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneRate]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneRate]))
+                    .await?;
             }
             GoXLRCommand::SetHardTuneWindow(value) => {
                 self.profile
                     .get_active_hardtune_profile_mut()
                     .set_window(value)?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneWindow]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneWindow]))
+                    .await?;
+            }
+            GoXLRCommand::SetEncoderValue(encoder, value) => {
+                let mut encoders = [
+                    self.profile.get_pitch_encoder_position(),
+                    self.profile.get_gender_value(),
+                    self.profile.get_reverb_value(),
+                    self.profile.get_echo_value(),
+                ];
+                encoders[encoder as usize] = value;
+                self.update_encoders_to(encoders).await?;
+
+                // Move the dial on the device to match, pitch may have been adjusted for HardTune
+                let position = match encoder {
+                    EncoderName::Pitch => self.profile.get_pitch_encoder_position(),
+                    _ => value,
+                };
+                self.goxlr.set_encoder_value(encoder, position).await?;
             }
             GoXLRCommand::SetHardTuneSource(value) => {
                 if self.profile.get_hardtune_source() == value {
@@ -2102,22 +4017,23 @@ impl<'a> Device<'a> {
                     self.profile.set_hardtune_source(value)?;
 
                     // One way or another, we need to update all the inputs..
-                    self.apply_routing(BasicInputDevice::Music)?;
-                    self.apply_routing(BasicInputDevice::Game)?;
-                    self.apply_routing(BasicInputDevice::LineIn)?;
-                    self.apply_routing(BasicInputDevice::System)?;
+                    self.apply_routing(BasicInputDevice::Music).await?;
+                    self.apply_routing(BasicInputDevice::Game).await?;
+                    self.apply_routing(BasicInputDevice::LineIn).await?;
+                    self.apply_routing(BasicInputDevice::System).await?;
                 } else {
                     let current = self.profile.get_active_hardtune_source();
                     self.profile.set_hardtune_source(value)?;
                     let new = self.profile.get_active_hardtune_source();
 
                     // Remove from current, add to New.
-                    self.apply_routing(current)?;
-                    self.apply_routing(new)?;
+                    self.apply_routing(current).await?;
+                    self.apply_routing(new).await?;
                 }
 
                 // TODO: Check this..
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneKeySource]))?;
+                self.apply_effects(LinkedHashSet::from_iter([EffectKey::HardTuneKeySource]))
+                    .await?;
             }
 
             // Sampler..
@@ -2126,47 +4042,28 @@ impl<'a> Device<'a> {
             }
             GoXLRCommand::SetSamplerFunction(bank, button, function) => {
                 self.profile.set_sampler_function(bank, button, function);
+                self.queued_samples[bank][button].clear();
+
+                // A looping sample would otherwise keep running under the new mode, so stop
+                // anything playing on the button and let the next press use the new behaviour.
+                if let Some(audio_handler) = &mut self.audio_handler {
+                    if audio_handler.is_sample_playing(bank, button) {
+                        audio_handler.stop_playback(bank, button, false).await?;
+                    }
+                }
             }
             GoXLRCommand::SetSamplerOrder(bank, button, order) => {
                 self.profile.set_sampler_play_order(bank, button, order);
             }
             GoXLRCommand::AddSample(bank, button, filename) => {
-                let path = self
-                    .get_path_for_sample(PathBuf::from(filename.clone()))
-                    .await?;
-
-                // If we have an audio handler, try to calcuate the Gain..
-                if let Some(audio_handler) = &mut self.audio_handler {
-                    if audio_handler.is_calculating() {
-                        bail!("Gain Calculation already in progress..");
-                    }
-
-                    // V2 Here, this technically still blocks in it's current state, however, it
-                    // doesn't have to anymore.
-                    audio_handler.calculate_gain_thread(path, bank, button)?;
-
-                    // // This method will block until complete, but will give us a result..
-                    // let result = audio_handler.get_and_clear_calculating_result()?;
-                    //
-                    // // We can process any errors which occurred here..
-                    // if let Err(error) = result.result {
-                    //     bail!("Error Handling Sample: {}", error);
-                    // };
-                    //
-                    // let bank = result.bank;
-                    // let button = result.button;
-                    //
-                    // // Multi-part breakdown :p
-                    // let filename = result.file.file_name().unwrap();
-                    // let filename = filename.to_string_lossy().to_string();
-                    //
-                    // // Add the Track..
-                    // let track = self.profile.add_sample_file(bank, button, filename);
-                    // track.normalized_gain = result.gain;
-                }
-
-                // Update the lighting..
-                self.load_colour_map()?;
+                self.add_sample(bank, button, &filename).await?;
+            }
+            GoXLRCommand::ImportSample(bank, button, source) => {
+                let filename = self.import_sample_file(bank, button, &source).await?;
+                self.add_sample(bank, button, &filename).await?;
+            }
+            GoXLRCommand::RelinkSamples() => {
+                self.relink_samples().await;
             }
             GoXLRCommand::SetSampleStartPercent(bank, button, index, percent) => {
                 self.profile
@@ -2176,14 +4073,19 @@ impl<'a> Device<'a> {
                 self.profile
                     .set_sample_stop_pct(bank, button, index, percent)?;
             }
+            GoXLRCommand::SetSamplePlaybackSpeed(bank, button, index, speed) => {
+                self.profile
+                    .set_sample_playback_speed(bank, button, index, speed)?;
+            }
             GoXLRCommand::RemoveSampleByIndex(bank, button, index) => {
                 let remaining = self
                     .profile
                     .remove_sample_file_by_index(bank, button, index)?;
 
                 if remaining == 0 {
-                    self.load_colour_map()?;
+                    self.load_colour_map().await?;
                 }
+                self.validate_sampler().await?;
             }
             GoXLRCommand::PlaySampleByIndex(bank, button, index) => {
                 self.play_audio_file(
@@ -2193,16 +4095,20 @@ impl<'a> Device<'a> {
                     false,
                 )
                 .await?;
-                self.update_button_states()?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::PlayNextSample(bank, button) => {
-                let track = self.profile.get_track_by_bank_button(bank, button)?;
-                self.play_audio_file(bank, button, track, false).await?;
-                self.update_button_states()?;
+                if let Some(track) = self.get_next_available_track(bank, button).await? {
+                    self.play_audio_file(bank, button, track, false).await?;
+                }
+                self.update_button_states().await?;
             }
             GoXLRCommand::StopSamplePlayback(bank, button) => {
                 self.stop_sample_playback(bank, button).await?;
-                self.update_button_states()?;
+                self.update_button_states().await?;
+            }
+            GoXLRCommand::ClearSampleButton(bank, button) => {
+                self.clear_sample_button(bank, button).await?;
             }
 
             GoXLRCommand::SetScribbleIcon(fader, icon) => {
@@ -2221,6 +4127,13 @@ impl<'a> Device<'a> {
                 self.profile.set_scribble_inverted(fader, inverted);
                 self.apply_scribble(fader).await?;
             }
+            GoXLRCommand::SetScribbleStyle(fader, style) => {
+                self.settings
+                    .set_device_scribble_style(self.serial(), fader, style)
+                    .await;
+                self.settings.save().await;
+                self.apply_scribble(fader).await?;
+            }
 
             // Profiles
             GoXLRCommand::NewProfile(profile_name) => {
@@ -2260,6 +4173,31 @@ impl<'a> Device<'a> {
                     self.settings.save().await;
                 }
             }
+            GoXLRCommand::LoadProfileFromFile(path, save_change) => {
+                let profile = ProfileAdapter::from_file(&path)?;
+
+                // Check we can copy it before changing anything..
+                let profile_directory = self.settings.get_profile_directory().await;
+                if save_change {
+                    ProfileAdapter::can_create_new_file(
+                        profile.name().to_owned(),
+                        &profile_directory,
+                    )?;
+                }
+
+                self.stop_all_samples().await?;
+                let volumes = self.profile.get_current_state();
+                self.profile = profile;
+                self.apply_profile(Some(volumes)).await?;
+
+                if save_change {
+                    self.profile.save(&profile_directory, false)?;
+                    self.settings
+                        .set_device_profile_name(self.serial(), self.profile.name())
+                        .await;
+                    self.settings.save().await;
+                }
+            }
             GoXLRCommand::LoadProfileColours(profile_name) => {
                 debug!("Loading Colours For Profile: {}", profile_name);
                 let profile_directory = self.settings.get_profile_directory().await;
@@ -2267,17 +4205,23 @@ impl<'a> Device<'a> {
                 debug!("Profile Loaded, Applying Colours..");
                 self.profile.load_colour_profile(profile);
 
+                // The fader display styles come across with the colours, so need resending..
+                self.set_all_fader_display_from_profile().await?;
                 if self.device_supports_animations() {
-                    self.load_animation(false)?;
+                    self.load_animation(false).await?;
                 } else {
-                    self.load_colour_map()?;
+                    self.load_colour_map().await?;
                 }
-                self.update_button_states()?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::SaveProfile() => {
                 let profile_directory = self.settings.get_profile_directory().await;
                 self.profile.save(&profile_directory, true)?;
             }
+            GoXLRCommand::ExportProfile(profile_name) => {
+                let profile_directory = self.settings.get_profile_directory().await;
+                self.profile.export(profile_name, &profile_directory)?;
+            }
             GoXLRCommand::SaveProfileAs(profile_name) => {
                 let profile_directory = self.settings.get_profile_directory().await;
 
@@ -2340,10 +4284,37 @@ impl<'a> Device<'a> {
                     self.settings.save().await;
                 }
             }
+            GoXLRCommand::LoadMicProfileFromFile(path, save_change) => {
+                let mic_profile = MicProfileAdapter::from_file(&path)?;
+
+                let mic_profile_directory = self.settings.get_mic_profile_directory().await;
+                if save_change {
+                    MicProfileAdapter::can_create_new_file(
+                        mic_profile.name().to_owned(),
+                        &mic_profile_directory,
+                    )?;
+                }
+
+                self.mic_profile = mic_profile;
+                self.apply_mic_profile().await?;
+
+                if save_change {
+                    self.mic_profile.save(&mic_profile_directory, false)?;
+                    self.settings
+                        .set_device_mic_profile_name(self.serial(), self.mic_profile.name())
+                        .await;
+                    self.settings.save().await;
+                }
+            }
             GoXLRCommand::SaveMicProfile() => {
                 let mic_profile_directory = self.settings.get_mic_profile_directory().await;
                 self.mic_profile.save(&mic_profile_directory, true)?;
             }
+            GoXLRCommand::ExportMicProfile(profile_name) => {
+                let mic_profile_directory = self.settings.get_mic_profile_directory().await;
+                self.mic_profile
+                    .export(profile_name, &mic_profile_directory)?;
+            }
             GoXLRCommand::SaveMicProfileAs(profile_name) => {
                 let profile_directory = self.settings.get_mic_profile_directory().await;
                 MicProfileAdapter::can_create_new_file(profile_name.clone(), &profile_directory)?;
@@ -2351,31 +4322,77 @@ impl<'a> Device<'a> {
                 self.mic_profile
                     .save_as(profile_name.clone(), &profile_directory, false)?;
 
-                // Save the new name in the settings
+                // Save the new name in the settings
+                self.settings
+                    .set_device_mic_profile_name(self.serial(), profile_name.as_str())
+                    .await;
+
+                self.settings.save().await;
+            }
+            GoXLRCommand::DeleteMicProfile(profile_name) => {
+                if self.mic_profile.name() == profile_name {
+                    bail!("Unable to Remove Active Profile!");
+                }
+
+                let profile_directory = self.settings.get_mic_profile_directory().await;
+                self.mic_profile
+                    .delete_profile(profile_name.clone(), &profile_directory)?;
+            }
+
+            GoXLRCommand::SetMuteHoldDuration(duration) => {
+                self.hold_time = duration;
+                self.settings
+                    .set_device_mute_hold_duration(self.serial(), duration)
+                    .await;
+                self.settings.save().await;
+            }
+
+            GoXLRCommand::SetButtonHoldDuration(button, duration) => {
+                if let Some(duration) = duration {
+                    self.button_hold_times.insert(button, duration);
+                } else {
+                    self.button_hold_times.remove(&button);
+                }
+                self.settings
+                    .set_device_button_hold_time(self.serial(), button, duration)
+                    .await;
+                self.settings.save().await;
+            }
+
+            GoXLRCommand::SetDucking(ducking) => {
+                self.set_ducking(ducking).await?;
+            }
+            GoXLRCommand::SetPushToTalk(push_to_talk) => {
+                self.set_push_to_talk(push_to_talk).await?;
+            }
+            GoXLRCommand::SetVoiceActivity(voice_activity) => {
+                self.set_voice_activity(voice_activity).await?;
+            }
+            GoXLRCommand::SetFaderMeter(fader_meter) => {
+                self.set_fader_meter(fader_meter).await?;
+            }
+            GoXLRCommand::SetIdleLighting(idle) => {
+                self.set_idle_lighting(idle).await?;
+            }
+
+            GoXLRCommand::SetRestoreRuntimeState(enabled) => {
                 self.settings
-                    .set_device_mic_profile_name(self.serial(), profile_name.as_str())
+                    .set_device_restore_runtime_state(self.serial(), enabled)
                     .await;
-
                 self.settings.save().await;
             }
-            GoXLRCommand::DeleteMicProfile(profile_name) => {
-                if self.mic_profile.name() == profile_name {
-                    bail!("Unable to Remove Active Profile!");
+            GoXLRCommand::SetProfileVolumeFade(duration) => {
+                if duration > MAX_PROFILE_VOLUME_FADE {
+                    bail!(
+                        "Profile Volume Fade must be at most {}ms",
+                        MAX_PROFILE_VOLUME_FADE
+                    );
                 }
-
-                let profile_directory = self.settings.get_mic_profile_directory().await;
-                self.mic_profile
-                    .delete_profile(profile_name.clone(), &profile_directory)?;
-            }
-
-            GoXLRCommand::SetMuteHoldDuration(duration) => {
-                self.hold_time = duration;
                 self.settings
-                    .set_device_mute_hold_duration(self.serial(), duration)
+                    .set_device_profile_volume_fade(self.serial(), duration)
                     .await;
                 self.settings.save().await;
             }
-
             GoXLRCommand::SetVCMuteAlsoMuteCM(value) => {
                 self.vc_mute_also_mute_cm = value;
                 self.settings
@@ -2386,34 +4403,43 @@ impl<'a> Device<'a> {
 
             GoXLRCommand::SetActiveEffectPreset(preset) => {
                 self.load_effect_bank(preset).await?;
-                self.update_button_states()?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::SetActiveSamplerBank(bank) => {
                 self.load_sample_bank(bank).await?;
-                self.load_colour_map()?;
+                self.load_colour_map().await?;
             }
             GoXLRCommand::SetMegaphoneEnabled(enabled) => {
                 self.set_megaphone(enabled).await?;
-                self.update_button_states()?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::SetRobotEnabled(enabled) => {
                 self.set_robot(enabled).await?;
-                self.update_button_states()?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::SetHardTuneEnabled(enabled) => {
                 self.set_hardtune(enabled).await?;
-                self.update_button_states()?;
+                self.update_button_states().await?;
             }
             GoXLRCommand::SetFXEnabled(enabled) => {
                 self.set_effects(enabled).await?;
-                self.update_button_states()?;
+                self.update_button_states().await?;
+            }
+            GoXLRCommand::MuteAllToStream() => {
+                self.set_stream_muted(true).await?;
+            }
+            GoXLRCommand::RestoreStreamAudio() => {
+                self.set_stream_muted(false).await?;
+            }
+            GoXLRCommand::SetPushToTalkActive(active) => {
+                self.set_push_to_talk_active(active).await?;
             }
             GoXLRCommand::SetFaderMuteState(fader, state) => match state {
                 MuteState::Unmuted => self.unmute_fader(fader).await?,
                 MuteState::MutedToX => self.mute_fader_to_x(fader).await?,
                 MuteState::MutedToAll => self.mute_fader_to_all(fader, true).await?,
             },
-            GoXLRCommand::SetCoughMuteState(_state) => {}
+            GoXLRCommand::SetCoughMuteState(state) => self.set_cough_mute_state(state).await?,
             GoXLRCommand::SetSubMixEnabled(enabled) => {
                 let headphones = goxlr_types::OutputDevice::Headphones;
                 if self.profile.is_submix_enabled() != enabled {
@@ -2421,42 +4447,327 @@ impl<'a> Device<'a> {
                         // Submixes are being disabled, we need to revert the monitor..
                         self.profile.set_monitor_mix(headphones)?;
                         for device in BasicInputDevice::iter() {
-                            self.apply_routing(device)?;
+                            self.apply_routing(device).await?;
                         }
                     }
 
                     self.profile.set_submix_enabled(enabled)?;
-                    self.load_submix_settings(true)?;
+                    self.load_submix_settings(true).await?;
                 }
             }
             GoXLRCommand::SetSubMixVolume(channel, volume) => {
-                self.apply_submix_volume(channel, volume)?;
+                self.apply_submix_volume(channel, volume).await?;
             }
             GoXLRCommand::SetSubMixLinked(channel, linked) => {
                 self.link_submix_channel(channel, linked)?;
             }
             GoXLRCommand::SetSubMixOutputMix(device, mix) => {
                 self.profile.set_mix_output(device, mix)?;
-                self.load_submix_settings(false)?;
+                self.load_submix_settings(false).await?;
             }
             GoXLRCommand::SetMonitorMix(device) => {
                 self.profile.set_monitor_mix(device)?;
 
                 // Might be a cleaner way to do this, we only need to handle 1 output..
                 for device in BasicInputDevice::iter() {
-                    self.apply_routing(device)?;
+                    self.apply_routing(device).await?;
                 }
 
                 // Make sure to switch Headphones from A to B if needed.
-                self.load_submix_settings(false)?;
+                self.load_submix_settings(false).await?;
+            }
+
+            GoXLRCommand::SetChannelTemplate(channel, template) => {
+                self.settings
+                    .set_device_channel_template(self.serial(), channel, template)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetSchedule(name, schedule) => {
+                if let Some(schedule) = &schedule {
+                    if let Err(error) = cron::Schedule::from_str(&schedule.cron) {
+                        bail!("Invalid Schedule {}: {}", schedule.cron, error);
+                    }
+                }
+                self.settings
+                    .set_device_schedule(self.serial(), &name, schedule)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::RunMacro(name) => {
+                self.run_macro(&name).await?;
+            }
+            GoXLRCommand::CreateBackup(name) => {
+                let backups_directory = self.settings.get_backups_directory().await;
+                let path = get_backup_path(&backups_directory, &name)?;
+                create_dir_all(&path)?;
+                self.profile.save(&path, true)?;
+                self.mic_profile.save(&path, true)?;
+
+                let settings = self.settings.export_device_settings(self.serial()).await?;
+                fs::write(path.join(BACKUP_SETTINGS_FILE), settings)?;
+                info!("[{}] Backup {} created in {:?}", self.serial(), name, path);
+            }
+            GoXLRCommand::RestoreBackup(name) => {
+                let backups_directory = self.settings.get_backups_directory().await;
+                let path = get_backup_path(&backups_directory, &name)?;
+                if !path.is_dir() {
+                    bail!("Backup {} does not exist", name);
+                }
+
+                let settings = fs::read_to_string(path.join(BACKUP_SETTINGS_FILE))
+                    .context("Unable to read Settings Backup")?;
+
+                let serial = self.serial().to_owned();
+                let previous = self.settings.export_device_settings(&serial).await?;
+                let (profile_name, mic_profile_name) = self
+                    .settings
+                    .import_device_settings(&serial, &settings)
+                    .await?;
+
+                // If the profiles are missing from the backup, put the old settings back..
+                let profiles =
+                    ProfileAdapter::from_named(profile_name, &path).and_then(|profile| {
+                        Ok((
+                            profile,
+                            MicProfileAdapter::from_named(mic_profile_name, &path)?,
+                        ))
+                    });
+                let (mut profile, mut mic_profile) = match profiles {
+                    Ok(profiles) => profiles,
+                    Err(error) => {
+                        self.settings
+                            .import_device_settings(&serial, &previous)
+                            .await?;
+                        return Err(error);
+                    }
+                };
+
+                // Copy them into the profile directories, so they're there on next start..
+                let profile_directory = self.settings.get_profile_directory().await;
+                let mic_profile_directory = self.settings.get_mic_profile_directory().await;
+                profile.save(&profile_directory, true)?;
+                mic_profile.save(&mic_profile_directory, true)?;
+                self.settings.save().await;
+
+                self.stop_all_samples().await?;
+                let volumes = self.profile.get_current_state();
+                self.profile = profile;
+                self.mic_profile = mic_profile;
+                self.reload_settings().await;
+
+                self.apply_profile(Some(volumes)).await?;
+                self.apply_mic_profile().await?;
+                info!("[{}] Backup {} restored", serial, name);
+            }
+            GoXLRCommand::UpdateFirmware(path) => {
+                let update = FirmwareUpdate::new(&path, &self.hardware.device_type)?;
+                info!("[{}] Starting Firmware Update", self.serial());
+                self.firmware_update = Some(update);
+            }
+            GoXLRCommand::TakeSnapshot(name) => {
+                let snapshot = self.take_snapshot()?;
+                self.snapshots.insert(name, snapshot);
+            }
+            GoXLRCommand::RestoreSnapshot(name) => {
+                let snapshot = match self.snapshots.get(&name) {
+                    Some(snapshot) => snapshot.clone(),
+                    None => bail!("Snapshot {} does not exist", name),
+                };
+                self.restore_snapshot(&snapshot, false).await?;
+            }
+            GoXLRCommand::Undo() | GoXLRCommand::Redo() => {}
+        }
+        Ok(())
+    }
+
+    // Runs the commands against a detached simulated device, then puts everything back, returning
+    // the changes which would have been made to the status.
+    pub async fn dry_run(&mut self, commands: Vec<GoXLRCommand>) -> Result<Patch> {
+        if self.firmware_update.is_some() {
+            bail!("A Firmware Update is in progress on this device");
+        }
+
+        let capabilities = self.hardware.device_type.capabilities();
+        for command in &commands {
+            if !is_command_supported(command, &capabilities) {
+                bail!(DaemonError::CommandNotSupportedOnMini);
             }
+            if !can_dry_run(command) {
+                bail!("Unable to Dry Run {:?}", command);
+            }
+        }
+
+        let device_type = match self.hardware.device_type {
+            DeviceType::Mini => SimulatedDeviceType::Mini,
+            _ => SimulatedDeviceType::Full,
+        };
+
+        let before = serde_json::to_value(self.status().await)?;
+        let snapshot = self.take_snapshot()?;
+        let state = self.take_dry_run_state();
+
+        // Nothing should reach the hardware, and nothing should be announced..
+        let (events, _) = mpsc::channel(1);
+        let goxlr = std::mem::replace(&mut self.goxlr, create_detached_device(device_type));
+        let global_events = std::mem::replace(&mut self.global_events, events);
+
+        // Everything which can fail happens in here, so the device is always put back below
+        let result = self.run_detached(commands).await;
+
+        self.goxlr = goxlr;
+        self.global_events = global_events;
+        self.restore_dry_run_state(state);
+        self.profile.restore_snapshot(&snapshot.profile)?;
+        self.mic_profile.restore_snapshot(&snapshot.mic_profile)?;
+
+        let after = result?;
+        Ok(diff(&before, &after))
+    }
+
+    async fn run_detached(&mut self, commands: Vec<GoXLRCommand>) -> Result<Value> {
+        for command in commands {
+            self.execute_command(command).await?;
+        }
+        Ok(serde_json::to_value(self.status().await)?)
+    }
+
+    fn take_dry_run_state(&self) -> DryRunState {
+        DryRunState {
+            events: self.events.clone(),
+            last_buttons: self.last_buttons,
+            button_states: self.button_states,
+            volume_fades: self.volume_fades,
+            fader_last_seen: self.fader_last_seen,
+            fader_pause_until: self.fader_pause_until,
+            volume_announcements: self.volume_announcements,
+            ducking_state: self.ducking_state.clone(),
+            stream_muted: self.stream_muted,
+            push_to_talk_active: self.push_to_talk_active,
+            push_to_talk_release: self.push_to_talk_release,
+            mic_active: self.mic_active,
+            last_interaction: self.last_interaction,
+            is_idle: self.is_idle,
         }
+    }
+
+    fn restore_dry_run_state(&mut self, state: DryRunState) {
+        self.events = state.events;
+        self.last_buttons = state.last_buttons;
+        self.button_states = state.button_states;
+        self.volume_fades = state.volume_fades;
+        self.fader_last_seen = state.fader_last_seen;
+        self.fader_pause_until = state.fader_pause_until;
+        self.volume_announcements = state.volume_announcements;
+        self.ducking_state = state.ducking_state;
+        self.stream_muted = state.stream_muted;
+        self.push_to_talk_active = state.push_to_talk_active;
+        self.push_to_talk_release = state.push_to_talk_release;
+        self.mic_active = state.mic_active;
+        self.last_interaction = state.last_interaction;
+        self.is_idle = state.is_idle;
+    }
+
+    pub fn take_snapshot(&mut self) -> Result<DeviceSnapshot> {
+        Ok(DeviceSnapshot {
+            profile: self.profile.snapshot()?,
+            mic_profile: self.mic_profile.snapshot()?,
+        })
+    }
+
+    async fn undo(&mut self) -> Result<()> {
+        let entry = match self.history.take_undo() {
+            Some(entry) => entry,
+            None => bail!("Nothing to Undo"),
+        };
+
+        let current = self.take_snapshot()?;
+        let keep_live = !is_live_state_command(&entry.command);
+        self.restore_snapshot(&entry.snapshot, keep_live).await?;
+        debug!("Undone {:?}", entry.command);
+
+        self.history.push_redo(HistoryEntry {
+            command: entry.command,
+            snapshot: current,
+        });
+        Ok(())
+    }
+
+    async fn redo(&mut self) -> Result<()> {
+        let entry = match self.history.take_redo() {
+            Some(entry) => entry,
+            None => bail!("Nothing to Redo"),
+        };
+
+        let current = self.take_snapshot()?;
+        let keep_live = !is_live_state_command(&entry.command);
+        self.restore_snapshot(&entry.snapshot, keep_live).await?;
+        debug!("Redone {:?}", entry.command);
+
+        self.history.push_undo(HistoryEntry {
+            command: entry.command,
+            snapshot: current,
+        });
         Ok(())
     }
 
-    fn update_button_states(&mut self) -> Result<()> {
+    // When keep_live is set, the current volumes and mutes are kept rather than restored, as
+    // they'll have moved with the hardware since the snapshot was taken.
+    pub async fn restore_snapshot(
+        &mut self,
+        snapshot: &DeviceSnapshot,
+        keep_live: bool,
+    ) -> Result<()> {
+        let live_state = self.profile.get_runtime_state();
+        let current = self.profile.get_current_state();
+
+        // The scribble images aren't part of a snapshot, so they need to come from the profile..
+        if snapshot.profile.name() != self.profile.name() {
+            let profile_directory = self.settings.get_profile_directory().await;
+            let name = snapshot.profile.name().to_owned();
+            self.stop_all_samples().await?;
+            self.profile = ProfileAdapter::from_named(name, &profile_directory)?;
+        }
+
+        // Likewise switch back to the snapshot's mic profile, so its settings aren't saved over
+        // whichever mic profile happens to be loaded now..
+        if snapshot.mic_profile.name() != self.mic_profile.name() {
+            let mic_profile_directory = self.settings.get_mic_profile_directory().await;
+            let name = snapshot.mic_profile.name().to_owned();
+            self.mic_profile = MicProfileAdapter::from_named(name, &mic_profile_directory)?;
+        }
+
+        self.profile.restore_snapshot(&snapshot.profile)?;
+        self.mic_profile.restore_snapshot(&snapshot.mic_profile)?;
+        if keep_live {
+            self.profile.apply_runtime_state(&live_state)?;
+        }
+
+        self.apply_profile(Some(current)).await?;
+        self.apply_mic_profile().await
+    }
+
+    // Re-reads the device specific settings which are held on the Device.
+    pub async fn reload_settings(&mut self) {
+        let serial = self.hardware.serial_number.clone();
+        self.hold_time = self.settings.get_device_hold_time(&serial).await;
+        self.button_hold_times = self.settings.get_device_button_hold_times(&serial).await;
+        self.vc_mute_also_mute_cm = self
+            .settings
+            .get_device_chat_mute_mutes_mic_to_chat(&serial)
+            .await;
+        self.ducking = self.settings.get_device_ducking(&serial).await;
+        self.push_to_talk = self.settings.get_device_push_to_talk(&serial).await;
+        self.voice_activity = self.settings.get_device_voice_activity(&serial).await;
+        self.fader_meter = self.settings.get_device_fader_meter(&serial).await;
+        self.fader_meter_level = None;
+        self.brightness = self.settings.get_device_brightness(&serial).await;
+        self.idle_lighting = self.settings.get_device_idle_lighting(&serial).await;
+    }
+
+    async fn update_button_states(&mut self) -> Result<()> {
         let button_states = self.create_button_states();
-        self.goxlr.set_button_states(button_states)?;
+        self.goxlr.set_button_states(button_states).await?;
         Ok(())
     }
 
@@ -2469,11 +4780,18 @@ impl<'a> Device<'a> {
 
         // Replace the Cough Button button data with correct data.
         result[Buttons::MicrophoneMute as usize] = self.profile.get_mute_chat_button_colour_state();
+
+        // Buttons being held may have their own style..
+        for button in self.last_buttons {
+            if let Some(state) = self.profile.get_button_hold_state(button) {
+                result[button as usize] = state;
+            }
+        }
         result
     }
 
     // This applies routing for a single input channel..
-    fn apply_channel_routing(
+    async fn apply_channel_routing(
         &mut self,
         input: BasicInputDevice,
         router: EnumMap<BasicOutputDevice, bool>,
@@ -2514,9 +4832,30 @@ impl<'a> Device<'a> {
             }
         }
 
-        self.goxlr.set_routing(left_input, left)?;
-        self.goxlr.set_routing(right_input, right)?;
+        self.goxlr.set_routing(left_input, left).await?;
+        self.goxlr.set_routing(right_input, right).await?;
+
+        Ok(())
+    }
+
+    async fn set_stream_muted(&mut self, muted: bool) -> Result<()> {
+        if self.stream_muted == muted {
+            return Ok(());
+        }
+
+        self.stream_muted = muted;
+        for input in BasicInputDevice::iter() {
+            self.apply_routing(input).await?;
+        }
 
+        let message = match muted {
+            true => "Stream Muted",
+            false => "Stream Restored",
+        };
+        let _ = self
+            .global_events
+            .send(TTSMessage(message.to_string()))
+            .await;
         Ok(())
     }
 
@@ -2549,6 +4888,11 @@ impl<'a> Device<'a> {
             self.apply_transient_chat_mic_mute(router)?;
         }
 
+        // While the stream is muted, nothing gets through to the Broadcast Mix..
+        if self.stream_muted {
+            router[BasicOutputDevice::BroadcastMix] = false;
+        }
+
         self.apply_transient_cough_routing(channel_name, router)
     }
 
@@ -2639,7 +4983,102 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
-    fn apply_routing(&mut self, input: BasicInputDevice) -> Result<()> {
+    async fn load_routing_preset(&mut self, name: &str) -> Result<()> {
+        let presets = self
+            .settings
+            .get_device_routing_presets(self.serial())
+            .await;
+        let preset = match presets.get(name) {
+            Some(preset) => preset,
+            None => bail!("Routing Preset {} does not exist", name),
+        };
+
+        // Check everything first, so we don't end up with half a preset applied..
+        for (input, outputs) in &preset.routes {
+            for output in outputs.keys() {
+                validate_route(*input, *output)?;
+            }
+        }
+
+        debug!("Loading Routing Preset {}", name);
+        for (input, outputs) in &preset.routes {
+            for (output, enabled) in outputs {
+                self.profile.set_routing(*input, *output, *enabled)?;
+            }
+        }
+
+        // Each input only needs sending to the GoXLR once, regardless of how many routes changed.
+        for input in preset.routes.keys() {
+            self.apply_routing(*input).await?;
+        }
+        Ok(())
+    }
+
+    fn validate_volume_preset(&self, preset: &VolumePreset) -> Result<()> {
+        let capabilities = self.hardware.device_type.capabilities();
+        for channel in preset.volumes.keys().chain(preset.mutes.keys()) {
+            if !capabilities.has_channel(*channel) {
+                bail!(DaemonError::ChannelNotAvailable(*channel));
+            }
+        }
+        Ok(())
+    }
+
+    fn get_fader_mute_state(&self, fader: FaderName) -> MuteState {
+        let (muted_to_x, muted_to_all, _) = self.profile.get_mute_button_state(fader);
+        if muted_to_all {
+            MuteState::MutedToAll
+        } else if muted_to_x {
+            MuteState::MutedToX
+        } else {
+            MuteState::Unmuted
+        }
+    }
+
+    async fn load_volume_preset(&mut self, name: &str) -> Result<()> {
+        let presets = self.settings.get_device_volume_presets(self.serial()).await;
+        let preset = match presets.get(name) {
+            Some(preset) => preset,
+            None => bail!("Volume Preset {} does not exist", name),
+        };
+        self.validate_volume_preset(preset)?;
+
+        debug!("Loading Volume Preset {}", name);
+        for (channel, volume) in &preset.volumes {
+            self.volume_fades[*channel] = None;
+            self.set_channel_volume(*channel, *volume).await?;
+        }
+
+        let capabilities = self.hardware.device_type.capabilities();
+        for fader in FaderName::iter() {
+            if !capabilities.has_fader(fader) {
+                continue;
+            }
+            let channel = self.profile.get_fader_assignment(fader);
+            let state = match preset.mutes.get(&channel) {
+                Some(state) => *state,
+                None => continue,
+            };
+
+            let current = self.get_fader_mute_state(fader);
+            if current == state {
+                continue;
+            }
+
+            // Moving between mute types needs an unmute first, otherwise it's rejected..
+            if current != MuteState::Unmuted {
+                self.unmute_fader(fader).await?;
+            }
+            match state {
+                MuteState::Unmuted => {}
+                MuteState::MutedToX => self.mute_fader_to_x(fader).await?,
+                MuteState::MutedToAll => self.mute_fader_to_all(fader, true).await?,
+            }
+        }
+        Ok(())
+    }
+
+    async fn apply_routing(&mut self, input: BasicInputDevice) -> Result<()> {
         // Load the routing for this channel from the profile..
         let mut router = self.profile.get_router(input);
         self.apply_transient_routing(input, &mut router)?;
@@ -2651,12 +5090,12 @@ impl<'a> Device<'a> {
             router[BasicOutputDevice::Headphones] = router[monitor];
         }
 
-        self.apply_channel_routing(input, router)?;
+        self.apply_channel_routing(input, router).await?;
 
         Ok(())
     }
 
-    fn apply_mute_from_profile(
+    async fn apply_mute_from_profile(
         &mut self,
         fader: FaderName,
         current: Option<ChannelState>,
@@ -2673,13 +5112,13 @@ impl<'a> Device<'a> {
                         "Setting Channel {} to Muted (change from previous)",
                         channel
                     );
-                    self.goxlr.set_channel_state(channel, Muted)?;
+                    self.set_channel_state(channel, Muted).await?;
                 } else {
                     debug!("Fader {} is Already Muted, doing nothing.", fader);
                 }
             } else {
                 debug!("Setting Channel {} to Muted (no previous)", channel);
-                self.goxlr.set_channel_state(channel, Muted)?;
+                self.set_channel_state(channel, Muted).await?;
             }
 
             return Ok(());
@@ -2689,19 +5128,19 @@ impl<'a> Device<'a> {
         if let Some(current) = current {
             if current != Unmuted {
                 debug!("Channel {} set to Unmuted (change from previous)", channel);
-                self.goxlr.set_channel_state(channel, Unmuted)?;
+                self.set_channel_state(channel, Unmuted).await?;
             } else {
                 debug!("Channel {} already Unmuted, doing nothing.", fader);
             }
         } else {
             debug!("Channel {} set to Unmuted (no previous)", channel);
-            self.goxlr.set_channel_state(channel, Unmuted)?;
+            self.set_channel_state(channel, Unmuted).await?;
         }
 
         Ok(())
     }
 
-    fn apply_cough_from_profile(&mut self) -> Result<()> {
+    async fn apply_cough_from_profile(&mut self) -> Result<()> {
         // As above, but applies the cough profile.
         let (mute_toggle, muted_to_x, muted_to_all, mute_function) =
             self.profile.get_mute_chat_button_state();
@@ -2725,10 +5164,10 @@ impl<'a> Device<'a> {
 
         if muted_to_all || (muted_to_x && mute_function == MuteFunction::All) || muted_by_fader {
             debug!("Setting Mic to Muted");
-            self.goxlr.set_channel_state(ChannelName::Mic, Muted)?;
+            self.set_channel_state(ChannelName::Mic, Muted).await?;
         } else {
             debug!("Setting Mic to Unmuted");
-            self.goxlr.set_channel_state(ChannelName::Mic, Unmuted)?;
+            self.set_channel_state(ChannelName::Mic, Unmuted).await?;
         }
         Ok(())
     }
@@ -2741,7 +5180,7 @@ impl<'a> Device<'a> {
                 self.profile.set_mic_fader(fader)?;
             }
 
-            self.goxlr.set_fader(fader, new_channel)?;
+            self.goxlr.set_fader(fader, new_channel).await?;
             return Ok(());
         }
 
@@ -2768,23 +5207,27 @@ impl<'a> Device<'a> {
 
             // Now set the new fader..
             self.profile.set_fader_assignment(fader, new_channel);
-            self.goxlr.set_fader(fader, new_channel)?;
+            self.goxlr.set_fader(fader, new_channel).await?;
 
             // Due to motorised faders, the internal 'old' channel may be incorrectly set,
             // despite our config here being valid. So we'll force update the old channel.
-            self.goxlr.set_volume(
-                existing_channel,
-                self.profile.get_channel_volume(existing_channel),
-            )?;
+            self.goxlr
+                .set_volume(
+                    existing_channel,
+                    self.profile.get_channel_volume(existing_channel),
+                )
+                .await?;
 
             // Make sure the new channel comes in with the correct volume..
             if new_channel == ChannelName::Headphones || new_channel == ChannelName::LineOut {
                 let volume = self.profile.get_channel_volume(new_channel);
-                self.goxlr.set_volume(new_channel, volume)?;
+                self.goxlr.set_volume(new_channel, volume).await?;
             }
 
+            self.apply_channel_template(fader, new_channel).await?;
+
             // Remember to update the button states after change..
-            self.update_button_states()?;
+            self.update_button_states().await?;
 
             return Ok(());
         }
@@ -2807,24 +5250,30 @@ impl<'a> Device<'a> {
         }
 
         // Now switch the faders on the GoXLR..
-        self.goxlr.set_fader(fader, new_channel)?;
-        self.goxlr.set_fader(fader_to_switch, existing_channel)?;
+        self.goxlr.set_fader(fader, new_channel).await?;
+        self.goxlr
+            .set_fader(fader_to_switch, existing_channel)
+            .await?;
 
         // If the channel being moved is either Headphone or Line Out, reset the volume..
         if new_channel == ChannelName::Headphones || new_channel == ChannelName::LineOut {
             let volume = self.profile.get_channel_volume(new_channel);
-            self.goxlr.set_volume(new_channel, volume)?;
+            self.goxlr.set_volume(new_channel, volume).await?;
         }
         if existing_channel == ChannelName::Headphones || existing_channel == ChannelName::LineOut {
             let volume = self.profile.get_channel_volume(existing_channel);
-            self.goxlr.set_volume(existing_channel, volume)?;
+            self.goxlr.set_volume(existing_channel, volume).await?;
         }
 
         self.apply_scribble(fader).await?;
         self.apply_scribble(fader_to_switch).await?;
 
+        self.apply_channel_template(fader, new_channel).await?;
+        self.apply_channel_template(fader_to_switch, existing_channel)
+            .await?;
+
         // Finally update the button colours..
-        self.update_button_states()?;
+        self.update_button_states().await?;
 
         Ok(())
     }
@@ -2840,41 +5289,68 @@ impl<'a> Device<'a> {
         }
     }
 
-    fn set_all_fader_display_from_profile(&mut self) -> Result<()> {
+    async fn set_all_fader_display_from_profile(&mut self) -> Result<()> {
         for fader in FaderName::iter() {
-            self.set_fader_display_from_profile(fader)?;
+            self.set_fader_display_from_profile(fader).await?;
         }
         Ok(())
     }
 
-    fn set_fader_display_from_profile(&mut self, fader: FaderName) -> Result<()> {
-        self.goxlr.set_fader_display_mode(
-            fader,
-            self.profile.is_fader_gradient(fader),
-            self.profile.is_fader_meter(fader),
-        )?;
+    async fn set_fader_display_from_profile(&mut self, fader: FaderName) -> Result<()> {
+        self.goxlr
+            .set_fader_display_mode(
+                fader,
+                self.profile.is_fader_gradient(fader),
+                self.profile.is_fader_meter(fader),
+            )
+            .await?;
         Ok(())
     }
 
-    fn load_colour_map(&mut self) -> Result<()> {
+    async fn load_colour_map(&mut self) -> Result<()> {
         // The new colour format occurred on different firmware versions depending on device,
         // so do the check here.
 
         let use_1_3_40_format = self.device_supports_animations();
-        let colour_map = self.profile.get_colour_map(use_1_3_40_format);
+        let mut colour_map = self.profile.get_colour_map(use_1_3_40_format);
+
+        // Colours are stored as BGRA, scale the colour bytes if we need to..
+        let brightness = self.get_lighting_brightness();
+        if brightness < 100 {
+            for colour in colour_map.chunks_exact_mut(4) {
+                for value in colour.iter_mut().take(3) {
+                    *value = (*value as u16 * brightness as u16 / 100) as u8;
+                }
+            }
+        }
+
+        // In meter mode, faders showing the mic are dimmed down to the current mic level..
+        if self.fader_meter.enabled {
+            let level = self.fader_meter_level.unwrap_or(0) as u16;
+            for fader in FaderName::iter() {
+                if self.profile.get_fader_assignment(fader) != ChannelName::Mic {
+                    continue;
+                }
+                for position in get_fader_colour_positions(fader, use_1_3_40_format) {
+                    for value in colour_map[position..position + 3].iter_mut() {
+                        *value = (*value as u16 * level / FADER_METER_STEPS as u16) as u8;
+                    }
+                }
+            }
+        }
 
         if use_1_3_40_format {
-            self.goxlr.set_button_colours_1_3_40(colour_map)?;
+            self.goxlr.set_button_colours_1_3_40(colour_map).await?;
         } else {
             let mut map: [u8; 328] = [0; 328];
             map.copy_from_slice(&colour_map[0..328]);
-            self.goxlr.set_button_colours(map)?;
+            self.goxlr.set_button_colours(map).await?;
         }
 
         Ok(())
     }
 
-    fn load_animation(&mut self, map_set: bool) -> Result<()> {
+    async fn load_animation(&mut self, map_set: bool) -> Result<()> {
         let enabled = self.profile.get_animation_mode() != goxlr_types::AnimationMode::None;
 
         // This one is kinda weird, we go from profile -> types -> usb..
@@ -2896,14 +5372,15 @@ impl<'a> Device<'a> {
         };
 
         self.goxlr
-            .set_animation_mode(enabled, mode, mod1, mod2, waterfall)?;
+            .set_animation_mode(enabled, mode, mod1, mod2, waterfall)
+            .await?;
 
         if !map_set
             && (mode == AnimationMode::None
                 || mode == AnimationMode::Ripple
                 || mode == AnimationMode::Simple)
         {
-            self.load_colour_map()?;
+            self.load_colour_map().await?;
         }
 
         Ok(())
@@ -2923,13 +5400,13 @@ impl<'a> Device<'a> {
             if let Some(current) = &current {
                 if current.faders[fader] != assignment {
                     debug!("Setting Fader {} to {:?}", fader, assignment);
-                    self.goxlr.set_fader(fader, assignment)?;
+                    self.goxlr.set_fader(fader, assignment).await?;
                 } else {
                     debug!("Fader Already Assigned, ignoring");
                 }
             } else {
                 debug!("Setting Fader {} to {:?}", fader, assignment);
-                self.goxlr.set_fader(fader, assignment)?;
+                self.goxlr.set_fader(fader, assignment).await?;
             }
 
             // Force Mic Fader Assignment
@@ -2946,22 +5423,23 @@ impl<'a> Device<'a> {
         for channel in ChannelName::iter() {
             if channel == ChannelName::Mic {
                 debug!("Applying Microphone Mute State");
-                self.apply_cough_from_profile()?;
+                self.apply_cough_from_profile().await?;
             } else if let Some(fader) = self.profile.get_fader_from_channel(channel) {
                 debug!("Channel {} on Fader, Loading State from Profile", channel);
                 if let Some(current) = &current {
-                    self.apply_mute_from_profile(fader, Some(current.mute_state[channel]))?;
+                    self.apply_mute_from_profile(fader, Some(current.mute_state[channel]))
+                        .await?;
                 } else {
-                    self.apply_mute_from_profile(fader, None)?;
+                    self.apply_mute_from_profile(fader, None).await?;
                 }
             } else if let Some(current) = &current {
                 if current.mute_state[channel] != Unmuted {
                     debug!("Channel {} not on Fader, but muted. Unmuting..", channel);
-                    self.goxlr.set_channel_state(channel, Unmuted)?;
+                    self.set_channel_state(channel, Unmuted).await?;
                 }
             } else {
                 debug!("Unknown Channel state for {}, Unmuting.", channel);
-                self.goxlr.set_channel_state(channel, Unmuted)?;
+                self.set_channel_state(channel, Unmuted).await?;
             }
         }
 
@@ -2972,28 +5450,56 @@ impl<'a> Device<'a> {
             self.get_load_volume_order(None)
         };
 
+        let fade_time = self
+            .settings
+            .get_device_profile_volume_fade(self.serial())
+            .await;
         for channel in volumes {
             let channel_volume = self.profile.get_channel_volume(channel);
 
+            // Any fade in progress was for the old profile..
+            self.volume_fades[channel] = None;
+
+            // Large jumps up can be sudden and loud, so hold the current volume and fade from it..
+            if let Some(current) = &current {
+                let previous = current.volumes[channel];
+                if fade_time > 0 && channel_volume > previous.saturating_add(PROFILE_FADE_THRESHOLD)
+                {
+                    debug!(
+                        "Fading volume for {} from {} to {}",
+                        channel, previous, channel_volume
+                    );
+                    self.goxlr.set_volume(channel, previous).await?;
+                    self.profile.set_channel_volume(channel, previous)?;
+                    self.volume_fades[channel] = Some(VolumeFade {
+                        from: previous,
+                        to: channel_volume,
+                        start: self.get_epoch_ms(),
+                        duration: fade_time.into(),
+                    });
+                    continue;
+                }
+            }
+
             debug!("Setting volume for {} to {}", channel, channel_volume);
-            self.goxlr.set_volume(channel, channel_volume)?;
+            self.goxlr.set_volume(channel, channel_volume).await?;
         }
 
         debug!("Applying Submixing Settings..");
-        self.load_submix_settings(true)?;
+        self.load_submix_settings(true).await?;
 
         debug!("Loading Colour Map..");
-        self.load_colour_map()?;
+        self.load_colour_map().await?;
 
         if self.device_supports_animations() {
             // Load any animation settings..
-            self.load_animation(true)?;
+            self.load_animation(true).await?;
         }
 
         debug!("Setting Fader display modes..");
         for fader in FaderName::iter() {
             debug!("Setting display for {}", fader);
-            self.set_fader_display_from_profile(fader)?;
+            self.set_fader_display_from_profile(fader).await?;
         }
 
         if self.hardware.device_type == DeviceType::Full {
@@ -3003,21 +5509,22 @@ impl<'a> Device<'a> {
         }
 
         debug!("Updating button states..");
-        self.update_button_states()?;
+        self.update_button_states().await?;
 
         debug!("Applying Routing..");
         // For profile load, we should configure all the input channels from the profile,
         // this is split so we can do tweaks in places where needed.
         for input in BasicInputDevice::iter() {
-            self.apply_routing(input)?;
+            self.apply_routing(input).await?;
         }
 
         debug!("Applying Voice FX");
-        self.apply_voice_fx()?;
+        self.apply_voice_fx().await?;
 
         // Drop this to the end so it doesn't directly interfere with profile loading..
         debug!("Validating Sampler Configuration..");
         self.validate_sampler().await?;
+        self.relink_samples().await;
 
         Ok(())
     }
@@ -3072,16 +5579,16 @@ impl<'a> Device<'a> {
 
     /// Applies a Set of Microphone Parameters based on input, designed this way
     /// so that commands and other abstract entities can apply a subset of params
-    fn apply_mic_params(&mut self, params: HashSet<MicrophoneParamKey>) -> Result<()> {
+    async fn apply_mic_params(&mut self, params: HashSet<MicrophoneParamKey>) -> Result<()> {
         let mut vec = Vec::new();
         for param in params {
             vec.push((param, self.mic_profile.get_param_value(param)));
         }
-        self.goxlr.set_mic_param(vec.as_slice())?;
+        self.goxlr.set_mic_param(vec.as_slice()).await?;
         Ok(())
     }
 
-    fn apply_effects(&mut self, params: LinkedHashSet<EffectKey>) -> Result<()> {
+    async fn apply_effects(&mut self, params: LinkedHashSet<EffectKey>) -> Result<()> {
         let mut vec = Vec::new();
         for effect in params {
             vec.push((
@@ -3094,11 +5601,11 @@ impl<'a> Device<'a> {
             let (key, value) = effect;
             debug!("Setting {:?} to {}", key, value);
         }
-        self.goxlr.set_effect_values(vec.as_slice())?;
+        self.goxlr.set_effect_values(vec.as_slice()).await?;
         Ok(())
     }
 
-    fn apply_voice_fx(&mut self) -> Result<()> {
+    async fn apply_voice_fx(&mut self) -> Result<()> {
         if self.hardware.device_type == DeviceType::Mini {
             // Voice FX aren't present on the mini.
             return Ok(());
@@ -3112,26 +5619,26 @@ impl<'a> Device<'a> {
         send_keys.extend(fx_keys);
 
         // Apply these settings..
-        self.apply_effects(send_keys)?;
+        self.apply_effects(send_keys).await?;
 
         // Apply any Pitch / Encoder related Effects
-        self.set_pitch_mode()?;
-        self.load_encoder_effects()?;
+        self.set_pitch_mode().await?;
+        self.load_encoder_effects().await?;
 
         Ok(())
     }
 
-    fn apply_mic_gain(&mut self) -> Result<()> {
+    async fn apply_mic_gain(&mut self) -> Result<()> {
         let mic_type = self.mic_profile.mic_type();
         let gain = self.mic_profile.mic_gains()[mic_type];
-        self.goxlr.set_microphone_gain(mic_type, gain)?;
+        self.goxlr.set_microphone_gain(mic_type, gain).await?;
 
         Ok(())
     }
 
     async fn apply_mic_profile(&mut self) -> Result<()> {
         // Configure the microphone..
-        self.apply_mic_gain()?;
+        self.apply_mic_gain().await?;
 
         let mut keys = HashSet::new();
         for param in MicrophoneParamKey::iter() {
@@ -3143,57 +5650,114 @@ impl<'a> Device<'a> {
         keys.remove(&MicrophoneParamKey::CondenserGain);
         keys.remove(&MicrophoneParamKey::JackGain);
         keys.insert(self.mic_profile.mic_type().get_gain_param());
-        self.apply_mic_params(keys)?;
+        self.apply_mic_params(keys).await?;
 
         let mut keys = LinkedHashSet::new();
         keys.extend(self.mic_profile.get_mic_keys());
 
-        self.apply_effects(keys)?;
+        self.apply_effects(keys).await?;
 
         Ok(())
     }
 
-    fn load_encoder_effects(&mut self) -> Result<()> {
+    async fn load_encoder_effects(&mut self) -> Result<()> {
         // For now, we'll simply set the knob positions, more to come!
         let mut value = self.profile.get_pitch_encoder_position();
-        self.goxlr.set_encoder_value(EncoderName::Pitch, value)?;
+        self.goxlr
+            .set_encoder_value(EncoderName::Pitch, value)
+            .await?;
 
         value = self.profile.get_echo_value();
-        self.goxlr.set_encoder_value(EncoderName::Echo, value)?;
+        self.goxlr
+            .set_encoder_value(EncoderName::Echo, value)
+            .await?;
 
         value = self.profile.get_gender_value();
-        self.goxlr.set_encoder_value(EncoderName::Gender, value)?;
+        self.goxlr
+            .set_encoder_value(EncoderName::Gender, value)
+            .await?;
 
         value = self.profile.get_reverb_value();
-        self.goxlr.set_encoder_value(EncoderName::Reverb, value)?;
+        self.goxlr
+            .set_encoder_value(EncoderName::Reverb, value)
+            .await?;
+
+        Ok(())
+    }
+
+    // Updates the fader's colours and scribble from the channel's template, if one is set.
+    async fn apply_channel_template(
+        &mut self,
+        fader: FaderName,
+        channel: ChannelName,
+    ) -> Result<()> {
+        let templates = self
+            .settings
+            .get_device_channel_templates(self.serial())
+            .await;
+        let template = match templates.get(&channel) {
+            Some(template) => template.clone(),
+            None => return Ok(()),
+        };
+
+        if template.top_colour.is_some() || template.bottom_colour.is_some() {
+            let (top, bottom) = self.profile.get_fader_colours(fader);
+            self.profile.set_fader_colours(
+                fader,
+                template.top_colour.unwrap_or(top),
+                template.bottom_colour.unwrap_or(bottom),
+            )?;
+            self.load_colour_map().await?;
+        }
+
+        // Only the full device has scribble strips..
+        if self.hardware.device_type != DeviceType::Full {
+            return Ok(());
+        }
 
+        if template.scribble_icon.is_some() || template.scribble_text.is_some() {
+            if let Some(icon) = template.scribble_icon {
+                self.profile.set_scribble_icon(fader, Some(icon));
+            }
+            if let Some(text) = template.scribble_text {
+                self.profile.set_scribble_text(fader, text);
+            }
+            self.apply_scribble(fader).await?;
+        }
         Ok(())
     }
 
     async fn apply_scribble(&mut self, fader: FaderName) -> Result<()> {
         let icon_path = self.settings.get_icons_directory().await;
 
-        let scribble = self.profile.get_scribble_image(fader, &icon_path);
-        self.goxlr.set_fader_scribble(fader, scribble)?;
+        let style = self
+            .settings
+            .get_device_scribble_style(self.serial(), fader)
+            .await;
+
+        let scribble = self.profile.get_scribble_image(fader, &icon_path, &style);
+        self.goxlr.set_fader_scribble(fader, scribble).await?;
 
         Ok(())
     }
 
-    fn set_pitch_mode(&mut self) -> Result<()> {
+    async fn set_pitch_mode(&mut self) -> Result<()> {
         if self.hardware.device_type != DeviceType::Full {
             // Not a Full GoXLR, nothing to do.
             return Ok(());
         }
 
-        self.goxlr.set_encoder_mode(
-            EncoderName::Pitch,
-            self.profile.get_pitch_mode(),
-            self.profile.get_pitch_resolution(),
-        )?;
+        self.goxlr
+            .set_encoder_mode(
+                EncoderName::Pitch,
+                self.profile.get_pitch_mode(),
+                self.profile.get_pitch_resolution(),
+            )
+            .await?;
         Ok(())
     }
 
-    fn load_submix_settings(&mut self, apply_volumes: bool) -> Result<()> {
+    async fn load_submix_settings(&mut self, apply_volumes: bool) -> Result<()> {
         if !self.device_supports_submixes() {
             // Submixes not supported, do nothing.
             return Ok(());
@@ -3211,9 +5775,9 @@ impl<'a> Device<'a> {
                 // We need to make sure the monitor is on the right side..
                 if submix_enabled {
                     let mix = self.profile.get_submix_channel(device);
-                    self.goxlr.set_monitored_mix(mix)?;
+                    self.goxlr.set_monitored_mix(mix).await?;
                 } else {
-                    self.goxlr.set_monitored_mix(Mix::A)?;
+                    self.goxlr.set_monitored_mix(Mix::A).await?;
                 }
 
                 // Monitor Mix handled, move to the next channel
@@ -3235,41 +5799,45 @@ impl<'a> Device<'a> {
         let submix = [mix_a, mix_b].concat();
 
         // This should always be successful, in theory :D
-        self.goxlr.set_channel_mixes(submix.try_into().unwrap())?;
+        self.goxlr
+            .set_channel_mixes(submix.try_into().unwrap())
+            .await?;
 
         if submix_enabled && apply_volumes {
             for channel in ChannelName::iter() {
-                self.sync_submix_volume(channel)?;
+                self.sync_submix_volume(channel).await?;
             }
         }
 
         // If submixes are enabled, the Mic Monitor should be at 100% as monitoring
         // is supposed to be handled by the mix.
         if submix_enabled {
-            self.goxlr.set_volume(ChannelName::MicMonitor, 255)?;
+            self.goxlr.set_volume(ChannelName::MicMonitor, 255).await?;
         } else {
             let volume = self.profile.get_channel_volume(ChannelName::MicMonitor);
-            self.goxlr.set_volume(ChannelName::MicMonitor, volume)?;
+            self.goxlr
+                .set_volume(ChannelName::MicMonitor, volume)
+                .await?;
         }
 
         Ok(())
     }
 
-    fn sync_submix_volume(&mut self, channel: ChannelName) -> Result<()> {
+    async fn sync_submix_volume(&mut self, channel: ChannelName) -> Result<()> {
         if let Some(mix) = self.profile.get_submix_from_channel(channel) {
             if self.profile.is_channel_linked(mix) {
                 // Get the channels volume..
                 let volume = self.profile.get_channel_volume(channel);
-                self.update_submix_for(channel, volume)?;
+                self.update_submix_for(channel, volume).await?;
             } else {
                 let sub_volume = self.profile.get_submix_volume(mix);
-                self.goxlr.set_sub_volume(mix, sub_volume)?;
+                self.goxlr.set_sub_volume(mix, sub_volume).await?;
             }
         }
         Ok(())
     }
 
-    fn apply_submix_volume(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
+    async fn apply_submix_volume(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
         if let Some(mix) = self.profile.get_submix_from_channel(channel) {
             if self.profile.is_channel_linked(mix) {
                 // We need to calculate the new value for the main channel..
@@ -3283,13 +5851,13 @@ impl<'a> Device<'a> {
                         self.fader_pause_until[fader].until = linked_volume;
                     }
                     self.profile.set_channel_volume(channel, linked_volume)?;
-                    self.goxlr.set_volume(channel, linked_volume)?;
+                    self.goxlr.set_volume(channel, linked_volume).await?;
                 }
             }
 
             // Apply the submix volume..
             self.profile.set_submix_volume(mix, volume)?;
-            self.goxlr.set_sub_volume(mix, volume)?;
+            self.goxlr.set_sub_volume(mix, volume).await?;
         }
         Ok(())
     }
@@ -3351,6 +5919,117 @@ impl<'a> Device<'a> {
     }
 }
 
+// Checks whether the device physically has the parts a command targets, sending commands for
+// missing hardware (for example, the sampler on a Mini) will either be ignored or misbehave.
+fn is_command_supported(command: &GoXLRCommand, capabilities: &DeviceCapabilities) -> bool {
+    let sampler = matches!(
+        command,
+        GoXLRCommand::SetSamplerPreBufferDuration(..)
+            | GoXLRCommand::SetSamplerMonitorDevice(..)
+            | GoXLRCommand::SetSamplerNormalizationTarget(..)
+            | GoXLRCommand::SetSamplerMonitorRecording(..)
+            | GoXLRCommand::SetSamplerMaxRecordingLength(..)
+            | GoXLRCommand::SetSamplerRecordingTemplate(..)
+            | GoXLRCommand::SetSamplerQueueNext(..)
+            | GoXLRCommand::SetSampleColour(..)
+            | GoXLRCommand::SetSampleOffStyle(..)
+            | GoXLRCommand::ClearSampleProcessError()
+            | GoXLRCommand::SetSamplerFunction(..)
+            | GoXLRCommand::SetSamplerOrder(..)
+            | GoXLRCommand::AddSample(..)
+            | GoXLRCommand::ImportSample(..)
+            | GoXLRCommand::RelinkSamples()
+            | GoXLRCommand::SetSampleStartPercent(..)
+            | GoXLRCommand::SetSampleStopPercent(..)
+            | GoXLRCommand::SetSamplePlaybackSpeed(..)
+            | GoXLRCommand::RemoveSampleByIndex(..)
+            | GoXLRCommand::PlaySampleByIndex(..)
+            | GoXLRCommand::PlayNextSample(..)
+            | GoXLRCommand::StopSamplePlayback(..)
+            | GoXLRCommand::ClearSampleButton(..)
+            | GoXLRCommand::SetActiveSamplerBank(..)
+    );
+    if sampler && !capabilities.has_sampler {
+        return false;
+    }
+
+    let effects = matches!(
+        command,
+        GoXLRCommand::SetEncoderColour(..)
+            | GoXLRCommand::LoadEffectPreset(..)
+            | GoXLRCommand::RenameActivePreset(..)
+            | GoXLRCommand::SaveActivePreset()
+            | GoXLRCommand::SaveActivePresetAs(..)
+            | GoXLRCommand::LoadEffectPresetToBank(..)
+            | GoXLRCommand::SetReverbStyle(..)
+            | GoXLRCommand::SetReverbAmount(..)
+            | GoXLRCommand::SetReverbDecay(..)
+            | GoXLRCommand::SetReverbEarlyLevel(..)
+            | GoXLRCommand::SetReverbTailLevel(..)
+            | GoXLRCommand::SetReverbPreDelay(..)
+            | GoXLRCommand::SetReverbLowColour(..)
+            | GoXLRCommand::SetReverbHighColour(..)
+            | GoXLRCommand::SetReverbHighFactor(..)
+            | GoXLRCommand::SetReverbDiffuse(..)
+            | GoXLRCommand::SetReverbModSpeed(..)
+            | GoXLRCommand::SetReverbModDepth(..)
+            | GoXLRCommand::SetEchoStyle(..)
+            | GoXLRCommand::SetEchoAmount(..)
+            | GoXLRCommand::SetEchoFeedback(..)
+            | GoXLRCommand::SetEchoTempo(..)
+            | GoXLRCommand::SetEchoDelayLeft(..)
+            | GoXLRCommand::SetEchoDelayRight(..)
+            | GoXLRCommand::SetEchoFeedbackLeft(..)
+            | GoXLRCommand::SetEchoFeedbackRight(..)
+            | GoXLRCommand::SetEchoFeedbackXFBLtoR(..)
+            | GoXLRCommand::SetEchoFeedbackXFBRtoL(..)
+            | GoXLRCommand::SetPitchStyle(..)
+            | GoXLRCommand::SetPitchAmount(..)
+            | GoXLRCommand::SetPitchCharacter(..)
+            | GoXLRCommand::SetGenderStyle(..)
+            | GoXLRCommand::SetGenderAmount(..)
+            | GoXLRCommand::SetMegaphoneStyle(..)
+            | GoXLRCommand::SetMegaphoneAmount(..)
+            | GoXLRCommand::SetMegaphonePostGain(..)
+            | GoXLRCommand::SetRobotStyle(..)
+            | GoXLRCommand::SetRobotGain(..)
+            | GoXLRCommand::SetRobotFreq(..)
+            | GoXLRCommand::SetRobotWidth(..)
+            | GoXLRCommand::SetRobotWaveform(..)
+            | GoXLRCommand::SetRobotPulseWidth(..)
+            | GoXLRCommand::SetRobotThreshold(..)
+            | GoXLRCommand::SetRobotDryMix(..)
+            | GoXLRCommand::SetHardTuneStyle(..)
+            | GoXLRCommand::SetHardTuneAmount(..)
+            | GoXLRCommand::SetHardTuneRate(..)
+            | GoXLRCommand::SetHardTuneWindow(..)
+            | GoXLRCommand::SetHardTuneSource(..)
+            | GoXLRCommand::SetEncoderValue(..)
+            | GoXLRCommand::SetActiveEffectPreset(..)
+            | GoXLRCommand::SetMegaphoneEnabled(..)
+            | GoXLRCommand::SetRobotEnabled(..)
+            | GoXLRCommand::SetHardTuneEnabled(..)
+            | GoXLRCommand::SetFXEnabled(..)
+    );
+    if effects && !capabilities.has_effects {
+        return false;
+    }
+
+    match command {
+        GoXLRCommand::SetFader(fader, _)
+        | GoXLRCommand::SetFaderMuteFunction(fader, _)
+        | GoXLRCommand::SetFaderDisplayStyle(fader, _)
+        | GoXLRCommand::SetFaderColours(fader, _, _)
+        | GoXLRCommand::SetFaderMuteState(fader, _)
+        | GoXLRCommand::SetScribbleIcon(fader, _)
+        | GoXLRCommand::SetScribbleText(fader, _)
+        | GoXLRCommand::SetScribbleNumber(fader, _)
+        | GoXLRCommand::SetScribbleInvert(fader, _)
+        | GoXLRCommand::SetScribbleStyle(fader, _) => capabilities.has_fader(*fader),
+        _ => true,
+    }
+}
+
 fn tts_bool_to_state(bool: bool) -> String {
     match bool {
         true => "On".to_string(),
@@ -3367,3 +6046,209 @@ fn tts_target(target: MuteFunction) -> String {
         MuteFunction::ToLineOut => " to Line Out".to_string(),
     }
 }
+
+fn diagnostic_check(name: &str, passed: bool, detail: String) -> DiagnosticCheck {
+    DiagnosticCheck {
+        name: name.to_string(),
+        passed,
+        detail,
+    }
+}
+
+// Commands which change volumes and mutes, these track the hardware rather than the profile.
+fn is_live_state_command(command: &GoXLRCommand) -> bool {
+    matches!(
+        command,
+        GoXLRCommand::SetVolume(..)
+            | GoXLRCommand::FadeChannelVolume(..)
+            | GoXLRCommand::SetFaderMuteState(..)
+            | GoXLRCommand::SetCoughMuteState(..)
+            | GoXLRCommand::LoadVolumePreset(..)
+    )
+}
+
+// Dry runs only put back the profiles and the Device's own state, so only commands which change
+// nothing else are allowed. Anything touching the settings, files or audio (and any new command,
+// until it's added here) is refused.
+fn can_dry_run(command: &GoXLRCommand) -> bool {
+    matches!(
+        command,
+        GoXLRCommand::SetFader(..)
+            | GoXLRCommand::SetFaderMuteFunction(..)
+            | GoXLRCommand::SetVolume(..)
+            | GoXLRCommand::LoadVolumePreset(..)
+            | GoXLRCommand::SetMicrophoneType(..)
+            | GoXLRCommand::SetMicrophoneGain(..)
+            | GoXLRCommand::SetRouter(..)
+            | GoXLRCommand::LoadRoutingPreset(..)
+            | GoXLRCommand::SetCoughMuteFunction(..)
+            | GoXLRCommand::SetCoughIsHold(..)
+            | GoXLRCommand::SetSwearButtonVolume(..)
+            | GoXLRCommand::SetEqMiniGain(..)
+            | GoXLRCommand::SetEqMiniFreq(..)
+            | GoXLRCommand::SetEqGain(..)
+            | GoXLRCommand::SetEqFreq(..)
+            | GoXLRCommand::SetGateThreshold(..)
+            | GoXLRCommand::SetGateAttenuation(..)
+            | GoXLRCommand::SetGateAttack(..)
+            | GoXLRCommand::SetGateRelease(..)
+            | GoXLRCommand::SetGateActive(..)
+            | GoXLRCommand::SetCompressorThreshold(..)
+            | GoXLRCommand::SetCompressorRatio(..)
+            | GoXLRCommand::SetCompressorAttack(..)
+            | GoXLRCommand::SetCompressorReleaseTime(..)
+            | GoXLRCommand::SetCompressorMakeupGain(..)
+            | GoXLRCommand::SetElementDisplayMode(..)
+            | GoXLRCommand::SetDeeser(..)
+            | GoXLRCommand::SetAnimationMode(..)
+            | GoXLRCommand::SetAnimationMod1(..)
+            | GoXLRCommand::SetAnimationMod2(..)
+            | GoXLRCommand::SetAnimationWaterfall(..)
+            | GoXLRCommand::SetGlobalColour(..)
+            | GoXLRCommand::SetColourTheme(..)
+            | GoXLRCommand::SetFaderDisplayStyle(..)
+            | GoXLRCommand::SetFaderColours(..)
+            | GoXLRCommand::SetAllFaderColours(..)
+            | GoXLRCommand::SetAllFaderDisplayStyle(..)
+            | GoXLRCommand::SetButtonColours(..)
+            | GoXLRCommand::SetButtonOffStyle(..)
+            | GoXLRCommand::SetButtonHoldStyle(..)
+            | GoXLRCommand::SetButtonGroupColours(..)
+            | GoXLRCommand::SetButtonGroupOffStyle(..)
+            | GoXLRCommand::SetSimpleColour(..)
+            | GoXLRCommand::SetEncoderColour(..)
+            | GoXLRCommand::SetSampleColour(..)
+            | GoXLRCommand::SetSampleOffStyle(..)
+            | GoXLRCommand::RenameActivePreset(..)
+            | GoXLRCommand::SetReverbStyle(..)
+            | GoXLRCommand::SetReverbAmount(..)
+            | GoXLRCommand::SetReverbDecay(..)
+            | GoXLRCommand::SetReverbEarlyLevel(..)
+            | GoXLRCommand::SetReverbTailLevel(..)
+            | GoXLRCommand::SetReverbPreDelay(..)
+            | GoXLRCommand::SetReverbLowColour(..)
+            | GoXLRCommand::SetReverbHighColour(..)
+            | GoXLRCommand::SetReverbHighFactor(..)
+            | GoXLRCommand::SetReverbDiffuse(..)
+            | GoXLRCommand::SetReverbModSpeed(..)
+            | GoXLRCommand::SetReverbModDepth(..)
+            | GoXLRCommand::SetEchoStyle(..)
+            | GoXLRCommand::SetEchoAmount(..)
+            | GoXLRCommand::SetEchoFeedback(..)
+            | GoXLRCommand::SetEchoTempo(..)
+            | GoXLRCommand::SetEchoDelayLeft(..)
+            | GoXLRCommand::SetEchoDelayRight(..)
+            | GoXLRCommand::SetEchoFeedbackLeft(..)
+            | GoXLRCommand::SetEchoFeedbackRight(..)
+            | GoXLRCommand::SetEchoFeedbackXFBLtoR(..)
+            | GoXLRCommand::SetEchoFeedbackXFBRtoL(..)
+            | GoXLRCommand::SetPitchStyle(..)
+            | GoXLRCommand::SetPitchAmount(..)
+            | GoXLRCommand::SetPitchCharacter(..)
+            | GoXLRCommand::SetGenderStyle(..)
+            | GoXLRCommand::SetGenderAmount(..)
+            | GoXLRCommand::SetMegaphoneStyle(..)
+            | GoXLRCommand::SetMegaphoneAmount(..)
+            | GoXLRCommand::SetMegaphonePostGain(..)
+            | GoXLRCommand::SetRobotStyle(..)
+            | GoXLRCommand::SetRobotGain(..)
+            | GoXLRCommand::SetRobotFreq(..)
+            | GoXLRCommand::SetRobotWidth(..)
+            | GoXLRCommand::SetRobotWaveform(..)
+            | GoXLRCommand::SetRobotPulseWidth(..)
+            | GoXLRCommand::SetRobotThreshold(..)
+            | GoXLRCommand::SetRobotDryMix(..)
+            | GoXLRCommand::SetHardTuneStyle(..)
+            | GoXLRCommand::SetHardTuneAmount(..)
+            | GoXLRCommand::SetHardTuneRate(..)
+            | GoXLRCommand::SetHardTuneWindow(..)
+            | GoXLRCommand::SetHardTuneSource(..)
+            | GoXLRCommand::SetEncoderValue(..)
+            | GoXLRCommand::SetSamplerOrder(..)
+            | GoXLRCommand::SetSampleStartPercent(..)
+            | GoXLRCommand::SetSampleStopPercent(..)
+            | GoXLRCommand::SetSamplePlaybackSpeed(..)
+            | GoXLRCommand::RemoveSampleByIndex(..)
+            | GoXLRCommand::SetScribbleIcon(..)
+            | GoXLRCommand::SetScribbleText(..)
+            | GoXLRCommand::SetScribbleNumber(..)
+            | GoXLRCommand::SetScribbleInvert(..)
+            | GoXLRCommand::SetActiveEffectPreset(..)
+            | GoXLRCommand::SetActiveSamplerBank(..)
+            | GoXLRCommand::SetMegaphoneEnabled(..)
+            | GoXLRCommand::SetRobotEnabled(..)
+            | GoXLRCommand::SetHardTuneEnabled(..)
+            | GoXLRCommand::SetFXEnabled(..)
+            | GoXLRCommand::SetFaderMuteState(..)
+            | GoXLRCommand::SetCoughMuteState(..)
+            | GoXLRCommand::SetSubMixEnabled(..)
+            | GoXLRCommand::SetSubMixVolume(..)
+            | GoXLRCommand::SetSubMixLinked(..)
+            | GoXLRCommand::SetSubMixOutputMix(..)
+            | GoXLRCommand::SetMonitorMix(..)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use goxlr_usb::commands::Command;
+    use goxlr_usb::device::simulator::device::{add_simulated_device, find_devices};
+    use serde_json::json;
+
+    use crate::primary_worker::open_device;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn set_volume_is_sent_to_the_device() -> Result<()> {
+        // Keep everything the daemon writes inside a temporary directory..
+        let dir = std::env::temp_dir().join(format!("goxlr-device-test-{}", std::process::id()));
+        create_dir_all(&dir)?;
+        let mut settings = json!({ "devices": {} });
+        for name in [
+            "profile",
+            "mic_profile",
+            "samples",
+            "presets",
+            "icons",
+            "logs",
+            "scripts",
+            "backups",
+        ] {
+            settings[format!("{}_directory", name)] = json!(dir.join(name));
+        }
+        let settings_path = dir.join("settings.json");
+        fs::write(&settings_path, settings.to_string())?;
+        let settings = SettingsHandle::load(settings_path).await?;
+
+        let simulator = add_simulated_device(SimulatedDeviceType::Full);
+        let (disconnect_tx, _disconnect_rx) = mpsc::channel(1);
+        let (event_tx, _event_rx) = mpsc::channel(1);
+        let (global_tx, _global_rx) = mpsc::channel(32);
+
+        let usb_device = find_devices().pop().context("Simulated device not found")?;
+        let (goxlr, hardware) = open_device(usb_device, vec![], disconnect_tx, event_tx).await?;
+        let mut device = Device::new(
+            goxlr,
+            hardware,
+            None,
+            None,
+            &dir.join("profile"),
+            &dir.join("mic_profile"),
+            &settings,
+            global_tx,
+        )
+        .await?;
+
+        // Ignore everything sent while the profile was being applied..
+        simulator.take_history();
+        device
+            .perform_command(GoXLRCommand::SetVolume(ChannelName::Music, 128))
+            .await?;
+
+        let history = simulator.take_history();
+        let _ = fs::remove_dir_all(&dir);
+        assert!(history.contains(&(Command::SetChannelVolume(ChannelName::Music), vec![128])));
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 // This file primarily handles 'global' events which may occur inside the daemon from a potential
 // variety of sources, which affect other parts of the daemon.
 
+use crate::platform::show_notification;
 use crate::{SettingsHandle, Shutdown};
 use goxlr_ipc::{HttpSettings, PathTypes};
 use log::{debug, warn};
@@ -8,7 +9,7 @@ use std::process::{Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::{select, signal};
+use tokio::{select, signal, task};
 
 #[derive(Debug)]
 pub enum EventTriggers {
@@ -18,6 +19,7 @@ pub enum EventTriggers {
     Activate,
     OpenUi,
     DevicesStopped,
+    SystemResumed,
 }
 
 #[derive(Clone)]
@@ -40,6 +42,7 @@ pub async fn spawn_event_handler(
     state: DaemonState,
     mut rx: Receiver<EventTriggers>,
     device_stop_tx: Sender<()>,
+    device_resume_tx: Sender<()>,
 ) {
     let mut triggered_device_stop = false;
     debug!("Starting Event Loop..");
@@ -57,6 +60,14 @@ pub async fn spawn_event_handler(
             Some(event) = rx.recv() => {
                 match event {
                     EventTriggers::TTSMessage(message) => {
+                        if state.settings_handle.get_notifications_enabled().await {
+                            let notification = message.clone();
+                            task::spawn_blocking(move || {
+                                if let Err(error) = show_notification(&notification) {
+                                    warn!("Unable to Show Notification: {}", error);
+                                }
+                            });
+                        }
                         let _ = state.tts_sender.send(message).await;
                     }
                     EventTriggers::Stop => {
@@ -74,6 +85,10 @@ pub async fn spawn_event_handler(
                         state.shutdown_blocking.store(true, Ordering::Relaxed);
                         break;
                     }
+                    EventTriggers::SystemResumed => {
+                        debug!("System Resumed from Sleep, Re-initialising Devices..");
+                        let _ = device_resume_tx.send(()).await;
+                    }
                     EventTriggers::Open(path_type) => {
                         if let Err(error) = opener::open(match path_type {
                             PathTypes::Profiles => state.settings_handle.get_profile_directory().await,
@@ -11,16 +11,19 @@ use std::collections::BTreeMap;
 use std::ffi::OsString;
 use std::fs;
 use std::fs::{create_dir_all, File};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::process::{Command, Stdio};
 
 use anyhow::{anyhow, bail, Context, Result};
+use chrono::Local;
 // use futures::channel::mpsc::{channel, Receiver};
 // use futures::{SinkExt, StreamExt};
 use log::{debug, info, warn};
 
 use glob::glob;
-use goxlr_ipc::PathTypes;
+use goxlr_audio::metadata::get_audio_file_info;
+use goxlr_ipc::{PathTypes, SampleInfo};
+use goxlr_types::{SampleBank, SampleButtons};
 use notify::event::{CreateKind, ModifyKind, RemoveKind, RenameMode};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use tokio::sync::mpsc;
@@ -29,7 +32,7 @@ use tokio::sync::mpsc::{Receiver, Sender};
 use crate::{SettingsHandle, Shutdown};
 
 // This should probably be handled with an EnumSet..
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FilePaths {
     pub profiles: PathBuf,
     pub mic_profiles: PathBuf,
@@ -225,10 +228,17 @@ impl FileManager {
     pub fn paths(&self) -> &FilePaths {
         &self.paths
     }
+
+    // Called when the directories have been changed in the settings file..
+    pub fn set_paths(&mut self, paths: FilePaths) {
+        FileManager::create_paths(&paths);
+        self.paths = paths;
+    }
 }
 
 pub async fn spawn_file_notification_service(
-    paths: FilePaths,
+    mut paths: FilePaths,
+    mut paths_rx: Receiver<FilePaths>,
     sender: Sender<PathTypes>,
     mut shutdown_signal: Shutdown,
 ) -> Result<()> {
@@ -242,21 +252,7 @@ pub async fn spawn_file_notification_service(
     let (mut watcher, mut rx) = watcher.unwrap();
 
     // Add the Paths to the Watcher..
-    if let Err(error) = watcher.watch(&paths.profiles, RecursiveMode::NonRecursive) {
-        warn!("Unable to Monitor Profiles Path: {:?}", error);
-    }
-    if let Err(error) = watcher.watch(&paths.mic_profiles, RecursiveMode::NonRecursive) {
-        warn!("Unable to Monitor the Microphone Profile Path {:?}", error);
-    }
-    if let Err(error) = watcher.watch(&paths.presets, RecursiveMode::NonRecursive) {
-        warn!("Unable to Monitor the Presets Path: {:?}", error)
-    }
-    if let Err(error) = watcher.watch(&paths.icons, RecursiveMode::NonRecursive) {
-        warn!("Unable to monitor the Icons Path: {:?}", error);
-    }
-    if let Err(error) = watcher.watch(&paths.samples, RecursiveMode::Recursive) {
-        warn!("Unable to Monitor the Samples Path: {:?}", error);
-    }
+    watch_paths(&mut watcher, &paths);
 
     // Wait for any changes..
     loop {
@@ -265,6 +261,14 @@ pub async fn spawn_file_notification_service(
                 debug!("Shutdown Signal Received.");
                 break;
             },
+            Some(new_paths) = paths_rx.recv() => {
+                debug!("File Paths Changed, updating Watcher..");
+                for path in [&paths.profiles, &paths.mic_profiles, &paths.presets, &paths.icons, &paths.samples] {
+                    let _ = watcher.unwatch(path);
+                }
+                paths = new_paths;
+                watch_paths(&mut watcher, &paths);
+            },
             result = rx.recv() => {
                 if let Some(result) = result {
                     match result {
@@ -328,7 +332,26 @@ pub async fn spawn_file_notification_service(
     Ok(())
 }
 
-fn create_watcher() -> notify::Result<(RecommendedWatcher, Receiver<notify::Result<Event>>)> {
+fn watch_paths(watcher: &mut RecommendedWatcher, paths: &FilePaths) {
+    if let Err(error) = watcher.watch(&paths.profiles, RecursiveMode::NonRecursive) {
+        warn!("Unable to Monitor Profiles Path: {:?}", error);
+    }
+    if let Err(error) = watcher.watch(&paths.mic_profiles, RecursiveMode::NonRecursive) {
+        warn!("Unable to Monitor the Microphone Profile Path {:?}", error);
+    }
+    if let Err(error) = watcher.watch(&paths.presets, RecursiveMode::NonRecursive) {
+        warn!("Unable to Monitor the Presets Path: {:?}", error)
+    }
+    if let Err(error) = watcher.watch(&paths.icons, RecursiveMode::NonRecursive) {
+        warn!("Unable to monitor the Icons Path: {:?}", error);
+    }
+    if let Err(error) = watcher.watch(&paths.samples, RecursiveMode::Recursive) {
+        warn!("Unable to Monitor the Samples Path: {:?}", error);
+    }
+}
+
+pub(crate) fn create_watcher(
+) -> notify::Result<(RecommendedWatcher, Receiver<notify::Result<Event>>)> {
     let (tx, rx) = mpsc::channel(1);
 
     let watcher = RecommendedWatcher::new(
@@ -353,6 +376,141 @@ pub fn find_file_in_path(path: PathBuf, file: PathBuf) -> Option<PathBuf> {
     None
 }
 
+// Samples can be organised into per-bank and per-button folders (eg. 'A/TopLeft/Airhorn.wav'), so
+// tracks are first looked for relative to the root and the button's folders, before falling back
+// to searching the entire samples directory for the file name.
+pub fn find_sample_file(
+    root: &Path,
+    bank: SampleBank,
+    button: SampleButtons,
+    track: &str,
+) -> Option<PathBuf> {
+    let track = PathBuf::from(track);
+    if track.is_absolute() {
+        return if track.is_file() { Some(track) } else { None };
+    }
+
+    let bank_path = root.join(bank.to_string());
+    let candidates = [
+        root.join(&track),
+        bank_path.join(button.to_string()).join(&track),
+        bank_path.join(&track),
+    ];
+    if let Some(file) = candidates.into_iter().find(|path| path.is_file()) {
+        return Some(file);
+    }
+
+    if let Some(file) = find_file_in_path(root.to_path_buf(), track.clone()) {
+        return Some(file);
+    }
+
+    // The folder layout may be different on this machine, so try the file name by itself..
+    match track.file_name() {
+        Some(name) if track.parent() != Some(Path::new("")) => {
+            find_file_in_path(root.to_path_buf(), PathBuf::from(name))
+        }
+        _ => None,
+    }
+}
+
+// Returns the path stored in the profile for a sample, relative to the samples root where possible.
+pub fn get_sample_track_name(root: &Path, file: &Path) -> String {
+    match file.strip_prefix(root) {
+        Ok(relative) => relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<String>>()
+            .join("/"),
+        Err(_) => file.to_string_lossy().to_string(),
+    }
+}
+
+// Reads the details of a sample, the file is relative to the samples directory.
+pub async fn get_sample_info(root: PathBuf, file: String) -> Result<SampleInfo> {
+    let relative = PathBuf::from(&file);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        bail!("Invalid Sample Path: {}", file);
+    }
+
+    let path = root.join(relative);
+    if !path.is_file() {
+        bail!("Sample Not Found: {}", file);
+    }
+
+    let info = tokio::task::spawn_blocking(move || get_audio_file_info(&path)).await??;
+    Ok(SampleInfo {
+        file,
+        duration_ms: info.duration_ms,
+        sample_rate: info.sample_rate,
+        channels: info.channels,
+        tags: info.tags,
+    })
+}
+
+// Backups are referred to by name, and always live directly inside the backups directory.
+pub fn get_backup_path(root: &Path, name: &str) -> Result<PathBuf> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(root.join(name)),
+        _ => bail!("Invalid Backup Name: {}", name),
+    }
+}
+
+pub const DEFAULT_RECORDING_TEMPLATE: &str = "Recording_{date}";
+
+// Checks a recording template will produce a file inside the recordings directory.
+pub fn check_recording_template(template: &str) -> Result<()> {
+    let name = render_recording_template(template, SampleBank::A, SampleButtons::TopLeft);
+    let path = PathBuf::from(&name);
+    if name.trim().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        bail!("Invalid Recording Name Template: {}", template);
+    }
+    Ok(())
+}
+
+fn render_recording_template(template: &str, bank: SampleBank, button: SampleButtons) -> String {
+    template
+        .replace(
+            "{date}",
+            &Local::now().format("%Y-%m-%dT%H%M%S").to_string(),
+        )
+        .replace("{bank}", &bank.to_string())
+        .replace("{button}", &button.to_string())
+}
+
+// Builds the path for a new recording from the template, replacing {date}, {bank}, {button} and
+// {counter}. The counter is the lowest number which doesn't match an existing file, if the
+// template doesn't have one and the file already exists, a number is appended instead.
+pub fn get_recording_path(
+    directory: &Path,
+    template: &str,
+    bank: SampleBank,
+    button: SampleButtons,
+) -> Result<PathBuf> {
+    check_recording_template(template)?;
+
+    let name = render_recording_template(template, bank, button);
+    let mut counter = 1;
+    loop {
+        let file_name = if name.contains("{counter}") {
+            name.replace("{counter}", &counter.to_string())
+        } else if counter == 1 {
+            name.clone()
+        } else {
+            format!("{} ({})", name, counter - 1)
+        };
+
+        let path = directory.join(format!("{}.wav", file_name));
+        if !path.exists() {
+            return Ok(path);
+        }
+        counter += 1;
+    }
+}
+
 pub fn create_path(path: &Path) -> Result<()> {
     if !path.exists() {
         // Attempt to create the profile directory..
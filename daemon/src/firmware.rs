@@ -0,0 +1,217 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use log::{debug, info, warn};
+
+use goxlr_ipc::{DeviceType, FirmwareUpdateStage, FirmwareUpdateStatus};
+use goxlr_usb::device::base::{FullGoXLRDevice, FIRMWARE_CHUNK_SIZE};
+use goxlr_usb::firmware::FirmwareHeader;
+
+/*
+Firmware updates are long running (several minutes for a full upload), so rather than block the
+device worker, the update is driven one small step at a time from the Device's update_state
+loop. This keeps the daemon responsive, and allows progress to be reported via the normal status
+patches. The process mirrors the official app:
+
+1. Put the device into update mode, and erase the update partition
+2. Send the firmware in 1012 byte packets (the same size as the official app)
+3. Have the device hash the uploaded data (each response is fed into the next request)
+4. Ask the device to verify the image, this performs a CRC check on the device itself
+5. Finalise, and reboot
+
+If any step fails, the update is aborted and the device rebooted back into the old firmware.
+ */
+
+// How many packets to send on each step, keeps each step reasonably short.
+const CHUNKS_PER_STEP: usize = 16;
+
+pub struct FirmwareUpdate {
+    firmware: Vec<u8>,
+    stage: FirmwareUpdateStage,
+    progress: u8,
+    error: Option<String>,
+
+    sent: u64,
+    processed: u32,
+    hash: u32,
+}
+
+impl FirmwareUpdate {
+    pub fn new(path: &Path, device_type: &DeviceType) -> Result<Self> {
+        let firmware = std::fs::read(path).context("Unable to read Firmware File")?;
+        let header = FirmwareHeader::parse(&firmware)?;
+        let expected = match device_type {
+            DeviceType::Full => "GoXLR Firmware",
+            DeviceType::Mini => "GoXLR-Mini",
+            DeviceType::Unknown => bail!("Unable to update an unknown device type"),
+        };
+
+        if header.name != expected {
+            bail!(
+                "Incompatible Firmware, Expected '{}' received '{}'",
+                expected,
+                header.name
+            );
+        }
+
+        info!("Loaded {} version {}", header.name, header.version);
+
+        Ok(Self {
+            firmware,
+            stage: FirmwareUpdateStage::Starting,
+            progress: 0,
+            error: None,
+
+            sent: 0,
+            processed: 0,
+            hash: 0,
+        })
+    }
+
+    pub fn status(&self) -> FirmwareUpdateStatus {
+        FirmwareUpdateStatus {
+            stage: self.stage,
+            progress: self.progress,
+            error: self.error.clone(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.stage,
+            FirmwareUpdateStage::Complete | FirmwareUpdateStage::Failed
+        )
+    }
+
+    // Performs the next step of the update, returns true if the status has changed.
+    pub async fn step(&mut self, goxlr: &mut Box<dyn FullGoXLRDevice>) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        let previous = self.status();
+        if let Err(error) = self.run_step(goxlr).await {
+            warn!("Firmware Update Failed: {}", error);
+            self.fail(goxlr, error.to_string()).await;
+        }
+
+        let current = self.status();
+        previous.stage != current.stage || previous.progress != current.progress
+    }
+
+    async fn run_step(&mut self, goxlr: &mut Box<dyn FullGoXLRDevice>) -> Result<()> {
+        match self.stage {
+            FirmwareUpdateStage::Starting => {
+                info!("Putting Device in Firmware Update Mode..");
+                goxlr.stop_polling();
+                goxlr.begin_firmware_upload().await?;
+
+                info!("Beginning Erasure of Update Partition..");
+                goxlr.begin_erase_nvr().await?;
+                self.set_stage(FirmwareUpdateStage::Erasing);
+            }
+            FirmwareUpdateStage::Erasing => {
+                let progress = goxlr.poll_erase_nvr().await?;
+                self.progress = ((progress as f32 / 255.) * 100.) as u8;
+
+                if progress == 0xff {
+                    info!("Erase Complete, Sending Firmware..");
+                    self.set_stage(FirmwareUpdateStage::Uploading);
+                }
+            }
+            FirmwareUpdateStage::Uploading => {
+                let total = self.firmware.len();
+                let start = self.sent as usize;
+                let end = (start + FIRMWARE_CHUNK_SIZE * CHUNKS_PER_STEP).min(total);
+
+                let progress = &mut self.progress;
+                self.sent = goxlr
+                    .send_firmware_data(self.sent, &self.firmware[start..end], &mut |sent| {
+                        *progress = ((sent as f32 / total as f32) * 100.) as u8;
+                    })
+                    .await?;
+
+                if self.sent as usize == self.firmware.len() {
+                    info!("Data Sent, Beginning Validation..");
+                    self.set_stage(FirmwareUpdateStage::Validating);
+                }
+            }
+            FirmwareUpdateStage::Validating => {
+                let total = self.firmware.len() as u32;
+                let remaining = total - self.processed;
+
+                // The returned hash isn't something we can check (the official app doesn't
+                // either), it's passed into the next request and checked during verification.
+                let (hash, count) = goxlr
+                    .validate_firmware_packet(self.processed, self.hash, remaining)
+                    .await?;
+
+                self.processed += count;
+                self.hash = hash;
+                if self.processed > total {
+                    bail!("Validation Failed, device processed more data than was sent");
+                }
+
+                self.progress = ((self.processed as f32 / total as f32) * 100.) as u8;
+                if self.processed == total {
+                    info!("Validation complete, Beginning Hardware Verification..");
+                    goxlr.verify_firmware_status().await?;
+                    self.set_stage(FirmwareUpdateStage::Verifying);
+                }
+            }
+            FirmwareUpdateStage::Verifying => {
+                let (complete, total, done) = goxlr.poll_verify_firmware_status().await?;
+                self.progress = percent(done, total);
+
+                if complete {
+                    info!("Hardware Verification Complete, Finalising..");
+                    goxlr.finalise_firmware_upload().await?;
+                    self.set_stage(FirmwareUpdateStage::Finalising);
+                }
+            }
+            FirmwareUpdateStage::Finalising => {
+                let (complete, total, done) = goxlr.poll_finalise_firmware_upload().await?;
+                self.progress = percent(done, total);
+
+                if complete {
+                    self.set_stage(FirmwareUpdateStage::Rebooting);
+                }
+            }
+            FirmwareUpdateStage::Rebooting => {
+                info!("Firmware Update Complete, Rebooting GoXLR..");
+                goxlr.reboot_after_firmware_upload().await?;
+                self.set_stage(FirmwareUpdateStage::Complete);
+                self.progress = 100;
+            }
+            FirmwareUpdateStage::Complete | FirmwareUpdateStage::Failed => {}
+        }
+        Ok(())
+    }
+
+    async fn fail(&mut self, goxlr: &mut Box<dyn FullGoXLRDevice>, error: String) {
+        self.stage = FirmwareUpdateStage::Failed;
+        self.error = Some(error);
+
+        // Make sure we don't leave the device stuck in update mode..
+        info!("Aborting Firmware Update");
+        if let Err(error) = goxlr.abort_firmware_update().await {
+            warn!("Unable to Abort Firmware Update: {}", error);
+        }
+        if let Err(error) = goxlr.reboot_after_firmware_upload().await {
+            warn!("Unable to Reboot GoXLR: {}", error);
+        }
+    }
+
+    fn set_stage(&mut self, stage: FirmwareUpdateStage) {
+        debug!("Firmware Update Stage: {:?}", stage);
+        self.stage = stage;
+        self.progress = 0;
+    }
+}
+
+fn percent(done: u32, total: u32) -> u8 {
+    if total == 0 {
+        return 0;
+    }
+    ((done as f32 / total as f32) * 100.) as u8
+}
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use goxlr_ipc::GoXLRCommand;
+use serde_json::Value;
+
+use crate::mic_profile::MicProfileSnapshot;
+use crate::profile::ProfileSnapshot;
+
+/*
+A bounded history of the changes made to a device via commands, so they can be undone (and
+redone). Most commands can't simply be reversed (there's no 'opposite' of loading a colour
+scheme), so rather than working out the inverse of each one, we keep a copy of the profile and
+mic profile from before the command was run, and put that back.
+
+Repeated commands of the same type on the same target in quick succession (for example, dragging a
+slider in the UI) are grouped into a single entry, so one undo reverts the whole adjustment.
+ */
+const MAX_HISTORY: usize = 25;
+const MERGE_WINDOW: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceSnapshot {
+    pub profile: ProfileSnapshot,
+    pub mic_profile: MicProfileSnapshot,
+}
+
+#[derive(Debug)]
+pub struct HistoryEntry {
+    pub command: GoXLRCommand,
+    pub snapshot: DeviceSnapshot,
+}
+
+#[derive(Debug, Default)]
+pub struct CommandHistory {
+    undo: VecDeque<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+    last_recorded: Option<Instant>,
+}
+
+impl CommandHistory {
+    // Returns true if the command should be folded into the previous entry.
+    pub fn is_continuation(&mut self, command: &GoXLRCommand) -> bool {
+        if let (Some(last), Some(recorded)) = (self.undo.back(), self.last_recorded) {
+            if is_same_target(&last.command, command) && recorded.elapsed() < MERGE_WINDOW {
+                self.last_recorded = Some(Instant::now());
+                return true;
+            }
+        }
+        false
+    }
+
+    // Records a new change, anything which was undone can no longer be redone.
+    pub fn record(&mut self, entry: HistoryEntry) {
+        self.push_undo(entry);
+        self.redo.clear();
+        self.last_recorded = Some(Instant::now());
+    }
+
+    pub fn push_undo(&mut self, entry: HistoryEntry) {
+        if self.undo.len() >= MAX_HISTORY {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(entry);
+    }
+
+    pub fn push_redo(&mut self, entry: HistoryEntry) {
+        self.redo.push(entry);
+    }
+
+    pub fn take_undo(&mut self) -> Option<HistoryEntry> {
+        // Make sure the next command doesn't get merged into whatever's now at the end..
+        self.last_recorded = None;
+        self.undo.pop_back()
+    }
+
+    pub fn take_redo(&mut self) -> Option<HistoryEntry> {
+        self.last_recorded = None;
+        self.redo.pop()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+// Commands hit the same target if everything other than the final value matches, so for example
+// SetVolume(Mic, ..) and SetVolume(Music, ..) are different targets. Commands are compared in their
+// serialised form ({"SetVolume": ["Mic", 128]}), so targets don't need to be listed per command.
+fn is_same_target(a: &GoXLRCommand, b: &GoXLRCommand) -> bool {
+    match (command_target(a), command_target(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn command_target(command: &GoXLRCommand) -> Option<(String, Value)> {
+    match serde_json::to_value(command).ok()? {
+        Value::Object(map) => {
+            let (name, value) = map.into_iter().next()?;
+            let target = match value {
+                Value::Array(mut values) => {
+                    values.pop();
+                    Value::Array(values)
+                }
+                // A single value, the command itself is the target..
+                _ => Value::Null,
+            };
+            Some((name, target))
+        }
+        Value::String(name) => Some((name, Value::Null)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use goxlr_types::{ChannelName, FaderName};
+
+    use super::*;
+
+    #[test]
+    fn merges_repeated_commands_on_the_same_target() {
+        let first = GoXLRCommand::SetVolume(ChannelName::Mic, 100);
+        let second = GoXLRCommand::SetVolume(ChannelName::Mic, 120);
+        assert!(is_same_target(&first, &second));
+
+        let first = GoXLRCommand::SetFXEnabled(true);
+        let second = GoXLRCommand::SetFXEnabled(false);
+        assert!(is_same_target(&first, &second));
+    }
+
+    #[test]
+    fn keeps_different_targets_separate() {
+        let first = GoXLRCommand::SetVolume(ChannelName::Mic, 100);
+        let second = GoXLRCommand::SetVolume(ChannelName::Music, 100);
+        assert!(!is_same_target(&first, &second));
+
+        let first = GoXLRCommand::SetFader(FaderName::A, ChannelName::Mic);
+        let second = GoXLRCommand::SetFader(FaderName::B, ChannelName::Mic);
+        assert!(!is_same_target(&first, &second));
+
+        let first = GoXLRCommand::SetVolume(ChannelName::Mic, 100);
+        let second = GoXLRCommand::SetFXEnabled(true);
+        assert!(!is_same_target(&first, &second));
+    }
+}
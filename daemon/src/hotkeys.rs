@@ -0,0 +1,139 @@
+use crate::primary_worker::DeviceSender;
+use crate::settings::SettingsHandle;
+use crate::shutdown::Shutdown;
+use log::{info, warn};
+
+#[cfg(feature = "hotkeys")]
+use {
+    crate::primary_worker::DeviceCommand,
+    global_hotkey::hotkey::HotKey,
+    global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState},
+    goxlr_ipc::GoXLRCommand,
+    log::{debug, error},
+    std::collections::HashMap,
+    std::str::FromStr,
+    std::time::Duration,
+    tokio::sync::oneshot,
+    tokio::time::sleep,
+};
+
+/*
+Global hotkeys are configured per device in the settings file, as a map of key combinations to a
+GoXLRCommand, for example:
+
+"hotkeys": {
+    "ctrl+shift+KeyM": { "SetCoughMuteState": "MutedToAll" },
+    "ctrl+shift+KeyU": { "SetCoughMuteState": "Unmuted" }
+}
+
+Commands are run when the keys are pressed, with the exception of push to talk, a binding to
+{ "SetPushToTalkActive": true } will also send false when the keys are released, so the mic is
+only open while they're held.
+
+These are only read on startup, so the daemon needs restarting for changes to take effect.
+ */
+#[cfg(feature = "hotkeys")]
+pub async fn spawn_hotkey_service(
+    settings: SettingsHandle,
+    usb_tx: DeviceSender,
+    mut shutdown: Shutdown,
+) {
+    let configured = settings.get_hotkeys().await;
+    if configured.is_empty() {
+        debug!("No Hotkeys Configured, not starting Hotkey Service");
+        return;
+    }
+
+    info!("Starting Hotkey Service..");
+    let manager = match GlobalHotKeyManager::new() {
+        Ok(manager) => manager,
+        Err(error) => {
+            warn!("Unable to Start Hotkey Service: {}", error);
+            return;
+        }
+    };
+
+    // Multiple devices may be bound to the same key combination, so group by the hotkey id..
+    let mut bindings: HashMap<u32, Vec<(String, GoXLRCommand)>> = HashMap::new();
+    for (serial, keys, command) in configured {
+        let hotkey = match HotKey::from_str(&keys) {
+            Ok(hotkey) => hotkey,
+            Err(error) => {
+                warn!("Unable to Parse Hotkey {}: {}", keys, error);
+                continue;
+            }
+        };
+
+        if !bindings.contains_key(&hotkey.id()) {
+            if let Err(error) = manager.register(hotkey) {
+                warn!("Unable to Register Hotkey {}: {}", keys, error);
+                continue;
+            }
+            debug!("Registered Hotkey {}", keys);
+        }
+        bindings
+            .entry(hotkey.id())
+            .or_default()
+            .push((serial, command));
+    }
+
+    let receiver = GlobalHotKeyEvent::receiver();
+    loop {
+        tokio::select! {
+            () = shutdown.recv() => {
+                info!("Shutting down Hotkey Service");
+                return;
+            },
+            () = sleep(Duration::from_millis(20)) => {
+                while let Ok(event) = receiver.try_recv() {
+                    if let Some(commands) = bindings.get(&event.id) {
+                        for (serial, command) in commands {
+                            let command = match event.state {
+                                HotKeyState::Pressed => command.clone(),
+                                HotKeyState::Released => match command {
+                                    GoXLRCommand::SetPushToTalkActive(true) => {
+                                        GoXLRCommand::SetPushToTalkActive(false)
+                                    }
+                                    _ => continue,
+                                },
+                            };
+                            run_command(&usb_tx, serial.clone(), command).await;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "hotkeys")]
+async fn run_command(usb_tx: &DeviceSender, serial: String, command: GoXLRCommand) {
+    debug!("Hotkey Triggered, running {:?} on {}", command, serial);
+
+    let (tx, rx) = oneshot::channel();
+    let request = DeviceCommand::RunDeviceCommand(serial, command, tx);
+    if usb_tx.send(request).await.is_err() {
+        error!("Unable to send Hotkey command to the device task");
+        return;
+    }
+
+    match rx.await {
+        Ok(Err(error)) => warn!("Error Executing Hotkey Command: {}", error),
+        Err(error) => warn!("Error Executing Hotkey Command: {}", error),
+        Ok(Ok(())) => {}
+    }
+}
+
+/*
+Below is a 'Dummy' implementation of the service, for use if the global-hotkey package isn't
+included in the build.
+ */
+
+#[cfg(not(feature = "hotkeys"))]
+pub async fn spawn_hotkey_service(settings: SettingsHandle, _: DeviceSender, _: Shutdown) {
+    if !settings.get_hotkeys().await.is_empty() {
+        warn!("Hotkeys Configured, but the Hotkey Feature is not enabled in build.");
+        return;
+    }
+    info!("Hotkey Feature is not enabled in build, Hotkey Service not started.");
+}
@@ -0,0 +1,99 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context, Result};
+use file_rotate::compression::Compression;
+use file_rotate::suffix::AppendCount;
+use file_rotate::{ContentLimit, FileRotate};
+use goxlr_ipc::LogLevel;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::reload::Handle;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+/*
+All logging goes through tracing, existing 'log' macro calls (both ours, and from dependencies)
+are picked up by the tracing-log bridge, so everything ends up in the same place. Output goes to
+both the terminal and a rotating log file, and the level can be changed at runtime via IPC.
+
+Some packages are a little noisy, so we restrict them to warnings by default:
+- zbus will output to INFO every second when the tray is in use
+- actix is fairly chatty on startup and shutdown
+- symphonia header announcements are only useful in a development setting
+
+Additional per-module filters can be provided in the settings file using the 'log_filters' key,
+in the same format as RUST_LOG, for example "goxlr_daemon::device=trace,goxlr_usb=debug".
+ */
+const DEFAULT_FILTERS: &str = "zbus=warn,tracing=warn,actix_server=warn,symphonia=warn";
+
+static FILTER_HANDLE: Mutex<Option<FilterHandle>> = Mutex::new(None);
+
+struct FilterHandle {
+    handle: Handle<EnvFilter, Registry>,
+    extra_filters: Option<String>,
+}
+
+pub fn init_logging(
+    log_file: &Path,
+    level: &LogLevel,
+    extra_filters: Option<String>,
+) -> Result<()> {
+    // Create a file rotator, that will compress and rotate files after 2Mb
+    let file_rotator = FileRotate::new(
+        log_file,
+        AppendCount::new(5),
+        ContentLimit::Bytes(1024 * 1024 * 2),
+        Compression::OnRotate(1),
+        #[cfg(unix)]
+        None,
+    );
+
+    let filter = build_filter(level, &extra_filters)?;
+    let (filter, handle) = reload::Layer::new(filter);
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_target(true))
+        .with(
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(Mutex::new(file_rotator)),
+        )
+        .try_init()
+        .context("Could not configure the logger")?;
+
+    FILTER_HANDLE.lock().unwrap().replace(FilterHandle {
+        handle,
+        extra_filters,
+    });
+    Ok(())
+}
+
+pub fn set_log_level(level: &LogLevel) -> Result<()> {
+    let handle = FILTER_HANDLE.lock().unwrap();
+    let handle = handle
+        .as_ref()
+        .ok_or_else(|| anyhow!("Logging has not been initialised"))?;
+
+    let filter = build_filter(level, &handle.extra_filters)?;
+    handle.handle.reload(filter)?;
+    Ok(())
+}
+
+fn build_filter(level: &LogLevel, extra_filters: &Option<String>) -> Result<EnvFilter> {
+    let level = match level {
+        LogLevel::Off => "off",
+        LogLevel::Error => "error",
+        LogLevel::Warn => "warn",
+        LogLevel::Info => "info",
+        LogLevel::Debug => "debug",
+        LogLevel::Trace => "trace",
+    };
+
+    let mut filters = format!("{},{}", level, DEFAULT_FILTERS);
+    if let Some(extra) = extra_filters {
+        filters = format!("{},{}", filters, extra);
+    }
+
+    EnvFilter::try_new(&filters).context("Invalid Log Filter")
+}
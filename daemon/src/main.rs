@@ -2,54 +2,76 @@
 
 extern crate core;
 
+use std::env;
 use std::fs::create_dir_all;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use actix_web::dev::ServerHandle;
-use anyhow::{bail, Context, Result};
+use anyhow::{bail, Result};
 use clap::Parser;
-use file_rotate::compression::Compression;
-use file_rotate::suffix::AppendCount;
-use file_rotate::{ContentLimit, FileRotate};
 use json_patch::Patch;
 use log::{debug, error, info, warn};
-use simplelog::{
-    ColorChoice, CombinedLogger, ConfigBuilder, TermLogger, TerminalMode, WriteLogger,
-};
 use tokio::join;
 use tokio::sync::{broadcast, mpsc};
 
-use goxlr_ipc::{HttpSettings, LogLevel};
+use goxlr_ipc::clients::ipc::{socket_name, SOCKET_ENV};
+use goxlr_ipc::{DaemonEvent, HttpSettings, LogLevel};
+use goxlr_usb::device::simulator::device::{add_simulated_device, SimulatedDeviceType};
 
-use crate::cli::{Cli, LevelFilter};
+use crate::app_routing::spawn_app_routing_service;
+use crate::cli::{Cli, LevelFilter, SimulatedDevice};
 use crate::events::{spawn_event_handler, DaemonState, EventTriggers};
 use crate::files::{spawn_file_notification_service, FileManager};
+use crate::hotkeys::spawn_hotkey_service;
+use crate::logging::init_logging;
+use crate::metrics::METRICS;
 use crate::platform::perform_preflight;
 use crate::platform::spawn_runtime;
 use crate::primary_worker::spawn_usb_handler;
+use crate::scheduler::spawn_scheduler_service;
+use crate::scripting::spawn_script_service;
 use crate::servers::http_server::spawn_http_server;
 use crate::servers::ipc_server::{bind_socket, spawn_ipc_server};
 use crate::settings::SettingsHandle;
+use crate::settings_watcher::spawn_settings_watcher;
 use crate::shutdown::Shutdown;
 use crate::tts::spawn_tts_service;
+use crate::virtual_channels::spawn_virtual_channel_service;
+use crate::vod_mix::spawn_vod_mix_service;
 
+mod app_routing;
 mod audio;
 mod cli;
+mod command_queue;
 mod device;
 mod events;
 mod files;
+mod firmware;
+mod history;
+mod hotkeys;
+mod logging;
+mod metrics;
+mod mic_calibration;
 mod mic_profile;
 mod platform;
 mod primary_worker;
 mod profile;
+mod sample_hashes;
+mod scheduler;
+mod scripting;
 mod servers;
 mod settings;
+mod settings_watcher;
 mod shutdown;
+mod support_bundle;
 mod tray;
 mod tts;
+mod virtual_channels;
+mod vod_mix;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+const GIT_HASH: Option<&str> = option_env!("GIT_HASH");
 const ICON: &[u8] = include_bytes!("../resources/goxlr-utility-large.png");
 
 /**
@@ -60,10 +82,11 @@ rather than through additional parameters. When that comes, this will be removed
 static OVERRIDE_SAMPLER_INPUT: Mutex<Option<String>> = Mutex::new(None);
 static OVERRIDE_SAMPLER_OUTPUT: Mutex<Option<String>> = Mutex::new(None);
 
-// This is for global 'JSON Patches', for when something changes.
+// This is for global 'JSON Patches', for when something changes, and raw hardware events.
 #[derive(Debug, Clone)]
-pub struct PatchEvent {
-    pub data: Patch,
+pub enum PatchEvent {
+    Patch(Patch),
+    Event(DaemonEvent),
 }
 
 #[tokio::main]
@@ -82,64 +105,25 @@ async fn main() -> Result<()> {
     }
     let log_file = log_path.join("goxlr-daemon.log");
 
-    // We need to ignore a couple of packages log output so create a builder.
-    let mut config = ConfigBuilder::new();
-
-    // The tracing package, when used, will output to INFO from zbus every second..
-    config.add_filter_ignore_str("tracing");
-
-    // Actix is a little noisy on startup and shutdown, so quiet it a bit :)
-    config.add_filter_ignore_str("actix_server::accept");
-    config.add_filter_ignore_str("actix_server::worker");
-    config.add_filter_ignore_str("actix_server::server");
-    config.add_filter_ignore_str("actix_server::builder");
-
-    // I'm generally not interested in the Symphonia header announcements which go to INFO,
-    // it's only useful in a development setting!
-    config.add_filter_ignore_str("symphonia");
-
-    // Create a file rotator, that will compress and rotate files after 5Mb
-    let file_rotator = FileRotate::new(
-        log_file,
-        AppendCount::new(5),
-        ContentLimit::Bytes(1024 * 1024 * 2),
-        Compression::OnRotate(1),
-        #[cfg(unix)]
-        None,
-    );
-
     // Configure the log level, prioritise the CLI, but otherwise config.
     let log_level = if let Some(cli_level) = args.log_level {
         match cli_level {
-            LevelFilter::Off => log::LevelFilter::Off,
-            LevelFilter::Error => log::LevelFilter::Error,
-            LevelFilter::Warn => log::LevelFilter::Warn,
-            LevelFilter::Info => log::LevelFilter::Info,
-            LevelFilter::Debug => log::LevelFilter::Debug,
-            LevelFilter::Trace => log::LevelFilter::Trace,
+            LevelFilter::Off => LogLevel::Off,
+            LevelFilter::Error => LogLevel::Error,
+            LevelFilter::Warn => LogLevel::Warn,
+            LevelFilter::Info => LogLevel::Info,
+            LevelFilter::Debug => LogLevel::Debug,
+            LevelFilter::Trace => LogLevel::Trace,
         }
     } else {
-        match settings.get_log_level().await {
-            LogLevel::Off => log::LevelFilter::Off,
-            LogLevel::Error => log::LevelFilter::Error,
-            LogLevel::Warn => log::LevelFilter::Warn,
-            LogLevel::Info => log::LevelFilter::Info,
-            LogLevel::Debug => log::LevelFilter::Debug,
-            LogLevel::Trace => log::LevelFilter::Trace,
-        }
+        settings.get_log_level().await
     };
 
     // Create the loggers :)
-    CombinedLogger::init(vec![
-        TermLogger::new(
-            log_level,
-            config.build(),
-            TerminalMode::Mixed,
-            ColorChoice::Auto,
-        ),
-        WriteLogger::new(log_level, config.build(), file_rotator),
-    ])
-    .context("Could not configure the logger")?;
+    init_logging(&log_file, &log_level, settings.get_log_filters().await)?;
+
+    // This needs to be set before any devices are opened..
+    goxlr_usb::timing::set_timings(settings.get_usb_timings().await);
 
     if is_root() {
         if args.force_root {
@@ -172,8 +156,19 @@ async fn main() -> Result<()> {
         OVERRIDE_SAMPLER_OUTPUT.lock().unwrap().replace(device);
     }
 
+    for device in args.simulate_device {
+        warn!("Adding Simulated {:?} GoXLR", device);
+        add_simulated_device(match device {
+            SimulatedDevice::Full => SimulatedDeviceType::Full,
+            SimulatedDevice::Mini => SimulatedDeviceType::Mini,
+        });
+    }
+
     info!("Starting GoXLR Daemon v{}", VERSION);
 
+    // Make sure the uptime is measured from here..
+    lazy_static::initialize(&METRICS);
+
     // Before we do anything, perform platform pre-flight to make
     // sure we're allowed to start.
     info!("Performing Platform Preflight...");
@@ -192,14 +187,16 @@ async fn main() -> Result<()> {
         enabled: !args.http_disable,
         bind_address,
         cors_enabled: args.http_enable_cors,
+        metrics_enabled: args.http_enable_metrics,
         port: args.http_port,
     };
 
     // Create the Global Event Channel..
     let (global_tx, global_rx) = mpsc::channel(32);
 
-    // Create the 'Patch' Sending Channel..
-    let (broadcast_tx, broadcast_rx) = broadcast::channel(16);
+    // Create the 'Patch' Sending Channel, this is shared by every connected client. Clients
+    // which fall too far behind will be resynced with a full status.
+    let (broadcast_tx, broadcast_rx) = broadcast::channel(128);
     drop(broadcast_rx);
 
     // Create the USB Event Channel..
@@ -213,6 +210,7 @@ async fn main() -> Result<()> {
 
     // Create the Device shutdown signallers..
     let (device_stop_tx, device_stop_rx) = mpsc::channel(1);
+    let (device_resume_tx, device_resume_rx) = mpsc::channel(1);
 
     // Create the Shutdown Signallers..
     let shutdown = Shutdown::new();
@@ -229,14 +227,25 @@ async fn main() -> Result<()> {
     let file_paths = file_manager.paths().clone();
 
     let (file_tx, file_rx) = mpsc::channel(20);
+    let (file_paths_tx, file_paths_rx) = mpsc::channel(1);
     let file_handle = tokio::spawn(spawn_file_notification_service(
         file_paths.clone(),
+        file_paths_rx,
         file_tx,
         shutdown.clone(),
     ));
 
     // Spawn the IPC Socket..
-    let ipc_socket = bind_socket().await;
+    // The command line takes priority, followed by the environment, then the settings file..
+    let socket_name = match args.socket_path {
+        Some(path) => path.to_string_lossy().to_string(),
+        None => match (env::var_os(SOCKET_ENV), settings.get_socket_path().await) {
+            (None, Some(path)) => path.to_string_lossy().to_string(),
+            _ => socket_name(),
+        },
+    };
+    let socket_group = args.socket_group.or(settings.get_socket_group().await);
+    let ipc_socket = bind_socket(socket_name, socket_group).await;
     if ipc_socket.is_err() {
         error!("Error Starting Daemon: ");
         bail!("{}", ipc_socket.err().unwrap());
@@ -247,12 +256,14 @@ async fn main() -> Result<()> {
         usb_rx,
         file_rx,
         device_stop_rx,
+        device_resume_rx,
         broadcast_tx.clone(),
         global_tx.clone(),
         shutdown.clone(),
         settings.clone(),
         http_settings.clone(),
         file_manager,
+        file_paths_tx,
     ));
 
     // Launch the IPC Server..
@@ -260,6 +271,7 @@ async fn main() -> Result<()> {
     let communications_handle = tokio::spawn(spawn_ipc_server(
         ipc_socket,
         usb_tx.clone(),
+        broadcast_tx.clone(),
         shutdown.clone(),
     ));
 
@@ -290,6 +302,50 @@ async fn main() -> Result<()> {
         shutdown.clone(),
     ));
 
+    // Start the Hotkey Service..
+    let hotkey_handle = tokio::spawn(spawn_hotkey_service(
+        settings.clone(),
+        usb_tx.clone(),
+        shutdown.clone(),
+    ));
+
+    // Start the Scripting Service..
+    let script_handle = tokio::spawn(spawn_script_service(
+        settings.clone(),
+        usb_tx.clone(),
+        broadcast_tx.clone(),
+        shutdown.clone(),
+    ));
+
+    // Start the Scheduler Service..
+    let scheduler_handle = tokio::spawn(spawn_scheduler_service(
+        settings.clone(),
+        usb_tx.clone(),
+        shutdown.clone(),
+    ));
+
+    // Start the Application Routing Service..
+    let app_routing_handle = tokio::spawn(spawn_app_routing_service(
+        settings.clone(),
+        shutdown.clone(),
+    ));
+
+    // Start the Virtual Channel Service..
+    let virtual_channel_handle = tokio::spawn(spawn_virtual_channel_service(
+        settings.clone(),
+        shutdown.clone(),
+    ));
+
+    // Start the VOD Mix Service..
+    let vod_mix_handle = tokio::spawn(spawn_vod_mix_service(settings.clone(), shutdown.clone()));
+
+    // Start watching the Settings File..
+    let settings_watcher_handle = tokio::spawn(spawn_settings_watcher(
+        settings.clone(),
+        usb_tx.clone(),
+        shutdown.clone(),
+    ));
+
     let mut local_shutdown = shutdown.clone();
     let state = DaemonState {
         tts_sender,
@@ -307,6 +363,7 @@ async fn main() -> Result<()> {
         state.clone(),
         global_rx,
         device_stop_tx,
+        device_resume_tx,
     ));
 
     // Spawn the Platform Runtime (if needed)
@@ -333,6 +390,13 @@ async fn main() -> Result<()> {
             server.stop(false),
             file_handle,
             tts_handle,
+            hotkey_handle,
+            script_handle,
+            scheduler_handle,
+            app_routing_handle,
+            virtual_channel_handle,
+            vod_mix_handle,
+            settings_watcher_handle,
             event_handle,
             platform_handle
         );
@@ -342,6 +406,13 @@ async fn main() -> Result<()> {
             communications_handle,
             file_handle,
             tts_handle,
+            hotkey_handle,
+            script_handle,
+            scheduler_handle,
+            app_routing_handle,
+            virtual_channel_handle,
+            vod_mix_handle,
+            settings_watcher_handle,
             event_handle,
             platform_handle
         );
@@ -0,0 +1,230 @@
+use chrono::Local;
+use lazy_static::lazy_static;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/*
+Simple counters for the state of the daemon, these are exposed in the Prometheus text format on
+the /metrics endpoint of the HTTP server when started with --http-enable-metrics. They're kept as
+globals so they can be updated from anywhere without having to thread a handle through.
+ */
+const MAX_RECENT_USB_ERRORS: usize = 50;
+
+lazy_static! {
+    pub static ref METRICS: Metrics = Metrics::default();
+}
+
+#[derive(Debug)]
+pub struct Metrics {
+    started: Instant,
+    commands: AtomicU64,
+    command_errors: AtomicU64,
+    command_time_us: AtomicU64,
+    sample_playbacks: AtomicU64,
+    usb_errors: AtomicU64,
+    recent_usb_errors: Mutex<VecDeque<String>>,
+    ipc_clients: AtomicI64,
+    websocket_clients: AtomicI64,
+    devices: Mutex<HashMap<String, Instant>>,
+    device_polls: Mutex<HashMap<String, Instant>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            commands: Default::default(),
+            command_errors: Default::default(),
+            command_time_us: Default::default(),
+            sample_playbacks: Default::default(),
+            usb_errors: Default::default(),
+            recent_usb_errors: Default::default(),
+            ipc_clients: Default::default(),
+            websocket_clients: Default::default(),
+            devices: Default::default(),
+            device_polls: Default::default(),
+        }
+    }
+}
+
+impl Metrics {
+    pub fn command_executed(&self, duration: Duration, success: bool) {
+        self.commands.fetch_add(1, Ordering::Relaxed);
+        self.command_time_us
+            .fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+        if !success {
+            self.command_errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn sample_played(&self) {
+        self.sample_playbacks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Keeps the last few USB errors around, so they can be included in support bundles.
+    pub fn usb_error(&self, serial: &str, message: &str) {
+        self.usb_errors.fetch_add(1, Ordering::Relaxed);
+
+        let mut errors = self.recent_usb_errors.lock().unwrap();
+        if errors.len() >= MAX_RECENT_USB_ERRORS {
+            errors.pop_front();
+        }
+        errors.push_back(format!(
+            "{} [{}] {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S"),
+            serial,
+            message
+        ));
+    }
+
+    pub fn recent_usb_errors(&self) -> Vec<String> {
+        self.recent_usb_errors
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub fn ipc_client_connected(&self) {
+        self.ipc_clients.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn ipc_client_disconnected(&self) {
+        self.ipc_clients.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn websocket_client_connected(&self) {
+        self.websocket_clients.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn websocket_client_disconnected(&self) {
+        self.websocket_clients.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn device_connected(&self, serial: &str) {
+        let mut devices = self.devices.lock().unwrap();
+        devices.insert(serial.to_owned(), Instant::now());
+    }
+
+    pub fn device_disconnected(&self, serial: &str) {
+        let mut devices = self.devices.lock().unwrap();
+        devices.remove(serial);
+        self.device_polls.lock().unwrap().remove(serial);
+    }
+
+    pub fn device_polled(&self, serial: &str) {
+        let mut polls = self.device_polls.lock().unwrap();
+        polls.insert(serial.to_owned(), Instant::now());
+    }
+
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    pub fn device_uptime(&self, serial: &str) -> Option<Duration> {
+        let devices = self.devices.lock().unwrap();
+        devices.get(serial).map(|connected| connected.elapsed())
+    }
+
+    pub fn device_last_poll(&self, serial: &str) -> Option<Duration> {
+        let polls = self.device_polls.lock().unwrap();
+        polls.get(serial).map(|polled| polled.elapsed())
+    }
+
+    pub fn ipc_clients(&self) -> i64 {
+        self.ipc_clients.load(Ordering::Relaxed)
+    }
+
+    pub fn websocket_clients(&self) -> i64 {
+        self.websocket_clients.load(Ordering::Relaxed)
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+
+        let commands = self.commands.load(Ordering::Relaxed);
+        let time = self.command_time_us.load(Ordering::Relaxed) as f64 / 1_000_000.;
+        write_metric(
+            &mut output,
+            "goxlr_commands_total",
+            "counter",
+            "Number of commands sent to devices",
+            commands,
+        );
+        write_metric(
+            &mut output,
+            "goxlr_command_errors_total",
+            "counter",
+            "Number of commands which returned an error",
+            self.command_errors.load(Ordering::Relaxed),
+        );
+        write_metric(
+            &mut output,
+            "goxlr_command_seconds_total",
+            "counter",
+            "Total time spent executing device commands",
+            time,
+        );
+        write_metric(
+            &mut output,
+            "goxlr_sample_playbacks_total",
+            "counter",
+            "Number of samples played",
+            self.sample_playbacks.load(Ordering::Relaxed),
+        );
+        write_metric(
+            &mut output,
+            "goxlr_usb_errors_total",
+            "counter",
+            "Number of USB and protocol errors raised by devices",
+            self.usb_errors.load(Ordering::Relaxed),
+        );
+        write_metric(
+            &mut output,
+            "goxlr_ipc_clients",
+            "gauge",
+            "Number of connected IPC clients",
+            self.ipc_clients.load(Ordering::Relaxed),
+        );
+        write_metric(
+            &mut output,
+            "goxlr_websocket_clients",
+            "gauge",
+            "Number of connected Websocket clients",
+            self.websocket_clients.load(Ordering::Relaxed),
+        );
+
+        let devices = self.devices.lock().unwrap();
+        let _ = writeln!(
+            output,
+            "# HELP goxlr_device_uptime_seconds Time since the device was connected"
+        );
+        let _ = writeln!(output, "# TYPE goxlr_device_uptime_seconds gauge");
+        for (serial, connected) in devices.iter() {
+            let _ = writeln!(
+                output,
+                "goxlr_device_uptime_seconds{{serial=\"{}\"}} {}",
+                serial,
+                connected.elapsed().as_secs()
+            );
+        }
+
+        output
+    }
+}
+
+fn write_metric(
+    output: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    value: impl std::fmt::Display,
+) {
+    let _ = writeln!(output, "# HELP {} {}", name, help);
+    let _ = writeln!(output, "# TYPE {} {}", name, kind);
+    let _ = writeln!(output, "{} {}", name, value);
+}
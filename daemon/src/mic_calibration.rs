@@ -0,0 +1,105 @@
+use anyhow::{bail, Result};
+use goxlr_ipc::MicCalibrationResult;
+use goxlr_types::MicrophoneType;
+use std::time::{Duration, Instant};
+
+/*
+A helper for setting the mic gain, along the lines of the official app's mic setup wizard. While
+calibrating, the mic level is read on every poll as the user speaks, once finished the gain is
+adjusted so the loudest peaks sit at TARGET_PEAK, leaving some headroom before clipping.
+
+The GoXLR only gives us a level meter rather than the audio itself, so the loudness reported here
+is the average meter level while speaking, rather than a true LUFS measurement.
+ */
+const TARGET_PEAK: f64 = -10.;
+const SILENCE: f64 = -96.;
+
+// Readings below this are treated as pauses in speech, and not included in the average..
+const SPEECH_FLOOR: f64 = -50.;
+
+pub const MAX_CALIBRATION_SECONDS: u8 = 30;
+const MAX_GAIN: u16 = 72;
+
+#[derive(Debug)]
+pub struct MicCalibration {
+    started: Instant,
+    duration: Duration,
+    apply: bool,
+    mic_type: MicrophoneType,
+    gain: u16,
+
+    peak: f64,
+    speech_total: f64,
+    speech_readings: u32,
+}
+
+impl MicCalibration {
+    pub fn new(seconds: u8, apply: bool, mic_type: MicrophoneType, gain: u16) -> Result<Self> {
+        if seconds == 0 || seconds > MAX_CALIBRATION_SECONDS {
+            bail!(
+                "Calibration should last between 1 and {} seconds",
+                MAX_CALIBRATION_SECONDS
+            );
+        }
+
+        Ok(Self {
+            started: Instant::now(),
+            duration: Duration::from_secs(seconds as u64),
+            apply,
+            mic_type,
+            gain,
+
+            peak: SILENCE,
+            speech_total: 0.,
+            speech_readings: 0,
+        })
+    }
+
+    pub fn add_level(&mut self, level: u16) {
+        let level = level_to_db(level);
+        self.peak = self.peak.max(level);
+        if level >= SPEECH_FLOOR {
+            self.speech_total += level;
+            self.speech_readings += 1;
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.started.elapsed() >= self.duration
+    }
+
+    pub fn result(&self) -> MicCalibrationResult {
+        let speech_detected = self.speech_readings > 0;
+        let average = if speech_detected {
+            self.speech_total / self.speech_readings as f64
+        } else {
+            SILENCE
+        };
+
+        // Gain is in whole dB, so the difference to the target maps straight onto it..
+        let suggested_gain = if speech_detected {
+            let change = (TARGET_PEAK - self.peak).round() as i32;
+            (self.gain as i32 + change).clamp(0, MAX_GAIN as i32) as u16
+        } else {
+            self.gain
+        };
+
+        MicCalibrationResult {
+            mic_type: self.mic_type,
+            speech_detected,
+            peak_db: self.peak,
+            average_db: average,
+            previous_gain: self.gain,
+            suggested_gain,
+            applied: self.apply && speech_detected,
+        }
+    }
+}
+
+// The level is reported as a linear 16bit value, convert it to dBFS..
+pub fn level_to_db(level: u16) -> f64 {
+    if level == 0 {
+        return SILENCE;
+    }
+    (20. * (level as f64 / u16::MAX as f64).log10()).max(SILENCE)
+}
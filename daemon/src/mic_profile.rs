@@ -3,13 +3,13 @@ use crate::profile::ProfileAdapter;
 use anyhow::{anyhow, bail, Context, Result};
 use byteorder::{ByteOrder, LittleEndian};
 use enum_map::EnumMap;
-use goxlr_ipc::{Compressor, Equaliser, EqualiserMini, NoiseGate};
+use goxlr_ipc::{Compressor, DaemonError, Equaliser, EqualiserMini, NoiseGate};
 use goxlr_profile_loader::mic_profile::MicProfileSettings;
 use goxlr_types::{
     CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DisplayMode, EffectKey,
     EqFrequencies, GateTimes, MicrophoneParamKey, MicrophoneType, MiniEqFrequencies,
 };
-use log::warn;
+use log::{debug, warn};
 use ritelinked::LinkedHashSet;
 use std::collections::{HashMap, HashSet};
 use std::fs::{remove_file, File};
@@ -25,6 +25,18 @@ static GATE_ATTENUATION: [i8; 26] = [
     -26, -27, -28, -30, -32, -61,
 ];
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct MicProfileSnapshot {
+    name: String,
+    settings: Vec<u8>,
+}
+
+impl MicProfileSnapshot {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 #[derive(Debug)]
 pub struct MicProfileAdapter {
     name: String,
@@ -57,11 +69,23 @@ impl MicProfileAdapter {
             };
         }
 
-        bail!(
+        debug!(
             "Mic Profile {} does not exist inside {}",
             name,
             directory.to_string_lossy()
         );
+        bail!(DaemonError::ProfileNotFound(name));
+    }
+
+    // Loads a mic profile from anywhere on disk, the name is taken from the file name.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| anyhow!("Invalid Mic Profile Path"))?;
+
+        let file = File::open(path).context("Couldn't open mic profile for reading")?;
+        MicProfileAdapter::from_reader(name, file)
     }
 
     pub fn default() -> Self {
@@ -87,6 +111,15 @@ impl MicProfileAdapter {
         self.save(directory, overwrite)
     }
 
+    // Writes the current state to a new mic profile, without changing the active name.
+    pub fn export(&mut self, name: String, directory: &Path) -> Result<()> {
+        let path = directory.join(format!("{name}.goxlrMicProfile"));
+        can_create_new_file(path.clone())?;
+
+        self.profile.save(path)?;
+        Ok(())
+    }
+
     pub fn save(&mut self, directory: &Path, overwrite: bool) -> Result<()> {
         let name = &self.name;
         let path = directory.join(format!("{name}.goxlrMicProfile"));
@@ -110,6 +143,21 @@ impl MicProfileAdapter {
         &self.name
     }
 
+    pub fn snapshot(&self) -> Result<MicProfileSnapshot> {
+        let mut settings = Vec::new();
+        self.profile.write_to(&mut settings)?;
+        Ok(MicProfileSnapshot {
+            name: self.name.clone(),
+            settings,
+        })
+    }
+
+    pub fn restore_snapshot(&mut self, snapshot: &MicProfileSnapshot) -> Result<()> {
+        self.profile = MicProfileSettings::load(Cursor::new(&snapshot.settings))?;
+        self.name = snapshot.name.clone();
+        Ok(())
+    }
+
     pub fn mic_gains(&self) -> EnumMap<MicrophoneType, u16> {
         let mut gains = EnumMap::default();
         gains[MicrophoneType::Condenser] = self.profile.setup().condenser_mic_gain();
@@ -1,9 +1,16 @@
+use crate::events::{DaemonState, EventTriggers};
 use anyhow::{bail, Result};
+use dbus::arg::PropMap;
+use dbus::blocking::Connection;
+use dbus::message::MatchRule;
 use ini::Ini;
 use lazy_static::lazy_static;
-use log::debug;
+use log::{debug, info, warn};
 use std::path::PathBuf;
-use std::{env, fs};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::{env, fs, thread};
+use tokio::sync::mpsc;
 
 const AUTOSTART_FILENAME: &str = "goxlr-daemon.desktop";
 
@@ -84,3 +91,65 @@ fn get_startup_dir() -> Option<PathBuf> {
 
     None
 }
+
+/*
+When the system suspends, the GoXLR loses power and comes back in its default state, and the
+audio devices may be recreated with different names. logind sends 'PrepareForSleep' on the system
+bus both before sleeping (true) and after resuming (false), so we listen for the latter and let
+the daemon re-initialise everything.
+ */
+pub fn spawn_sleep_monitor(state: DaemonState, tx: mpsc::Sender<EventTriggers>) {
+    thread::spawn(move || {
+        if let Err(error) = run_sleep_monitor(state, tx) {
+            warn!("Unable to monitor for System Sleep: {}", error);
+        }
+    });
+}
+
+fn run_sleep_monitor(state: DaemonState, tx: mpsc::Sender<EventTriggers>) -> Result<()> {
+    let connection = Connection::new_system()?;
+    let rule = MatchRule::new_signal("org.freedesktop.login1.Manager", "PrepareForSleep");
+
+    connection.add_match(rule, move |(sleeping,): (bool,), _, _| {
+        if sleeping {
+            info!("System is going to Sleep");
+        } else {
+            info!("System has Resumed");
+            let _ = tx.blocking_send(EventTriggers::SystemResumed);
+        }
+        true
+    })?;
+
+    while !state.shutdown_blocking.load(Ordering::Relaxed) {
+        connection.process(Duration::from_millis(500))?;
+    }
+    debug!("Sleep Monitor Stopped");
+    Ok(())
+}
+
+// Sends a desktop notification via the freedesktop notification service (libnotify and friends)
+pub fn show_notification(message: &str) -> Result<()> {
+    let connection = Connection::new_session()?;
+    let proxy = connection.with_proxy(
+        "org.freedesktop.Notifications",
+        "/org/freedesktop/Notifications",
+        Duration::from_millis(1000),
+    );
+
+    let actions: Vec<&str> = vec![];
+    let _: (u32,) = proxy.method_call(
+        "org.freedesktop.Notifications",
+        "Notify",
+        (
+            "GoXLR Utility",
+            0_u32,
+            "",
+            "GoXLR",
+            message,
+            actions,
+            PropMap::new(),
+            3000_i32,
+        ),
+    )?;
+    Ok(())
+}
@@ -2,6 +2,7 @@ use crate::events::EventTriggers;
 use crate::DaemonState;
 use anyhow::Result;
 use cfg_if::cfg_if;
+use std::path::Path;
 use tokio::sync::mpsc;
 
 cfg_if! {
@@ -20,6 +21,14 @@ cfg_if! {
             windows::has_autostart()
         }
 
+        pub fn get_free_space(path: &Path) -> Option<u64> {
+            windows::get_free_space(path)
+        }
+
+        pub fn show_notification(_message: &str) -> Result<()> {
+            Ok(())
+        }
+
         pub fn set_autostart(enabled: bool) -> Result<()> {
             if enabled {
                 return windows::create_startup_link();
@@ -42,6 +51,7 @@ cfg_if! {
         }
 
         pub async fn spawn_runtime(state: DaemonState, tx: mpsc::Sender<EventTriggers>) -> Result<()> {
+            linux::spawn_sleep_monitor(state.clone(), tx.clone());
             unix::spawn_platform_runtime(state, tx).await
         }
 
@@ -49,6 +59,14 @@ cfg_if! {
             linux::has_autostart()
         }
 
+        pub fn get_free_space(path: &Path) -> Option<u64> {
+            unix::get_free_space(path)
+        }
+
+        pub fn show_notification(message: &str) -> Result<()> {
+            linux::show_notification(message)
+        }
+
         pub fn set_autostart(enabled: bool) -> Result<()> {
             if enabled {
                 return linux::create_startup_link();
@@ -71,6 +89,14 @@ cfg_if! {
             false
         }
 
+        pub fn get_free_space(path: &Path) -> Option<u64> {
+            unix::get_free_space(path)
+        }
+
+        pub fn show_notification(_message: &str) -> Result<()> {
+            Ok(())
+        }
+
         pub fn set_autostart(_enabled: bool) -> Result<()> {
             bail!("Autostart Not Supported on this Platform");
         }
@@ -89,6 +115,14 @@ cfg_if! {
             false
         }
 
+        pub fn get_free_space(_path: &Path) -> Option<u64> {
+            None
+        }
+
+        pub fn show_notification(_message: &str) -> Result<()> {
+            Ok(())
+        }
+
         pub fn set_autostart(_enabled: bool) -> Result<()> {
             bail!("Autostart Not Supported on this Platform");
         }
@@ -2,6 +2,8 @@ use crate::events::EventTriggers;
 use crate::DaemonState;
 use anyhow::Result;
 use log::debug;
+use nix::sys::statvfs::statvfs;
+use std::path::Path;
 use tokio::select;
 use tokio::signal::unix::{signal, SignalKind};
 use tokio::sync::mpsc;
@@ -26,3 +28,8 @@ pub async fn spawn_platform_runtime(
     debug!("Platform Runtime Ended");
     Ok(())
 }
+
+pub fn get_free_space(path: &Path) -> Option<u64> {
+    let stats = statvfs(path).ok()?;
+    Some(stats.blocks_available() as u64 * stats.fragment_size() as u64)
+}
@@ -7,13 +7,15 @@ use mslnk::ShellLink;
 use std::ffi::OsStr;
 use std::iter::once;
 use std::os::windows::ffi::OsStrExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::ptr::null_mut;
 use std::{env, fs};
 use tokio::signal::windows::{ctrl_break, ctrl_close, ctrl_logoff, ctrl_shutdown};
 use tokio::sync::mpsc;
 use tokio::time::Duration;
 use tokio::{select, time};
+use winapi::shared::ntdef::ULARGE_INTEGER;
+use winapi::um::fileapi::GetDiskFreeSpaceExW;
 use winapi::um::winuser;
 use winreg::enums::HKEY_CURRENT_USER;
 use winreg::RegKey;
@@ -196,3 +198,15 @@ fn get_startup_dir() -> Option<PathBuf> {
     }
     None
 }
+
+pub fn get_free_space(path: &Path) -> Option<u64> {
+    let path = to_wide(&path.to_string_lossy());
+    let mut available: ULARGE_INTEGER = unsafe { std::mem::zeroed() };
+
+    let result =
+        unsafe { GetDiskFreeSpaceExW(path.as_ptr(), &mut available, null_mut(), null_mut()) };
+    if result == 0 {
+        return None;
+    }
+    Some(unsafe { *available.QuadPart() })
+}
@@ -1,23 +1,33 @@
+use crate::command_queue::CommandQueue;
 use crate::device::Device;
 use crate::events::EventTriggers;
-use crate::files::extract_defaults;
+use crate::files::{extract_defaults, get_sample_info, FilePaths};
+use crate::logging::set_log_level;
+use crate::metrics::METRICS;
 use crate::platform::{has_autostart, set_autostart};
+use crate::support_bundle::create_support_bundle;
 use crate::{FileManager, PatchEvent, SettingsHandle, Shutdown, VERSION};
 use anyhow::{anyhow, Result};
 use goxlr_ipc::{
-    DaemonCommand, DaemonConfig, DaemonStatus, DeviceType, Files, GoXLRCommand, HardwareStatus,
-    HttpSettings, PathTypes, Paths, UsbProductInformation,
+    ButtonStates, DaemonCommand, DaemonConfig, DaemonError, DaemonEvent, DaemonStatus, DcpFeatures,
+    DeviceType, DiagnosticReport, Files, GoXLRCommand, HardwareStatus, HttpSettings, MissingSample,
+    PathTypes, Paths, SampleInfo, UsbProductInformation,
 };
+use goxlr_usb::device::base::FullGoXLRDevice;
 use goxlr_usb::device::base::GoXLRDevice;
 use goxlr_usb::device::{find_devices, from_device};
+use goxlr_usb::error::{find_protocol_error, find_usb_error, is_disconnect_error};
+use goxlr_usb::transfer_log;
 use goxlr_usb::{PID_GOXLR_FULL, PID_GOXLR_MINI};
-use json_patch::diff;
+use json_patch::{diff, Patch};
 use log::{error, info, warn};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use tokio::sync::broadcast::Sender as BroadcastSender;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::sync::{mpsc, oneshot};
+use tokio::task;
 use tokio::time::sleep;
 
 // Adding a third entry has tripped enum_variant_names, I'll probably need to rename
@@ -27,9 +37,20 @@ pub enum DeviceCommand {
     SendDaemonStatus(oneshot::Sender<DaemonStatus>),
     RunDaemonCommand(DaemonCommand, oneshot::Sender<Result<()>>),
     RunDeviceCommand(String, GoXLRCommand, oneshot::Sender<Result<()>>),
+    RunDeviceCommands(String, Vec<GoXLRCommand>, oneshot::Sender<Result<()>>),
+    GetButtonStates(String, oneshot::Sender<Result<ButtonStates>>),
+    RunDiagnostics(String, oneshot::Sender<Result<DiagnosticReport>>),
+    ValidateSamples(String, oneshot::Sender<Result<Vec<MissingSample>>>),
+    GetSampleInfo(String, oneshot::Sender<Result<SampleInfo>>),
+    CreateSupportBundle(oneshot::Sender<Result<PathBuf>>),
+    ReloadSettings(oneshot::Sender<Result<bool>>),
+    DryRunDeviceCommands(String, Vec<GoXLRCommand>, oneshot::Sender<Result<Patch>>),
 }
 
 pub type DeviceSender = Sender<DeviceCommand>;
+
+// If a device's inputs haven't been read for this long, check whether it's still responding
+const WATCHDOG_TIMEOUT: Duration = Duration::from_secs(5);
 pub type DeviceReceiver = Receiver<DeviceCommand>;
 
 // Fix this later..
@@ -38,12 +59,14 @@ pub async fn spawn_usb_handler(
     mut command_rx: DeviceReceiver,
     mut file_rx: Receiver<PathTypes>,
     mut device_stop_rx: Receiver<()>,
+    mut device_resume_rx: Receiver<()>,
     broadcast_tx: BroadcastSender<PatchEvent>,
     global_tx: Sender<EventTriggers>,
     mut shutdown: Shutdown,
     settings: SettingsHandle,
     http_settings: HttpSettings,
     mut file_manager: FileManager,
+    file_paths_tx: Sender<FilePaths>,
 ) {
     // We can probably either merge these, or struct them..
     let (disconnect_sender, mut disconnect_receiver) = mpsc::channel(16);
@@ -68,6 +91,8 @@ pub async fn spawn_usb_handler(
         get_daemon_status(&devices, &settings, &http_settings, files.clone()).await;
 
     let mut shutdown_triggered = false;
+    let mut command_queue = CommandQueue::default();
+    let mut last_watchdog_check = Instant::now();
 
     loop {
         let mut change_found = false;
@@ -83,9 +108,15 @@ pub async fn spawn_usb_handler(
                         device_identifier = Some(identifier.clone());
                     }
 
-                    match load_device(device, existing_serials, disconnect_sender.clone(), event_sender.clone(), global_tx.clone(), &settings).await {
-                        Ok(device) => {
-                            devices.insert(device.serial().to_owned(), device);
+                    let opened = open_device(device, existing_serials, disconnect_sender.clone(), event_sender.clone()).await;
+                    let attached = match opened {
+                        Ok((goxlr, hardware)) => attach_device(&mut devices, goxlr, hardware, global_tx.clone(), &settings).await,
+                        Err(e) => Err(e),
+                    };
+
+                    match attached {
+                        Ok(serial) => {
+                            METRICS.device_connected(&serial);
                             change_found = true;
                         }
                         Err(e) => {
@@ -101,33 +132,61 @@ pub async fn spawn_usb_handler(
                 detection_sleep.as_mut().reset(tokio::time::Instant::now() + detection_duration);
             },
             () = &mut update_sleep => {
-                for device in devices.values_mut() {
+                if last_watchdog_check.elapsed() >= WATCHDOG_TIMEOUT {
+                    last_watchdog_check = Instant::now();
+                    if check_watchdog(&mut devices).await {
+                        ignore_list.clear();
+                        change_found = true;
+                    }
+                }
+
+                for device in devices.values_mut().filter(|device| device.is_connected()) {
                     let updated = device.update_state().await;
 
                     if let Ok(result) = updated {
                         change_found = result;
                     }
 
+                    for event in device.take_events() {
+                        let _ = broadcast_tx.send(PatchEvent::Event(event));
+                    }
+
                     if let Err(error) = updated {
                         warn!("Error Received from {} while updating state: {}", device.serial(), error);
+                        change_found |= check_disconnected(device, &error);
                     }
                 }
                 update_sleep.as_mut().reset(tokio::time::Instant::now() + update_duration);
             }
             Some(serial) = disconnect_receiver.recv() => {
                 info!("[{}] Device Disconnected", serial);
-                devices.remove(&serial);
+
+                // Keep the device around, so its state can be restored if it comes back..
+                if let Some(device) = devices.get_mut(&serial) {
+                    device.set_disconnected();
+                }
+                METRICS.device_disconnected(&serial);
                 change_found = true;
             },
             Some(serial) = event_receiver.recv() => {
                 if let Some(device) = devices.get_mut(&serial) {
+                    if !device.is_connected() {
+                        continue;
+                    }
+
                     let result = device.monitor_inputs().await;
                     if let Ok(changed) = result {
+                        METRICS.device_polled(&serial);
                         change_found = changed;
                     }
 
+                    for event in device.take_events() {
+                        let _ = broadcast_tx.send(PatchEvent::Event(event));
+                    }
+
                     if let Err(error) = result {
                         warn!("Error Received from {}: {}", device.serial(), error);
+                        change_found |= check_disconnected(device, &error);
                     }
                 } else {
                     warn!("Cannot find registered device with serial: {}", &serial);
@@ -141,101 +200,40 @@ pub async fn spawn_usb_handler(
                 shutdown_triggered = true;
 
                 // Flip through all the devices, send a shutdown signal..
-                for device in devices.values_mut() {
+                for device in devices.values_mut().filter(|device| device.is_connected()) {
                     device.shutdown().await;
                 }
 
                 // Send a notification that we're done here..
                 let _ = global_tx.send(EventTriggers::DevicesStopped).await;
             }
+            Some(()) = device_resume_rx.recv() => {
+                // After a suspend the USB handles (and audio devices) may no longer be valid, so
+                // treat every device as disconnected and let detection bring them back..
+                for device in devices.values_mut().filter(|device| device.is_connected()) {
+                    device.set_disconnected();
+                    device.reset_audio_devices();
+                    METRICS.device_disconnected(device.serial());
+                }
+                ignore_list.clear();
+                change_found = true;
+            }
             () = shutdown.recv() => {
                 info!("Shutting down device worker");
                 return;
             },
             Some(command) = command_rx.recv() => {
-                match command {
-                    DeviceCommand::SendDaemonStatus(sender) => {
-                        let _ = sender.send(daemon_status.clone());
-                    }
+                command_queue.push(command);
 
-                    DeviceCommand::RunDaemonCommand(command, sender) => {
-                        match command {
-                            DaemonCommand::StopDaemon => {
-                                // These should probably be moved upstream somewhere, they're not
-                                // device specific!
-                                let _ = global_tx.send(EventTriggers::Stop).await;
-                                let _ = sender.send(Ok(()));
-                            }
-                            DaemonCommand::OpenUi => {
-                                let _ = global_tx.send(EventTriggers::OpenUi).await;
-                                let _ = sender.send(Ok(()));
-                            }
-                            DaemonCommand::Activate => {
-                                let _ = global_tx.send(EventTriggers::Activate).await;
-                                let _ = sender.send(Ok(()));
-                            }
-                            DaemonCommand::RecoverDefaults(path_type) => {
-                                let path = match path_type {
-                                    PathTypes::Profiles => settings.get_profile_directory().await,
-                                    PathTypes::Presets => settings.get_presets_directory().await,
-                                    PathTypes::Icons => settings.get_icons_directory().await,
-                                    PathTypes::MicProfiles => settings.get_mic_profile_directory().await,
-                                    _ => {
-                                        let _ = sender.send(Err(anyhow!("Invalid Path type Sent")));
-                                        return;
-                                    }
-                                };
-                                let _ = sender.send(extract_defaults(path_type, &path));
-                            }
-                            DaemonCommand::SetAutoStartEnabled(enabled) => {
-                                let _ = sender.send(set_autostart(enabled));
-                            }
-                            DaemonCommand::SetLogLevel(level) => {
-                                settings.set_log_level(level).await;
-                                settings.save().await;
-                                change_found = true;
-                                let _ = sender.send(Ok(()));
-                            }
-                            DaemonCommand::SetShowTrayIcon(enabled) => {
-                                settings.set_show_tray_icon(enabled).await;
-                                settings.save().await;
-                                change_found = true;
-                                let _ = sender.send(Ok(()));
-                            }
-                            DaemonCommand::SetTTSEnabled(enabled) => {
-                                settings.set_tts_enabled(enabled).await;
-                                settings.save().await;
-                                change_found = true;
-                                let _ = sender.send(Ok(()));
-                            }
-                            DaemonCommand::SetAllowNetworkAccess(enabled) => {
-                                settings.set_allow_network_access(enabled).await;
-                                settings.save().await;
-                                change_found = true;
-                                let _ = sender.send(Ok(()));
-                            }
-                            DaemonCommand::OpenPath(path_type) => {
-                                // There's nothing we can really do if this errors..
-                                let _ = global_tx.send(EventTriggers::Open(path_type)).await;
-                                let _ = sender.send(Ok(()));
-                            }
-                        }
-                    },
-
-                    DeviceCommand::RunDeviceCommand(serial, command, sender) => {
-                        if let Some(device) = devices.get_mut(&serial) {
-                            let _ = sender.send(device.perform_command(command).await);
-                            change_found = true;
-                        } else {
-                            let _ = sender.send(Err(anyhow!("Device {} is not connected", serial)));
-                        }
-                    },
+                // Grab anything else which has arrived while we were busy, so it can be coalesced..
+                while let Ok(command) = command_rx.try_recv() {
+                    command_queue.push(command);
                 }
             },
             Some(path) = file_rx.recv() => {
                 // Notify devices if Samples have changed..
                 if path == PathTypes::Samples {
-                    for device in devices.values_mut() {
+                    for device in devices.values_mut().filter(|device| device.is_connected()) {
                         let _ = device.validate_sampler().await;
                     }
                 }
@@ -245,27 +243,269 @@ pub async fn spawn_usb_handler(
             }
         }
 
-        if change_found {
-            let new_status =
-                get_daemon_status(&devices, &settings, &http_settings, files.clone()).await;
+        while let Some(command) = command_queue.pop() {
+            match command {
+                DeviceCommand::SendDaemonStatus(sender) => {
+                    // Make sure changes from commands earlier in the queue are included..
+                    if change_found {
+                        let new_status =
+                            get_daemon_status(&devices, &settings, &http_settings, files.clone())
+                                .await;
+                        send_status_patch(&daemon_status, &new_status, &broadcast_tx);
+                        daemon_status = new_status;
+                        change_found = false;
+                    }
+                    let _ = sender.send(daemon_status.clone());
+                }
+
+                DeviceCommand::RunDaemonCommand(command, sender) => {
+                    match command {
+                        DaemonCommand::StopDaemon => {
+                            // These should probably be moved upstream somewhere, they're not
+                            // device specific!
+                            let _ = global_tx.send(EventTriggers::Stop).await;
+                            let _ = sender.send(Ok(()));
+                        }
+                        DaemonCommand::OpenUi => {
+                            let _ = global_tx.send(EventTriggers::OpenUi).await;
+                            let _ = sender.send(Ok(()));
+                        }
+                        DaemonCommand::Activate => {
+                            let _ = global_tx.send(EventTriggers::Activate).await;
+                            let _ = sender.send(Ok(()));
+                        }
+                        DaemonCommand::RecoverDefaults(path_type) => {
+                            let path = match path_type {
+                                PathTypes::Profiles => settings.get_profile_directory().await,
+                                PathTypes::Presets => settings.get_presets_directory().await,
+                                PathTypes::Icons => settings.get_icons_directory().await,
+                                PathTypes::MicProfiles => {
+                                    settings.get_mic_profile_directory().await
+                                }
+                                _ => {
+                                    let _ = sender.send(Err(anyhow!("Invalid Path type Sent")));
+                                    return;
+                                }
+                            };
+                            let _ = sender.send(extract_defaults(path_type, &path));
+                        }
+                        DaemonCommand::SetAutoStartEnabled(enabled) => {
+                            let _ = sender.send(set_autostart(enabled));
+                        }
+                        DaemonCommand::SetLogLevel(level) => {
+                            let result = set_log_level(&level);
+                            settings.set_log_level(level).await;
+                            settings.save().await;
+                            change_found = true;
+                            let _ = sender.send(result);
+                        }
+                        DaemonCommand::SetUsbTraceEnabled(enabled) => {
+                            let result = if enabled {
+                                let path = settings
+                                    .get_log_directory()
+                                    .await
+                                    .join("goxlr-usb-trace.log");
+                                info!("Writing USB Trace to {}", path.to_string_lossy());
+                                transfer_log::enable(&path)
+                            } else {
+                                transfer_log::disable();
+                                Ok(())
+                            };
+                            change_found = true;
+                            let _ = sender.send(result);
+                        }
+                        DaemonCommand::SetShowTrayIcon(enabled) => {
+                            settings.set_show_tray_icon(enabled).await;
+                            settings.save().await;
+                            change_found = true;
+                            let _ = sender.send(Ok(()));
+                        }
+                        DaemonCommand::SetTTSEnabled(enabled) => {
+                            settings.set_tts_enabled(enabled).await;
+                            settings.save().await;
+                            change_found = true;
+                            let _ = sender.send(Ok(()));
+                        }
+                        DaemonCommand::SetNotificationsEnabled(enabled) => {
+                            settings.set_notifications_enabled(enabled).await;
+                            settings.save().await;
+                            change_found = true;
+                            let _ = sender.send(Ok(()));
+                        }
+                        DaemonCommand::SetAllowNetworkAccess(enabled) => {
+                            settings.set_allow_network_access(enabled).await;
+                            settings.save().await;
+                            change_found = true;
+                            let _ = sender.send(Ok(()));
+                        }
+                        DaemonCommand::SetVirtualChannel(name, channel) => {
+                            let result = if name.trim().is_empty() {
+                                Err(anyhow!("Virtual Channel name cannot be empty"))
+                            } else {
+                                settings.set_virtual_channel(&name, channel).await;
+                                settings.save().await;
+                                Ok(())
+                            };
+                            change_found = true;
+                            let _ = sender.send(result);
+                        }
+                        DaemonCommand::SetVodMix(vod_mix) => {
+                            let result = if vod_mix.name.trim().is_empty() {
+                                Err(anyhow!("VOD Mix name cannot be empty"))
+                            } else {
+                                settings.set_vod_mix(vod_mix).await;
+                                settings.save().await;
+                                Ok(())
+                            };
+                            change_found = true;
+                            let _ = sender.send(result);
+                        }
+                        DaemonCommand::OpenPath(path_type) => {
+                            // There's nothing we can really do if this errors..
+                            let _ = global_tx.send(EventTriggers::Open(path_type)).await;
+                            let _ = sender.send(Ok(()));
+                        }
+                    }
+                }
+
+                DeviceCommand::RunDeviceCommand(serial, command, sender) => {
+                    if let Some(device) = connected_device(&mut devices, &serial) {
+                        let start = Instant::now();
+                        let result = device.perform_command(command).await;
+                        METRICS.command_executed(start.elapsed(), result.is_ok());
+                        if let Err(error) = &result {
+                            check_disconnected(device, error);
+                        }
+                        let _ = sender.send(result);
+                        change_found = true;
+                    } else {
+                        let _ = sender.send(Err(DaemonError::DeviceNotFound(serial).into()));
+                    }
+                }
+
+                DeviceCommand::GetButtonStates(serial, sender) => {
+                    if let Some(device) = connected_device(&mut devices, &serial) {
+                        let _ = sender.send(Ok(device.button_states()));
+                    } else {
+                        let _ = sender.send(Err(DaemonError::DeviceNotFound(serial).into()));
+                    }
+                }
+
+                DeviceCommand::RunDiagnostics(serial, sender) => {
+                    if let Some(device) = connected_device(&mut devices, &serial) {
+                        let _ = sender.send(Ok(device.run_diagnostics().await));
+                    } else {
+                        let _ = sender.send(Err(DaemonError::DeviceNotFound(serial).into()));
+                    }
+                }
 
-            // Convert them to JSON..
-            let json_old = serde_json::to_value(&daemon_status).unwrap();
-            let json_new = serde_json::to_value(&new_status).unwrap();
+                DeviceCommand::ValidateSamples(serial, sender) => {
+                    if let Some(device) = connected_device(&mut devices, &serial) {
+                        let _ = sender.send(Ok(device.find_missing_samples().await));
+                    } else {
+                        let _ = sender.send(Err(DaemonError::DeviceNotFound(serial).into()));
+                    }
+                }
 
-            let patch = diff(&json_old, &json_new);
+                DeviceCommand::GetSampleInfo(file, sender) => {
+                    let sample_path = settings.get_samples_directory().await;
+                    tokio::spawn(async move {
+                        let _ = sender.send(get_sample_info(sample_path, file).await);
+                    });
+                }
+
+                DeviceCommand::CreateSupportBundle(sender) => {
+                    // Reading the logs can take a moment, so don't hold up the devices for it..
+                    let settings = settings.clone();
+                    let status = daemon_status.clone();
+                    tokio::spawn(async move {
+                        let _ = sender.send(create_support_bundle(settings, status).await);
+                    });
+                }
 
-            // Only send a patch if something has changed..
-            if !patch.0.is_empty() {
-                let _ = broadcast_tx.send(PatchEvent { data: patch });
+                DeviceCommand::ReloadSettings(sender) => {
+                    let previous_level = settings.get_log_level().await;
+                    let result = settings.reload().await;
+                    if let Ok(true) = result {
+                        let level = settings.get_log_level().await;
+                        if level != previous_level {
+                            if let Err(error) = set_log_level(&level) {
+                                warn!("Unable to Change Log Level: {}", error);
+                            }
+                        }
+
+                        let paths = FileManager::get_file_paths_from_settings(&settings).await;
+                        if &paths != file_manager.paths() {
+                            let samples_changed = paths.samples != file_manager.paths().samples;
+                            file_manager.set_paths(paths.clone());
+                            let _ = file_paths_tx.send(paths).await;
+                            files = get_files(&mut file_manager).await;
+
+                            if samples_changed {
+                                for device in devices.values_mut().filter(|d| d.is_connected()) {
+                                    let _ = device.validate_sampler().await;
+                                }
+                            }
+                        }
+
+                        for device in devices.values_mut() {
+                            device.reload_settings().await;
+                        }
+                        let _ = broadcast_tx.send(PatchEvent::Event(DaemonEvent::SettingsChanged));
+                        change_found = true;
+                    }
+                    let _ = sender.send(result);
+                }
+
+                DeviceCommand::DryRunDeviceCommands(serial, commands, sender) => {
+                    if let Some(device) = connected_device(&mut devices, &serial) {
+                        let _ = sender.send(device.dry_run(commands).await);
+                    } else {
+                        let _ = sender.send(Err(DaemonError::DeviceNotFound(serial).into()));
+                    }
+                }
+
+                DeviceCommand::RunDeviceCommands(serial, commands, sender) => {
+                    if let Some(device) = connected_device(&mut devices, &serial) {
+                        let result = run_commands(device, commands).await;
+                        if let Err(error) = &result {
+                            check_disconnected(device, error);
+                        }
+                        let _ = sender.send(result);
+                        change_found = true;
+                    } else {
+                        let _ = sender.send(Err(DaemonError::DeviceNotFound(serial).into()));
+                    }
+                }
             }
+        }
 
-            // Send the patch to the tokio broadcaster, for handling by clients..
+        if change_found {
+            let new_status =
+                get_daemon_status(&devices, &settings, &http_settings, files.clone()).await;
+            send_status_patch(&daemon_status, &new_status, &broadcast_tx);
             daemon_status = new_status;
         }
     }
 }
 
+fn send_status_patch(
+    old_status: &DaemonStatus,
+    new_status: &DaemonStatus,
+    broadcast_tx: &BroadcastSender<PatchEvent>,
+) {
+    // Convert them to JSON..
+    let json_old = serde_json::to_value(old_status).unwrap();
+    let json_new = serde_json::to_value(new_status).unwrap();
+
+    let patch = diff(&json_old, &json_new);
+
+    // Only send a patch if something has changed..
+    if !patch.0.is_empty() {
+        let _ = broadcast_tx.send(PatchEvent::Patch(patch));
+    }
+}
+
 async fn get_daemon_status(
     devices: &HashMap<String, Device<'_>>,
     settings: &SettingsHandle,
@@ -279,8 +519,12 @@ async fn get_daemon_status(
             autostart_enabled: has_autostart(),
             show_tray_icon: settings.get_show_tray_icon().await,
             tts_enabled: settings.get_tts_enabled().await,
+            notifications_enabled: settings.get_notifications_enabled().await,
             allow_network_access: settings.get_allow_network_access().await,
             log_level: settings.get_log_level().await,
+            usb_trace_enabled: transfer_log::is_enabled(),
+            virtual_channels: settings.get_virtual_channels().await,
+            vod_mix: settings.get_vod_mix().await,
         },
         paths: Paths {
             profile_directory: settings.get_profile_directory().await,
@@ -357,48 +601,145 @@ fn find_new_device(
     let goxlr_devices = find_devices();
     goxlr_devices.into_iter().find(|device| {
         // Check the Mixers on the existing DaemonStatus..
-        !current_status.mixers.values().any(|d| {
-            if let Some(identifier) = device.identifier() {
-                if let Some(device_identifier) = &d.hardware.usb_device.identifier {
-                    return identifier.clone() == device_identifier.clone();
-                }
-            }
-            d.hardware.usb_device.bus_number == device.bus_number()
-                && d.hardware.usb_device.address == device.address()
-        }) && !devices_to_ignore
-            .iter()
-            .any(|((bus_number, address, identifier), expires)| {
-                if let Some(identifier) = identifier {
-                    if let Some(device_identifier) = device.identifier() {
-                        return identifier == device_identifier && expires > &now;
+        // Mixers which are offline may come back on a different address, so are ignored here..
+        !current_status
+            .mixers
+            .values()
+            .filter(|d| d.connected)
+            .any(|d| {
+                if let Some(identifier) = device.identifier() {
+                    if let Some(device_identifier) = &d.hardware.usb_device.identifier {
+                        return identifier.clone() == device_identifier.clone();
                     }
                 }
-                *bus_number == device.bus_number() && *address == device.address() && expires > &now
+                d.hardware.usb_device.bus_number == device.bus_number()
+                    && d.hardware.usb_device.address == device.address()
             })
+            && !devices_to_ignore
+                .iter()
+                .any(|((bus_number, address, identifier), expires)| {
+                    if let Some(identifier) = identifier {
+                        if let Some(device_identifier) = device.identifier() {
+                            return identifier == device_identifier && expires > &now;
+                        }
+                    }
+                    *bus_number == device.bus_number()
+                        && *address == device.address()
+                        && expires > &now
+                })
     })
 }
 
+// Looks for devices whose inputs haven't been read recently, and if they don't respond to a
+// probe either, treats them as disconnected so detection will reopen and reinitialise them.
+// Returns true if any devices were reset.
+async fn check_watchdog(devices: &mut HashMap<String, Device<'_>>) -> bool {
+    let mut reset = false;
+    for device in devices.values_mut() {
+        if !device.is_connected() || device.is_updating_firmware() {
+            continue;
+        }
+
+        // Some platforms only report input changes, so a quiet device isn't necessarily stuck..
+        let serial = device.serial().to_owned();
+        let last_poll = METRICS
+            .device_last_poll(&serial)
+            .or_else(|| METRICS.device_uptime(&serial));
+        match last_poll {
+            Some(poll) if poll >= WATCHDOG_TIMEOUT => {}
+            _ => continue,
+        }
+
+        if device.probe().await {
+            METRICS.device_polled(&serial);
+            continue;
+        }
+
+        warn!("[{}] Device has stopped responding, resetting..", serial);
+        device.set_disconnected();
+        METRICS.device_disconnected(&serial);
+        reset = true;
+    }
+    reset
+}
+
 fn get_all_serials(existing_devices: &HashMap<String, Device>) -> Vec<String> {
     let mut serials: Vec<String> = vec![];
 
-    for device in existing_devices {
-        serials.push(device.0.clone());
+    // Offline devices are excluded so their serial can be reclaimed when they return..
+    for (serial, device) in existing_devices {
+        if device.is_connected() {
+            serials.push(serial.clone());
+        }
     }
 
     serials
 }
 
-async fn load_device(
+fn connected_device<'a, 'b>(
+    devices: &'b mut HashMap<String, Device<'a>>,
+    serial: &str,
+) -> Option<&'b mut Device<'a>> {
+    devices
+        .get_mut(serial)
+        .filter(|device| device.is_connected())
+}
+
+// If an error was caused by the device going away, mark it offline and stop talking to it.
+fn check_disconnected(device: &mut Device, error: &anyhow::Error) -> bool {
+    let usb_error =
+        find_usb_error(error).or_else(|| find_protocol_error(error).map(|e| e.to_string()));
+    if let Some(message) = usb_error {
+        METRICS.usb_error(device.serial(), &message);
+    }
+
+    if is_disconnect_error(error) {
+        device.set_disconnected();
+        return true;
+    }
+    false
+}
+
+// A batch is applied as a whole, if a command fails the profile and mic profile are put back to
+// how they were before the batch started (and re-applied to the device). Changes made outside of
+// them by earlier commands (such as a saved profile file, or a daemon setting) are not undone.
+async fn run_commands(device: &mut Device<'_>, commands: Vec<GoXLRCommand>) -> Result<()> {
+    let snapshot = device.take_snapshot()?;
+
+    for (index, command) in commands.into_iter().enumerate() {
+        let description = format!("{:?}", command);
+
+        let start = Instant::now();
+        let result = device.perform_command(command).await;
+        METRICS.command_executed(start.elapsed(), result.is_ok());
+
+        if let Err(error) = result {
+            if let Err(restore_error) = device.restore_snapshot(&snapshot, false).await {
+                warn!("Unable to Roll Back Failed Batch: {}", restore_error);
+            }
+            return Err(anyhow!(
+                "Batch Command {} ({}) failed, the batch has been rolled back: {}",
+                index,
+                description,
+                error
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) async fn open_device(
     device: GoXLRDevice,
     existing_serials: Vec<String>,
     disconnect_sender: Sender<String>,
     event_sender: Sender<String>,
-    global_events: Sender<EventTriggers>,
-    settings: &SettingsHandle,
-) -> Result<Device<'_>> {
+) -> Result<(Box<dyn FullGoXLRDevice>, HardwareStatus)> {
     let device_copy = device.clone();
 
-    let mut handled_device = from_device(device, disconnect_sender, event_sender)?;
+    // Opening (and possibly initialising) the device is blocking, so keep it off the runtime.
+    let mut handled_device =
+        task::spawn_blocking(move || from_device(device, disconnect_sender, event_sender))
+            .await??;
     let descriptor = handled_device.get_descriptor()?;
 
     let device_type = match descriptor.product_id() {
@@ -408,6 +749,7 @@ async fn load_device(
     };
     let device_version = descriptor.device_version();
     let version = (device_version.0, device_version.1, device_version.2);
+    let categories = handled_device.get_supported_dcp_categories().await;
     let usb_device = UsbProductInformation {
         manufacturer_name: descriptor.device_manufacturer(),
         product_name: descriptor.product_name(),
@@ -415,8 +757,9 @@ async fn load_device(
         address: device_copy.address(),
         identifier: device_copy.identifier().clone(),
         version,
+        dcp_features: DcpFeatures::from(categories.as_slice()),
     };
-    let (mut serial_number, manufactured_date) = handled_device.get_serial_number()?;
+    let (mut serial_number, manufactured_date) = handled_device.get_serial_number().await?;
     if serial_number.is_empty() {
         let mut serial = String::from("");
         for i in 0..=24 {
@@ -433,18 +776,38 @@ async fn load_device(
     handled_device.set_unique_identifier(serial_number.clone());
 
     let hardware = HardwareStatus {
-        versions: handled_device.get_firmware_version()?,
+        versions: handled_device.get_firmware_version().await?,
         serial_number: serial_number.clone(),
         manufactured_date,
         device_type,
         usb_device,
     };
+    Ok((handled_device, hardware))
+}
+
+// Attaches an opened device, if we already know about it (because it was previously unplugged)
+// the existing Device is reconnected and restored, otherwise a new one is created.
+async fn attach_device<'a>(
+    devices: &mut HashMap<String, Device<'a>>,
+    goxlr: Box<dyn FullGoXLRDevice>,
+    hardware: HardwareStatus,
+    global_events: Sender<EventTriggers>,
+    settings: &'a SettingsHandle,
+) -> Result<String> {
+    let serial_number = hardware.serial_number.clone();
+    if let Some(device) = devices.get_mut(&serial_number) {
+        if !device.is_connected() {
+            device.reconnect(goxlr, hardware).await?;
+            return Ok(serial_number);
+        }
+    }
+
     let profile_directory = settings.get_profile_directory().await;
     let profile_name = settings.get_device_profile_name(&serial_number).await;
     let mic_profile_name = settings.get_device_mic_profile_name(&serial_number).await;
     let mic_profile_directory = settings.get_mic_profile_directory().await;
     let device = Device::new(
-        handled_device,
+        goxlr,
         hardware,
         profile_name,
         mic_profile_name,
@@ -461,5 +824,6 @@ async fn load_device(
         .set_device_mic_profile_name(&serial_number, device.mic_profile().name())
         .await;
     settings.save().await;
-    Ok(device)
+    devices.insert(serial_number.clone(), device);
+    Ok(serial_number)
 }
@@ -11,14 +11,14 @@ use log::{debug, warn};
 use strum::IntoEnumIterator;
 
 use goxlr_ipc::{
-    ActiveEffects, AnimationLighting, ButtonLighting, CoughButton, Echo, Effects, FaderLighting,
-    Gender, HardTune, Lighting, Megaphone, OneColour, Pitch, Reverb, Robot, Sample,
-    SampleProcessState, Sampler, SamplerButton, SamplerLighting, Scribble, Submix, Submixes,
-    ThreeColours, TwoColours,
+    ActiveEffects, AnimationLighting, BleepButton, ButtonLighting, CoughButton, DaemonError, Echo,
+    Effects, EncoderPositions, FaderLighting, Gender, HardTune, Lighting, Megaphone, MissingSample,
+    OneColour, Pitch, Reverb, Robot, Sample, SampleProcessState, Sampler, SamplerButton,
+    SamplerLighting, Scribble, ScribbleStyle, Submix, Submixes, ThreeColours, TwoColours,
 };
 use goxlr_profile_loader::components::animation::{AnimationMode, WaterfallDirection};
 use goxlr_profile_loader::components::colours::{
-    Colour, ColourDisplay, ColourMap, ColourOffStyle, ColourState,
+    Colour, ColourDisplay, ColourHoldStyle, ColourMap, ColourOffStyle, ColourState,
 };
 use goxlr_profile_loader::components::echo::{EchoEncoder, EchoStyle};
 use goxlr_profile_loader::components::gender::{GenderEncoder, GenderStyle};
@@ -36,12 +36,13 @@ use goxlr_profile_loader::components::submix::mix_routing_tree::Mix;
 use goxlr_profile_loader::profile::{Profile, ProfileSettings};
 use goxlr_profile_loader::SampleButtons::{BottomLeft, BottomRight, Clear, TopLeft, TopRight};
 use goxlr_profile_loader::{Faders, Preset, SampleButtons};
-use goxlr_scribbles::get_scribble;
+use goxlr_scribbles::{get_scribble, TextAlignment, TextStyle};
 use goxlr_types::{
-    Button, ButtonColourGroups, ButtonColourOffStyle as BasicColourOffStyle, ChannelName,
-    EffectBankPresets, EncoderColourTargets, FaderDisplayStyle as BasicColourDisplay,
-    FaderDisplayStyle, FaderName, InputDevice, MuteFunction as BasicMuteFunction, MuteState,
-    OutputDevice, SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets, SimpleColourTargets,
+    Button, ButtonColourGroups, ButtonColourHoldStyle as BasicColourHoldStyle,
+    ButtonColourOffStyle as BasicColourOffStyle, ChannelName, EffectBankPresets,
+    EncoderColourTargets, FaderDisplayStyle as BasicColourDisplay, FaderDisplayStyle, FaderName,
+    InputDevice, MuteFunction as BasicMuteFunction, MuteState, OutputDevice, SamplePlayOrder,
+    SamplePlaybackMode, SamplerColourTargets, ScribbleAlignment, SimpleColourTargets,
     SubMixChannelName, VersionNumber,
 };
 use goxlr_usb::buttonstate::{ButtonStates, Buttons};
@@ -51,10 +52,37 @@ use goxlr_usb::colouring::ColourTargets;
 use crate::audio::{AudioFile, AudioHandler};
 use crate::device::CurrentState;
 use crate::files::can_create_new_file;
+use crate::settings::RuntimeState;
 
 pub const DEFAULT_PROFILE_NAME: &str = "Default";
 const DEFAULT_PROFILE: &[u8] = include_bytes!("../profiles/Default.goxlr");
 
+// The profile settings at a point in time, the scribble images aren't included as they can't be
+// changed while a profile is loaded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileSnapshot {
+    name: String,
+    settings: Vec<u8>,
+
+    // Writing resets monitoring to the headphones, so it's stored here and put back on restore.
+    monitor: OutputDevice,
+}
+
+impl ProfileSnapshot {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+// The sampler settings held by the daemon rather than the profile, reported alongside it.
+pub struct SamplerSettings {
+    pub pre_record: u16,
+    pub normalization_target: f32,
+    pub max_recording_length: u16,
+    pub queue_next: bool,
+    pub recording_template: String,
+}
+
 #[derive(Debug)]
 pub struct ProfileAdapter {
     name: String,
@@ -80,11 +108,24 @@ impl ProfileAdapter {
             return ProfileAdapter::from_reader(name, file);
         }
 
-        bail!(
+        debug!(
             "Profile {} does not exist inside {:?}",
             name,
             directory.to_string_lossy()
         );
+        bail!(DaemonError::ProfileNotFound(name));
+    }
+
+    // Loads a profile from anywhere on disk, the name is taken from the file name.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let name = path
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or_else(|| anyhow!("Invalid Profile Path"))?;
+
+        debug!("Loading Profile From {}", path.to_string_lossy());
+        let file = File::open(path).context("Couldn't open profile for reading")?;
+        ProfileAdapter::from_reader(name, file)
     }
 
     pub fn default() -> Self {
@@ -110,6 +151,15 @@ impl ProfileAdapter {
         self.save(directory, overwrite)
     }
 
+    // Writes the current state to a new profile, without changing the active profile's name.
+    pub fn export(&mut self, name: String, directory: &Path) -> Result<()> {
+        let path = directory.join(format!("{name}.goxlr"));
+        can_create_new_file(path.clone())?;
+
+        self.profile.save(path)?;
+        Ok(())
+    }
+
     pub fn save(&mut self, directory: &Path, overwrite: bool) -> Result<()> {
         let name = &self.name;
         let path = directory.join(format!("{name}.goxlr"));
@@ -139,6 +189,24 @@ impl ProfileAdapter {
         &self.name
     }
 
+    pub fn snapshot(&mut self) -> Result<ProfileSnapshot> {
+        let mut settings = Vec::new();
+        self.profile.settings_mut().write_to(&mut settings)?;
+
+        Ok(ProfileSnapshot {
+            name: self.name.clone(),
+            settings,
+            monitor: self.get_monitoring_mix(),
+        })
+    }
+
+    pub fn restore_snapshot(&mut self, snapshot: &ProfileSnapshot) -> Result<()> {
+        let settings = ProfileSettings::load(Cursor::new(&snapshot.settings))?;
+        *self.profile.settings_mut() = settings;
+        self.name = snapshot.name.clone();
+        self.set_monitor_mix(snapshot.monitor)
+    }
+
     pub fn load_colour_profile(&mut self, new_profile: ProfileAdapter) {
         for colour in ColourTargets::iter() {
             let our_map = get_profile_colour_map_mut(self.profile.settings_mut(), colour);
@@ -155,7 +223,12 @@ impl ProfileAdapter {
             .replace(new_tree);
     }
 
-    pub fn load_preset(&mut self, name: String, directories: Vec<&Path>) -> Result<()> {
+    pub fn load_preset(
+        &mut self,
+        name: String,
+        directories: Vec<&Path>,
+        bank: EffectBankPresets,
+    ) -> Result<()> {
         let mut dir_list = "".to_string();
 
         // Loop through the provided directories, and try to find the preset..
@@ -166,7 +239,9 @@ impl ProfileAdapter {
                 debug!("Loading Preset From {}", path.to_string_lossy());
                 let file = File::open(path).context("Couldn't open preset for reading")?;
 
-                self.profile.settings_mut().load_preset(file)?;
+                self.profile
+                    .settings_mut()
+                    .load_preset_into(file, standard_to_profile_preset(bank))?;
                 return Ok(());
             }
             dir_list = format!("{}, {}", dir_list, directory.to_string_lossy());
@@ -198,6 +273,57 @@ impl ProfileAdapter {
         }
     }
 
+    pub(crate) fn get_runtime_state(&self) -> RuntimeState {
+        let mut volumes = HashMap::new();
+        for channel in ChannelName::iter() {
+            volumes.insert(channel, self.get_channel_volume(channel));
+        }
+
+        let mut fader_mutes = HashMap::new();
+        let mut previous_volumes = HashMap::new();
+        for fader in FaderName::iter() {
+            fader_mutes.insert(fader, self.get_ipc_mute_state(fader));
+            previous_volumes.insert(fader, self.get_mute_button_previous_volume(fader));
+        }
+
+        let (_, muted_to_x, muted_to_all, _) = self.get_mute_chat_button_state();
+        let mic_mute = if muted_to_all {
+            MuteState::MutedToAll
+        } else if muted_to_x {
+            MuteState::MutedToX
+        } else {
+            MuteState::Unmuted
+        };
+
+        RuntimeState {
+            profile: self.name().to_owned(),
+            volumes,
+            fader_mutes,
+            previous_volumes,
+            mic_mute,
+        }
+    }
+
+    // Applies a previous snapshot to the profile, this should be followed by a profile apply.
+    pub(crate) fn apply_runtime_state(&mut self, state: &RuntimeState) -> Result<()> {
+        for (channel, volume) in &state.volumes {
+            self.set_channel_volume(*channel, *volume)?;
+        }
+
+        for (fader, mute_state) in &state.fader_mutes {
+            self.set_mute_button_on(*fader, *mute_state != MuteState::Unmuted)?;
+            self.set_mute_button_blink(*fader, *mute_state == MuteState::MutedToAll)?;
+        }
+
+        for (fader, volume) in &state.previous_volumes {
+            self.set_mute_previous_volume(*fader, *volume)?;
+        }
+
+        self.set_mute_chat_button_on(state.mic_mute != MuteState::Unmuted);
+        self.set_mute_chat_button_blink(state.mic_mute == MuteState::MutedToAll);
+        Ok(())
+    }
+
     fn get_channel_mute_state(&self, channel: ChannelName) -> ChannelState {
         // Is this assigned to a fader?
         if let Some(fader) = self.get_fader_from_channel(channel) {
@@ -264,12 +390,7 @@ impl ProfileAdapter {
         let value = if enabled { 8192 } else { 0 };
 
         // Before we do anything before we do anything, make sure it's valid..
-        if input == InputDevice::Chat && output == OutputDevice::ChatMic {
-            bail!("Invalid Route: Chat -> Chat Mic");
-        }
-        if input == InputDevice::Samples && output == OutputDevice::Sampler {
-            bail!("Invalid Route: Samples -> Sampler");
-        }
+        validate_route(input, output)?;
 
         // Before we do anything, are we changing Headphones while they're not the active Monitor?
         if monitoring != OutputDevice::Headphones && output == OutputDevice::Headphones {
@@ -398,6 +519,18 @@ impl ProfileAdapter {
         colours.set_fader_display(standard_to_profile_fader_display(display))
     }
 
+    pub fn get_fader_colours(&self, fader: FaderName) -> (String, String) {
+        let colours = self
+            .profile
+            .settings()
+            .fader(standard_to_profile_fader(fader))
+            .colour_map();
+        (
+            colours.colour_or_default(0).to_rgb(),
+            colours.colour_or_default(1).to_rgb(),
+        )
+    }
+
     // We have a return type here, as there's string parsing involved..
     pub fn set_fader_colours(
         &mut self,
@@ -415,7 +548,12 @@ impl ProfileAdapter {
         Ok(())
     }
 
-    pub fn get_scribble_image(&self, fader: FaderName, path: &Path) -> [u8; 1024] {
+    pub fn get_scribble_image(
+        &self,
+        fader: FaderName,
+        path: &Path,
+        style: &ScribbleStyle,
+    ) -> [u8; 1024] {
         let scribble = self
             .profile
             .settings()
@@ -431,6 +569,10 @@ impl ProfileAdapter {
             scribble.text_bottom_middle(),
             scribble.text_top_left(),
             scribble.is_style_invert(),
+            &TextStyle {
+                font: style.font.clone(),
+                alignment: standard_to_scribble_alignment(style.alignment),
+            },
         )
     }
 
@@ -613,6 +755,7 @@ impl ProfileAdapter {
             let colour_map = get_profile_colour_map(self.profile.settings(), colour_target);
 
             let off_style = profile_to_standard_colour_off_style(*colour_map.get_off_style());
+            let hold_style = profile_to_standard_colour_hold_style(colour_map.get_hold_style());
 
             // TODO: Sampler Buttons are technically three colours!
 
@@ -620,6 +763,7 @@ impl ProfileAdapter {
                 button,
                 ButtonLighting {
                     off_style,
+                    hold_style,
                     colours: TwoColours {
                         colour_one: colour_map.colour_or_default(0).to_rgb(),
                         colour_two: colour_map.colour_or_default(1).to_rgb(),
@@ -839,6 +983,12 @@ impl ProfileAdapter {
                 robot,
                 hard_tune,
             },
+            encoders: EncoderPositions {
+                pitch: self.get_pitch_encoder_position(),
+                gender: self.get_gender_value(),
+                reverb: self.get_reverb_value(),
+                echo: self.get_echo_value(),
+            },
         })
     }
 
@@ -846,8 +996,9 @@ impl ProfileAdapter {
         &self,
         is_device_mini: bool,
         audio_handler: &Option<AudioHandler>,
-        sampler_prerecord: u16,
+        settings: SamplerSettings,
         processing_state: SampleProcessState,
+        missing_samples: &[MissingSample],
     ) -> Option<Sampler> {
         if is_device_mini {
             return None;
@@ -868,19 +1019,28 @@ impl ProfileAdapter {
 
                 let mut tracks = vec![];
                 for track in sample_bank.get_tracks() {
+                    let missing = missing_samples.iter().any(|sample| {
+                        sample.bank == bank
+                            && sample.button == button
+                            && sample.track == track.track
+                    });
                     tracks.push(Sample {
                         name: track.track.clone(),
                         start_pct: track.start_position,
                         stop_pct: track.end_position,
+                        playback_speed: track.playback_speed,
+                        missing,
                     });
                 }
 
                 let mut is_playing = false;
                 let mut is_recording = false;
+                let mut active = None;
 
                 if let Some(audio_handler) = audio_handler {
                     is_playing = audio_handler.is_sample_playing(bank, button);
                     is_recording = audio_handler.sample_recording(bank, button);
+                    active = audio_handler.get_active_sample(bank, button);
                 }
 
                 // Create a SamplerButton
@@ -892,6 +1052,7 @@ impl ProfileAdapter {
                     samples: tracks,
                     is_playing,
                     is_recording,
+                    active,
                 };
                 buttons.insert(button, sampler_button);
             }
@@ -903,7 +1064,18 @@ impl ProfileAdapter {
             processing_state,
             active_bank: self.get_active_sample_bank(),
             clear_active: self.is_sample_clear_active(),
-            record_buffer: sampler_prerecord,
+            record_buffer: settings.pre_record,
+            monitor_device: audio_handler
+                .as_ref()
+                .and_then(|handler| handler.get_monitor_device()),
+            monitor_recording: audio_handler
+                .as_ref()
+                .map(|handler| handler.is_monitor_recording())
+                .unwrap_or(false),
+            max_recording_length: settings.max_recording_length,
+            queue_next: settings.queue_next,
+            recording_template: settings.recording_template,
+            normalization_target: settings.normalization_target,
             banks: sampler_map,
         })
     }
@@ -1074,8 +1246,8 @@ impl ProfileAdapter {
         };
     }
 
-    pub fn get_cough_status(&self) -> CoughButton {
-        let (_, muted_to_x, muted_to_all, _) = self.get_mute_chat_button_state();
+    pub fn get_cough_status(&self, pressed: bool) -> CoughButton {
+        let (mute_toggle, muted_to_x, muted_to_all, _) = self.get_mute_chat_button_state();
         let mic_state = if muted_to_all {
             MuteState::MutedToAll
         } else if muted_to_x {
@@ -1084,12 +1256,40 @@ impl ProfileAdapter {
             MuteState::Unmuted
         };
 
+        let mute_type = profile_to_standard_mute_function(
+            *self.profile.settings().mute_chat().cough_mute_source(),
+        );
+
+        let muted_outputs = match mic_state {
+            MuteState::Unmuted => vec![],
+            MuteState::MutedToAll => OutputDevice::iter().collect(),
+            MuteState::MutedToX => match mute_type {
+                BasicMuteFunction::All => OutputDevice::iter().collect(),
+                BasicMuteFunction::ToStream => vec![OutputDevice::BroadcastMix],
+                BasicMuteFunction::ToVoiceChat => vec![OutputDevice::ChatMic],
+                BasicMuteFunction::ToPhones => vec![OutputDevice::Headphones],
+                BasicMuteFunction::ToLineOut => vec![OutputDevice::LineOut],
+            },
+        };
+
         CoughButton {
-            is_toggle: self.profile.settings().mute_chat().is_cough_toggle(),
-            mute_type: profile_to_standard_mute_function(
-                *self.profile.settings().mute_chat().cough_mute_source(),
-            ),
+            is_toggle: mute_toggle,
+            mute_type,
             state: mic_state,
+            pressed,
+            latched: mute_toggle && mic_state != MuteState::Unmuted,
+            muted_outputs,
+        }
+    }
+
+    pub fn get_bleep_status(&self, pressed: bool) -> BleepButton {
+        BleepButton {
+            pressed,
+            muted_channels: if pressed {
+                vec![ChannelName::Mic]
+            } else {
+                vec![]
+            },
         }
     }
 
@@ -1729,22 +1929,6 @@ impl ProfileAdapter {
         true
     }
 
-    pub fn get_next_track(&mut self, button: goxlr_types::SampleButtons) -> Result<AudioFile> {
-        let bank = self.profile.settings().context().selected_sample();
-        let track = self
-            .profile
-            .settings_mut()
-            .sample_button_mut(standard_to_profile_sample_button(button))
-            .get_stack_mut(bank)
-            .get_next_track();
-
-        if let Some(track) = track {
-            return Ok(ProfileAdapter::track_to_audio(track));
-        }
-
-        Err(anyhow!("Unable to Find Track to play!"))
-    }
-
     pub fn get_track_by_index(
         &self,
         bank: goxlr_types::SampleBank,
@@ -1786,6 +1970,7 @@ impl ProfileAdapter {
         let mut gain = None;
         let mut start_pct = None;
         let mut stop_pct = None;
+        let mut speed = None;
 
         if track.normalized_gain() != 1.0 {
             gain = Some(track.normalized_gain());
@@ -1799,11 +1984,16 @@ impl ProfileAdapter {
             stop_pct = Some(track.end_position() as f64);
         }
 
+        if track.playback_speed() != 1.0 {
+            speed = Some(track.playback_speed() as f64);
+        }
+
         return AudioFile {
             file: PathBuf::from(track.track()),
             gain,
             start_pct,
             stop_pct,
+            speed,
             fade_on_stop: false,
         };
     }
@@ -1868,13 +2058,15 @@ impl ProfileAdapter {
             .set_blink_on(active)
     }
 
-    pub fn clear_all_samples(&mut self, button: goxlr_types::SampleButtons) {
-        let bank = self.profile.settings().context().selected_sample();
-
+    pub fn clear_all_samples(
+        &mut self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+    ) {
         self.profile
             .settings_mut()
             .sample_button_mut(standard_to_profile_sample_button(button))
-            .get_stack_mut(bank)
+            .get_stack_mut(standard_to_profile_sample_bank(bank))
             .clear_tracks();
     }
 
@@ -2005,6 +2197,8 @@ impl ProfileAdapter {
             start_position: 0.0,
             end_position: 100.0,
             normalized_gain: 1.0,
+            playback_speed: 1.0,
+            hash: None,
         };
 
         // Add this to the list, then return the track..
@@ -2015,6 +2209,23 @@ impl ProfileAdapter {
             .add_track(track)
     }
 
+    pub fn set_sample_playback_speed(
+        &mut self,
+        bank: goxlr_types::SampleBank,
+        button: goxlr_types::SampleButtons,
+        index: usize,
+        speed: f32,
+    ) -> Result<()> {
+        let track = self
+            .profile
+            .settings_mut()
+            .sample_button_mut(standard_to_profile_sample_button(button))
+            .get_stack_mut(standard_to_profile_sample_bank(bank))
+            .get_track_by_index_mut(index)?;
+
+        track.set_playback_speed(speed)
+    }
+
     pub fn set_sample_start_pct(
         &mut self,
         bank: goxlr_types::SampleBank,
@@ -2081,6 +2292,16 @@ impl ProfileAdapter {
             .set_off_style(standard_to_profile_colour_off_style(off_style))
     }
 
+    pub fn set_button_hold_style(
+        &mut self,
+        target: Button,
+        hold_style: BasicColourHoldStyle,
+    ) -> Result<()> {
+        let colour_target = standard_to_colour_target(target);
+        get_profile_colour_map_mut(self.profile.settings_mut(), colour_target)
+            .set_hold_style(standard_to_profile_colour_hold_style(hold_style))
+    }
+
     ////////////////////// SUBMIXES //////////////////////
     pub fn is_submix_enabled(&self) -> bool {
         self.profile.settings().submixes().submix_enabled()
@@ -2364,6 +2585,43 @@ impl ProfileAdapter {
         Ok(())
     }
 
+    // Builds a full scheme from one or two colours, if no accent is provided, one is derived by
+    // taking the complement of the primary colour.
+    pub fn set_colour_theme(&mut self, primary: String, accent: Option<String>) -> Result<()> {
+        let accent = match accent {
+            Some(accent) => accent,
+            None => derive_accent_colour(&primary)?,
+        };
+
+        // Start from the global colour, this handles the meters, encoders and off styles..
+        self.set_global_colour(primary.clone())?;
+
+        let primary = Colour::fromrgb(primary.as_str())?;
+        let accent = Colour::fromrgb(accent.as_str())?;
+
+        // Faders fade from the accent at the top, to the primary colour at the bottom..
+        for fader in FaderName::iter() {
+            let map = get_profile_colour_map_mut(
+                self.profile.settings_mut(),
+                map_fader_to_colour_target(fader),
+            );
+            map.set_colour(0, accent)?;
+            map.set_colour(1, primary)?;
+        }
+
+        // Buttons use the accent as their second colour, so Colour2 off styles stay in theme..
+        for button in Buttons::iter() {
+            let colour_target = map_button_to_colour_target(button);
+            let map = get_profile_colour_map_mut(self.profile.settings_mut(), colour_target);
+            map.set_colour(1, accent)?;
+        }
+
+        let map = get_profile_colour_map_mut(self.profile.settings_mut(), ColourTargets::LogoX);
+        map.set_colour(0, accent)?;
+
+        Ok(())
+    }
+
     /** Mix Monitoring **/
     pub fn get_monitoring_mix(&self) -> OutputDevice {
         profile_to_standard_output(
@@ -2514,6 +2772,17 @@ impl ProfileAdapter {
         };
     }
 
+    // Returns the state to show while the button is held, if it's been configured..
+    pub fn get_button_hold_state(&self, button: Buttons) -> Option<ButtonStates> {
+        self.get_button_colour_map(button)
+            .get_hold_style()
+            .map(|style| match style {
+                ColourHoldStyle::Colour1 => ButtonStates::Colour1,
+                ColourHoldStyle::Colour2 => ButtonStates::Colour2,
+                ColourHoldStyle::Flashing => ButtonStates::Flashing,
+            })
+    }
+
     pub fn profile(&self) -> &Profile {
         &self.profile
     }
@@ -2589,6 +2858,24 @@ fn standard_to_profile_mute_function(value: BasicMuteFunction) -> MuteFunction {
     }
 }
 
+pub fn validate_route(input: InputDevice, output: OutputDevice) -> Result<()> {
+    if input == InputDevice::Chat && output == OutputDevice::ChatMic {
+        bail!("Invalid Route: Chat -> Chat Mic");
+    }
+    if input == InputDevice::Samples && output == OutputDevice::Sampler {
+        bail!("Invalid Route: Samples -> Sampler");
+    }
+    Ok(())
+}
+
+fn standard_to_scribble_alignment(value: ScribbleAlignment) -> TextAlignment {
+    match value {
+        ScribbleAlignment::Left => TextAlignment::Left,
+        ScribbleAlignment::Centre => TextAlignment::Centre,
+        ScribbleAlignment::Right => TextAlignment::Right,
+    }
+}
+
 fn standard_to_profile_fader_display(value: BasicColourDisplay) -> ColourDisplay {
     match value {
         BasicColourDisplay::TwoColour => ColourDisplay::TwoColour,
@@ -2624,6 +2911,70 @@ fn profile_to_standard_colour_off_style(value: ColourOffStyle) -> BasicColourOff
     }
 }
 
+// Rotates the hue of an RRGGBB colour by 180 degrees, keeping its saturation and brightness.
+fn derive_accent_colour(rgb: &str) -> Result<String> {
+    if rgb.len() != 6 {
+        bail!("Expected Length: 6 (RRGGBB), Got: {}", rgb);
+    }
+
+    let red = u8::from_str_radix(&rgb[0..2], 16)? as f32 / 255.;
+    let green = u8::from_str_radix(&rgb[2..4], 16)? as f32 / 255.;
+    let blue = u8::from_str_radix(&rgb[4..6], 16)? as f32 / 255.;
+
+    let max = red.max(green).max(blue);
+    let min = red.min(green).min(blue);
+    let delta = max - min;
+
+    let mut hue = if delta == 0. {
+        0.
+    } else if max == red {
+        60. * (((green - blue) / delta) % 6.)
+    } else if max == green {
+        60. * (((blue - red) / delta) + 2.)
+    } else {
+        60. * (((red - green) / delta) + 4.)
+    };
+    hue = (hue + 180.).rem_euclid(360.);
+
+    // Back from HSV to RGB..
+    let chroma = delta;
+    let x = chroma * (1. - ((hue / 60.) % 2. - 1.).abs());
+    let (r, g, b) = match hue as u16 / 60 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+
+    let to_byte = |value: f32| ((value + min) * 255.).round() as u8;
+    Ok(format!(
+        "{:02X}{:02X}{:02X}",
+        to_byte(r),
+        to_byte(g),
+        to_byte(b)
+    ))
+}
+
+fn standard_to_profile_colour_hold_style(value: BasicColourHoldStyle) -> Option<ColourHoldStyle> {
+    match value {
+        BasicColourHoldStyle::Unchanged => None,
+        BasicColourHoldStyle::Colour1 => Some(ColourHoldStyle::Colour1),
+        BasicColourHoldStyle::Colour2 => Some(ColourHoldStyle::Colour2),
+        BasicColourHoldStyle::Flashing => Some(ColourHoldStyle::Flashing),
+    }
+}
+
+fn profile_to_standard_colour_hold_style(value: Option<ColourHoldStyle>) -> BasicColourHoldStyle {
+    match value {
+        None => BasicColourHoldStyle::Unchanged,
+        Some(ColourHoldStyle::Colour1) => BasicColourHoldStyle::Colour1,
+        Some(ColourHoldStyle::Colour2) => BasicColourHoldStyle::Colour2,
+        Some(ColourHoldStyle::Flashing) => BasicColourHoldStyle::Flashing,
+    }
+}
+
 fn profile_to_standard_channel(value: FullChannelList) -> ChannelName {
     match value {
         FullChannelList::Mic => ChannelName::Mic,
@@ -2930,6 +3281,14 @@ fn get_profile_colour_map_mut(
     }
 }
 
+// Returns where each of a fader's colours is stored in the colour map..
+pub fn get_fader_colour_positions(fader: FaderName, use_format_1_3_40: bool) -> Vec<usize> {
+    let target = map_fader_to_colour_target(fader);
+    (0..target.get_colour_count())
+        .map(|index| target.position(index, use_format_1_3_40))
+        .collect()
+}
+
 pub fn standard_to_colour_target(target: Button) -> ColourTargets {
     match target {
         Button::Fader1Mute => ColourTargets::Fader1Mute,
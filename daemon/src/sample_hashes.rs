@@ -0,0 +1,239 @@
+use crate::files::{find_sample_file, get_sample_track_name};
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use glob::glob;
+use goxlr_types::{SampleBank, SampleButtons};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::fs::{create_dir_all, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/*
+Content hashes for sample files. These are stored against each track in the profile so that if
+the file is moved (or the profile is copied to another machine with a different layout), it can be
+found again by its contents, and are used when importing to avoid keeping two copies of the same
+clip.
+
+Hashing a large sample library isn't quick, so hashes are cached (keyed on the file's path, size
+and modification time) in the cache directory, and only recalculated when a file changes. Relinking
+a profile's samples can still mean hashing the whole library, so it's run on a blocking thread
+against a copy of the cache, which is merged back in when it's done.
+ */
+const SAMPLE_EXTENSIONS: [&str; 2] = ["wav", "mp3"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedHash {
+    size: u64,
+    modified: u64,
+    hash: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SampleHashes {
+    files: HashMap<PathBuf, CachedHash>,
+
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl SampleHashes {
+    pub fn load() -> Self {
+        let path = match cache_path() {
+            Some(path) => path,
+            None => return Self::default(),
+        };
+
+        match File::open(&path) {
+            Ok(reader) => serde_json::from_reader(reader).unwrap_or_else(|e| {
+                warn!("Unable to read the sample hash cache, rebuilding: {}", e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&mut self) {
+        if !self.changed {
+            return;
+        }
+
+        if let Some(path) = cache_path() {
+            if let Err(e) = self.write(&path) {
+                warn!("Unable to write the sample hash cache: {}", e);
+            }
+        }
+        self.changed = false;
+    }
+
+    // Takes on any hashes calculated by a copy of the cache (which will have saved them itself).
+    pub fn merge(&mut self, other: SampleHashes) {
+        self.files.extend(other.files);
+    }
+
+    fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+        let writer = File::create(path).context("Unable to create sample hash cache")?;
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    pub fn get_hash(&mut self, file: &Path) -> Result<String> {
+        let metadata = file.metadata()?;
+        let size = metadata.len();
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or(0);
+
+        if let Some(cached) = self.files.get(file) {
+            if cached.size == size && cached.modified == modified {
+                return Ok(cached.hash.clone());
+            }
+        }
+
+        debug!("Calculating hash for {}", file.to_string_lossy());
+        let hash = hash_file(file)?;
+        self.files.insert(
+            file.to_path_buf(),
+            CachedHash {
+                size,
+                modified,
+                hash: hash.clone(),
+            },
+        );
+        self.changed = true;
+        Ok(hash)
+    }
+
+    // Looks through the samples directory for a file with matching contents.
+    pub fn find_by_hash(&mut self, root: &Path, hash: &str) -> Option<PathBuf> {
+        // Check what we already know about first, to avoid hashing anything new..
+        let known = self
+            .files
+            .iter()
+            .filter(|(path, cached)| cached.hash == hash && path.starts_with(root))
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<PathBuf>>();
+        for path in known {
+            if self.get_hash(&path).ok().as_deref() == Some(hash) {
+                return Some(path);
+            }
+        }
+
+        for extension in SAMPLE_EXTENSIONS {
+            let format = format!("{}/**/*.{}", root.to_string_lossy(), extension);
+            if let Ok(files) = glob(format.as_str()) {
+                for file in files.flatten() {
+                    if self.get_hash(&file).ok().as_deref() == Some(hash) {
+                        return Some(file);
+                    }
+                }
+            }
+        }
+
+        // Forget about anything which has been removed..
+        let before = self.files.len();
+        self.files.retain(|path, _| path.exists());
+        self.changed |= before != self.files.len();
+        None
+    }
+}
+
+// A sample in the profile to be checked by a relink.
+#[derive(Debug, Clone)]
+pub struct RelinkTrack {
+    pub bank: SampleBank,
+    pub button: SampleButtons,
+    pub track: String,
+    pub hash: Option<String>,
+}
+
+// A change found by a relink, either a new location for a missing sample, or a missing hash.
+#[derive(Debug)]
+pub struct RelinkResult {
+    pub bank: SampleBank,
+    pub button: SampleButtons,
+    pub from: String,
+    pub track: String,
+    pub hash: Option<String>,
+}
+
+// Samples which have been moved are found again by their contents, and any found tracks which
+// don't have a hash yet have one calculated, so they can be found if they move later.
+pub fn relink_tracks(
+    mut hashes: SampleHashes,
+    root: &Path,
+    tracks: Vec<RelinkTrack>,
+) -> (SampleHashes, Vec<RelinkResult>) {
+    let mut results = vec![];
+    for track in tracks {
+        match find_sample_file(root, track.bank, track.button, &track.track) {
+            Some(file) => {
+                if track.hash.is_some() {
+                    continue;
+                }
+                if let Ok(hash) = hashes.get_hash(&file) {
+                    results.push(RelinkResult {
+                        bank: track.bank,
+                        button: track.button,
+                        from: track.track.clone(),
+                        track: track.track,
+                        hash: Some(hash),
+                    });
+                }
+            }
+            None => {
+                let hash = match track.hash {
+                    Some(hash) => hash,
+                    None => continue,
+                };
+                if let Some(file) = hashes.find_by_hash(root, &hash) {
+                    let name = get_sample_track_name(root, &file);
+                    info!("Relinked Sample {} to {}", track.track, name);
+                    results.push(RelinkResult {
+                        bank: track.bank,
+                        button: track.button,
+                        from: track.track,
+                        track: name,
+                        hash: Some(hash),
+                    });
+                }
+            }
+        }
+    }
+    hashes.save();
+    (hashes, results)
+}
+
+pub fn hash_file(file: &Path) -> Result<String> {
+    let mut reader =
+        File::open(file).with_context(|| format!("Unable to open {}", file.to_string_lossy()))?;
+
+    let mut hasher = Sha1::new();
+    let mut buffer = [0; 65536];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+fn cache_path() -> Option<PathBuf> {
+    ProjectDirs::from("org", "GoXLR-on-Linux", "GoXLR-Utility")
+        .map(|dirs| dirs.cache_dir().join("sample-hashes.json"))
+}
@@ -0,0 +1,85 @@
+use crate::primary_worker::{DeviceCommand, DeviceSender};
+use crate::settings::SettingsHandle;
+use crate::shutdown::Shutdown;
+use chrono::Local;
+use cron::Schedule;
+use goxlr_ipc::GoXLRCommand;
+use log::{debug, error, info, warn};
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::sleep;
+
+/*
+Schedules are configured per device, either in the settings file or via the SetSchedule command,
+as a name mapped to a cron expression and a list of commands to run, for example:
+
+"schedules": {
+    "office": {
+        "cron": "0 0 9 * * Mon-Fri",
+        "commands": [{ "LoadProfile": ["Office", false] }]
+    }
+}
+
+The cron expression includes seconds, so the format is 'sec min hour day month weekday [year]'.
+Schedules are re-read from the settings every check, so changes take effect immediately.
+ */
+pub async fn spawn_scheduler_service(
+    settings: SettingsHandle,
+    usb_tx: DeviceSender,
+    mut shutdown: Shutdown,
+) {
+    info!("Starting Scheduler Service..");
+
+    let mut last_check = Local::now();
+    loop {
+        tokio::select! {
+            () = shutdown.recv() => {
+                info!("Shutting down Scheduler Service");
+                return;
+            },
+            () = sleep(Duration::from_millis(500)) => {
+                let now = Local::now();
+                for (serial, name, schedule) in settings.get_schedules().await {
+                    let cron = match Schedule::from_str(&schedule.cron) {
+                        Ok(cron) => cron,
+                        Err(error) => {
+                            warn!("Unable to Parse Schedule {}: {}", name, error);
+                            continue;
+                        }
+                    };
+
+                    // Check whether this schedule should have fired since our last check..
+                    let due = cron
+                        .after(&last_check)
+                        .next()
+                        .map(|next| next <= now)
+                        .unwrap_or(false);
+
+                    if due {
+                        debug!("Running Schedule {} on {}", name, serial);
+                        for command in schedule.commands {
+                            run_command(&usb_tx, serial.clone(), command).await;
+                        }
+                    }
+                }
+                last_check = now;
+            }
+        }
+    }
+}
+
+async fn run_command(usb_tx: &DeviceSender, serial: String, command: GoXLRCommand) {
+    let (tx, rx) = oneshot::channel();
+    let request = DeviceCommand::RunDeviceCommand(serial, command, tx);
+    if usb_tx.send(request).await.is_err() {
+        error!("Unable to send Scheduled command to the device task");
+        return;
+    }
+
+    match rx.await {
+        Ok(Err(error)) => warn!("Error Executing Scheduled Command: {}", error),
+        Err(error) => warn!("Error Executing Scheduled Command: {}", error),
+        Ok(Ok(())) => {}
+    }
+}
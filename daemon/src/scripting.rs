@@ -0,0 +1,254 @@
+use crate::primary_worker::DeviceSender;
+use crate::settings::SettingsHandle;
+use crate::shutdown::Shutdown;
+use crate::PatchEvent;
+use log::info;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+
+#[cfg(feature = "scripting")]
+use {
+    crate::servers::server_packet::handle_packet,
+    goxlr_ipc::{DaemonRequest, DaemonResponse, GoXLRCommand},
+    json_patch::PatchOperation,
+    log::{debug, warn},
+    rhai::serde::{from_dynamic, to_dynamic},
+    rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST},
+    serde_json::Value,
+    std::fs,
+    std::path::Path,
+    std::sync::{Arc, Mutex},
+};
+
+/*
+The scripting service loads any '.rhai' files from the scripts directory on startup, and calls
+functions defined in them when something on a device changes. Scripts can define any of the
+following functions:
+
+on_button_down(serial, button)
+on_button_up(serial, button)
+on_volume_change(serial, channel, volume)
+on_change(serial, path, value)
+
+Functions are called with 'this' bound to an object map which is kept between calls, so scripts
+can track state (for example, 'this.last_press = timestamp();'). Commands can be sent to a device
+using command(serial, command), where the command is in the same format as the IPC, for example:
+
+command(serial, #{ SetFXEnabled: true });
+command(serial, #{ SetFaderMuteState: ["A", "MutedToAll"] });
+ */
+#[cfg(feature = "scripting")]
+struct Script {
+    name: String,
+    ast: AST,
+    state: Dynamic,
+}
+
+#[cfg(feature = "scripting")]
+type CommandQueue = Arc<Mutex<Vec<(String, GoXLRCommand)>>>;
+
+#[cfg(feature = "scripting")]
+pub async fn spawn_script_service(
+    settings: SettingsHandle,
+    mut usb_tx: DeviceSender,
+    broadcast_tx: BroadcastSender<PatchEvent>,
+    mut shutdown: Shutdown,
+) {
+    let commands: CommandQueue = Arc::new(Mutex::new(vec![]));
+    let engine = create_engine(commands.clone());
+
+    let mut scripts = load_scripts(&engine, &settings.get_scripts_directory().await);
+    if scripts.is_empty() {
+        debug!("No Scripts Found, not starting Script Service");
+        return;
+    }
+
+    info!("Starting Script Service with {} script(s)..", scripts.len());
+    let mut broadcast_rx = broadcast_tx.subscribe();
+    loop {
+        tokio::select! {
+            () = shutdown.recv() => {
+                info!("Shutting down Script Service");
+                return;
+            },
+            Ok(PatchEvent::Patch(patch)) = broadcast_rx.recv() => {
+                for operation in patch.0 {
+                    let (path, value) = match operation {
+                        PatchOperation::Add(op) => (op.path, op.value),
+                        PatchOperation::Replace(op) => (op.path, op.value),
+                        _ => continue,
+                    };
+
+                    for script in &mut scripts {
+                        handle_change(&engine, script, &path, &value);
+                    }
+
+                    // Pull out anything the scripts have asked us to do, and run it..
+                    let queued: Vec<(String, GoXLRCommand)> =
+                        commands.lock().unwrap().drain(..).collect();
+                    for (serial, command) in queued {
+                        let request = DaemonRequest::Command(serial, command);
+                        match handle_packet(request, &mut usb_tx).await {
+                            Ok(DaemonResponse::Error(error)) => {
+                                warn!("Error Running Script Command: {}", error)
+                            }
+                            Err(error) => warn!("Error Running Script Command: {}", error),
+                            Ok(_) => {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "scripting")]
+fn create_engine(commands: CommandQueue) -> Engine {
+    let mut engine = Engine::new();
+
+    // Prevent a broken script from locking up the daemon..
+    engine.set_max_operations(100_000);
+    engine.on_print(|message| info!("[Script] {}", message));
+    engine.on_debug(|message, _, _| debug!("[Script] {}", message));
+
+    engine.register_fn(
+        "command",
+        move |serial: &str, command: Dynamic| -> Result<(), Box<EvalAltResult>> {
+            let command: GoXLRCommand = from_dynamic(&command)?;
+            commands.lock().unwrap().push((serial.to_string(), command));
+            Ok(())
+        },
+    );
+
+    engine
+}
+
+#[cfg(feature = "scripting")]
+fn load_scripts(engine: &Engine, path: &Path) -> Vec<Script> {
+    let mut scripts = vec![];
+
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return scripts,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().map(|ext| ext != "rhai").unwrap_or(true) {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        let ast = match engine.compile_file(path) {
+            Ok(ast) => ast,
+            Err(error) => {
+                warn!("Unable to Load Script {}: {}", name, error);
+                continue;
+            }
+        };
+
+        // Run any top level statements in the script, to allow it to perform setup..
+        let mut scope = Scope::new();
+        if let Err(error) = engine.run_ast_with_scope(&mut scope, &ast) {
+            warn!("Error Running Script {}: {}", name, error);
+            continue;
+        }
+
+        debug!("Loaded Script {}", name);
+        scripts.push(Script {
+            name,
+            ast,
+            state: Dynamic::from_map(Map::new()),
+        });
+    }
+    scripts
+}
+
+#[cfg(feature = "scripting")]
+fn handle_change(engine: &Engine, script: &mut Script, path: &str, value: &Value) {
+    // We only care about changes to devices, which are formatted as /mixers/<serial>/<path>
+    let mut parts = path.trim_start_matches('/').splitn(3, '/');
+    if parts.next() != Some("mixers") {
+        return;
+    }
+
+    let (serial, path) = match (parts.next(), parts.next()) {
+        (Some(serial), Some(path)) => (serial.to_string(), path.to_string()),
+        _ => return,
+    };
+
+    if let Some(button) = path.strip_prefix("button_down/") {
+        let name = match value.as_bool() {
+            Some(true) => "on_button_down",
+            Some(false) => "on_button_up",
+            None => return,
+        };
+        let args = (serial.clone(), button.to_string());
+        call_function(engine, script, name, 2, args);
+    }
+
+    if let Some(channel) = path.strip_prefix("levels/volumes/") {
+        if let Some(volume) = value.as_i64() {
+            let args = (serial.clone(), channel.to_string(), volume);
+            call_function(engine, script, "on_volume_change", 3, args);
+        }
+    }
+
+    if let Ok(value) = to_dynamic(value) {
+        call_function(engine, script, "on_change", 3, (serial, path, value));
+    }
+}
+
+#[cfg(feature = "scripting")]
+fn call_function(
+    engine: &Engine,
+    script: &mut Script,
+    name: &str,
+    params: usize,
+    args: impl rhai::FuncArgs,
+) {
+    let defined = script
+        .ast
+        .iter_functions()
+        .any(|function| function.name == name && function.params.len() == params);
+
+    if !defined {
+        return;
+    }
+
+    let options = CallFnOptions::new()
+        .eval_ast(false)
+        .bind_this_ptr(&mut script.state);
+
+    let mut scope = Scope::new();
+    let result =
+        engine.call_fn_with_options::<Dynamic>(options, &mut scope, &script.ast, name, args);
+    if let Err(error) = result {
+        warn!("Error in Script {} ({}): {}", script.name, name, error);
+    }
+}
+
+/*
+Below is a 'Dummy' implementation of the service, for use if the rhai package isn't included in
+the build.
+ */
+
+#[cfg(not(feature = "scripting"))]
+pub async fn spawn_script_service(
+    settings: SettingsHandle,
+    _: DeviceSender,
+    _: BroadcastSender<PatchEvent>,
+    _: Shutdown,
+) {
+    let path = settings.get_scripts_directory().await;
+    if let Ok(entries) = std::fs::read_dir(path) {
+        let has_scripts = entries
+            .flatten()
+            .any(|entry| entry.path().extension().map(|ext| ext == "rhai") == Some(true));
+
+        if has_scripts {
+            log::warn!("Scripts Found, but the Scripting Feature is not enabled in build.");
+            return;
+        }
+    }
+    info!("Scripting Feature is not enabled in build, Script Service not started.");
+}
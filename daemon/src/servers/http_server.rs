@@ -9,6 +9,7 @@ use actix::{
 };
 use actix_cors::Cors;
 use actix_web::dev::ServerHandle;
+use actix_web::http::header;
 use actix_web::http::header::ContentType;
 use actix_web::middleware::Condition;
 use actix_web::web::Data;
@@ -20,19 +21,24 @@ use include_dir::{include_dir, Dir};
 use jsonpath_rust::JsonPathQuery;
 use log::{debug, error, info, warn};
 use mime_guess::MimeGuess;
-use serde_json::Value;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::broadcast::Sender as BroadcastSender;
 use tokio::sync::oneshot::Sender;
 use tokio::sync::Mutex;
 
 use crate::files::{find_file_in_path, FilePaths};
+use crate::metrics::METRICS;
 use crate::PatchEvent;
 use goxlr_ipc::{
-    DaemonRequest, DaemonResponse, DaemonStatus, HttpSettings, WebsocketRequest, WebsocketResponse,
+    DaemonError, DaemonRequest, DaemonResponse, DaemonStatus, GoXLRCommand, HttpSettings,
+    WebsocketRequest, WebsocketResponse,
 };
+use goxlr_types::{EffectBankPresets, FaderName, MuteState, SampleBank, SampleButtons};
 
 use crate::primary_worker::DeviceSender;
-use crate::servers::server_packet::handle_packet;
+use crate::servers::server_packet::{get_resync_patch, handle_packet, to_daemon_error};
 
 const WEB_CONTENT: Dir = include_dir!("./daemon/web-content/");
 
@@ -45,26 +51,45 @@ impl Actor for Websocket {
     type Context = ws::WebsocketContext<Self>;
 
     fn started(&mut self, ctx: &mut Self::Context) {
+        METRICS.websocket_client_connected();
+
         let address = ctx.address();
+        let mut usb_tx = self.usb_tx.clone();
         let mut broadcast_rx = self.broadcast_tx.subscribe();
 
         // Create a future that simply monitors the global broadcast bus, and pushes any changes
         // out to the WebSocket.
         let future = Box::pin(async move {
             loop {
-                if let Ok(event) = broadcast_rx.recv().await {
-                    // We've received a message, attempt to trigger the WsMessage Handle..
-                    if let Err(error) = address.clone().try_send(WsResponse(WebsocketResponse {
-                        id: u64::MAX,
-                        data: DaemonResponse::Patch(event.data),
-                    })) {
-                        error!(
-                            "Error Occurred when sending message to websocket: {:?}",
-                            error
-                        );
-                        warn!("Aborting Websocket pushes for this client.");
-                        break;
+                // Events (such as sample progress) are sent alongside Patches with the same ID,
+                // clients which don't understand them can simply ignore them.
+                let data = match broadcast_rx.recv().await {
+                    Ok(PatchEvent::Patch(patch)) => DaemonResponse::Patch(patch),
+                    Ok(PatchEvent::Event(event)) => DaemonResponse::Event(event),
+                    Err(RecvError::Lagged(count)) => {
+                        warn!("Websocket missed {} patches, resyncing", count);
+                        match get_resync_patch(&mut usb_tx).await {
+                            Ok(patch) => DaemonResponse::Patch(patch),
+                            Err(error) => {
+                                warn!("Unable to resync Websocket: {}", error);
+                                break;
+                            }
+                        }
                     }
+                    Err(RecvError::Closed) => break,
+                };
+
+                // We've received a message, attempt to trigger the WsMessage Handle..
+                if let Err(error) = address
+                    .clone()
+                    .try_send(WsResponse(WebsocketResponse { id: u64::MAX, data }))
+                {
+                    error!(
+                        "Error Occurred when sending message to websocket: {:?}",
+                        error
+                    );
+                    warn!("Aborting Websocket pushes for this client.");
+                    break;
                 }
             }
         });
@@ -72,6 +97,10 @@ impl Actor for Websocket {
         let future = future.into_actor(self);
         ctx.spawn(future);
     }
+
+    fn stopped(&mut self, _: &mut Self::Context) {
+        METRICS.websocket_client_disconnected();
+    }
 }
 
 #[derive(Message)]
@@ -82,9 +111,11 @@ impl Handler<WsResponse> for Websocket {
     type Result = ();
 
     fn handle(&mut self, msg: WsResponse, ctx: &mut Self::Context) -> Self::Result {
-        if let Ok(result) = serde_json::to_string(&msg.0) {
-            ctx.text(result);
-        }
+        let response = json!({
+            "id": msg.0.id,
+            "data": to_web_response(msg.0.data),
+        });
+        ctx.text(response.to_string());
     }
 }
 
@@ -99,36 +130,18 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for Websocket {
                         let mut usb_tx = self.usb_tx.clone();
                         let future = async move {
                             let request_id = request.id;
-                            let result = handle_packet(request.data, &mut usb_tx).await;
-                            match result {
-                                Ok(resp) => match resp {
-                                    DaemonResponse::Ok => {
-                                        recipient.do_send(WsResponse(WebsocketResponse {
-                                            id: request_id,
-                                            data: DaemonResponse::Ok,
-                                        }));
-                                    }
-                                    DaemonResponse::Error(error) => {
-                                        recipient.do_send(WsResponse(WebsocketResponse {
-                                            id: request_id,
-                                            data: DaemonResponse::Error(error),
-                                        }));
-                                    }
-                                    DaemonResponse::Status(status) => {
-                                        recipient.do_send(WsResponse(WebsocketResponse {
-                                            id: request_id,
-                                            data: DaemonResponse::Status(status),
-                                        }));
-                                    }
-                                    _ => {}
-                                },
-                                Err(error) => {
-                                    recipient.do_send(WsResponse(WebsocketResponse {
-                                        id: request_id,
-                                        data: DaemonResponse::Error(error.to_string()),
-                                    }));
+                            let data = match handle_packet(request.data, &mut usb_tx).await {
+                                // Patches and Events are only pushed to subscribers, never replied..
+                                Ok(DaemonResponse::Patch(_)) | Ok(DaemonResponse::Event(_)) => {
+                                    return
                                 }
-                            }
+                                Ok(response) => response,
+                                Err(error) => DaemonResponse::Error(to_daemon_error(error).into()),
+                            };
+                            recipient.do_send(WsResponse(WebsocketResponse {
+                                id: request_id,
+                                data,
+                            }));
                         };
                         future.into_actor(self).spawn(ctx);
                     }
@@ -147,7 +160,9 @@ impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for Websocket {
                                     let recipient = ctx.address().recipient();
                                     recipient.do_send(WsResponse(WebsocketResponse {
                                         id: request_id,
-                                        data: DaemonResponse::Error(error.to_string()),
+                                        data: DaemonResponse::Error(
+                                            DaemonError::Other(error.to_string()).into(),
+                                        ),
                                     }));
                                 } else {
                                     warn!("id missing, Cannot continue. Closing connection");
@@ -220,7 +235,18 @@ pub async fn spawn_http_server(
             .service(get_devices)
             .service(get_sample)
             .service(get_path)
+            .service(simple_get_state)
+            .service(simple_set_fader_mute)
+            .service(simple_set_cough_mute)
+            .service(simple_play_sample)
+            .service(simple_stop_sample)
+            .service(simple_load_profile)
             .service(websocket)
+            .configure(|config| {
+                if settings.metrics_enabled {
+                    config.service(metrics);
+                }
+            })
             .default_service(web::to(default))
     })
     .bind((settings.bind_address.clone(), settings.port))?
@@ -232,6 +258,14 @@ pub async fn spawn_http_server(
         settings.port,
     );
 
+    if settings.metrics_enabled {
+        info!(
+            "Metrics available at http://{}:{}/metrics",
+            settings.bind_address.as_str(),
+            settings.port,
+        );
+    }
+
     let _ = handle_tx.send(server.handle());
     server.await?;
     Ok(())
@@ -267,8 +301,10 @@ async fn execute_command(
 
     // Errors propagate weirdly in the javascript world, so send all as OK, and handle there.
     match handle_packet(request.0, &mut sender.usb_tx).await {
-        Ok(result) => HttpResponse::Ok().json(result),
-        Err(error) => HttpResponse::Ok().json(DaemonResponse::Error(error.to_string())),
+        Ok(result) => HttpResponse::Ok().json(to_web_response(result)),
+        Err(error) => HttpResponse::Ok().json(to_web_response(DaemonResponse::Error(
+            to_daemon_error(error).into(),
+        ))),
     }
 }
 
@@ -308,6 +344,210 @@ async fn get_path(app_data: Data<Mutex<AppData>>, req: HttpRequest) -> HttpRespo
     HttpResponse::InternalServerError().finish()
 }
 
+// The 'Simple' API is a small, flat set of requests intended for tools such as the Elgato Stream
+// Deck or Bitfocus Companion, which can only fire a URL on a button press and poll another for
+// feedback. These paths should be considered stable, they are:
+//
+// GET  /api/simple/{serial}/state                        - Current button states (see SimpleState)
+// POST /api/simple/{serial}/fader/{fader}/mute/{state}   - Set a Fader's (A-D) Mute State
+// POST /api/simple/{serial}/cough/{state}                - Set the Cough Button's Mute State
+// POST /api/simple/{serial}/sample/{bank}/{button}/play  - Play the next sample on a button
+// POST /api/simple/{serial}/sample/{bank}/{button}/stop  - Stop playback on a button
+// POST /api/simple/{serial}/profile/{name}               - Load (and persist) a profile
+//
+// Mute states are Unmuted, MutedToX or MutedToAll, banks are A-C and buttons are TopLeft,
+// TopRight, BottomLeft or BottomRight. Commands respond with a DaemonResponse in the same way
+// as /api/command.
+//
+// Anything which changes state is a POST, so it can't be triggered by simply loading a URL (for
+// example, an <img> on another website). As forms can still POST across sites, requests from a
+// browser whose Origin doesn't match the host are also refused, these tools don't send one.
+#[derive(Debug, Serialize)]
+struct SimpleState {
+    profile: String,
+    mic_profile: String,
+    faders: HashMap<FaderName, MuteState>,
+    cough: MuteState,
+    effects_enabled: bool,
+    effects_preset: Option<EffectBankPresets>,
+    megaphone_enabled: bool,
+    robot_enabled: bool,
+    hardtune_enabled: bool,
+    sampler_bank: Option<SampleBank>,
+    sampler: HashMap<SampleBank, HashMap<SampleButtons, SimpleSamplerState>>,
+}
+
+#[derive(Debug, Serialize)]
+struct SimpleSamplerState {
+    has_samples: bool,
+    playing: bool,
+    recording: bool,
+}
+
+#[get("/api/simple/{serial}/state")]
+async fn simple_get_state(
+    serial: web::Path<String>,
+    app_data: Data<Mutex<AppData>>,
+) -> HttpResponse {
+    let serial = serial.into_inner();
+    let status = match get_status(app_data).await {
+        Ok(status) => status,
+        Err(_) => return HttpResponse::InternalServerError().finish(),
+    };
+
+    let mixer = match status.mixers.get(&serial) {
+        Some(mixer) => mixer,
+        None => return HttpResponse::NotFound().finish(),
+    };
+
+    let mut faders = HashMap::new();
+    for (fader, status) in mixer.fader_status.iter() {
+        faders.insert(fader, status.mute_state);
+    }
+
+    let mut sampler = HashMap::new();
+    if let Some(mixer_sampler) = &mixer.sampler {
+        for (bank, buttons) in mixer_sampler.banks.iter() {
+            let mut bank_state = HashMap::new();
+            for (button, state) in buttons.iter() {
+                bank_state.insert(
+                    *button,
+                    SimpleSamplerState {
+                        has_samples: !state.samples.is_empty(),
+                        playing: state.is_playing,
+                        recording: state.is_recording,
+                    },
+                );
+            }
+            sampler.insert(*bank, bank_state);
+        }
+    }
+
+    let effects = mixer.effects.as_ref();
+    HttpResponse::Ok().json(SimpleState {
+        profile: mixer.profile_name.clone(),
+        mic_profile: mixer.mic_profile_name.clone(),
+        faders,
+        cough: mixer.cough_button.state,
+        effects_enabled: effects.map(|e| e.is_enabled).unwrap_or(false),
+        effects_preset: effects.map(|e| e.active_preset),
+        megaphone_enabled: effects
+            .map(|e| e.current.megaphone.is_enabled)
+            .unwrap_or(false),
+        robot_enabled: effects.map(|e| e.current.robot.is_enabled).unwrap_or(false),
+        hardtune_enabled: effects
+            .map(|e| e.current.hard_tune.is_enabled)
+            .unwrap_or(false),
+        sampler_bank: mixer.sampler.as_ref().map(|s| s.active_bank),
+        sampler,
+    })
+}
+
+#[post("/api/simple/{serial}/fader/{fader}/mute/{state}")]
+async fn simple_set_fader_mute(
+    req: HttpRequest,
+    path: web::Path<(String, FaderName, MuteState)>,
+    app_data: Data<Mutex<AppData>>,
+) -> HttpResponse {
+    let (serial, fader, state) = path.into_inner();
+    let command = GoXLRCommand::SetFaderMuteState(fader, state);
+    simple_command(req, app_data, serial, command).await
+}
+
+#[post("/api/simple/{serial}/cough/{state}")]
+async fn simple_set_cough_mute(
+    req: HttpRequest,
+    path: web::Path<(String, MuteState)>,
+    app_data: Data<Mutex<AppData>>,
+) -> HttpResponse {
+    let (serial, state) = path.into_inner();
+    let command = GoXLRCommand::SetCoughMuteState(state);
+    simple_command(req, app_data, serial, command).await
+}
+
+#[post("/api/simple/{serial}/sample/{bank}/{button}/play")]
+async fn simple_play_sample(
+    req: HttpRequest,
+    path: web::Path<(String, SampleBank, SampleButtons)>,
+    app_data: Data<Mutex<AppData>>,
+) -> HttpResponse {
+    let (serial, bank, button) = path.into_inner();
+    let command = GoXLRCommand::PlayNextSample(bank, button);
+    simple_command(req, app_data, serial, command).await
+}
+
+#[post("/api/simple/{serial}/sample/{bank}/{button}/stop")]
+async fn simple_stop_sample(
+    req: HttpRequest,
+    path: web::Path<(String, SampleBank, SampleButtons)>,
+    app_data: Data<Mutex<AppData>>,
+) -> HttpResponse {
+    let (serial, bank, button) = path.into_inner();
+    let command = GoXLRCommand::StopSamplePlayback(bank, button);
+    simple_command(req, app_data, serial, command).await
+}
+
+#[post("/api/simple/{serial}/profile/{name}")]
+async fn simple_load_profile(
+    req: HttpRequest,
+    path: web::Path<(String, String)>,
+    app_data: Data<Mutex<AppData>>,
+) -> HttpResponse {
+    let (serial, name) = path.into_inner();
+    let command = GoXLRCommand::LoadProfile(name, true);
+    simple_command(req, app_data, serial, command).await
+}
+
+async fn simple_command(
+    req: HttpRequest,
+    app_data: Data<Mutex<AppData>>,
+    serial: String,
+    command: GoXLRCommand,
+) -> HttpResponse {
+    if is_cross_origin(&req) {
+        warn!("Refusing cross-origin Simple API request to {}", req.path());
+        return HttpResponse::Forbidden().finish();
+    }
+
+    let mut guard = app_data.lock().await;
+    let sender = guard.deref_mut();
+
+    let request = DaemonRequest::Command(serial, command);
+    match handle_packet(request, &mut sender.usb_tx).await {
+        Ok(result) => HttpResponse::Ok().json(to_web_response(result)),
+        Err(error) => HttpResponse::Ok().json(to_web_response(DaemonResponse::Error(
+            to_daemon_error(error).into(),
+        ))),
+    }
+}
+
+// The bundled Web UI (built from the separate goxlr-ui project) shows 'Error' as a string, so web
+// clients are sent the error's message in that form, rather than the structured ErrorResponse.
+fn to_web_response(response: DaemonResponse) -> Value {
+    match response {
+        DaemonResponse::Error(error) => json!({ "Error": error.message }),
+        response => serde_json::to_value(response).unwrap_or(Value::Null),
+    }
+}
+
+// Browsers always send an Origin with a POST, so if there is one it needs to point at us.
+fn is_cross_origin(req: &HttpRequest) -> bool {
+    let origin = match req.headers().get(header::ORIGIN) {
+        Some(origin) => origin.to_str().unwrap_or_default(),
+        None => return false,
+    };
+    let host = match req.headers().get(header::HOST) {
+        Some(host) => host.to_str().unwrap_or_default(),
+        None => return true,
+    };
+
+    let origin_host = origin
+        .split_once("://")
+        .map(|(_, host)| host)
+        .unwrap_or_default();
+    origin_host.is_empty() || origin_host != host
+}
+
 #[get("/files/samples/{sample}")]
 async fn get_sample(sample: web::Path<String>, app_data: Data<Mutex<AppData>>) -> HttpResponse {
     // Get the Base Samples Path..
@@ -337,6 +577,13 @@ async fn get_sample(sample: web::Path<String>, app_data: Data<Mutex<AppData>>) -
     HttpResponse::NotFound().finish()
 }
 
+#[get("/metrics")]
+async fn metrics() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(METRICS.render())
+}
+
 async fn default(req: HttpRequest) -> HttpResponse {
     let path = if req.path() == "/" || req.path() == "" {
         "/index.html"
@@ -1,23 +1,28 @@
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 use interprocess::local_socket::tokio::{LocalSocketListener, LocalSocketStream};
 use interprocess::local_socket::NameTypeSupport;
 use log::{debug, info, warn};
 use std::fs;
 use std::path::Path;
+use std::sync::Mutex;
 
 use NameTypeSupport::*;
 
 use goxlr_ipc::clients::ipc::ipc_socket::Socket;
-use goxlr_ipc::{DaemonRequest, DaemonResponse};
+use goxlr_ipc::{DaemonError, DaemonRequest, DaemonResponse, IpcRequest, IpcResponse, EVENT_ID};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::sync::mpsc;
 
+use crate::metrics::METRICS;
 use crate::primary_worker::DeviceSender;
-use crate::servers::server_packet::handle_packet;
-use crate::Shutdown;
+use crate::servers::server_packet::{get_resync_patch, handle_packet, to_daemon_error};
+use crate::{PatchEvent, Shutdown};
 
-static SOCKET_PATH: &str = "/tmp/goxlr.socket";
-static NAMED_PIPE: &str = "@goxlr.socket";
+// The name the socket was bound to, used for cleanup and diagnostics.
+static SOCKET_NAME: Mutex<Option<String>> = Mutex::new(None);
 
-async fn ipc_tidy() -> Result<()> {
+async fn ipc_tidy(name: &str) -> Result<()> {
     // We only need a possible cleanup if we're using file based sockets..
     let socket_type = NameTypeSupport::query();
     if socket_type == OnlyNamespaced {
@@ -25,25 +30,29 @@ async fn ipc_tidy() -> Result<()> {
     }
 
     // Check to see if the socket exists,
-    if !Path::new(SOCKET_PATH).exists() {
+    if !Path::new(name).exists() {
         return Ok(());
     }
 
     debug!("Existing Socket Present, testing..");
     // Try sending a message to the socket, see if we get a reply..
-    let connection = LocalSocketStream::connect(SOCKET_PATH).await;
+    let connection = LocalSocketStream::connect(name).await;
     if connection.is_err() {
         debug!("Unable to connect to the socket, removing..");
-        fs::remove_file(SOCKET_PATH)?;
+        fs::remove_file(name)?;
         return Ok(());
     }
 
     debug!("Connected to socket, seeing if there's a Daemon on the other side..");
     let connection = connection.unwrap();
-    let mut socket: Socket<DaemonResponse, DaemonRequest> = Socket::new(connection);
-    if socket.send(DaemonRequest::Ping).await.is_err() {
+    let mut socket: Socket<IpcResponse, IpcRequest> = Socket::new(connection);
+    let request = IpcRequest {
+        id: 0,
+        data: DaemonRequest::Ping,
+    };
+    if socket.send(request).await.is_err() {
         debug!("Socket Not Active, removing file..");
-        fs::remove_file(SOCKET_PATH)?;
+        fs::remove_file(name)?;
         return Ok(());
     }
 
@@ -51,24 +60,59 @@ async fn ipc_tidy() -> Result<()> {
     bail!("The GoXLR Daemon is already running.");
 }
 
-pub async fn bind_socket() -> Result<LocalSocketListener> {
-    ipc_tidy().await?;
+pub fn socket_name() -> String {
+    SOCKET_NAME.lock().unwrap().clone().unwrap_or_default()
+}
+
+pub async fn bind_socket(name: String, group: Option<String>) -> Result<LocalSocketListener> {
+    ipc_tidy(&name).await?;
 
-    let name = {
-        match NameTypeSupport::query() {
-            OnlyPaths | Both => SOCKET_PATH,
-            OnlyNamespaced => NAMED_PIPE,
+    let file_based = NameTypeSupport::query() != OnlyNamespaced;
+    if file_based {
+        if let Some(parent) = Path::new(&name).parent() {
+            fs::create_dir_all(parent)?;
         }
-    };
+    }
 
-    let listener = LocalSocketListener::bind(name)?;
+    let listener = LocalSocketListener::bind(name.as_str())?;
     info!("Bound IPC Socket @ {}", name);
+
+    if file_based {
+        set_socket_permissions(&name, group)?;
+    }
+
+    SOCKET_NAME.lock().unwrap().replace(name);
     Ok(listener)
 }
 
+// Only the owner can use the socket, unless a group is configured, in which case it's shared.
+#[cfg(unix)]
+fn set_socket_permissions(name: &str, group: Option<String>) -> Result<()> {
+    use nix::unistd::{chown, Group};
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut mode = 0o600;
+    if let Some(group) = group {
+        let gid = Group::from_name(&group)?
+            .ok_or_else(|| anyhow!("Socket Group {} does not exist", group))?
+            .gid;
+        chown(name, None, Some(gid))?;
+        mode = 0o660;
+    }
+
+    fs::set_permissions(name, fs::Permissions::from_mode(mode))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_socket_permissions(_name: &str, _group: Option<String>) -> Result<()> {
+    Ok(())
+}
+
 pub async fn spawn_ipc_server(
     listener: LocalSocketListener,
     usb_tx: DeviceSender,
+    broadcast_tx: BroadcastSender<PatchEvent>,
     mut shutdown_signal: Shutdown,
 ) {
     debug!("Running IPC Server..");
@@ -77,15 +121,16 @@ pub async fn spawn_ipc_server(
             Ok(connection) = listener.accept() => {
                 let socket = Socket::new(connection);
                 let usb_tx = usb_tx.clone();
+                let broadcast_tx = broadcast_tx.clone();
                 tokio::spawn(async move {
-                    handle_connection(socket, usb_tx).await;
+                    handle_connection(socket, usb_tx, broadcast_tx).await;
                 });
             }
             () = shutdown_signal.recv() => {
                 // If we're using a unix domain socket, remove it.
                 match NameTypeSupport::query() {
                     OnlyPaths | Both => {
-                        let _ = fs::remove_file(SOCKET_PATH);
+                        let _ = fs::remove_file(socket_name());
                     },
                     OnlyNamespaced => {},
                 }
@@ -96,27 +141,122 @@ pub async fn spawn_ipc_server(
 }
 
 async fn handle_connection(
-    mut socket: Socket<DaemonRequest, DaemonResponse>,
-    mut usb_tx: DeviceSender,
+    mut socket: Socket<IpcRequest, IpcResponse>,
+    usb_tx: DeviceSender,
+    broadcast_tx: BroadcastSender<PatchEvent>,
 ) {
-    while let Some(msg) = socket.read().await {
-        match msg {
-            Ok(msg) => match handle_packet(msg, &mut usb_tx).await {
-                Ok(response) => {
-                    if let Err(e) = socket.send(response).await {
-                        warn!("Couldn't reply to {:?}: {}", socket.address(), e);
-                        return;
+    METRICS.ipc_client_connected();
+
+    // Requests are handled by a worker task so events can still be sent while a slow command runs.
+    // There's one worker per connection, so requests are handled (and replied to) in the order they
+    // were sent, a SetVolume followed by a SaveProfile will always save the new volume.
+    let (request_tx, request_rx) = mpsc::channel(32);
+    let (response_tx, mut response_rx) = mpsc::unbounded_channel();
+    let worker = tokio::spawn(handle_requests(request_rx, response_tx, usb_tx.clone()));
+    let mut broadcast_rx = broadcast_tx.subscribe();
+    let mut subscribed = false;
+
+    loop {
+        tokio::select! {
+            msg = socket.read() => {
+                let msg = match msg {
+                    Some(msg) => msg,
+                    None => break,
+                };
+
+                match msg {
+                    Ok(request) => {
+                        // This has to be answered before anything else is written, as everything
+                        // after the Ok uses the new encoding.
+                        if let DaemonRequest::SetEncoding(encoding) = request.data {
+                            let response = IpcResponse { id: request.id, data: DaemonResponse::Ok };
+                            if let Err(e) = socket.send(response).await {
+                                warn!("Couldn't reply to {:?}: {}", socket.address(), e);
+                                break;
+                            }
+                            debug!("{:?} switched to {:?} encoding", socket.address(), encoding);
+                            socket.set_encoding(encoding);
+                            continue;
+                        }
+
+                        if let DaemonRequest::Subscribe = request.data {
+                            subscribed = true;
+                        }
+
+                        if request_tx.send(request).await.is_err() {
+                            break;
+                        }
                     }
+                    Err(e) => {
+                        warn!("Invalid message from {:?}: {}", socket.address(), e);
+
+                        // The request ID can't be read from a message that doesn't decode, so reply
+                        // with ID 0. This is the ID of a client's first request (the Hello), so a
+                        // client using an older protocol will fail its handshake rather than hang.
+                        let message = format!("Unable to decode request: {}", e);
+                        let error = DaemonError::Other(message).into();
+                        let response = IpcResponse { id: 0, data: DaemonResponse::Error(error) };
+                        if let Err(e) = socket.send(response).await {
+                            warn!("Couldn't reply to {:?}: {}", socket.address(), e);
+                            break;
+                        }
+                    }
+                }
+            }
+            Some(response) = response_rx.recv() => {
+                if let Err(e) = socket.send(response).await {
+                    warn!("Couldn't reply to {:?}: {}", socket.address(), e);
+                    break;
                 }
-                Err(e) => {
-                    if let Err(e) = socket.send(DaemonResponse::Error(e.to_string())).await {
-                        warn!("Couldn't reply to {:?}: {}", socket.address(), e);
-                        return;
+            }
+            event = broadcast_rx.recv(), if subscribed => {
+                let data = match event {
+                    Ok(PatchEvent::Patch(patch)) => DaemonResponse::Patch(patch),
+                    Ok(PatchEvent::Event(event)) => DaemonResponse::Event(event),
+                    Err(RecvError::Lagged(count)) => {
+                        warn!("{:?} missed {} patches, resyncing", socket.address(), count);
+                        match get_resync_patch(&mut usb_tx.clone()).await {
+                            Ok(patch) => DaemonResponse::Patch(patch),
+                            Err(e) => {
+                                warn!("Unable to resync {:?}: {}", socket.address(), e);
+                                break;
+                            }
+                        }
                     }
+                    Err(RecvError::Closed) => break,
+                };
+
+                let response = IpcResponse { id: EVENT_ID, data };
+                if let Err(e) = socket.send(response).await {
+                    warn!("Couldn't send event to {:?}: {}", socket.address(), e);
+                    break;
                 }
-            },
-            Err(e) => warn!("Invalid message from {:?}: {}", socket.address(), e),
+            }
         }
     }
+    worker.abort();
+    METRICS.ipc_client_disconnected();
     debug!("Disconnected {:?}", socket.address());
 }
+
+async fn handle_requests(
+    mut request_rx: mpsc::Receiver<IpcRequest>,
+    response_tx: mpsc::UnboundedSender<IpcResponse>,
+    mut usb_tx: DeviceSender,
+) {
+    while let Some(request) = request_rx.recv().await {
+        let data = match handle_packet(request.data, &mut usb_tx).await {
+            Ok(response) => response,
+            Err(e) => DaemonResponse::Error(to_daemon_error(e).into()),
+        };
+        if response_tx
+            .send(IpcResponse {
+                id: request.id,
+                data,
+            })
+            .is_err()
+        {
+            return;
+        }
+    }
+}
@@ -1,24 +1,104 @@
+use crate::metrics::METRICS;
 use crate::primary_worker::{DeviceCommand, DeviceSender};
-use anyhow::{anyhow, Context, Result};
-use goxlr_ipc::{DaemonRequest, DaemonResponse};
+use crate::servers::ipc_server::socket_name;
+use crate::{GIT_HASH, VERSION};
+use anyhow::{anyhow, bail, Context, Result};
+use goxlr_ipc::{
+    DaemonError, DaemonInfo, DaemonRequest, DaemonResponse, DeviceHealth, DevicePollHealth,
+    PingInfo, PROTOCOL_VERSION,
+};
+use goxlr_usb::error::{find_protocol_error, find_usb_error};
+use json_patch::{Patch, PatchOperation, ReplaceOperation};
+use serde_json::Value;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::oneshot;
 
+// Devices are polled every 20ms, if nothing's been read for this long, the poll has stalled.
+const POLL_STALL_THRESHOLD: Duration = Duration::from_secs(1);
+
 pub async fn handle_packet(
     request: DaemonRequest,
     usb_tx: &mut DeviceSender,
 ) -> Result<DaemonResponse> {
     match request {
-        DaemonRequest::Ping => Ok(DaemonResponse::Ok),
-        DaemonRequest::GetStatus => {
+        DaemonRequest::Ping => get_ping_info(usb_tx).await,
+        DaemonRequest::Hello(version) => {
+            // For now, we simply require an exact match. If we ever need to support multiple
+            // versions this is where the negotiation would happen.
+            if version != PROTOCOL_VERSION {
+                bail!(
+                    "Client Protocol Version {} is not supported by this Daemon (Version {}), please make sure the Utility and Client versions match",
+                    version,
+                    PROTOCOL_VERSION
+                );
+            }
+            Ok(DaemonResponse::Hello(PROTOCOL_VERSION))
+        }
+        // Subscriptions are handled by the socket, the Websocket always receives patches.
+        DaemonRequest::Subscribe => Ok(DaemonResponse::Ok),
+        DaemonRequest::GetStatus => get_status(usb_tx).await,
+        DaemonRequest::GetDaemonStatus => get_daemon_info(usb_tx).await,
+        DaemonRequest::GetButtonStates(serial) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::GetButtonStates(serial, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            let states = rx
+                .await
+                .context("Could not fetch the button states from the GoXLR device")??;
+            Ok(DaemonResponse::ButtonStates(states))
+        }
+        DaemonRequest::RunDiagnostics(serial) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::RunDiagnostics(serial, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            let report = rx
+                .await
+                .context("Could not run diagnostics on the GoXLR device")??;
+            Ok(DaemonResponse::Diagnostics(report))
+        }
+        DaemonRequest::ValidateSamples(serial) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::ValidateSamples(serial, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            let samples = rx
+                .await
+                .context("Could not validate the samples on the GoXLR device")??;
+            Ok(DaemonResponse::MissingSamples(samples))
+        }
+        DaemonRequest::GetSampleInfo(file) => {
             let (tx, rx) = oneshot::channel();
             usb_tx
-                .send(DeviceCommand::SendDaemonStatus(tx))
+                .send(DeviceCommand::GetSampleInfo(file, tx))
                 .await
                 .map_err(|e| anyhow!(e.to_string()))
                 .context("Could not communicate with the device task")?;
-            Ok(DaemonResponse::Status(rx.await.context(
-                "Could not execute the command on the device task",
-            )?))
+            let info = rx
+                .await
+                .context("Could not read the sample information")??;
+            Ok(DaemonResponse::SampleInfo(info))
+        }
+        // The IPC socket handles this itself, as it needs to switch the socket's encoding
+        DaemonRequest::SetEncoding(_) => {
+            bail!("The encoding can only be changed on the IPC socket")
+        }
+        DaemonRequest::CreateSupportBundle => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::CreateSupportBundle(tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the device task")?;
+            let path = rx.await.context("Could not create the support bundle")??;
+            Ok(DaemonResponse::SupportBundle(path))
         }
         DaemonRequest::Daemon(command) => {
             let (tx, rx) = oneshot::channel();
@@ -42,5 +122,150 @@ pub async fn handle_packet(
                 .context("Could not execute the command on the GoXLR device")??;
             Ok(DaemonResponse::Ok)
         }
+        DaemonRequest::Batch(serial, commands) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::RunDeviceCommands(serial, commands, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            rx.await
+                .context("Could not execute the commands on the GoXLR device")??;
+
+            // Respond with the final state, so clients don't need a second round trip..
+            get_status(usb_tx).await
+        }
+        DaemonRequest::DryRun(serial, commands) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::DryRunDeviceCommands(serial, commands, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            let patch = rx
+                .await
+                .context("Could not check the commands against the GoXLR device")??;
+            Ok(DaemonResponse::DryRun(patch))
+        }
+    }
+}
+
+// If a client falls behind on the patch broadcast it will have missed changes, rather than let it
+// drift out of sync with everyone else we send it a patch which replaces the entire status. This
+// is done a key at a time, as some patch libraries (including the one used by the Web UI) return
+// a new document for a replace of the root, rather than updating the existing one.
+pub async fn get_resync_patch(usb_tx: &mut DeviceSender) -> Result<Patch> {
+    let status = match get_status(usb_tx).await? {
+        DaemonResponse::Status(status) => status,
+        _ => bail!("Unexpected response when fetching the Daemon Status"),
+    };
+
+    let status = match serde_json::to_value(status)? {
+        Value::Object(status) => status,
+        _ => bail!("Daemon Status is not an Object"),
+    };
+
+    let operations = status
+        .into_iter()
+        .map(|(key, value)| {
+            PatchOperation::Replace(ReplaceOperation {
+                path: format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+                value,
+            })
+        })
+        .collect();
+    Ok(Patch(operations))
+}
+
+async fn get_status(usb_tx: &mut DeviceSender) -> Result<DaemonResponse> {
+    let (tx, rx) = oneshot::channel();
+    usb_tx
+        .send(DeviceCommand::SendDaemonStatus(tx))
+        .await
+        .map_err(|e| anyhow!(e.to_string()))
+        .context("Could not communicate with the device task")?;
+    Ok(DaemonResponse::Status(rx.await.context(
+        "Could not execute the command on the device task",
+    )?))
+}
+
+async fn get_ping_info(usb_tx: &mut DeviceSender) -> Result<DaemonResponse> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis() as u64)
+        .unwrap_or(0);
+
+    let status = match get_status(usb_tx).await? {
+        DaemonResponse::Status(status) => status,
+        _ => bail!("Unexpected response from the device task"),
+    };
+
+    let devices = status
+        .mixers
+        .values()
+        .filter(|mixer| mixer.connected)
+        .map(|mixer| {
+            let last_poll = METRICS.device_last_poll(&mixer.hardware.serial_number);
+            DevicePollHealth {
+                serial: mixer.hardware.serial_number.clone(),
+                last_poll: last_poll.map(|poll| poll.as_millis() as u64),
+                responsive: last_poll
+                    .map(|poll| poll < POLL_STALL_THRESHOLD)
+                    .unwrap_or(false),
+            }
+        })
+        .collect();
+
+    Ok(DaemonResponse::Pong(PingInfo { timestamp, devices }))
+}
+
+async fn get_daemon_info(usb_tx: &mut DeviceSender) -> Result<DaemonResponse> {
+    let status = match get_status(usb_tx).await? {
+        DaemonResponse::Status(status) => status,
+        _ => bail!("Unexpected response from the device task"),
+    };
+
+    let devices = status
+        .mixers
+        .values()
+        .map(|mixer| DeviceHealth {
+            serial: mixer.hardware.serial_number.clone(),
+            device_type: mixer.hardware.device_type.clone(),
+            connected: mixer.connected,
+            firmware: mixer.hardware.versions.firmware.clone(),
+            uptime: METRICS
+                .device_uptime(&mixer.hardware.serial_number)
+                .map(|uptime| uptime.as_secs()),
+            firmware_update: mixer.firmware_update.as_ref().map(|update| update.stage),
+        })
+        .collect();
+
+    Ok(DaemonResponse::DaemonInfo(DaemonInfo {
+        version: String::from(VERSION),
+        git_hash: GIT_HASH.map(String::from),
+        protocol_version: PROTOCOL_VERSION,
+        uptime: METRICS.uptime().as_secs(),
+        socket_path: socket_name(),
+        http_settings: status.config.http_settings,
+        ipc_clients: METRICS.ipc_clients(),
+        websocket_clients: METRICS.websocket_clients(),
+        devices,
+    }))
+}
+
+// Converts an error from the daemon into something which can be sent to a client..
+pub fn to_daemon_error(error: anyhow::Error) -> DaemonError {
+    if let Some(daemon_error) = error.downcast_ref::<DaemonError>() {
+        return daemon_error.clone();
+    }
+
+    if let Some(message) = find_usb_error(&error) {
+        return DaemonError::UsbError { message };
+    }
+    if let Some(protocol_error) = find_protocol_error(&error) {
+        return DaemonError::UsbError {
+            message: protocol_error.to_string(),
+        };
     }
+    DaemonError::Other(error.to_string())
 }
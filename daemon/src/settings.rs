@@ -1,8 +1,15 @@
+use crate::files::DEFAULT_RECORDING_TEMPLATE;
 use crate::mic_profile::DEFAULT_MIC_PROFILE_NAME;
 use crate::profile::DEFAULT_PROFILE_NAME;
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use directories::ProjectDirs;
-use goxlr_ipc::{GoXLRCommand, LogLevel};
+use goxlr_audio::player::NORMALIZATION_TARGET;
+use goxlr_ipc::{
+    ChannelTemplate, Ducking, FaderMeter, GoXLRCommand, IdleLighting, LogLevel, PushToTalk,
+    RoutingPreset, Schedule, ScribbleStyle, VirtualChannel, VodMix, VoiceActivity, VolumePreset,
+};
+use goxlr_types::{Button, ChannelName, FaderName, MuteState};
+use goxlr_usb::timing::UsbTimings;
 use log::error;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -20,66 +27,34 @@ pub struct SettingsHandle {
 
 impl SettingsHandle {
     pub async fn load(path: PathBuf) -> Result<SettingsHandle> {
-        // This is only used for defaults
-        let proj_dirs = ProjectDirs::from("org", "GoXLR-on-Linux", "GoXLR-Utility")
-            .context("Couldn't find project directories")?;
-        let data_dir = proj_dirs.data_dir();
+        let (data_dir, log_dir) = get_default_paths()?;
+        let data_dir = data_dir.as_path();
 
         let mut settings = Settings::read(&path)?.unwrap_or_else(|| Settings {
             show_tray_icon: Some(true),
             tts_enabled: Some(false),
+            notifications_enabled: Some(false),
             allow_network_access: Some(false),
             profile_directory: Some(data_dir.join("profiles")),
             mic_profile_directory: Some(data_dir.join("mic-profiles")),
             samples_directory: Some(data_dir.join("samples")),
             presets_directory: Some(data_dir.join("presets")),
             icons_directory: Some(data_dir.join("icons")),
-            logs_directory: Some(data_dir.join("logs")),
+            logs_directory: Some(log_dir.clone()),
+            scripts_directory: Some(data_dir.join("scripts")),
+            backups_directory: Some(data_dir.join("backups")),
             log_level: Some(LogLevel::Debug),
+            log_filters: None,
             activate: None,
+            socket_path: None,
+            socket_group: None,
+            app_routing: None,
+            usb_timings: None,
+            virtual_channels: None,
+            vod_mix: None,
             devices: Default::default(),
         });
-
-        // Set these values if they're missing from the configuration
-        if settings.profile_directory.is_none() {
-            settings.profile_directory = Some(data_dir.join("profiles"));
-        }
-
-        if settings.mic_profile_directory.is_none() {
-            settings.mic_profile_directory = Some(data_dir.join("mic-profiles"));
-        }
-
-        if settings.samples_directory.is_none() {
-            settings.samples_directory = Some(data_dir.join("samples"));
-        }
-
-        if settings.presets_directory.is_none() {
-            settings.presets_directory = Some(data_dir.join("presets"));
-        }
-
-        if settings.icons_directory.is_none() {
-            settings.icons_directory = Some(data_dir.join("icons"));
-        }
-
-        if settings.logs_directory.is_none() {
-            settings.logs_directory = Some(data_dir.join("logs"));
-        }
-
-        if settings.log_level.is_none() {
-            settings.log_level = Some(LogLevel::Info);
-        }
-
-        if settings.show_tray_icon.is_none() {
-            settings.show_tray_icon = Some(true);
-        }
-
-        if settings.tts_enabled.is_none() {
-            settings.tts_enabled = Some(false);
-        }
-
-        if settings.allow_network_access.is_none() {
-            settings.allow_network_access = Some(false);
-        }
+        settings.fill_defaults(data_dir, log_dir);
 
         let handle = SettingsHandle {
             path,
@@ -89,6 +64,29 @@ impl SettingsHandle {
         Ok(handle)
     }
 
+    // Re-reads the settings file after it's been changed on disk, returning true if the contents
+    // differ from what's loaded (our own saves will also trigger this, and should be ignored).
+    pub async fn reload(&self) -> Result<bool> {
+        let mut new_settings = match Settings::read(&self.path)? {
+            Some(settings) => settings,
+            None => return Ok(false),
+        };
+
+        let (data_dir, log_dir) = get_default_paths()?;
+        new_settings.fill_defaults(&data_dir, log_dir);
+
+        let mut settings = self.settings.write().await;
+        if serde_json::to_value(&*settings)? == serde_json::to_value(&new_settings)? {
+            return Ok(false);
+        }
+        *settings = new_settings;
+        Ok(true)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     pub async fn save(&self) {
         let settings = self.settings.write().await;
         if let Err(e) = settings.write(&self.path) {
@@ -129,6 +127,16 @@ impl SettingsHandle {
         settings.tts_enabled = Some(enabled);
     }
 
+    pub async fn get_notifications_enabled(&self) -> bool {
+        let settings = self.settings.read().await;
+        settings.notifications_enabled.unwrap()
+    }
+
+    pub async fn set_notifications_enabled(&self, enabled: bool) {
+        let mut settings = self.settings.write().await;
+        settings.notifications_enabled = Some(enabled);
+    }
+
     pub async fn get_allow_network_access(&self) -> bool {
         let settings = self.settings.read().await;
         settings.allow_network_access.unwrap()
@@ -164,11 +172,27 @@ impl SettingsHandle {
         settings.icons_directory.clone().unwrap()
     }
 
+    // A copy of the settings as they would be written to disk, used by the support bundle.
+    pub async fn get_json(&self) -> Result<serde_json::Value> {
+        let settings = self.settings.read().await;
+        Ok(serde_json::to_value(&*settings)?)
+    }
+
     pub async fn get_log_directory(&self) -> PathBuf {
         let settings = self.settings.read().await;
         settings.logs_directory.clone().unwrap()
     }
 
+    pub async fn get_scripts_directory(&self) -> PathBuf {
+        let settings = self.settings.read().await;
+        settings.scripts_directory.clone().unwrap()
+    }
+
+    pub async fn get_backups_directory(&self) -> PathBuf {
+        let settings = self.settings.read().await;
+        settings.backups_directory.clone().unwrap()
+    }
+
     pub async fn set_log_level(&self, level: LogLevel) {
         let mut settings = self.settings.write().await;
         settings.log_level = Some(level);
@@ -179,6 +203,67 @@ impl SettingsHandle {
         settings.log_level.clone().unwrap_or(LogLevel::Info)
     }
 
+    pub async fn get_log_filters(&self) -> Option<String> {
+        let settings = self.settings.read().await;
+        settings.log_filters.clone()
+    }
+
+    pub async fn get_socket_path(&self) -> Option<PathBuf> {
+        let settings = self.settings.read().await;
+        settings.socket_path.clone()
+    }
+
+    pub async fn get_socket_group(&self) -> Option<String> {
+        let settings = self.settings.read().await;
+        settings.socket_group.clone()
+    }
+
+    pub async fn get_app_routing(&self) -> Vec<AppRoutingRule> {
+        let settings = self.settings.read().await;
+        settings.app_routing.clone().unwrap_or_default()
+    }
+
+    pub async fn get_usb_timings(&self) -> UsbTimings {
+        let settings = self.settings.read().await;
+        let defaults = UsbTimings::default();
+        match &settings.usb_timings {
+            Some(timings) => UsbTimings {
+                timeout_ms: timings.timeout_ms.unwrap_or(defaults.timeout_ms),
+                read_retries: timings.read_retries.unwrap_or(defaults.read_retries),
+                command_delay_ms: timings.command_delay_ms.or(defaults.command_delay_ms),
+            },
+            None => defaults,
+        }
+    }
+
+    pub async fn get_virtual_channels(&self) -> Vec<VirtualChannel> {
+        let settings = self.settings.read().await;
+        settings.virtual_channels.clone().unwrap_or_default()
+    }
+
+    pub async fn set_virtual_channel(&self, name: &str, channel: Option<ChannelName>) {
+        let mut settings = self.settings.write().await;
+        let channels = settings.virtual_channels.get_or_insert_with(Vec::new);
+        channels.retain(|virtual_channel| virtual_channel.name != name);
+
+        if let Some(channel) = channel {
+            channels.push(VirtualChannel {
+                name: name.to_owned(),
+                channel,
+            });
+        }
+    }
+
+    pub async fn get_vod_mix(&self) -> VodMix {
+        let settings = self.settings.read().await;
+        settings.vod_mix.clone().unwrap_or_default()
+    }
+
+    pub async fn set_vod_mix(&self, vod_mix: VodMix) {
+        let mut settings = self.settings.write().await;
+        settings.vod_mix = Some(vod_mix);
+    }
+
     pub async fn get_activate(&self) -> Option<String> {
         let settings = self.settings.read().await;
         settings.activate.clone()
@@ -219,6 +304,216 @@ impl SettingsHandle {
         vec![]
     }
 
+    // Returns a list of (Serial, Key Combination, Command) for all configured devices.
+    pub async fn get_hotkeys(&self) -> Vec<(String, String, GoXLRCommand)> {
+        let settings = self.settings.read().await;
+
+        let mut hotkeys = vec![];
+        for (serial, device) in &settings.devices {
+            for (keys, command) in &device.hotkeys {
+                hotkeys.push((serial.clone(), keys.clone(), command.clone()));
+            }
+        }
+        hotkeys
+    }
+
+    // Returns a list of (Serial, Name, Schedule) for all configured devices.
+    pub async fn get_schedules(&self) -> Vec<(String, String, Schedule)> {
+        let settings = self.settings.read().await;
+
+        let mut schedules = vec![];
+        for (serial, device) in &settings.devices {
+            for (name, schedule) in &device.schedules {
+                schedules.push((serial.clone(), name.clone(), schedule.clone()));
+            }
+        }
+        schedules
+    }
+
+    pub async fn get_device_schedules(&self, device_serial: &str) -> HashMap<String, Schedule> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.schedules.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn get_device_channel_templates(
+        &self,
+        device_serial: &str,
+    ) -> HashMap<ChannelName, ChannelTemplate> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.channel_templates.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn set_device_channel_template(
+        &self,
+        device_serial: &str,
+        channel: ChannelName,
+        template: Option<ChannelTemplate>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+
+        if let Some(template) = template {
+            entry.channel_templates.insert(channel, template);
+        } else {
+            entry.channel_templates.remove(&channel);
+        }
+    }
+
+    pub async fn get_device_scribble_style(
+        &self,
+        device_serial: &str,
+        fader: FaderName,
+    ) -> ScribbleStyle {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.scribble_styles.get(&fader).cloned())
+            .unwrap_or_default()
+    }
+
+    pub async fn get_device_scribble_styles(
+        &self,
+        device_serial: &str,
+    ) -> HashMap<FaderName, ScribbleStyle> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.scribble_styles.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn set_device_scribble_style(
+        &self,
+        device_serial: &str,
+        fader: FaderName,
+        style: ScribbleStyle,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.scribble_styles.insert(fader, style);
+    }
+
+    pub async fn get_device_routing_presets(
+        &self,
+        device_serial: &str,
+    ) -> HashMap<String, RoutingPreset> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.routing_presets.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn set_device_routing_preset(
+        &self,
+        device_serial: &str,
+        name: &str,
+        preset: Option<RoutingPreset>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+
+        if let Some(preset) = preset {
+            entry.routing_presets.insert(name.to_owned(), preset);
+        } else {
+            entry.routing_presets.remove(name);
+        }
+    }
+
+    pub async fn get_device_volume_presets(
+        &self,
+        device_serial: &str,
+    ) -> HashMap<String, VolumePreset> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.volume_presets.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn set_device_volume_preset(
+        &self,
+        device_serial: &str,
+        name: &str,
+        preset: Option<VolumePreset>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+
+        if let Some(preset) = preset {
+            entry.volume_presets.insert(name.to_owned(), preset);
+        } else {
+            entry.volume_presets.remove(name);
+        }
+    }
+
+    pub async fn set_device_schedule(
+        &self,
+        device_serial: &str,
+        name: &str,
+        schedule: Option<Schedule>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+
+        if let Some(schedule) = schedule {
+            entry.schedules.insert(name.to_owned(), schedule);
+        } else {
+            entry.schedules.remove(name);
+        }
+    }
+
+    pub async fn get_device_macro(
+        &self,
+        device_serial: &str,
+        name: &str,
+    ) -> Option<Vec<GoXLRCommand>> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.macros.get(name).cloned())
+    }
+
+    pub async fn get_device_button_macro(
+        &self,
+        device_serial: &str,
+        button: Button,
+    ) -> Option<ButtonMacro> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.button_macros.get(&button).cloned())
+    }
+
     pub async fn get_device_sampler_pre_buffer(&self, device_serial: &str) -> u16 {
         let settings = self.settings.read().await;
         let value = settings
@@ -231,6 +526,59 @@ impl SettingsHandle {
         0
     }
 
+    pub async fn get_device_sampler_monitor_device(&self, device_serial: &str) -> Option<String> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.sampler_monitor_device.clone())
+    }
+
+    pub async fn get_device_sampler_monitor_recording(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.sampler_monitor_recording)
+            .unwrap_or(false)
+    }
+
+    pub async fn get_device_sampler_queue_next(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.sampler_queue_next)
+            .unwrap_or(false)
+    }
+
+    pub async fn get_device_sampler_max_recording(&self, device_serial: &str) -> u16 {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.sampler_max_recording)
+            .unwrap_or(600)
+    }
+
+    pub async fn get_device_sampler_recording_template(&self, device_serial: &str) -> String {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.sampler_recording_template.clone())
+            .unwrap_or_else(|| String::from(DEFAULT_RECORDING_TEMPLATE))
+    }
+
+    pub async fn get_device_sampler_normalization_target(&self, device_serial: &str) -> f32 {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.sampler_normalization_target)
+            .unwrap_or(NORMALIZATION_TARGET as f32)
+    }
+
     pub async fn get_device_hold_time(&self, device_serial: &str) -> u16 {
         let settings = self.settings.read().await;
         let value = settings
@@ -244,6 +592,15 @@ impl SettingsHandle {
         500
     }
 
+    pub async fn get_device_button_hold_times(&self, device_serial: &str) -> HashMap<Button, u16> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.button_hold_delays.clone())
+            .unwrap_or_default()
+    }
+
     // I absolutely hate this naming.. O_O
     pub async fn get_device_chat_mute_mutes_mic_to_chat(&self, device_serial: &str) -> bool {
         let settings = self.settings.read().await;
@@ -258,6 +615,110 @@ impl SettingsHandle {
         true
     }
 
+    pub async fn get_device_restore_runtime_state(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.restore_runtime_state)
+            .unwrap_or(true)
+    }
+
+    pub async fn get_device_profile_volume_fade(&self, device_serial: &str) -> u16 {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.profile_volume_fade)
+            .unwrap_or(0)
+    }
+
+    pub async fn get_device_runtime_state(&self, device_serial: &str) -> Option<RuntimeState> {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .and_then(|d| d.runtime_state.clone())
+    }
+
+    pub async fn get_device_ducking(&self, device_serial: &str) -> Ducking {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.ducking.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn get_device_push_to_talk(&self, device_serial: &str) -> PushToTalk {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.push_to_talk.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn get_device_voice_activity(&self, device_serial: &str) -> VoiceActivity {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.voice_activity.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn get_device_fader_meter(&self, device_serial: &str) -> FaderMeter {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.fader_meter.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn get_device_brightness(&self, device_serial: &str) -> u8 {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.brightness)
+            .unwrap_or(100)
+    }
+
+    pub async fn get_device_idle_lighting(&self, device_serial: &str) -> IdleLighting {
+        let settings = self.settings.read().await;
+        settings
+            .devices
+            .get(device_serial)
+            .map(|d| d.idle_lighting.clone())
+            .unwrap_or_default()
+    }
+
+    pub async fn export_device_settings(&self, device_serial: &str) -> Result<String> {
+        let settings = self.settings.read().await;
+        let device = settings
+            .devices
+            .get(device_serial)
+            .ok_or_else(|| anyhow!("No Settings found for {}", device_serial))?;
+        Ok(serde_json::to_string_pretty(device)?)
+    }
+
+    // Replaces a device's settings, returns the profile and mic profile names they reference.
+    pub async fn import_device_settings(
+        &self,
+        device_serial: &str,
+        json: &str,
+    ) -> Result<(String, String)> {
+        let device: DeviceSettings =
+            serde_json::from_str(json).context("Unable to parse Device Settings")?;
+        let names = (device.profile.clone(), device.mic_profile.clone());
+
+        let mut settings = self.settings.write().await;
+        settings.devices.insert(device_serial.to_owned(), device);
+        Ok(names)
+    }
+
     pub async fn set_device_profile_name(&self, device_serial: &str, profile_name: &str) {
         let mut settings = self.settings.write().await;
         let entry = settings
@@ -298,6 +759,68 @@ impl SettingsHandle {
         entry.sampler_pre_buffer = Some(duration);
     }
 
+    pub async fn set_device_sampler_monitor_device(
+        &self,
+        device_serial: &str,
+        device: Option<String>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.sampler_monitor_device = device;
+    }
+
+    pub async fn set_device_sampler_monitor_recording(&self, device_serial: &str, enabled: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.sampler_monitor_recording = Some(enabled);
+    }
+
+    pub async fn set_device_sampler_queue_next(&self, device_serial: &str, enabled: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.sampler_queue_next = Some(enabled);
+    }
+
+    pub async fn set_device_sampler_max_recording(&self, device_serial: &str, seconds: u16) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.sampler_max_recording = Some(seconds);
+    }
+
+    pub async fn set_device_sampler_recording_template(
+        &self,
+        device_serial: &str,
+        template: String,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.sampler_recording_template = Some(template);
+    }
+
+    pub async fn set_device_sampler_normalization_target(&self, device_serial: &str, target: f32) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.sampler_normalization_target = Some(target);
+    }
+
     pub async fn set_device_mute_hold_duration(&self, device_serial: &str, duration: u16) {
         let mut settings = self.settings.write().await;
         let entry = settings
@@ -307,6 +830,111 @@ impl SettingsHandle {
         entry.hold_delay = Some(duration);
     }
 
+    pub async fn set_device_button_hold_time(
+        &self,
+        device_serial: &str,
+        button: Button,
+        duration: Option<u16>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+
+        if let Some(duration) = duration {
+            entry.button_hold_delays.insert(button, duration);
+        } else {
+            entry.button_hold_delays.remove(&button);
+        }
+    }
+
+    pub async fn set_device_ducking(&self, device_serial: &str, ducking: Ducking) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.ducking = ducking;
+    }
+
+    pub async fn set_device_push_to_talk(&self, device_serial: &str, push_to_talk: PushToTalk) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.push_to_talk = push_to_talk;
+    }
+
+    pub async fn set_device_voice_activity(&self, device_serial: &str, activity: VoiceActivity) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.voice_activity = activity;
+    }
+
+    pub async fn set_device_fader_meter(&self, device_serial: &str, fader_meter: FaderMeter) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.fader_meter = fader_meter;
+    }
+
+    pub async fn set_device_brightness(&self, device_serial: &str, brightness: u8) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.brightness = brightness;
+    }
+
+    pub async fn set_device_idle_lighting(&self, device_serial: &str, idle: IdleLighting) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.idle_lighting = idle;
+    }
+
+    pub async fn set_device_profile_volume_fade(&self, device_serial: &str, duration: u16) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.profile_volume_fade = Some(duration);
+    }
+
+    pub async fn set_device_restore_runtime_state(&self, device_serial: &str, enabled: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.restore_runtime_state = Some(enabled);
+
+        // Don't hold on to a state that won't be used..
+        if !enabled {
+            entry.runtime_state = None;
+        }
+    }
+
+    pub async fn set_device_runtime_state(&self, device_serial: &str, state: RuntimeState) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.runtime_state = Some(state);
+    }
+
     pub async fn set_device_vc_mute_also_mute_cm(&self, device_serial: &str, setting: bool) {
         let mut settings = self.settings.write().await;
         let entry = settings
@@ -321,6 +949,7 @@ impl SettingsHandle {
 pub struct Settings {
     show_tray_icon: Option<bool>,
     tts_enabled: Option<bool>,
+    notifications_enabled: Option<bool>,
     allow_network_access: Option<bool>,
     profile_directory: Option<PathBuf>,
     mic_profile_directory: Option<PathBuf>,
@@ -328,12 +957,105 @@ pub struct Settings {
     presets_directory: Option<PathBuf>,
     icons_directory: Option<PathBuf>,
     logs_directory: Option<PathBuf>,
+    scripts_directory: Option<PathBuf>,
+    backups_directory: Option<PathBuf>,
     log_level: Option<LogLevel>,
+    log_filters: Option<String>,
     activate: Option<String>,
+    socket_path: Option<PathBuf>,
+    socket_group: Option<String>,
+    app_routing: Option<Vec<AppRoutingRule>>,
+    usb_timings: Option<UsbTimingSettings>,
+    virtual_channels: Option<Vec<VirtualChannel>>,
+    vod_mix: Option<VodMix>,
+
+    // Everything specific to a device is held here keyed by its serial, so a different unit gets
+    // its own profiles and settings, rather than picking up those of the previous one.
     devices: HashMap<String, DeviceSettings>,
 }
 
+// The directories used when they're not set in the settings file.
+fn get_default_paths() -> Result<(PathBuf, PathBuf)> {
+    let proj_dirs = ProjectDirs::from("org", "GoXLR-on-Linux", "GoXLR-Utility")
+        .context("Couldn't find project directories")?;
+    let data_dir = proj_dirs.data_dir();
+
+    // Logs belong in the state directory where the platform has one (XDG_STATE_HOME)..
+    let log_dir = proj_dirs.state_dir().unwrap_or(data_dir).join("logs");
+    Ok((data_dir.to_path_buf(), log_dir))
+}
+
+// Moves any playback stream from 'process' to the GoXLR's output for 'channel'.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRoutingRule {
+    pub process: String,
+    pub channel: ChannelName,
+}
+
+// Overrides for the USB request timings, anything not set uses the default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsbTimingSettings {
+    pub timeout_ms: Option<u64>,
+    pub read_retries: Option<u8>,
+    pub command_delay_ms: Option<u64>,
+}
+
 impl Settings {
+    // Set these values if they're missing from the configuration
+    fn fill_defaults(&mut self, data_dir: &Path, log_dir: PathBuf) {
+        if self.profile_directory.is_none() {
+            self.profile_directory = Some(data_dir.join("profiles"));
+        }
+
+        if self.mic_profile_directory.is_none() {
+            self.mic_profile_directory = Some(data_dir.join("mic-profiles"));
+        }
+
+        if self.samples_directory.is_none() {
+            self.samples_directory = Some(data_dir.join("samples"));
+        }
+
+        if self.presets_directory.is_none() {
+            self.presets_directory = Some(data_dir.join("presets"));
+        }
+
+        if self.icons_directory.is_none() {
+            self.icons_directory = Some(data_dir.join("icons"));
+        }
+
+        if self.logs_directory.is_none() {
+            self.logs_directory = Some(log_dir);
+        }
+
+        if self.scripts_directory.is_none() {
+            self.scripts_directory = Some(data_dir.join("scripts"));
+        }
+
+        if self.backups_directory.is_none() {
+            self.backups_directory = Some(data_dir.join("backups"));
+        }
+
+        if self.log_level.is_none() {
+            self.log_level = Some(LogLevel::Info);
+        }
+
+        if self.show_tray_icon.is_none() {
+            self.show_tray_icon = Some(true);
+        }
+
+        if self.tts_enabled.is_none() {
+            self.tts_enabled = Some(false);
+        }
+
+        if self.notifications_enabled.is_none() {
+            self.notifications_enabled = Some(false);
+        }
+
+        if self.allow_network_access.is_none() {
+            self.allow_network_access = Some(false);
+        }
+    }
+
     pub fn read(path: &Path) -> Result<Option<Settings>> {
         match File::open(path) {
             Ok(reader) => Ok(Some(serde_json::from_reader(reader).context(format!(
@@ -379,13 +1101,97 @@ struct DeviceSettings {
 
     hold_delay: Option<u16>,
 
+    // Per button overrides of the hold delay..
+    button_hold_delays: HashMap<Button, u16>,
+
     sampler_pre_buffer: Option<u16>,
 
+    // An extra output device which samples are also played to, for local monitoring..
+    sampler_monitor_device: Option<String>,
+    sampler_monitor_recording: Option<bool>,
+
+    // Whether pressing a 'Play Next' button while it's playing queues the next track..
+    sampler_queue_next: Option<bool>,
+
+    // The longest a recording can run (in seconds) before it's automatically stopped, 0 for no limit
+    sampler_max_recording: Option<u16>,
+
+    // How recordings are named, see files::get_recording_path for the supported placeholders..
+    sampler_recording_template: Option<String>,
+
+    // The loudness (LUFS) samples are normalised to, if different from the default..
+    sampler_normalization_target: Option<f32>,
+
     // 'Voice Chat Mute All Also Mutes Mic to Chat Mic' O_O
     chat_mute_mutes_mic_to_chat: Option<bool>,
 
+    // Volumes and mutes which haven't been saved to the profile, restored on the next start..
+    restore_runtime_state: Option<bool>,
+    runtime_state: Option<RuntimeState>,
+
+    // Milliseconds taken to raise volumes which jump up when a profile is loaded, 0 is instant..
+    profile_volume_fade: Option<u16>,
+
+    // Lower other channels while the mic is active..
+    ducking: Ducking,
+
+    // Keep the mic muted unless the push to talk trigger is held..
+    push_to_talk: PushToTalk,
+
+    // Report when the mic is picking up sound..
+    voice_activity: VoiceActivity,
+
+    // Drive the fader LEDs from the mic level..
+    fader_meter: FaderMeter,
+
+    // Percentage brightness applied to all colours sent to the device..
+    brightness: u8,
+
+    // Lighting changes when the device hasn't been touched for a while..
+    idle_lighting: IdleLighting,
+
     // 'Shutdown' commands..
     shutdown_commands: Vec<GoXLRCommand>,
+
+    // Global Hotkeys, a key combination (eg. "ctrl+shift+KeyM") mapped to a command..
+    hotkeys: HashMap<String, GoXLRCommand>,
+
+    // Macros, a named list of commands which are run in order..
+    macros: HashMap<String, Vec<GoXLRCommand>>,
+
+    // Buttons with a macro assigned, these replace the button's normal behaviour..
+    button_macros: HashMap<Button, ButtonMacro>,
+
+    // Named schedules, a cron expression and a list of commands to run..
+    schedules: HashMap<String, Schedule>,
+
+    // Colours and scribbles applied to a fader when a channel is assigned to it..
+    channel_templates: HashMap<ChannelName, ChannelTemplate>,
+
+    // Fonts and alignment used when drawing scribble text..
+    scribble_styles: HashMap<FaderName, ScribbleStyle>,
+
+    // Named sets of routes, applied with LoadRoutingPreset..
+    routing_presets: HashMap<String, RoutingPreset>,
+
+    // Named sets of channel volumes and mutes, applied with LoadVolumePreset..
+    volume_presets: HashMap<String, VolumePreset>,
+}
+
+// A snapshot of the live state of a device, only valid for the profile it was taken from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeState {
+    pub profile: String,
+    pub volumes: HashMap<ChannelName, u8>,
+    pub fader_mutes: HashMap<FaderName, MuteState>,
+    pub previous_volumes: HashMap<FaderName, u8>,
+    pub mic_mute: MuteState,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ButtonMacro {
+    pub tap: Option<String>,
+    pub hold: Option<String>,
 }
 
 impl Default for DeviceSettings {
@@ -395,10 +1201,34 @@ impl Default for DeviceSettings {
             mic_profile: DEFAULT_MIC_PROFILE_NAME.to_owned(),
 
             hold_delay: Some(500),
+            button_hold_delays: Default::default(),
             sampler_pre_buffer: None,
+            sampler_monitor_device: None,
+            sampler_monitor_recording: None,
+            sampler_queue_next: None,
+            sampler_max_recording: None,
+            sampler_recording_template: None,
+            sampler_normalization_target: None,
             chat_mute_mutes_mic_to_chat: Some(true),
+            restore_runtime_state: None,
+            profile_volume_fade: None,
+            runtime_state: None,
+            ducking: Default::default(),
+            push_to_talk: Default::default(),
+            voice_activity: Default::default(),
+            fader_meter: Default::default(),
+            brightness: 100,
+            idle_lighting: Default::default(),
 
             shutdown_commands: vec![],
+            hotkeys: Default::default(),
+            macros: Default::default(),
+            button_macros: Default::default(),
+            schedules: Default::default(),
+            channel_templates: Default::default(),
+            scribble_styles: Default::default(),
+            routing_presets: Default::default(),
+            volume_presets: Default::default(),
         }
     }
 }
@@ -0,0 +1,108 @@
+use crate::files::create_watcher;
+use crate::primary_worker::{DeviceCommand, DeviceSender};
+use crate::settings::SettingsHandle;
+use crate::shutdown::Shutdown;
+use log::{debug, info, warn};
+use notify::event::ModifyKind;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::time::sleep;
+
+/*
+The settings file can be edited by hand while the daemon is running, so its directory is watched
+(editors tend to replace the file rather than write to it) and once things have settled, the
+device worker is asked to reload it. Our own saves also land here, but are ignored when the file
+matches what's already loaded.
+
+Only some options are applied straight away:
+- log_level
+- the profile, mic profile, preset, icon and sample directories
+- device settings held by the Device (hold times, ducking, push to talk, brightness, etc.)
+
+Everything else is updated in memory, but will only take effect when the daemon is restarted.
+ */
+
+// Editors may write the file in several steps, so wait for them to finish before reading it
+const RELOAD_DELAY: Duration = Duration::from_millis(500);
+
+pub async fn spawn_settings_watcher(
+    settings: SettingsHandle,
+    usb_tx: DeviceSender,
+    mut shutdown: Shutdown,
+) {
+    let path = settings.path().to_path_buf();
+    let (directory, file_name) = match (path.parent(), path.file_name()) {
+        (Some(directory), Some(file_name)) => (directory.to_path_buf(), file_name.to_owned()),
+        _ => {
+            warn!("Unable to Determine the Settings Directory, not watching for changes");
+            return;
+        }
+    };
+
+    let (mut watcher, mut rx) = match create_watcher() {
+        Ok(watcher) => watcher,
+        Err(error) => {
+            warn!("Unable to Create the Settings Watcher: {:?}", error);
+            return;
+        }
+    };
+
+    if let Err(error) = watcher.watch(&directory, RecursiveMode::NonRecursive) {
+        warn!("Unable to Monitor the Settings Directory: {:?}", error);
+        return;
+    }
+
+    debug!("Watching {} for changes", path.to_string_lossy());
+    let mut pending = false;
+    loop {
+        tokio::select! {
+            () = shutdown.recv() => {
+                debug!("Shutting down Settings Watcher");
+                return;
+            },
+            result = rx.recv() => {
+                let event = match result {
+                    Some(Ok(event)) => event,
+                    Some(Err(error)) => {
+                        warn!("Error Reading Settings File Event: {:?}", error);
+                        continue;
+                    }
+                    None => return,
+                };
+
+                let relevant = matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(ModifyKind::Data(_))
+                        | EventKind::Modify(ModifyKind::Name(_)) | EventKind::Modify(ModifyKind::Any)
+                );
+                if relevant && event.paths.iter().any(|p| p.file_name() == Some(&file_name)) {
+                    pending = true;
+                }
+            },
+            () = sleep(RELOAD_DELAY), if pending => {
+                pending = false;
+                reload_settings(&usb_tx).await;
+            }
+        }
+    }
+}
+
+async fn reload_settings(usb_tx: &DeviceSender) {
+    let (tx, rx) = oneshot::channel();
+    if usb_tx
+        .send(DeviceCommand::ReloadSettings(tx))
+        .await
+        .is_err()
+    {
+        warn!("Unable to send Settings Reload to the device task");
+        return;
+    }
+
+    match rx.await {
+        Ok(Ok(true)) => info!("Settings File Changed, Reloaded"),
+        Ok(Ok(false)) => {}
+        Ok(Err(error)) => warn!("Unable to Reload Settings: {}", error),
+        Err(error) => warn!("Unable to Reload Settings: {}", error),
+    }
+}
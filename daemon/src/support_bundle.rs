@@ -0,0 +1,129 @@
+use crate::metrics::METRICS;
+use crate::settings::SettingsHandle;
+use crate::VERSION;
+use anyhow::{Context, Result};
+use chrono::Local;
+use directories::BaseDirs;
+use goxlr_ipc::DaemonStatus;
+use serde_json::Value;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/*
+Bundles up everything we normally ask for when someone opens an issue (the logs, the settings, the
+device status and any recent USB errors) into a single zip in the logs directory.
+
+Settings which can contain commands are blanked, and the user's home directory is replaced with ~
+so usernames don't end up attached to public issues.
+ */
+const REDACTED: &str = "<redacted>";
+const REDACTED_KEYS: [&str; 2] = ["activate", "socket_group"];
+
+pub async fn create_support_bundle(
+    settings: SettingsHandle,
+    status: DaemonStatus,
+) -> Result<PathBuf> {
+    let log_directory = settings.get_log_directory().await;
+    let settings = settings.get_json().await?;
+    let status = serde_json::to_value(status)?;
+
+    let file_name = format!(
+        "goxlr-support-{}.zip",
+        Local::now().format("%Y-%m-%d_%H-%M-%S")
+    );
+    let path = log_directory.join(file_name);
+
+    let bundle_path = path.clone();
+    tokio::task::spawn_blocking(move || {
+        write_bundle(&bundle_path, &log_directory, settings, status)
+    })
+    .await??;
+
+    Ok(path)
+}
+
+fn write_bundle(path: &Path, log_directory: &Path, settings: Value, status: Value) -> Result<()> {
+    let file = File::create(path).context(format!(
+        "Unable to create support bundle at {}",
+        path.to_string_lossy()
+    ))?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let home = BaseDirs::new().map(|dirs| dirs.home_dir().to_string_lossy().to_string());
+
+    let mut info = format!("GoXLR Utility {}\n", VERSION);
+    info.push_str(&format!("Platform: {}\n", std::env::consts::OS));
+    info.push_str(&format!("Created: {}\n", Local::now().to_rfc3339()));
+    zip.start_file("info.txt", options)?;
+    zip.write_all(info.as_bytes())?;
+
+    let settings = redact(settings, home.as_deref());
+    zip.start_file("settings.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&settings)?.as_bytes())?;
+
+    let status = redact(status, home.as_deref());
+    zip.start_file("status.json", options)?;
+    zip.write_all(serde_json::to_string_pretty(&status)?.as_bytes())?;
+
+    let errors = METRICS.recent_usb_errors();
+    zip.start_file("usb_errors.txt", options)?;
+    if errors.is_empty() {
+        zip.write_all(b"No USB errors recorded.\n")?;
+    }
+    for error in errors {
+        zip.write_all(format!("{}\n", error).as_bytes())?;
+    }
+
+    // Grab the current and rotated logs, skipping any previous bundles..
+    if let Ok(entries) = std::fs::read_dir(log_directory) {
+        for entry in entries.flatten() {
+            let log_path = entry.path();
+            if !log_path.is_file()
+                || log_path.extension().and_then(|ext| ext.to_str()) == Some("zip")
+            {
+                continue;
+            }
+
+            let contents = std::fs::read(&log_path)?;
+            let contents = String::from_utf8_lossy(&contents);
+            let contents = match &home {
+                Some(home) => contents.replace(home.as_str(), "~"),
+                None => contents.to_string(),
+            };
+
+            let name = entry.file_name().to_string_lossy().to_string();
+            zip.start_file(format!("logs/{}", name), options)?;
+            zip.write_all(contents.as_bytes())?;
+        }
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+fn redact(value: Value, home: Option<&str>) -> Value {
+    match value {
+        Value::String(string) => match home {
+            Some(home) => Value::String(string.replace(home, "~")),
+            None => Value::String(string),
+        },
+        Value::Array(values) => Value::Array(values.into_iter().map(|v| redact(v, home)).collect()),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    if REDACTED_KEYS.contains(&key.as_str()) && !value.is_null() {
+                        (key, Value::String(REDACTED.to_string()))
+                    } else {
+                        let value = redact(value, home);
+                        (key, value)
+                    }
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
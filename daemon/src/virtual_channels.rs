@@ -0,0 +1,183 @@
+use crate::settings::SettingsHandle;
+use crate::shutdown::Shutdown;
+use log::{debug, info};
+
+#[cfg(target_os = "linux")]
+use {
+    crate::app_routing::find_channel_sink,
+    anyhow::Result,
+    goxlr_audio::{get_output_sinks, load_module, unload_module},
+    goxlr_ipc::VirtualChannel,
+    log::warn,
+    std::collections::HashMap,
+    std::time::Duration,
+    tokio::time::sleep,
+};
+
+/*
+The GoXLR (and especially the Mini) only has a handful of outputs, so on Linux the daemon can
+create extra 'virtual' outputs which play into one of the GoXLR channels, for example a 'Browser'
+output which plays through the System channel. These are configured with the SetVirtualChannel
+daemon command, or in the settings file:
+
+"virtual_channels": [
+    { "name": "Browser", "channel": "System" }
+]
+
+Each one is a null sink (which applications can select as an output), and a loopback from that
+sink's monitor into the GoXLR channel. The loopback is only created while the GoXLR is present,
+and everything is removed again when the daemon stops.
+ */
+#[cfg(target_os = "linux")]
+pub(crate) const VIRTUAL_SINK_PREFIX: &str = "goxlr_virtual_";
+
+#[cfg(target_os = "linux")]
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct CreatedChannel {
+    channel: VirtualChannel,
+    sink_module: u32,
+    loopback_module: Option<u32>,
+}
+
+#[cfg(target_os = "linux")]
+pub async fn spawn_virtual_channel_service(settings: SettingsHandle, mut shutdown: Shutdown) {
+    info!("Starting Virtual Channel Service..");
+
+    let mut created: HashMap<String, CreatedChannel> = HashMap::new();
+    loop {
+        tokio::select! {
+            () = shutdown.recv() => {
+                info!("Shutting down Virtual Channel Service");
+                let _ = tokio::task::spawn_blocking(move || {
+                    for (_, existing) in created.drain() {
+                        remove_channel(&existing);
+                    }
+                }).await;
+                return;
+            },
+            () = sleep(CHECK_INTERVAL) => {
+                let configured = settings.get_virtual_channels().await;
+                if configured.is_empty() && created.is_empty() {
+                    continue;
+                }
+
+                // Talking to Pulse blocks, so keep it off the runtime..
+                let task = tokio::task::spawn_blocking(move || {
+                    let result = sync_channels(&configured, &mut created);
+                    (created, result)
+                });
+
+                match task.await {
+                    Ok((returned, result)) => {
+                        created = returned;
+                        if let Err(error) = result {
+                            warn!("Unable to Update Virtual Channels: {}", error);
+                        }
+                    }
+                    Err(error) => {
+                        warn!("Virtual Channel Task Failed: {}", error);
+                        created = HashMap::new();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn sync_channels(
+    configured: &[VirtualChannel],
+    created: &mut HashMap<String, CreatedChannel>,
+) -> Result<()> {
+    // Remove anything which has been removed, or has changed..
+    let stale: Vec<String> = created
+        .iter()
+        .filter(|(_, existing)| !configured.contains(&existing.channel))
+        .map(|(name, _)| name.clone())
+        .collect();
+    for name in stale {
+        if let Some(existing) = created.remove(&name) {
+            remove_channel(&existing);
+        }
+    }
+
+    let sinks = get_output_sinks()?;
+    for channel in configured {
+        if !created.contains_key(&channel.name) {
+            info!("Creating Virtual Channel {}", channel.name);
+            let argument = format!(
+                "sink_name={} sink_properties=\"device.description='{}'\"",
+                sink_name(&channel.name),
+                channel.name.replace(['\'', '"'], "")
+            );
+            let sink_module = load_module("module-null-sink", &argument)?;
+            created.insert(
+                channel.name.clone(),
+                CreatedChannel {
+                    channel: channel.clone(),
+                    sink_module,
+                    loopback_module: None,
+                },
+            );
+        }
+
+        let existing = match created.get_mut(&channel.name) {
+            Some(existing) => existing,
+            None => continue,
+        };
+
+        // The GoXLR may come and go, so only hook up the loopback while its output exists..
+        match find_channel_sink(&sinks, channel.channel) {
+            Some(target) => {
+                if existing.loopback_module.is_none() {
+                    debug!("Connecting {} to {}", channel.name, target.name);
+                    let argument = format!(
+                        "source={}.monitor sink={} latency_msec=20",
+                        sink_name(&channel.name),
+                        target.name
+                    );
+                    existing.loopback_module = Some(load_module("module-loopback", &argument)?);
+                }
+            }
+            None => {
+                // Pulse removes the loopback when the sink goes away, so just forget about it.
+                existing.loopback_module = None;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn remove_channel(existing: &CreatedChannel) {
+    info!("Removing Virtual Channel {}", existing.channel.name);
+    if let Some(loopback) = existing.loopback_module {
+        if let Err(error) = unload_module(loopback) {
+            debug!("Unable to remove loopback: {}", error);
+        }
+    }
+    if let Err(error) = unload_module(existing.sink_module) {
+        warn!("Unable to remove Virtual Channel: {}", error);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn sink_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{}{}", VIRTUAL_SINK_PREFIX, name.to_lowercase())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub async fn spawn_virtual_channel_service(settings: SettingsHandle, _: Shutdown) {
+    if !settings.get_virtual_channels().await.is_empty() {
+        info!("Virtual Channels Configured, but are only supported on Linux.");
+        return;
+    }
+    debug!("Virtual Channels are not supported on this platform.");
+}
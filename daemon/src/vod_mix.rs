@@ -0,0 +1,221 @@
+use crate::settings::SettingsHandle;
+use crate::shutdown::Shutdown;
+use log::{debug, info};
+
+#[cfg(target_os = "linux")]
+use {
+    crate::app_routing::find_channel_sink,
+    crate::virtual_channels::VIRTUAL_SINK_PREFIX,
+    anyhow::Result,
+    goxlr_audio::{
+        get_input_sources, get_output_sinks, load_module, unload_module, InputSource, OutputSink,
+    },
+    goxlr_ipc::VodMix,
+    goxlr_types::ChannelName,
+    log::warn,
+    std::collections::HashMap,
+    std::time::Duration,
+    tokio::time::sleep,
+};
+
+/*
+The GoXLR's Stream Mix contains everything, including Music, which is a problem for VODs where
+copyrighted music can get the recording muted or removed. On Linux, the daemon can build a
+second 'VOD Mix' in software, a null sink which any of the GoXLR channels (by default, everything
+except Music) are looped back into. Recording software can then capture the monitor of this sink
+as a separate track.
+
+This is configured with the SetVodMix daemon command, or in the settings file:
+
+"vod_mix": {
+    "enabled": true,
+    "name": "GoXLR VOD Mix",
+    "channels": ["Mic", "System", "Game", "Chat", "Sample"]
+}
+
+As with the Virtual Channels, the loopbacks are only created while the GoXLR is present.
+
+The mix is taken before the GoXLR's faders, the loopbacks capture what applications send to each
+channel's output, and the Mic comes straight from the device's Mic source. This means the GoXLR's
+volumes and mutes (including Mute to Stream, and muting everything to the stream) have no effect
+on the VOD Mix, so recording software should be used to mute it if needed.
+ */
+#[cfg(target_os = "linux")]
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct CreatedMix {
+    name: String,
+    sink_module: u32,
+    loopbacks: HashMap<ChannelName, u32>,
+}
+
+#[cfg(target_os = "linux")]
+pub async fn spawn_vod_mix_service(settings: SettingsHandle, mut shutdown: Shutdown) {
+    info!("Starting VOD Mix Service..");
+
+    let mut created: Option<CreatedMix> = None;
+    loop {
+        tokio::select! {
+            () = shutdown.recv() => {
+                info!("Shutting down VOD Mix Service");
+                if let Some(existing) = created {
+                    let _ = tokio::task::spawn_blocking(move || remove_mix(&existing)).await;
+                }
+                return;
+            },
+            () = sleep(CHECK_INTERVAL) => {
+                let configured = settings.get_vod_mix().await;
+                if !configured.enabled && created.is_none() {
+                    continue;
+                }
+
+                let task = tokio::task::spawn_blocking(move || {
+                    let result = sync_mix(&configured, &mut created);
+                    (created, result)
+                });
+
+                match task.await {
+                    Ok((returned, result)) => {
+                        created = returned;
+                        if let Err(error) = result {
+                            warn!("Unable to Update VOD Mix: {}", error);
+                        }
+                    }
+                    Err(error) => {
+                        warn!("VOD Mix Task Failed: {}", error);
+                        created = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn sync_mix(configured: &VodMix, created: &mut Option<CreatedMix>) -> Result<()> {
+    // If the Mix has been disabled or renamed, start from scratch..
+    if let Some(existing) = created {
+        if !configured.enabled || existing.name != configured.name {
+            remove_mix(existing);
+            *created = None;
+        }
+    }
+
+    if !configured.enabled {
+        return Ok(());
+    }
+
+    let existing = match created {
+        Some(existing) => existing,
+        None => {
+            info!("Creating VOD Mix {}", configured.name);
+            let argument = format!(
+                "sink_name={} sink_properties=\"device.description='{}'\"",
+                sink_name(),
+                configured.name.replace(['\'', '"'], "")
+            );
+            created.insert(CreatedMix {
+                name: configured.name.clone(),
+                sink_module: load_module("module-null-sink", &argument)?,
+                loopbacks: HashMap::new(),
+            })
+        }
+    };
+
+    // Drop any channels which are no longer part of the mix..
+    let removed: Vec<ChannelName> = existing
+        .loopbacks
+        .keys()
+        .filter(|channel| !configured.channels.contains(channel))
+        .copied()
+        .collect();
+    for channel in removed {
+        if let Some(loopback) = existing.loopbacks.remove(&channel) {
+            debug!("Removing {} from the VOD Mix", channel);
+            unload_module(loopback)?;
+        }
+    }
+
+    let sinks = get_output_sinks()?;
+    let sources = get_input_sources()?;
+    for channel in &configured.channels {
+        match find_channel_source(&sinks, &sources, *channel) {
+            Some(source) => {
+                if !existing.loopbacks.contains_key(channel) {
+                    debug!("Adding {} to the VOD Mix", channel);
+                    let argument =
+                        format!("source={} sink={} latency_msec=20", source, sink_name());
+                    let loopback = load_module("module-loopback", &argument)?;
+                    existing.loopbacks.insert(*channel, loopback);
+                }
+            }
+            None => {
+                // Pulse removes the loopback with the source, so just forget about it.
+                existing.loopbacks.remove(channel);
+            }
+        }
+    }
+    Ok(())
+}
+
+// The Mic comes from the GoXLR's own source, everything else is taken from the monitor of the
+// channel's output.
+#[cfg(target_os = "linux")]
+fn find_channel_source(
+    sinks: &[OutputSink],
+    sources: &[InputSource],
+    channel: ChannelName,
+) -> Option<String> {
+    if channel != ChannelName::Mic {
+        return find_channel_sink(sinks, channel).map(|sink| format!("{}.monitor", sink.name));
+    }
+
+    sources
+        .iter()
+        .find(|source| is_mic_source(source))
+        .map(|source| source.name.clone())
+}
+
+// The GoXLR has other sources with 'Mic' in the name (such as the Chat Mic), so this needs to be
+// an exact match, either on the channel part of the name ('..HiFi__Mic__source') or on the
+// description ('GoXLR Mic').
+#[cfg(target_os = "linux")]
+fn is_mic_source(source: &InputSource) -> bool {
+    let name = source.name.to_lowercase();
+    if name.ends_with(".monitor") {
+        return false;
+    }
+
+    let description = source.description.clone().unwrap_or_default();
+    (name.contains("goxlr") && name.split("__").any(|part| part == "mic"))
+        || description.eq_ignore_ascii_case("goxlr mic")
+}
+
+#[cfg(target_os = "linux")]
+fn remove_mix(existing: &CreatedMix) {
+    info!("Removing VOD Mix {}", existing.name);
+    for loopback in existing.loopbacks.values() {
+        if let Err(error) = unload_module(*loopback) {
+            debug!("Unable to remove loopback: {}", error);
+        }
+    }
+    if let Err(error) = unload_module(existing.sink_module) {
+        warn!("Unable to remove VOD Mix: {}", error);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn sink_name() -> String {
+    format!("{}vod_mix", VIRTUAL_SINK_PREFIX)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub async fn spawn_vod_mix_service(settings: SettingsHandle, _: Shutdown) {
+    if settings.get_vod_mix().await.enabled {
+        info!("VOD Mix Enabled, but is only supported on Linux.");
+        return;
+    }
+    debug!("VOD Mix is not supported on this platform.");
+}
@@ -1,10 +1,9 @@
 use anyhow::{bail, Result};
-use byteorder::{LittleEndian, ReadBytesExt};
-use goxlr_ipc::{DeviceType, HardwareStatus, UsbProductInformation};
-use goxlr_types::VersionNumber;
+use goxlr_ipc::{DcpFeatures, DeviceType, HardwareStatus, UsbProductInformation};
 use goxlr_usb::device::base::FullGoXLRDevice;
 use goxlr_usb::device::{find_devices, from_device};
-use std::io::{Cursor, Write};
+use goxlr_usb::firmware::FirmwareHeader;
+use std::io::Write;
 use std::path::PathBuf;
 use std::time::Duration;
 use std::{env, io};
@@ -53,6 +52,7 @@ async fn main() -> Result<()> {
     };
     let device_version = descriptor.device_version();
     let version = (device_version.0, device_version.1, device_version.2);
+    let categories = handled_device.get_supported_dcp_categories().await;
     let usb_device = UsbProductInformation {
         manufacturer_name: descriptor.device_manufacturer(),
         product_name: descriptor.product_name(),
@@ -60,8 +60,9 @@ async fn main() -> Result<()> {
         address: device_clone.address(),
         identifier: device_clone.identifier().clone(),
         version,
+        dcp_features: DcpFeatures::from(categories.as_slice()),
     };
-    let (serial_number, manufactured_date) = handled_device.get_serial_number()?;
+    let (serial_number, manufactured_date) = handled_device.get_serial_number().await?;
     if serial_number.is_empty() {
         bail!("Unable to Obtain GoXLR Serial Number!");
     }
@@ -69,7 +70,7 @@ async fn main() -> Result<()> {
     handled_device.set_unique_identifier(serial_number.clone());
 
     let hardware = HardwareStatus {
-        versions: handled_device.get_firmware_version()?,
+        versions: handled_device.get_firmware_version().await?,
         serial_number,
         manufactured_date,
         device_type,
@@ -80,9 +81,9 @@ async fn main() -> Result<()> {
 
     if let Err(error) = do_firmware_upload(&mut handled_device, firmware).await {
         println!("Firmware Update Failed: {}", error);
-        abort_update(&mut handled_device)?;
+        abort_update(&mut handled_device).await?;
     }
-    reboot_goxlr(&mut handled_device)?;
+    reboot_goxlr(&mut handled_device).await?;
     Ok(())
 }
 
@@ -97,17 +98,17 @@ async fn do_firmware_upload(
     println!("Starting..");
 
     println!("Putting Device in Firmware Update Mode..");
-    device.begin_firmware_upload()?;
+    device.begin_firmware_upload().await?;
 
     println!("Beginning Erasure of Update Partition..");
-    device.begin_erase_nvr()?;
+    device.begin_erase_nvr().await?;
 
     let mut last_percent = 0_u8;
     let mut progress = 0;
     print!("Erasing: ");
     while progress != 0xff {
         sleep(Duration::from_millis(100)).await;
-        progress = device.poll_erase_nvr()?;
+        progress = device.poll_erase_nvr().await?;
 
         // Can output a percentage here..
         let percent = ((progress as f32 / 255_f32) * 100.) as u8;
@@ -121,23 +122,20 @@ async fn do_firmware_upload(
     println!("Complete.");
     println!("Beginning Sending of Data..");
 
-    // This is to match the Official App
-    let chunk_size = 1012;
     let mut last_percent = 0_u8;
+    let total = firmware.len();
 
     print!("Sending: ");
-    let mut sent = 0;
-    for chunk in firmware.chunks(chunk_size) {
-        device.send_firmware_packet(sent, chunk)?;
-        sent += chunk.len() as u64;
-
-        let percent = ((sent as f32 / firmware.len() as f32) * 100.) as u8;
-        if percent != last_percent {
-            last_percent = percent;
-            print!(" {}%", percent);
-            let _ = io::stdout().flush();
-        }
-    }
+    let sent = device
+        .send_firmware_data(0, &firmware, &mut |sent| {
+            let percent = ((sent as f32 / total as f32) * 100.) as u8;
+            if percent != last_percent {
+                last_percent = percent;
+                print!(" {}%", percent);
+                let _ = io::stdout().flush();
+            }
+        })
+        .await?;
     println!();
 
     println!("Data Sent, Beginning Validation..");
@@ -155,7 +153,9 @@ async fn do_firmware_upload(
     let mut last_percent = 0_u8;
     print!("Validating: ");
     while remaining_bytes > 0 {
-        let (hash, count) = device.validate_firmware_packet(processed, hash_in, remaining_bytes)?;
+        let (hash, count) = device
+            .validate_firmware_packet(processed, hash_in, remaining_bytes)
+            .await?;
 
         processed += count;
         if processed > total_bytes {
@@ -190,13 +190,13 @@ async fn do_firmware_upload(
 
     // Send the 'Verify' Command..
     println!("Beginning Hardware Verification..");
-    device.verify_firmware_status()?;
+    device.verify_firmware_status().await?;
 
     print!("Verifying: ");
     let mut complete = false;
     let mut last_percent = 0_u8;
     while !complete {
-        let (completed, total, done) = device.poll_verify_firmware_status()?;
+        let (completed, total, done) = device.poll_verify_firmware_status().await?;
         complete = completed;
         let percent = ((done as f32 / total as f32) * 100.) as u8;
         if percent != last_percent {
@@ -210,14 +210,14 @@ async fn do_firmware_upload(
     println!("Hardware Verification Complete..");
 
     println!("Beginning Hardware Finalise..");
-    device.finalise_firmware_upload()?;
+    device.finalise_firmware_upload().await?;
 
     print!("Finalising: ");
     // Same again, except this time for finalise..
     complete = false;
     last_percent = 0;
     while !complete {
-        let (completed, total, done) = device.poll_finalise_firmware_upload()?;
+        let (completed, total, done) = device.poll_finalise_firmware_upload().await?;
         complete = completed;
         let percent = ((done as f32 / total as f32) * 100.) as u8;
         if percent != last_percent {
@@ -233,68 +233,37 @@ async fn do_firmware_upload(
     Ok(())
 }
 
-fn abort_update(device: &mut Box<dyn FullGoXLRDevice>) -> Result<()> {
+async fn abort_update(device: &mut Box<dyn FullGoXLRDevice>) -> Result<()> {
     println!("Aborting Update");
-    let _ = device.abort_firmware_update()?;
+    let _ = device.abort_firmware_update().await?;
     Ok(())
 }
 
-fn reboot_goxlr(device: &mut Box<dyn FullGoXLRDevice>) -> Result<()> {
+async fn reboot_goxlr(device: &mut Box<dyn FullGoXLRDevice>) -> Result<()> {
     println!("Rebooting GoXLR..");
-    device.reboot_after_firmware_upload()
+    device.reboot_after_firmware_upload().await
 }
 
 fn load_firmware_file(file: &PathBuf, device_type: DeviceType) -> Result<Vec<u8>> {
     let firmware = std::fs::read(file)?;
 
-    let name = get_firmware_name(&firmware[0..16]);
-    if device_type == DeviceType::Full && name != "GoXLR Firmware" {
+    let header = FirmwareHeader::parse(&firmware)?;
+    if device_type == DeviceType::Full && header.name != "GoXLR Firmware" {
         bail!(
             "Incompatible Firmware, Expected 'GoXLR Firmware' received '{}'",
-            name
+            header.name
         );
     }
 
-    if device_type == DeviceType::Mini && name != "GoXLR-Mini" {
+    if device_type == DeviceType::Mini && header.name != "GoXLR-Mini" {
         bail!(
             "Incompatible Firmware, Expected 'GoXLR-Mini' received '{}'",
-            name
+            header.name
         );
     }
 
-    println!("{:?}", get_firmware_name(&firmware[0..16]));
-    println!("{:?}", get_firmware_version(&firmware[24..32]));
-
-    // 25 + 26
-
-    Ok(firmware)
-}
-
-fn get_firmware_name(src: &[u8]) -> String {
-    let mut end_index = 0;
-    for byte in src {
-        if *byte == 0x00 {
-            break;
-        }
-        end_index += 1;
-    }
-    return String::from_utf8_lossy(&src[0..end_index]).to_string();
-}
-
-fn get_firmware_version(src: &[u8]) -> Result<VersionNumber> {
-    println!("{}", src.len());
-    println!("{:x?}", src);
-
-    // Unpack the firmware version..
-    let mut cursor = Cursor::new(src);
-    let firmware_packed = cursor.read_u32::<LittleEndian>()?;
-    let firmware_build = cursor.read_u32::<LittleEndian>()?;
-    let firmware = VersionNumber(
-        firmware_packed >> 12,
-        (firmware_packed >> 8) & 0xF,
-        firmware_packed & 0xFF,
-        firmware_build,
-    );
+    println!("{:?}", header.name);
+    println!("{:?}", header.version);
 
     Ok(firmware)
 }
@@ -1,12 +1,23 @@
-use crate::{DaemonRequest, DaemonStatus, GoXLRCommand, HttpSettings};
+use crate::{
+    DaemonInfo, DaemonRequest, DaemonStatus, DiagnosticReport, GoXLRCommand, HttpSettings,
+    MissingSample, PingInfo, SampleInfo,
+};
 use anyhow::Result;
 use async_trait::async_trait;
+use std::path::PathBuf;
 
 #[async_trait]
 pub trait Client {
     async fn send(&mut self, request: DaemonRequest) -> Result<()>;
+    async fn handshake(&mut self) -> Result<()>;
     async fn poll_status(&mut self) -> Result<()>;
     async fn command(&mut self, serial: &str, command: GoXLRCommand) -> Result<()>;
+    async fn daemon_info(&mut self) -> Result<DaemonInfo>;
+    async fn ping(&mut self) -> Result<PingInfo>;
+    async fn diagnostics(&mut self, serial: &str) -> Result<DiagnosticReport>;
+    async fn support_bundle(&mut self) -> Result<PathBuf>;
+    async fn validate_samples(&mut self, serial: &str) -> Result<Vec<MissingSample>>;
+    async fn sample_info(&mut self, file: &str) -> Result<SampleInfo>;
     fn status(&self) -> &DaemonStatus;
     fn http_status(&self) -> &HttpSettings;
 }
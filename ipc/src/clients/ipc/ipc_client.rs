@@ -1,41 +1,178 @@
 use crate::client::Client;
 use crate::clients::ipc::ipc_socket::Socket;
-use crate::{DaemonRequest, DaemonResponse, DaemonStatus, GoXLRCommand, HttpSettings};
+use crate::{
+    ButtonStates, DaemonEvent, DaemonInfo, DaemonRequest, DaemonResponse, DaemonStatus,
+    DiagnosticReport, GoXLRCommand, HttpSettings, IpcEncoding, IpcRequest, IpcResponse,
+    MissingSample, PingInfo, SampleInfo, EVENT_ID, PROTOCOL_VERSION,
+};
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
+use json_patch::Patch;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub struct IPCClient {
-    socket: Socket<DaemonResponse, DaemonRequest>,
+    socket: Socket<IpcResponse, IpcRequest>,
     status: DaemonStatus,
     http_settings: HttpSettings,
+
+    next_id: u64,
+    responses: HashMap<u64, DaemonResponse>,
+    patches: Vec<Patch>,
+    events: Vec<DaemonEvent>,
 }
 
 impl IPCClient {
-    pub fn new(socket: Socket<DaemonResponse, DaemonRequest>) -> Self {
+    pub fn new(socket: Socket<IpcResponse, IpcRequest>) -> Self {
         Self {
             socket,
             status: DaemonStatus::default(),
             http_settings: Default::default(),
+
+            next_id: 0,
+            responses: HashMap::new(),
+            patches: vec![],
+            events: vec![],
         }
     }
-}
 
-#[async_trait]
-impl Client for IPCClient {
-    async fn send(&mut self, request: DaemonRequest) -> Result<()> {
+    /// Sends a request without waiting for the response, returning the ID which can later be
+    /// passed to `wait_for` to collect it. This allows multiple requests to be in flight.
+    pub async fn submit(&mut self, request: DaemonRequest) -> Result<u64> {
+        let id = self.next_id;
+        self.next_id = (self.next_id + 1) % EVENT_ID;
+
         self.socket
-            .send(request)
+            .send(IpcRequest { id, data: request })
             .await
             .context("Failed to send a command to the GoXLR daemon process")?;
-        let result = self
+        Ok(id)
+    }
+
+    /// Waits for the response to a specific request, any responses for other requests (or
+    /// events) which arrive in the meantime are held until they're asked for.
+    pub async fn wait_for(&mut self, id: u64) -> Result<DaemonResponse> {
+        loop {
+            if let Some(response) = self.responses.remove(&id) {
+                return Ok(response);
+            }
+
+            self.read_message().await?;
+        }
+    }
+
+    /// Waits for the next Patch from the daemon, responses to requests which arrive in the
+    /// meantime are held until they're asked for.
+    pub async fn next_patch(&mut self) -> Result<Patch> {
+        loop {
+            if !self.patches.is_empty() {
+                return Ok(self.patches.remove(0));
+            }
+            self.read_message().await?;
+        }
+    }
+
+    /// Returns any Patches received since the last call, these are only sent by the daemon once
+    /// a DaemonRequest::Subscribe has been sent.
+    pub fn take_patches(&mut self) -> Vec<Patch> {
+        std::mem::take(&mut self.patches)
+    }
+
+    /// Applies a Patch to the held status, allowing it to be kept up to date from the Patches
+    /// sent after a DaemonRequest::Subscribe, rather than fetching the full status each time.
+    pub fn apply_patch(&mut self, patch: &Patch) -> Result<()> {
+        let mut status = serde_json::to_value(&self.status)?;
+        json_patch::patch(&mut status, patch).context("Unable to apply Patch to the Status")?;
+        self.status = serde_json::from_value(status)?;
+        Ok(())
+    }
+
+    /// Waits for the next hardware Event from the daemon, as with Patches these are only sent
+    /// once a DaemonRequest::Subscribe has been sent.
+    pub async fn next_event(&mut self) -> Result<DaemonEvent> {
+        loop {
+            if !self.events.is_empty() {
+                return Ok(self.events.remove(0));
+            }
+            self.read_message().await?;
+        }
+    }
+
+    /// Returns any Events received since the last call.
+    pub fn take_events(&mut self) -> Vec<DaemonEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Fetches the currently held buttons and encoder positions for a device.
+    pub async fn button_states(&mut self, serial: &str) -> Result<ButtonStates> {
+        match self
+            .request(DaemonRequest::GetButtonStates(serial.to_string()))
+            .await?
+        {
+            DaemonResponse::ButtonStates(states) => Ok(states),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => Err(anyhow!("Unexpected response to a Button States request")),
+        }
+    }
+
+    /// Checks a list of commands without applying them, returning the changes they would make
+    /// to the device's status.
+    pub async fn dry_run(&mut self, serial: &str, commands: Vec<GoXLRCommand>) -> Result<Patch> {
+        match self
+            .request(DaemonRequest::DryRun(serial.to_string(), commands))
+            .await?
+        {
+            DaemonResponse::DryRun(patch) => Ok(patch),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => Err(anyhow!("Unexpected response to a Dry Run request")),
+        }
+    }
+
+    /// Switches the socket to a different encoding, the daemon confirms the change using the old
+    /// encoding before switching, so this waits for that before changing our side.
+    pub async fn set_encoding(&mut self, encoding: IpcEncoding) -> Result<()> {
+        match self.request(DaemonRequest::SetEncoding(encoding)).await? {
+            DaemonResponse::Ok => {
+                self.socket.set_encoding(encoding);
+                Ok(())
+            }
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => Err(anyhow!("Unexpected response to a Set Encoding request")),
+        }
+    }
+
+    // Sends a request and waits for its response.
+    async fn request(&mut self, request: DaemonRequest) -> Result<DaemonResponse> {
+        let id = self.submit(request).await?;
+        self.wait_for(id).await
+    }
+
+    async fn read_message(&mut self) -> Result<()> {
+        let response = self
             .socket
             .read()
             .await
             .context("Failed to retrieve the command result from the GoXLR daemon process")?
             .context("Failed to parse the command result from the GoXLR daemon process")?;
 
-        match result {
+        if response.id == EVENT_ID {
+            match response.data {
+                DaemonResponse::Patch(patch) => self.patches.push(patch),
+                DaemonResponse::Event(event) => self.events.push(event),
+                _ => {}
+            }
+        } else {
+            self.responses.insert(response.id, response.data);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Client for IPCClient {
+    async fn send(&mut self, request: DaemonRequest) -> Result<()> {
+        match self.request(request).await? {
             DaemonResponse::Status(status) => {
                 self.status = status.clone();
                 self.http_settings = status.config.http_settings;
@@ -46,9 +183,34 @@ impl Client for IPCClient {
             DaemonResponse::Patch(_patch) => {
                 Err(anyhow!("Received Patch as response, shouldn't happen!"))
             }
+            DaemonResponse::Hello(version) => {
+                if version != PROTOCOL_VERSION {
+                    return Err(anyhow!(
+                        "Daemon Protocol Version {} does not match Client Version {}",
+                        version,
+                        PROTOCOL_VERSION
+                    ));
+                }
+                Ok(())
+            }
+            DaemonResponse::DaemonInfo(_info) => Ok(()),
+            DaemonResponse::ButtonStates(_states) => Ok(()),
+            DaemonResponse::DryRun(_patch) => Ok(()),
+            DaemonResponse::Pong(_info) => Ok(()),
+            DaemonResponse::Diagnostics(_report) => Ok(()),
+            DaemonResponse::SupportBundle(_path) => Ok(()),
+            DaemonResponse::MissingSamples(_samples) => Ok(()),
+            DaemonResponse::SampleInfo(_info) => Ok(()),
+            DaemonResponse::Event(_event) => {
+                Err(anyhow!("Received Event as response, shouldn't happen!"))
+            }
         }
     }
 
+    async fn handshake(&mut self) -> Result<()> {
+        self.send(DaemonRequest::Hello(PROTOCOL_VERSION)).await
+    }
+
     async fn poll_status(&mut self) -> Result<()> {
         self.send(DaemonRequest::GetStatus).await
     }
@@ -58,6 +220,63 @@ impl Client for IPCClient {
             .await
     }
 
+    async fn daemon_info(&mut self) -> Result<DaemonInfo> {
+        match self.request(DaemonRequest::GetDaemonStatus).await? {
+            DaemonResponse::DaemonInfo(info) => Ok(info),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => Err(anyhow!("Unexpected response to a Daemon Status request")),
+        }
+    }
+
+    async fn ping(&mut self) -> Result<PingInfo> {
+        match self.request(DaemonRequest::Ping).await? {
+            DaemonResponse::Pong(info) => Ok(info),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => Err(anyhow!("Unexpected response to a Ping")),
+        }
+    }
+
+    async fn diagnostics(&mut self, serial: &str) -> Result<DiagnosticReport> {
+        match self
+            .request(DaemonRequest::RunDiagnostics(serial.to_string()))
+            .await?
+        {
+            DaemonResponse::Diagnostics(report) => Ok(report),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => Err(anyhow!("Unexpected response to a Diagnostics request")),
+        }
+    }
+
+    async fn support_bundle(&mut self) -> Result<PathBuf> {
+        match self.request(DaemonRequest::CreateSupportBundle).await? {
+            DaemonResponse::SupportBundle(path) => Ok(path),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => Err(anyhow!("Unexpected response to a Support Bundle request")),
+        }
+    }
+
+    async fn validate_samples(&mut self, serial: &str) -> Result<Vec<MissingSample>> {
+        match self
+            .request(DaemonRequest::ValidateSamples(serial.to_string()))
+            .await?
+        {
+            DaemonResponse::MissingSamples(samples) => Ok(samples),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => Err(anyhow!("Unexpected response to a Validate Samples request")),
+        }
+    }
+
+    async fn sample_info(&mut self, file: &str) -> Result<SampleInfo> {
+        match self
+            .request(DaemonRequest::GetSampleInfo(file.to_string()))
+            .await?
+        {
+            DaemonResponse::SampleInfo(info) => Ok(info),
+            DaemonResponse::Error(error) => Err(anyhow!("{}", error)),
+            _ => Err(anyhow!("Unexpected response to a Sample Info request")),
+        }
+    }
+
     fn status(&self) -> &DaemonStatus {
         &self.status
     }
@@ -1,27 +1,36 @@
-use futures::{SinkExt, StreamExt, TryStreamExt};
+use crate::IpcEncoding;
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::{SinkExt, StreamExt};
 use interprocess::local_socket::tokio::LocalSocketStream;
 use interprocess::local_socket::tokio::{OwnedReadHalf, OwnedWriteHalf};
 use serde::{Deserialize, Serialize};
-use std::io::Error;
+use std::io::{Error, ErrorKind};
+use std::marker::PhantomData;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use tokio_serde::formats::SymmetricalJson;
-use tokio_serde::SymmetricallyFramed;
 use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
 use tokio_util::compat::{Compat, FuturesAsyncReadCompatExt, FuturesAsyncWriteCompatExt};
 
+/*
+Messages are sent as length prefixed frames (a 4 byte big endian length, followed by the payload),
+FramedRead holds on to partial frames until the rest arrives, so reads are safe to cancel (for
+example, inside a tokio::select!) without losing data.
+
+Payloads are decoded directly from the frame buffer, and encoded into a buffer which is reused
+between messages, so a busy event stream doesn't allocate for every Patch. Connections start out
+as JSON, and can be switched to CBOR with a DaemonRequest::SetEncoding.
+ */
+
+// Larger than any status we'd expect to send, but small enough to reject garbage lengths quickly
+const MAX_FRAME_LENGTH: usize = 16 * 1024 * 1024;
+
 #[derive(Debug)]
 pub struct Socket<In, Out> {
     address: SocketAddr,
-    reader: SymmetricallyFramed<
-        FramedRead<Compat<OwnedReadHalf>, LengthDelimitedCodec>,
-        In,
-        SymmetricalJson<In>,
-    >,
-    writer: SymmetricallyFramed<
-        FramedWrite<Compat<OwnedWriteHalf>, LengthDelimitedCodec>,
-        Out,
-        SymmetricalJson<Out>,
-    >,
+    reader: FramedRead<Compat<OwnedReadHalf>, LengthDelimitedCodec>,
+    writer: FramedWrite<Compat<OwnedWriteHalf>, LengthDelimitedCodec>,
+    encoding: IpcEncoding,
+    buffer: BytesMut,
+    _types: PhantomData<(In, Out)>,
 }
 
 impl<In, Out> Socket<In, Out>
@@ -32,34 +41,73 @@ where
     // This is basically identical to the existing one, except we take an interprocess LocalSocketStream instead..
     pub fn new(stream: LocalSocketStream) -> Self {
         let (stream_read, stream_write) = stream.into_split();
-        let length_delimited_read =
-            FramedRead::new(stream_read.compat(), LengthDelimitedCodec::new());
-        let reader = SymmetricallyFramed::new(length_delimited_read, SymmetricalJson::default());
-
-        let length_delimited_write =
-            FramedWrite::new(stream_write.compat_write(), LengthDelimitedCodec::new());
-        let writer = SymmetricallyFramed::new(length_delimited_write, SymmetricalJson::default());
+        let reader = FramedRead::new(stream_read.compat(), Self::codec());
+        let writer = FramedWrite::new(stream_write.compat_write(), Self::codec());
 
         Self {
             address: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 0),
             reader,
             writer,
+            encoding: IpcEncoding::Json,
+            buffer: BytesMut::new(),
+            _types: PhantomData,
         }
     }
 
+    fn codec() -> LengthDelimitedCodec {
+        LengthDelimitedCodec::builder()
+            .max_frame_length(MAX_FRAME_LENGTH)
+            .new_codec()
+    }
+
     pub async fn read(&mut self) -> Option<Result<In, Error>> {
-        self.reader.next().await
+        let frame = match self.reader.next().await? {
+            Ok(frame) => frame,
+            Err(error) => return Some(Err(error)),
+        };
+        Some(self.decode(&frame))
     }
 
     pub async fn try_read(&mut self) -> Result<Option<In>, Error> {
-        self.reader.try_next().await
+        self.read().await.transpose()
     }
 
     pub async fn send(&mut self, out: Out) -> Result<(), Error> {
-        self.writer.send(out).await
+        let frame = self.encode(&out)?;
+        self.writer.send(frame).await
+    }
+
+    /// Changes the encoding used for all messages after this point, in both directions.
+    pub fn set_encoding(&mut self, encoding: IpcEncoding) {
+        self.encoding = encoding;
+    }
+
+    pub fn encoding(&self) -> IpcEncoding {
+        self.encoding
     }
 
     pub fn address(&self) -> &SocketAddr {
         &self.address
     }
+
+    fn decode(&self, frame: &[u8]) -> Result<In, Error> {
+        match self.encoding {
+            IpcEncoding::Json => serde_json::from_slice(frame).map_err(Error::from),
+            IpcEncoding::Cbor => ciborium::de::from_reader(frame)
+                .map_err(|error| Error::new(ErrorKind::InvalidData, error.to_string())),
+        }
+    }
+
+    fn encode(&mut self, out: &Out) -> Result<Bytes, Error> {
+        // The previous frame has been copied into the codec's buffer by now, so this will
+        // generally reclaim the same allocation rather than creating a new one.
+        self.buffer.clear();
+        let writer = (&mut self.buffer).writer();
+        match self.encoding {
+            IpcEncoding::Json => serde_json::to_writer(writer, out).map_err(Error::from)?,
+            IpcEncoding::Cbor => ciborium::ser::into_writer(out, writer)
+                .map_err(|error| Error::new(ErrorKind::InvalidData, error.to_string()))?,
+        }
+        Ok(self.buffer.split().freeze())
+    }
 }
@@ -1,2 +1,38 @@
+use interprocess::local_socket::NameTypeSupport;
+use std::env;
+use std::path::PathBuf;
+
 pub mod ipc_client;
 pub mod ipc_socket;
+
+/*
+Where file based sockets are available, the socket lives in XDG_RUNTIME_DIR by default, which is
+private to the user (unlike /tmp, where anyone can create or replace it). Platforms without it
+fall back to the temp directory. Setting GOXLR_SOCKET overrides the location for both the daemon
+and clients, and on Windows it replaces the named pipe.
+ */
+pub const SOCKET_ENV: &str = "GOXLR_SOCKET";
+pub const SOCKET_FILE: &str = "goxlr.socket";
+pub const NAMED_PIPE: &str = "@goxlr.socket";
+
+pub fn default_socket_path() -> PathBuf {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|path| path.is_dir())
+        .unwrap_or_else(env::temp_dir)
+        .join(SOCKET_FILE)
+}
+
+// Returns the socket name to use, unless overridden on the command line.
+pub fn socket_name() -> String {
+    if let Ok(name) = env::var(SOCKET_ENV) {
+        return name;
+    }
+
+    match NameTypeSupport::query() {
+        NameTypeSupport::OnlyPaths | NameTypeSupport::Both => {
+            default_socket_path().to_string_lossy().to_string()
+        }
+        NameTypeSupport::OnlyNamespaced => String::from(NAMED_PIPE),
+    }
+}
@@ -1,7 +1,11 @@
 use crate::client::Client;
-use crate::{DaemonRequest, DaemonResponse, DaemonStatus, GoXLRCommand, HttpSettings};
+use crate::{
+    DaemonInfo, DaemonRequest, DaemonResponse, DaemonStatus, DiagnosticReport, GoXLRCommand,
+    HttpSettings, MissingSample, PingInfo, SampleInfo, PROTOCOL_VERSION,
+};
 use anyhow::bail;
 use async_trait::async_trait;
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub struct WebClient {
@@ -18,21 +22,24 @@ impl WebClient {
             http_settings: Default::default(),
         }
     }
-}
 
-#[async_trait]
-impl Client for WebClient {
-    async fn send(&mut self, request: DaemonRequest) -> anyhow::Result<()> {
-        let resp = reqwest::Client::new()
+    async fn request(&self, request: DaemonRequest) -> anyhow::Result<DaemonResponse> {
+        let response = reqwest::Client::new()
             .post(&self.url)
             .json(&request)
             .send()
             .await?
             .json::<DaemonResponse>()
             .await?;
+        Ok(response)
+    }
+}
 
+#[async_trait]
+impl Client for WebClient {
+    async fn send(&mut self, request: DaemonRequest) -> anyhow::Result<()> {
         // Should probably abstract this part, it's common between clients..
-        match resp {
+        match self.request(request).await? {
             DaemonResponse::Status(status) => {
                 self.status = status.clone();
                 self.http_settings = status.config.http_settings;
@@ -43,9 +50,34 @@ impl Client for WebClient {
             DaemonResponse::Patch(_patch) => {
                 bail!("Received Patch as response, shouldn't happen!")
             }
+            DaemonResponse::Hello(version) => {
+                if version != PROTOCOL_VERSION {
+                    bail!(
+                        "Daemon Protocol Version {} does not match Client Version {}",
+                        version,
+                        PROTOCOL_VERSION
+                    );
+                }
+                Ok(())
+            }
+            DaemonResponse::DaemonInfo(_info) => Ok(()),
+            DaemonResponse::ButtonStates(_states) => Ok(()),
+            DaemonResponse::DryRun(_patch) => Ok(()),
+            DaemonResponse::Pong(_info) => Ok(()),
+            DaemonResponse::Diagnostics(_report) => Ok(()),
+            DaemonResponse::SupportBundle(_path) => Ok(()),
+            DaemonResponse::MissingSamples(_samples) => Ok(()),
+            DaemonResponse::SampleInfo(_info) => Ok(()),
+            DaemonResponse::Event(_event) => {
+                bail!("Received Event as response, shouldn't happen!")
+            }
         }
     }
 
+    async fn handshake(&mut self) -> anyhow::Result<()> {
+        self.send(DaemonRequest::Hello(PROTOCOL_VERSION)).await
+    }
+
     async fn poll_status(&mut self) -> anyhow::Result<()> {
         self.send(DaemonRequest::GetStatus).await
     }
@@ -55,6 +87,63 @@ impl Client for WebClient {
             .await
     }
 
+    async fn daemon_info(&mut self) -> anyhow::Result<DaemonInfo> {
+        match self.request(DaemonRequest::GetDaemonStatus).await? {
+            DaemonResponse::DaemonInfo(info) => Ok(info),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response to a Daemon Status request"),
+        }
+    }
+
+    async fn ping(&mut self) -> anyhow::Result<PingInfo> {
+        match self.request(DaemonRequest::Ping).await? {
+            DaemonResponse::Pong(info) => Ok(info),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response to a Ping"),
+        }
+    }
+
+    async fn diagnostics(&mut self, serial: &str) -> anyhow::Result<DiagnosticReport> {
+        match self
+            .request(DaemonRequest::RunDiagnostics(serial.to_string()))
+            .await?
+        {
+            DaemonResponse::Diagnostics(report) => Ok(report),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response to a Diagnostics request"),
+        }
+    }
+
+    async fn support_bundle(&mut self) -> anyhow::Result<PathBuf> {
+        match self.request(DaemonRequest::CreateSupportBundle).await? {
+            DaemonResponse::SupportBundle(path) => Ok(path),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response to a Support Bundle request"),
+        }
+    }
+
+    async fn validate_samples(&mut self, serial: &str) -> anyhow::Result<Vec<MissingSample>> {
+        match self
+            .request(DaemonRequest::ValidateSamples(serial.to_string()))
+            .await?
+        {
+            DaemonResponse::MissingSamples(samples) => Ok(samples),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response to a Validate Samples request"),
+        }
+    }
+
+    async fn sample_info(&mut self, file: &str) -> anyhow::Result<SampleInfo> {
+        match self
+            .request(DaemonRequest::GetSampleInfo(file.to_string()))
+            .await?
+        {
+            DaemonResponse::SampleInfo(info) => Ok(info),
+            DaemonResponse::Error(error) => bail!("{}", error),
+            _ => bail!("Unexpected response to a Sample Info request"),
+        }
+    }
+
     fn status(&self) -> &DaemonStatus {
         &self.status
     }
@@ -1,14 +1,15 @@
-use crate::{GoXLRCommand, LogLevel};
+use crate::{EncoderPositions, GoXLRCommand, LogLevel};
 use enum_map::EnumMap;
 use goxlr_types::MuteState::Unmuted;
 use goxlr_types::{
-    AnimationMode, Button, ButtonColourOffStyle, ChannelName, CompressorAttackTime,
-    CompressorRatio, CompressorReleaseTime, DisplayMode, EchoStyle, EffectBankPresets,
-    EncoderColourTargets, EqFrequencies, FaderDisplayStyle, FaderName, FirmwareVersions, GateTimes,
-    GenderStyle, HardTuneSource, HardTuneStyle, InputDevice, MegaphoneStyle, MicrophoneType,
-    MiniEqFrequencies, Mix, MuteFunction, MuteState, OutputDevice, PitchStyle, ReverbStyle,
-    RobotStyle, SampleBank, SampleButtons, SamplePlayOrder, SamplePlaybackMode,
-    SamplerColourTargets, SimpleColourTargets, SubMixChannelName, WaterfallDirection,
+    AnimationMode, Button, ButtonColourHoldStyle, ButtonColourOffStyle, ChannelName,
+    CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DCPCategory, DeviceCapabilities,
+    DisplayMode, EchoStyle, EffectBankPresets, EncoderColourTargets, EqFrequencies,
+    FaderDisplayStyle, FaderName, FirmwareVersions, GateTimes, GenderStyle, HardTuneSource,
+    HardTuneStyle, InputDevice, MegaphoneStyle, MicrophoneType, MiniEqFrequencies, Mix,
+    MuteFunction, MuteState, OutputDevice, PitchStyle, ReverbStyle, RobotStyle, SampleBank,
+    SampleButtons, SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets, ScribbleAlignment,
+    SimpleColourTargets, SubMixChannelName, VersionNumber, VolumeLevel, WaterfallDirection,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
@@ -29,8 +30,91 @@ pub struct DaemonConfig {
     pub autostart_enabled: bool,
     pub show_tray_icon: bool,
     pub tts_enabled: Option<bool>,
+    pub notifications_enabled: bool,
     pub allow_network_access: bool,
     pub log_level: LogLevel,
+    pub usb_trace_enabled: bool,
+    pub virtual_channels: Vec<VirtualChannel>,
+    pub vod_mix: VodMix,
+}
+
+// An extra output created by the daemon (Linux only), which plays through a GoXLR channel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VirtualChannel {
+    pub name: String,
+    pub channel: ChannelName,
+}
+
+// A separate mix of the GoXLR channels (Linux only), normally everything except Music, which can be
+// recorded as a separate track to keep VODs free of copyrighted audio. This is mixed before the
+// GoXLR's faders, so channel volumes and mutes don't apply to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VodMix {
+    pub enabled: bool,
+    pub name: String,
+    pub channels: Vec<ChannelName>,
+}
+
+impl Default for VodMix {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            name: String::from("GoXLR VOD Mix"),
+            channels: vec![
+                ChannelName::Mic,
+                ChannelName::System,
+                ChannelName::Game,
+                ChannelName::Chat,
+                ChannelName::Sample,
+            ],
+        }
+    }
+}
+
+// Diagnostic information about the daemon itself, useful when reporting issues.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonInfo {
+    pub version: String,
+    pub git_hash: Option<String>,
+    pub protocol_version: u32,
+
+    // Uptime in seconds
+    pub uptime: u64,
+    pub socket_path: String,
+    pub http_settings: HttpSettings,
+    pub ipc_clients: i64,
+    pub websocket_clients: i64,
+    pub devices: Vec<DeviceHealth>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceHealth {
+    pub serial: String,
+    pub device_type: DeviceType,
+    pub connected: bool,
+    pub firmware: VersionNumber,
+
+    // Seconds since the device was connected, None if it's offline
+    pub uptime: Option<u64>,
+    pub firmware_update: Option<FirmwareUpdateStage>,
+}
+
+// The response to a Ping, lets clients measure latency and check that devices are still being
+// polled, a stuck USB read won't otherwise show up as the socket will remain alive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingInfo {
+    // Milliseconds since the Unix Epoch, according to the daemon
+    pub timestamp: u64,
+    pub devices: Vec<DevicePollHealth>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevicePollHealth {
+    pub serial: String,
+
+    // Milliseconds since the device's inputs were last read, None if they never have been
+    pub last_poll: Option<u64>,
+    pub responsive: bool,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -38,25 +122,48 @@ pub struct HttpSettings {
     pub enabled: bool,
     pub bind_address: String,
     pub cors_enabled: bool,
+    pub metrics_enabled: bool,
     pub port: u16,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MixerStatus {
     pub hardware: HardwareStatus,
+
+    // What the device supports, including the channels which can be assigned to a fader.
+    pub capabilities: DeviceCapabilities,
+
+    // False while the device is unplugged, the last known state is kept until it returns.
+    pub connected: bool,
+    pub firmware_update: Option<FirmwareUpdateStatus>,
+
     pub shutdown_commands: Vec<GoXLRCommand>,
     pub fader_status: EnumMap<FaderName, FaderStatus>,
     pub mic_status: MicSettings,
     pub levels: Levels,
     pub router: EnumMap<InputDevice, EnumMap<OutputDevice, bool>>,
+
+    // True while everything is cut from the Broadcast Mix by MuteAllToStream
+    pub stream_muted: bool,
+
+    // True while the push to talk trigger is held (or its release delay is running)
+    pub push_to_talk_active: bool,
+
+    // True while the mic is unmuted and picking up sound, only updated if VoiceActivity is enabled
+    pub mic_active: bool,
     pub cough_button: CoughButton,
+    pub bleep_button: BleepButton,
     pub lighting: Lighting,
     pub effects: Option<Effects>,
     pub sampler: Option<Sampler>,
     pub settings: Settings,
     pub button_down: EnumMap<Button, bool>,
+    pub button_held: EnumMap<Button, bool>,
     pub profile_name: String,
     pub mic_profile_name: String,
+
+    // The names of the in-memory state snapshots, these are lost when the daemon stops.
+    pub snapshots: Vec<String>,
 }
 
 impl MixerStatus {
@@ -90,11 +197,26 @@ pub struct FaderStatus {
     pub mute_state: MuteState,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoughButton {
     pub is_toggle: bool,
     pub mute_type: MuteFunction,
     pub state: MuteState,
+
+    // The live state of the hardware button, latched is only set while a toggle is muting
+    pub pressed: bool,
+    pub latched: bool,
+
+    // The outputs the Mic is currently muted to by the button, empty when unmuted
+    pub muted_outputs: Vec<OutputDevice>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BleepButton {
+    pub pressed: bool,
+
+    // The channels being replaced by the bleep while the button is held
+    pub muted_channels: Vec<ChannelName>,
 }
 
 impl Default for FaderStatus {
@@ -117,6 +239,21 @@ pub struct MicSettings {
     pub equaliser_mini: EqualiserMini,
     pub noise_gate: NoiseGate,
     pub compressor: Compressor,
+
+    pub calibrating: bool,
+    pub last_calibration: Option<MicCalibrationResult>,
+}
+
+// The outcome of a mic gain calibration, levels are in dBFS and the gains in dB.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MicCalibrationResult {
+    pub mic_type: MicrophoneType,
+    pub speech_detected: bool,
+    pub peak_db: f64,
+    pub average_db: f64,
+    pub previous_gain: u16,
+    pub suggested_gain: u16,
+    pub applied: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -124,6 +261,7 @@ pub struct Levels {
     pub submix_supported: bool,
     pub output_monitor: OutputDevice,
     pub volumes: EnumMap<ChannelName, u8>,
+    pub volume_levels: EnumMap<ChannelName, VolumeLevel>,
     pub submix: Option<Submixes>,
     pub bleep: i8,
     pub deess: u8,
@@ -194,6 +332,7 @@ pub struct AnimationLighting {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ButtonLighting {
     pub off_style: ButtonColourOffStyle,
+    pub hold_style: ButtonColourHoldStyle,
     pub colours: TwoColours,
 }
 
@@ -233,6 +372,7 @@ pub struct Effects {
     pub active_preset: EffectBankPresets,
     pub preset_names: HashMap<EffectBankPresets, String>,
     pub current: ActiveEffects,
+    pub encoders: EncoderPositions,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -332,6 +472,12 @@ pub struct Sampler {
     pub active_bank: SampleBank,
     pub clear_active: bool,
     pub record_buffer: u16,
+    pub monitor_device: Option<String>,
+    pub monitor_recording: bool,
+    pub max_recording_length: u16,
+    pub queue_next: bool,
+    pub recording_template: String,
+    pub normalization_target: f32,
     pub banks: HashMap<SampleBank, HashMap<SampleButtons, SamplerButton>>,
 }
 
@@ -348,6 +494,16 @@ pub struct SamplerButton {
     pub samples: Vec<Sample>,
     pub is_playing: bool,
     pub is_recording: bool,
+    pub active: Option<ActiveSample>,
+}
+
+// Details of the sample currently playing (or being recorded) on a button, the start time is in
+// milliseconds since the epoch so clients can calculate the elapsed time themselves.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveSample {
+    pub track: String,
+    pub recording: bool,
+    pub started: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -355,13 +511,187 @@ pub struct Sample {
     pub name: String,
     pub start_pct: f32,
     pub stop_pct: f32,
+    pub playback_speed: f32,
+
+    // The file couldn't be found in the samples directory, so will be skipped during playback.
+    pub missing: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub display: Display,
     pub mute_hold_duration: u16,
+    pub button_hold_durations: HashMap<Button, u16>,
     pub vc_mute_also_mute_cm: bool,
+    pub restore_runtime_state: bool,
+    pub profile_volume_fade: u16,
+    pub ducking: Ducking,
+    pub push_to_talk: PushToTalk,
+    pub voice_activity: VoiceActivity,
+    pub fader_meter: FaderMeter,
+    pub idle_lighting: IdleLighting,
+    pub brightness: u8,
+    pub schedules: HashMap<String, Schedule>,
+    pub channel_templates: HashMap<ChannelName, ChannelTemplate>,
+    pub scribble_styles: HashMap<FaderName, ScribbleStyle>,
+    pub routing_presets: HashMap<String, RoutingPreset>,
+    pub volume_presets: HashMap<String, VolumePreset>,
+}
+
+// Changes the lighting when nothing has been touched on the device for 'timeout' minutes, a
+// timeout of 0 disables this. Brightness is a percentage, used when the behaviour is Dim.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdleLighting {
+    pub timeout: u16,
+    pub behaviour: IdleBehaviour,
+    pub brightness: u8,
+}
+
+impl Default for IdleLighting {
+    fn default() -> Self {
+        IdleLighting {
+            timeout: 0,
+            behaviour: IdleBehaviour::Dim,
+            brightness: 20,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub enum IdleBehaviour {
+    Dim,
+    Off,
+    Rainbow,
+}
+
+// A cron expression (including seconds), and the commands to run when it triggers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    pub cron: String,
+    pub commands: Vec<GoXLRCommand>,
+}
+
+// Reduces the volume of the channels when the mic level goes above the threshold. Attack, Hold
+// and Release are in milliseconds, the threshold is the raw level reported by the GoXLR.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ducking {
+    pub enabled: bool,
+    pub channels: Vec<ChannelName>,
+    pub amount: u8,
+    pub threshold: u16,
+    pub attack: u16,
+    pub hold: u16,
+    pub release: u16,
+}
+
+impl Default for Ducking {
+    fn default() -> Self {
+        Ducking {
+            enabled: false,
+            channels: vec![ChannelName::Music, ChannelName::Game],
+            amount: 50,
+            threshold: 2000,
+            attack: 100,
+            hold: 750,
+            release: 1000,
+        }
+    }
+}
+
+// Keeps the mic muted unless the trigger is held. The button is optional (the trigger can also
+// come from a hotkey, or SetPushToTalkActive), the release delay is in milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushToTalk {
+    pub enabled: bool,
+    pub button: Option<Button>,
+    pub release_delay: u16,
+}
+
+impl Default for PushToTalk {
+    fn default() -> Self {
+        PushToTalk {
+            enabled: false,
+            button: None,
+            release_delay: 300,
+        }
+    }
+}
+
+// A named set of routes which can be applied in one go, only the routes listed are changed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RoutingPreset {
+    pub routes: HashMap<InputDevice, HashMap<OutputDevice, bool>>,
+}
+
+// A named set of channel volumes and mutes, only the channels listed are changed. Mutes only
+// apply to channels which are currently assigned to a fader.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VolumePreset {
+    pub volumes: HashMap<ChannelName, u8>,
+    pub mutes: HashMap<ChannelName, MuteState>,
+}
+
+// How the text on a fader's scribble strip is drawn, the font is the path to a TTF file, if not
+// set (or it can't be loaded) the built in font is used.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScribbleStyle {
+    pub font: Option<PathBuf>,
+    pub alignment: ScribbleAlignment,
+}
+
+// Applied to a fader when a channel is assigned to it, so it's immediately recognisable. Anything
+// left as None is kept as it was.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChannelTemplate {
+    pub top_colour: Option<String>,
+    pub bottom_colour: Option<String>,
+    pub scribble_icon: Option<String>,
+    pub scribble_text: Option<String>,
+}
+
+// Reports whether the mic is in use (for example, to drive a 'talking' indicator). The threshold is
+// the raw level reported by the GoXLR, and hold (in milliseconds) stops it flickering between words.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceActivity {
+    pub enabled: bool,
+    pub threshold: u16,
+    pub hold: u16,
+}
+
+impl Default for VoiceActivity {
+    fn default() -> Self {
+        VoiceActivity {
+            enabled: false,
+            threshold: 2000,
+            hold: 500,
+        }
+    }
+}
+
+// Dims the fader LEDs of any fader assigned to the Mic in time with the mic level, refreshed every
+// 'refresh_rate' milliseconds. The GoXLR only reports the mic level, so other channels are unaffected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaderMeter {
+    pub enabled: bool,
+    pub refresh_rate: u16,
+}
+
+impl Default for FaderMeter {
+    fn default() -> Self {
+        FaderMeter {
+            enabled: false,
+            refresh_rate: 100,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -399,6 +729,28 @@ pub struct Scribble {
     pub inverted: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirmwareUpdateStatus {
+    pub stage: FirmwareUpdateStage,
+
+    // Percentage of the current stage which has been completed
+    pub progress: u8,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub enum FirmwareUpdateStage {
+    Starting,
+    Erasing,
+    Uploading,
+    Validating,
+    Verifying,
+    Finalising,
+    Rebooting,
+    Complete,
+    Failed,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsbProductInformation {
     pub manufacturer_name: String,
@@ -407,6 +759,27 @@ pub struct UsbProductInformation {
     pub bus_number: u8,
     pub address: u8,
     pub identifier: Option<String>,
+    pub dcp_features: DcpFeatures,
+}
+
+// The DCP categories the device's firmware reports as supported, probed when it's attached.
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DcpFeatures {
+    pub peaks: bool,
+    pub router: bool,
+    pub mixer: bool,
+    pub nvm: bool,
+}
+
+impl From<&[DCPCategory]> for DcpFeatures {
+    fn from(categories: &[DCPCategory]) -> Self {
+        Self {
+            peaks: categories.contains(&DCPCategory::Peaks),
+            router: categories.contains(&DCPCategory::Router),
+            mixer: categories.contains(&DCPCategory::Mixer),
+            nvm: categories.contains(&DCPCategory::NVM),
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -416,3 +789,13 @@ pub enum DeviceType {
     Full,
     Mini,
 }
+
+impl DeviceType {
+    // Unknown devices are treated as a Full, as that's how the rest of the daemon handles them.
+    pub fn capabilities(&self) -> DeviceCapabilities {
+        match self {
+            DeviceType::Mini => DeviceCapabilities::mini(),
+            DeviceType::Full | DeviceType::Unknown => DeviceCapabilities::full(),
+        }
+    }
+}
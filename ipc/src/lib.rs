@@ -1,5 +1,8 @@
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Formatter;
+use std::path::PathBuf;
 
 pub mod client;
 pub mod clients;
@@ -7,30 +10,246 @@ mod device;
 
 pub use device::*;
 use goxlr_types::{
-    AnimationMode, Button, ButtonColourGroups, ButtonColourOffStyle, ChannelName,
-    CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DisplayMode,
-    DisplayModeComponents, EchoStyle, EffectBankPresets, EncoderColourTargets, EqFrequencies,
-    FaderDisplayStyle, FaderName, GateTimes, GenderStyle, HardTuneSource, HardTuneStyle,
-    InputDevice, MegaphoneStyle, MicrophoneType, MiniEqFrequencies, Mix, MuteFunction, MuteState,
-    OutputDevice, PitchStyle, ReverbStyle, RobotRange, RobotStyle, SampleBank, SampleButtons,
-    SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets, SimpleColourTargets,
-    WaterfallDirection,
+    AnimationMode, Button, ButtonColourGroups, ButtonColourHoldStyle, ButtonColourOffStyle,
+    ChannelName, CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DisplayMode,
+    DisplayModeComponents, EchoStyle, EffectBankPresets, EncoderColourTargets, EncoderName,
+    EqFrequencies, FaderDisplayStyle, FaderName, GateTimes, GenderStyle, HardTuneSource,
+    HardTuneStyle, InputDevice, MegaphoneStyle, MicrophoneType, MiniEqFrequencies, Mix,
+    MuteFunction, MuteState, OutputDevice, PitchStyle, ReverbStyle, RobotRange, RobotStyle,
+    SampleBank, SampleButtons, SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets,
+    SimpleColourTargets, WaterfallDirection,
 };
 
+// This should be bumped whenever a change is made to the IPC structures which would break
+// existing clients, clients send this in a Hello on connection and are rejected if it differs.
+pub const PROTOCOL_VERSION: u32 = 7;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DaemonRequest {
     Ping,
+    Hello(u32),
+    Subscribe,
     GetStatus,
+    GetDaemonStatus,
+    GetButtonStates(String),
     Daemon(DaemonCommand),
     Command(String, GoXLRCommand),
+
+    // Runs a list of commands in order, nothing else will be run on the device in between. If
+    // a command fails, the remaining commands are skipped and the profile and mic profile are
+    // rolled back to how they were before the batch. Responds with the Status on success.
+    Batch(String, Vec<GoXLRCommand>),
+
+    // Checks a list of commands against the device without applying them, responds with a
+    // DryRun containing the changes they would make to the device's status.
+    DryRun(String, Vec<GoXLRCommand>),
+
+    // Runs a set of read-only checks against the device (and its audio devices), responds with
+    // a Diagnostics report which can be attached to bug reports.
+    RunDiagnostics(String),
+
+    // Collects the logs, settings, device status and recent USB errors into a zip, responds with
+    // the path of the created file.
+    CreateSupportBundle,
+
+    // Checks the samples in the device's profile against the samples directory, responds with any
+    // which can't be found.
+    ValidateSamples(String),
+
+    // Reads the details of a file in the samples directory (as listed in Files), for showing in
+    // a sample picker.
+    GetSampleInfo(String),
+
+    // Switches the encoding of the IPC socket, the Ok is sent using the old encoding and anything
+    // after it uses the new one. Nothing else should be sent until the Ok has been received.
+    SetEncoding(IpcEncoding),
+}
+
+// How messages on the IPC socket are encoded. Connections start as Json, Cbor is more compact and
+// quicker to handle, which helps clients following the event stream.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IpcEncoding {
+    Json,
+    Cbor,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DaemonResponse {
     Ok,
-    Error(String),
+    Error(ErrorResponse),
     Status(DaemonStatus),
     Patch(Patch),
+    Hello(u32),
+    DaemonInfo(DaemonInfo),
+    ButtonStates(ButtonStates),
+    Event(DaemonEvent),
+    DryRun(Patch),
+    Pong(PingInfo),
+    Diagnostics(DiagnosticReport),
+    SupportBundle(PathBuf),
+    MissingSamples(Vec<MissingSample>),
+    SampleInfo(SampleInfo),
+}
+
+// The raw state of the hardware inputs, for tools which want to build on top of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ButtonStates {
+    pub pressed: Vec<Button>,
+    pub encoders: EncoderPositions,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticReport {
+    pub serial: String,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampleInfo {
+    pub file: String,
+    pub duration_ms: Option<u64>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<usize>,
+    pub tags: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MissingSample {
+    pub bank: SampleBank,
+    pub button: SampleButtons,
+    pub track: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncoderPositions {
+    pub pitch: i8,
+    pub gender: i8,
+    pub reverb: i8,
+    pub echo: i8,
+}
+
+// Events are sent to subscribed IPC clients (and all Websocket clients) alongside Patches, the
+// first field is the serial.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DaemonEvent {
+    ButtonDown(String, Button),
+    ButtonUp(String, Button),
+    SampleStarted(String, SampleBank, SampleButtons, ActiveSample),
+    SampleStopped(String, SampleBank, SampleButtons),
+    SampleProgress(String, SampleBank, SampleButtons, PlaybackProgress),
+    SampleRecordingLevel(String, SampleBank, SampleButtons, f64),
+
+    // A recording has finished and been added to the button, with the path of the new file.
+    RecordingStopped(String, SampleBank, SampleButtons, PathBuf),
+    MicCalibrationComplete(String, MicCalibrationResult),
+
+    // A sample in the profile can't be found, so the user can be prompted to relocate it.
+    SampleMissing(String, SampleBank, SampleButtons, String),
+    MicActivity(String, bool),
+
+    // The settings file was changed outside the daemon, and has been reloaded.
+    SettingsChanged,
+}
+
+// Recordings have no known length, so only report how long they've been running.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybackProgress {
+    pub percent: Option<u8>,
+    pub position_ms: u64,
+}
+
+// Errors are categorised where possible so clients can react to them, anything which doesn't fit
+// one of the categories is returned as Other, with the original error message.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub enum DaemonError {
+    DeviceNotFound(String),
+    CommandNotSupportedOnMini,
+    ChannelNotAvailable(ChannelName),
+    ProfileNotFound(String),
+    UsbError { message: String },
+    Other(String),
+}
+
+impl std::fmt::Display for DaemonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DaemonError::DeviceNotFound(serial) => write!(f, "Device {} is not connected", serial),
+            DaemonError::CommandNotSupportedOnMini => {
+                write!(f, "This command is not supported on the GoXLR Mini")
+            }
+            DaemonError::ChannelNotAvailable(channel) => {
+                write!(f, "The {} channel is not available on this device", channel)
+            }
+            DaemonError::ProfileNotFound(name) => write!(f, "Profile {} does not exist", name),
+            DaemonError::UsbError { message } => write!(f, "USB Error: {}", message),
+            DaemonError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for DaemonError {}
+
+// Errors are sent with their message alongside the category, so clients which only need to show
+// the error don't need to understand the categories. The HTTP and websocket APIs send just the
+// message (which the Web UI expects), that's read back as an Other error.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(from = "ErrorResponseFormat")]
+pub struct ErrorResponse {
+    pub code: DaemonError,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ErrorResponseFormat {
+    Full { code: DaemonError, message: String },
+    Message(String),
+}
+
+impl From<ErrorResponseFormat> for ErrorResponse {
+    fn from(format: ErrorResponseFormat) -> Self {
+        match format {
+            ErrorResponseFormat::Full { code, message } => Self { code, message },
+            ErrorResponseFormat::Message(message) => DaemonError::Other(message).into(),
+        }
+    }
+}
+
+impl From<DaemonError> for ErrorResponse {
+    fn from(code: DaemonError) -> Self {
+        let message = code.to_string();
+        Self { code, message }
+    }
+}
+
+impl std::fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+// Messages on the IPC socket are wrapped with an ID, allowing a client to have multiple requests in
+// flight, responses will carry the ID of the request they're for. Once a client has sent a
+// Subscribe, Patches and Events will also be sent with an ID of EVENT_ID.
+pub const EVENT_ID: u64 = u64::MAX;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcRequest {
+    pub id: u64,
+    pub data: DaemonRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcResponse {
+    pub id: u64,
+    pub data: DaemonResponse,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,25 +292,55 @@ pub enum DaemonCommand {
     StopDaemon,
     OpenPath(PathTypes),
     SetLogLevel(LogLevel),
+    SetUsbTraceEnabled(bool),
     SetShowTrayIcon(bool),
     SetTTSEnabled(bool),
+    SetNotificationsEnabled(bool),
     SetAutoStartEnabled(bool),
     SetAllowNetworkAccess(bool),
     RecoverDefaults(PathTypes),
+
+    // Creates (or changes the channel of) a virtual output, removing it if the channel is None.
+    SetVirtualChannel(String, Option<ChannelName>),
+    SetVodMix(VodMix),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum GoXLRCommand {
     SetShutdownCommands(Vec<GoXLRCommand>),
     SetSamplerPreBufferDuration(u16),
+    SetSamplerMonitorDevice(Option<String>),
+    SetSamplerNormalizationTarget(f32),
+    SetSamplerMonitorRecording(bool),
+    SetSamplerMaxRecordingLength(u16),
+    SetSamplerQueueNext(bool),
+    SetSamplerRecordingTemplate(String),
 
     SetFader(FaderName, ChannelName),
     SetFaderMuteFunction(FaderName, MuteFunction),
 
+    // Also sets the output levels (Headphones, Line Out and Mic Monitor). While submixes are
+    // active the Mic Monitor is held at full volume, so its level is stored and applied later.
     SetVolume(ChannelName, u8),
+
+    // Fades a channel to the target volume over the duration (in milliseconds)
+    FadeChannelVolume(ChannelName, u8, u32),
+
+    // Volume presets can be bound to a button via a macro containing LoadVolumePreset.
+    SetVolumePreset(String, Option<VolumePreset>),
+    SaveVolumePreset(String),
+    LoadVolumePreset(String),
+
     SetMicrophoneType(MicrophoneType),
     SetMicrophoneGain(MicrophoneType, u16),
+
+    // Measures the mic level for a number of seconds while the user speaks, and suggests a gain
+    // (applying it if the flag is set), the result is in the status and a MicCalibrationComplete event.
+    CalibrateMicGain(u8, bool),
     SetRouter(InputDevice, OutputDevice, bool),
+    SetRoutingPreset(String, Option<RoutingPreset>),
+    SaveRoutingPreset(String),
+    LoadRoutingPreset(String),
 
     // Cough Button
     SetCoughMuteFunction(MuteFunction),
@@ -133,6 +382,8 @@ pub enum GoXLRCommand {
     SetAnimationWaterfall(WaterfallDirection),
 
     SetGlobalColour(String),
+    SetColourTheme(String, Option<String>),
+    SetGlobalBrightness(u8),
 
     SetFaderDisplayStyle(FaderName, FaderDisplayStyle),
     SetFaderColours(FaderName, String, String),
@@ -141,6 +392,7 @@ pub enum GoXLRCommand {
 
     SetButtonColours(Button, String, Option<String>),
     SetButtonOffStyle(Button, ButtonColourOffStyle),
+    SetButtonHoldStyle(Button, ButtonColourHoldStyle),
     SetButtonGroupColours(ButtonColourGroups, String, Option<String>),
     SetButtonGroupOffStyle(ButtonColourGroups, ButtonColourOffStyle),
 
@@ -151,8 +403,10 @@ pub enum GoXLRCommand {
 
     // Effect Related Settings..
     LoadEffectPreset(String),
+    LoadEffectPresetToBank(String, EffectBankPresets),
     RenameActivePreset(String),
     SaveActivePreset(),
+    SaveActivePresetAs(String),
 
     // Reverb
     SetReverbStyle(ReverbStyle),
@@ -211,41 +465,70 @@ pub enum GoXLRCommand {
     SetHardTuneWindow(u16),
     SetHardTuneSource(HardTuneSource),
 
+    // Encoders, behaves as if the dial had been turned to this position
+    SetEncoderValue(EncoderName, i8),
+
     // Sampler..
     ClearSampleProcessError(),
     SetSamplerFunction(SampleBank, SampleButtons, SamplePlaybackMode),
     SetSamplerOrder(SampleBank, SampleButtons, SamplePlayOrder),
     AddSample(SampleBank, SampleButtons, String),
+
+    // Copies a file into the samples directory (unless it's already there) and adds it.
+    ImportSample(SampleBank, SampleButtons, PathBuf),
+
+    // Searches the samples directory for any samples which have moved, this also happens
+    // automatically when a profile is loaded.
+    RelinkSamples(),
     SetSampleStartPercent(SampleBank, SampleButtons, usize, f32),
     SetSampleStopPercent(SampleBank, SampleButtons, usize, f32),
+    SetSamplePlaybackSpeed(SampleBank, SampleButtons, usize, f32),
     RemoveSampleByIndex(SampleBank, SampleButtons, usize),
     PlaySampleByIndex(SampleBank, SampleButtons, usize),
     PlayNextSample(SampleBank, SampleButtons),
     StopSamplePlayback(SampleBank, SampleButtons),
+    ClearSampleButton(SampleBank, SampleButtons),
 
     // Scribbles
     SetScribbleIcon(FaderName, Option<String>),
     SetScribbleText(FaderName, String),
     SetScribbleNumber(FaderName, String),
     SetScribbleInvert(FaderName, bool),
+    SetScribbleStyle(FaderName, ScribbleStyle),
 
     // Profile Handling..
     NewProfile(String),
     LoadProfile(String, bool),
+
+    // Loads a profile from a file outside the profile directory, optionally copying it in.
+    LoadProfileFromFile(PathBuf, bool),
     LoadProfileColours(String),
     SaveProfile(),
     SaveProfileAs(String),
+
+    // Snapshots the current state into a new profile, the active profile is unchanged.
+    ExportProfile(String),
     DeleteProfile(String),
 
     NewMicProfile(String),
     LoadMicProfile(String, bool),
+    LoadMicProfileFromFile(PathBuf, bool),
     SaveMicProfile(),
     SaveMicProfileAs(String),
+    ExportMicProfile(String),
     DeleteMicProfile(String),
 
     // General Settings
     SetMuteHoldDuration(u16),
+    SetButtonHoldDuration(Button, Option<u16>),
     SetVCMuteAlsoMuteCM(bool),
+    SetRestoreRuntimeState(bool),
+    SetProfileVolumeFade(u16),
+    SetDucking(Ducking),
+    SetPushToTalk(PushToTalk),
+    SetVoiceActivity(VoiceActivity),
+    SetFaderMeter(FaderMeter),
+    SetIdleLighting(IdleLighting),
 
     // These control the current GoXLR 'State'..
     SetActiveEffectPreset(EffectBankPresets),
@@ -255,6 +538,13 @@ pub enum GoXLRCommand {
     SetHardTuneEnabled(bool),
     SetFXEnabled(bool),
     SetFaderMuteState(FaderName, MuteState),
+
+    // Cuts every input from the Broadcast Mix (leaving the headphones alone), until restored.
+    MuteAllToStream(),
+    RestoreStreamAudio(),
+
+    // The push to talk trigger, the mic is open while this is true.
+    SetPushToTalkActive(bool),
     SetCoughMuteState(MuteState),
 
     // Submix Commands
@@ -265,4 +555,28 @@ pub enum GoXLRCommand {
 
     // Mix Monitoring
     SetMonitorMix(OutputDevice),
+
+    // Macros
+    RunMacro(String),
+
+    // Scheduled Commands
+    SetSchedule(String, Option<Schedule>),
+    SetChannelTemplate(ChannelName, Option<ChannelTemplate>),
+
+    // Uploads the firmware file at the path to the device, progress is reported in the status.
+    UpdateFirmware(PathBuf),
+
+    // Copies the current profile, mic profile and the daemon's settings for the device into a named
+    // backup (in the backups directory), and restores them from one. The GoXLR itself has no way
+    // to read back its on-board settings, everything it holds is sent by the daemon from these.
+    CreateBackup(String),
+    RestoreBackup(String),
+
+    // Reverts (or re-applies) the most recent changes made to the profile and mic profile.
+    Undo(),
+    Redo(),
+
+    // Holds the full mixer state in memory under a name, so it can quickly be returned to.
+    TakeSnapshot(String),
+    RestoreSnapshot(String),
 }
@@ -6,13 +6,11 @@ use std::path::PathBuf;
 use goxlr_ipc::client::Client;
 use goxlr_ipc::clients::ipc::ipc_client::IPCClient;
 use goxlr_ipc::clients::ipc::ipc_socket::Socket;
-use goxlr_ipc::{DaemonCommand, DaemonRequest, DaemonResponse};
+use goxlr_ipc::clients::ipc::socket_name;
+use goxlr_ipc::{DaemonCommand, DaemonRequest, IpcRequest, IpcResponse};
 use interprocess::local_socket::tokio::LocalSocketStream;
-use interprocess::local_socket::NameTypeSupport;
 use which::which;
 
-static SOCKET_PATH: &str = "/tmp/goxlr.socket";
-static NAMED_PIPE: &str = "@goxlr.socket";
 static DAEMON_NAME: &str = "goxlr-daemon";
 
 #[tokio::main]
@@ -27,11 +25,7 @@ async fn main() -> Result<()> {
 }
 
 async fn get_connection() -> std::io::Result<LocalSocketStream> {
-    LocalSocketStream::connect(match NameTypeSupport::query() {
-        NameTypeSupport::OnlyPaths | NameTypeSupport::Both => SOCKET_PATH,
-        NameTypeSupport::OnlyNamespaced => NAMED_PIPE,
-    })
-    .await
+    LocalSocketStream::connect(socket_name()).await
 }
 
 #[cfg(unix)]
@@ -103,8 +97,9 @@ async fn open_ui() -> Result<()> {
     }
 
     if let Some(connection) = usable_connection {
-        let socket: Socket<DaemonResponse, DaemonRequest> = Socket::new(connection);
+        let socket: Socket<IpcResponse, IpcRequest> = Socket::new(connection);
         let mut client = IPCClient::new(socket);
+        client.handshake().await?;
         client
             .send(DaemonRequest::Daemon(DaemonCommand::Activate))
             .await?;
@@ -37,6 +37,9 @@ pub struct ColourMap {
     // The Presented Style when object is 'Off'
     off_style: ColourOffStyle,
 
+    // The Presented Style while a button is held, this isn't used by the official app.
+    hold_style: Option<ColourHoldStyle>,
+
     // Whether a button is currently 'On'
     state: Option<ColourState>,
 
@@ -64,6 +67,7 @@ impl ColourMap {
             prefix,
             selected: None,
             off_style: ColourOffStyle::Dimmed,
+            hold_style: None,
             state: None,
             blink: None,
             velocity: None,
@@ -85,6 +89,12 @@ impl ColourMap {
             return Ok(true);
         }
 
+        attr_key = format!("{}holdStyle", &self.prefix);
+        if attribute.name == attr_key {
+            self.hold_style = Some(ColourHoldStyle::from_str(&attribute.value)?);
+            return Ok(true);
+        }
+
         attr_key = format!("{}selected", &self.prefix);
         if attribute.name == attr_key {
             self.selected = Some(u8::from_str(attribute.value.as_str())?);
@@ -149,6 +159,7 @@ impl ColourMap {
     // This would probably be easier to do with traits one level up, but for now..
     pub fn replace(&mut self, colour_map: &ColourMap) {
         self.off_style = colour_map.off_style;
+        self.hold_style = colour_map.hold_style;
         self.velocity = colour_map.velocity;
         self.colour_list = colour_map.colour_list.clone();
         self.colour_display = colour_map.colour_display;
@@ -167,6 +178,11 @@ impl ColourMap {
         let mut key = format!("{prefix}offStyle");
         attributes.insert(key, self.off_style.to_string());
 
+        if let Some(hold_style) = &self.hold_style {
+            key = format!("{prefix}holdStyle");
+            attributes.insert(key, hold_style.to_string());
+        }
+
         if let Some(selected) = self.selected {
             attributes.insert(format!("{prefix}selected"), format!("{selected}"));
         }
@@ -225,6 +241,9 @@ impl ColourMap {
     pub fn get_off_style(&self) -> &ColourOffStyle {
         &self.off_style
     }
+    pub fn get_hold_style(&self) -> Option<ColourHoldStyle> {
+        self.hold_style
+    }
     pub fn is_fader_gradient(&self) -> bool {
         if self.colour_display.is_none() {
             return false;
@@ -323,6 +342,10 @@ impl ColourMap {
         self.off_style = off_style;
         Ok(())
     }
+    pub fn set_hold_style(&mut self, hold_style: Option<ColourHoldStyle>) -> Result<()> {
+        self.hold_style = hold_style;
+        Ok(())
+    }
     pub fn fader_display(&self) -> &Option<ColourDisplay> {
         &self.colour_display
     }
@@ -347,6 +370,18 @@ pub enum ColourOffStyle {
     DimmedColour2,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, Display)]
+pub enum ColourHoldStyle {
+    #[strum(to_string = "COLOUR1")]
+    Colour1,
+
+    #[strum(to_string = "COLOUR2")]
+    Colour2,
+
+    #[strum(to_string = "FLASHING")]
+    Flashing,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, Display)]
 pub enum ColourDisplay {
     #[strum(to_string = "GRADIENT")]
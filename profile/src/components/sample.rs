@@ -138,7 +138,20 @@ impl SampleBase {
                         end = start;
                     }
 
-                    let track = Track::new(track.to_string(), start, end, gain.parse()?);
+                    let mut track = Track::new(track.to_string(), start, end, gain.parse()?);
+
+                    // Not part of the official profile, so only present if it's been changed..
+                    if let Some(speed) = map.get(&format!("track_{i}PlaybackSpeed")) {
+                        // NaN would pass straight through the clamp, so reject it here..
+                        let speed = speed.parse::<f32>()?;
+                        if !speed.is_finite() {
+                            bail!("Invalid Playback Speed: {}", speed);
+                        }
+                        track.playback_speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+                    }
+                    if let Some(hash) = map.get(&format!("track_{i}Hash")) {
+                        track.hash = Some(hash.clone());
+                    }
                     sample_stack.tracks.push(track);
                 }
             }
@@ -216,6 +229,15 @@ impl SampleBase {
                     format!("track_{i}EndPosition"),
                     format!("{}", value.tracks.get(i).unwrap().end_position),
                 );
+
+                let speed = value.tracks.get(i).unwrap().playback_speed;
+                if speed != 1.0 {
+                    sub_attributes.insert(format!("track_{i}PlaybackSpeed"), format!("{speed}"));
+                }
+
+                if let Some(hash) = &value.tracks.get(i).unwrap().hash {
+                    sub_attributes.insert(format!("track_{i}Hash"), hash.clone());
+                }
             }
 
             if let Some(output) = &value.playback_mode {
@@ -387,12 +409,20 @@ impl SampleStack {
     }
 }
 
+// The range of playback speeds supported for a track, 1.0 being the original speed.
+pub const MIN_SPEED: f32 = 0.25;
+pub const MAX_SPEED: f32 = 4.0;
+
 #[derive(Debug, Clone)]
 pub struct Track {
     pub track: String,
     pub start_position: f32,
     pub end_position: f32,
     pub normalized_gain: f64,
+    pub playback_speed: f32,
+
+    // A hash of the file's contents, used to find the file again if it's been moved.
+    pub hash: Option<String>,
 }
 
 impl Track {
@@ -407,6 +437,8 @@ impl Track {
             start_position,
             end_position,
             normalized_gain,
+            playback_speed: 1.0,
+            hash: None,
         }
     }
 
@@ -422,6 +454,12 @@ impl Track {
     pub fn normalized_gain(&self) -> f64 {
         self.normalized_gain
     }
+    pub fn playback_speed(&self) -> f32 {
+        self.playback_speed
+    }
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
 
     pub fn set_start_position(&mut self, start: f32) -> Result<()> {
         if !(0. ..=100.).contains(&start) {
@@ -448,6 +486,18 @@ impl Track {
         self.end_position = end;
         Ok(())
     }
+
+    pub fn set_playback_speed(&mut self, speed: f32) -> Result<()> {
+        if !(MIN_SPEED..=MAX_SPEED).contains(&speed) {
+            bail!(
+                "Playback Speed should be between {} and {}",
+                MIN_SPEED,
+                MAX_SPEED
+            );
+        }
+        self.playback_speed = speed;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Copy, Clone, Enum, EnumProperty)]
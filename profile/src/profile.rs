@@ -517,15 +517,18 @@ impl ProfileSettings {
     }
 
     pub fn load_preset<R: Read>(&mut self, read: R) -> Result<()> {
+        // Firstly, we need the current preset to overwrite.
+        let current = self.context().selected_effects();
+        self.load_preset_into(read, current)
+    }
+
+    pub fn load_preset_into<R: Read>(&mut self, read: R, current: Preset) -> Result<()> {
         let buf_reader = BufReader::new(read);
         let mut reader = Reader::from_reader(buf_reader);
 
         // So, in principle here, all we need to do is loop over the tags, check on the
         // tag name, and load it directly into the relevant effect. This should force a
         // replace of the current effect, and bam, done.
-
-        // Firstly, we need the current preset to overwrite.
-        let current = self.context().selected_effects();
         let mut buf = Vec::new();
         loop {
             match reader.read_event_into(&mut buf) {
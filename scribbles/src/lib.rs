@@ -9,11 +9,32 @@ use std::path::PathBuf;
 
 static FONT: &[u8] = include_bytes!("../fonts/Play-Bold.ttf");
 
+// The height of a single line of text, the scribble is 64 pixels high so fits 3 at most, but we
+// only allow 2 so there's still room for an icon.
+const LINE_HEIGHT: u32 = 19;
+const MAX_LINES: usize = 2;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum TextAlignment {
+    Left,
+    #[default]
+    Centre,
+    Right,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TextStyle {
+    // A TTF file to use instead of the built in font..
+    pub font: Option<PathBuf>,
+    pub alignment: TextAlignment,
+}
+
 pub fn get_scribble(
     path: Option<PathBuf>,
     bottom_text: Option<String>,
     top_right: Option<String>,
     invert: bool,
+    style: &TextStyle,
 ) -> [u8; 1024] {
     let mut processed_image = None;
     let mut bottom_image = None;
@@ -25,19 +46,25 @@ pub fn get_scribble(
         }
     }
 
+    let font = load_font(&style.font);
     if let Some(text) = bottom_text {
-        if let Ok(image) = create_text_image(&text) {
+        if let Ok(image) = create_text_image(&font, &text, style.alignment) {
             bottom_image = Some(image);
         }
     }
 
     if let Some(text) = top_right {
-        if let Ok(image) = create_text_image(&text) {
+        if let Ok(image) = create_text_image(&font, &text, TextAlignment::Left) {
             top_right_image = Some(image);
         }
     }
 
-    let image = create_final_image(processed_image, bottom_image, top_right_image);
+    let image = create_final_image(
+        processed_image,
+        bottom_image,
+        top_right_image,
+        style.alignment,
+    );
 
     if let Ok(result) = to_goxlr(image, invert) {
         result
@@ -67,41 +94,81 @@ fn load_grayscale_image(path: PathBuf) -> Result<DynamicImage> {
     Ok(img)
 }
 
-fn create_text_image(text: &str) -> Result<DynamicImage> {
-    let draw_font = Font::try_from_bytes(FONT).ok_or(std::fmt::Error)?;
+fn load_font(path: &Option<PathBuf>) -> Font<'static> {
+    if let Some(path) = path {
+        match std::fs::read(path) {
+            Ok(bytes) => match Font::try_from_vec(bytes) {
+                Some(font) => return font,
+                None => warn!("Unable to Parse Font {}", path.to_string_lossy()),
+            },
+            Err(error) => warn!("Unable to Load Font {}: {}", path.to_string_lossy(), error),
+        }
+    }
+
+    // The embedded font is known good, so this can't fail.
+    Font::try_from_bytes(FONT).unwrap()
+}
 
+fn create_text_image(
+    draw_font: &Font,
+    text: &str,
+    alignment: TextAlignment,
+) -> Result<DynamicImage> {
     let scale = Scale {
         x: 23_f32,
-        y: 19_f32,
+        y: LINE_HEIGHT as f32,
     };
 
+    // Text can be split over two lines with a line break..
+    let lines: Vec<&str> = text.lines().take(MAX_LINES).collect();
+    if lines.is_empty() {
+        bail!("No Text to Draw");
+    }
+
     // Calculate the draw width..
-    let (width, _height) = text_size(scale, &draw_font, text);
-    let draw_width = if width < 128 { width } else { 128 };
+    let widths: Vec<u32> = lines
+        .iter()
+        .map(|line| text_size(scale, draw_font, line).0.clamp(0, 128) as u32)
+        .collect();
+    let draw_width = widths.iter().copied().max().unwrap_or(0).max(1);
 
-    let mut image = DynamicImage::new_rgb8(draw_width as u32, 19);
+    let mut image = DynamicImage::new_rgb8(draw_width, LINE_HEIGHT * lines.len() as u32);
     image
         .clone()
         .pixels()
         .for_each(|f| image.put_pixel(f.0, f.1, Rgba::from([255, 255, 255, 255])));
 
-    draw_text_mut(
-        &mut image,
-        Rgba::from([0, 0, 0, 0]),
-        0,
-        0,
-        scale,
-        &draw_font,
-        text,
-    );
+    for (index, line) in lines.iter().enumerate() {
+        let x = aligned_position(draw_width, widths[index], alignment);
+        draw_text_mut(
+            &mut image,
+            Rgba::from([0, 0, 0, 0]),
+            x as i32,
+            (LINE_HEIGHT * index as u32) as i32,
+            scale,
+            draw_font,
+            line,
+        );
+    }
 
     Ok(image)
 }
 
+// Returns the x position of something 'width' wide, inside something 'container' wide.
+fn aligned_position(container: u32, width: u32, alignment: TextAlignment) -> u32 {
+    let space = container.saturating_sub(width);
+    match alignment {
+        TextAlignment::Left => 0,
+        TextAlignment::Centre => space / 2,
+        TextAlignment::Right => space,
+    }
+}
+
 fn create_final_image(
     mut icon: Option<DynamicImage>,
     text: Option<DynamicImage>,
     number: Option<DynamicImage>,
+    alignment: TextAlignment,
 ) -> GrayImage {
     // Ok, firstly, create an image and make it completely white..
     let mut image = DynamicImage::new_rgb8(128, 64);
@@ -113,7 +180,10 @@ fn create_final_image(
     // Ok, now we need to position and draw the specific components onto it..
     if let Some(ref mut icon) = icon {
         // We have an icon, we need to resize and position based on the existance of text..
-        let (w, h) = if text.is_some() { (80, 41) } else { (120, 60) };
+        let (w, h) = match &text {
+            Some(text) => (80, 60 - text.height()),
+            None => (120, 60),
+        };
 
         // Before we resize it, we wanna stretch it by about 20% to offset the differences in pixel sizes on the GoXLR..
         *icon = icon.resize_exact(
@@ -134,7 +204,7 @@ fn create_final_image(
     }
 
     if let Some(text) = text {
-        let position_x = (image.width() - text.width()) / 2;
+        let position_x = aligned_position(image.width(), text.width(), alignment);
         let position_y = if icon.is_some() {
             image.height() - text.height()
         } else {
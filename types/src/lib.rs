@@ -6,9 +6,10 @@ use enum_map::Enum;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use std::fmt::Formatter;
-use strum::{Display, EnumCount, EnumIter};
+use std::str::FromStr;
+use strum::{Display, EnumCount, EnumIter, IntoEnumIterator};
 
-#[derive(Default, Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq)]
+#[derive(Default, Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ChannelName {
@@ -284,6 +285,16 @@ pub enum FaderDisplayStyle {
     GradientMeter,
 }
 
+#[derive(Default, Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScribbleAlignment {
+    Left,
+    #[default]
+    Centre,
+    Right,
+}
+
 #[derive(Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -370,6 +381,17 @@ pub enum ButtonColourOffStyle {
     DimmedColour2,
 }
 
+// How a button is presented while it's physically held down
+#[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ButtonColourHoldStyle {
+    Unchanged,
+    Colour1,
+    Colour2,
+    Flashing,
+}
+
 // MuteChat
 #[derive(Debug, Copy, Clone, Display, EnumIter, EnumCount, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
@@ -763,3 +785,148 @@ pub enum WaterfallDirection {
     Up,
     Off,
 }
+
+// The groups of DCP (Device Control Protocol) commands a device's firmware can report supporting.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter)]
+pub enum DCPCategory {
+    Peaks,
+    Router,
+    Mixer,
+    NVM,
+}
+
+// Describes what a model of GoXLR physically has, the Mini lacks both the Sampler and Effects
+// sections, so commands targeting them can't be handled. It does still have the Sample channel,
+// which carries anything the PC sends to the 'Sample' output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DeviceCapabilities {
+    pub has_sampler: bool,
+    pub has_effects: bool,
+    pub fader_count: usize,
+    pub channels: Vec<ChannelName>,
+}
+
+impl DeviceCapabilities {
+    pub fn full() -> Self {
+        Self {
+            has_sampler: true,
+            has_effects: true,
+            fader_count: FaderName::COUNT,
+            channels: ChannelName::iter().collect(),
+        }
+    }
+
+    pub fn mini() -> Self {
+        Self {
+            has_sampler: false,
+            has_effects: false,
+            fader_count: FaderName::COUNT,
+            channels: ChannelName::iter().collect(),
+        }
+    }
+
+    pub fn has_channel(&self, channel: ChannelName) -> bool {
+        self.channels.contains(&channel)
+    }
+
+    // The monitor and output levels are only adjustable in software, they can't be put on a fader.
+    pub fn is_fader_channel(&self, channel: ChannelName) -> bool {
+        self.has_channel(channel)
+            && !matches!(
+                channel,
+                ChannelName::Headphones | ChannelName::MicMonitor | ChannelName::LineOut
+            )
+    }
+
+    pub fn fader_channels(&self) -> Vec<ChannelName> {
+        self.channels
+            .iter()
+            .copied()
+            .filter(|channel| self.is_fader_channel(*channel))
+            .collect()
+    }
+
+    pub fn has_fader(&self, fader: FaderName) -> bool {
+        (fader as usize) < self.fader_count
+    }
+}
+
+// Channel volumes are sent to the device as 0-255, this carries that alongside the percentage and
+// an approximate dB value (None when silent).
+//
+// The GoXLR's own volume curve isn't documented, so dB values use a square law taper (the gain is
+// (raw / 255)², or 40 * log10(raw / 255) in dB), which is the usual approximation for an audio
+// fader: 100% is 0dB, 50% is about -12dB, and 10% is about -40dB. Treat it as a guide rather than
+// a measured level.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VolumeLevel {
+    pub raw: u8,
+    pub percent: f32,
+    pub db: Option<f32>,
+}
+
+impl VolumeLevel {
+    pub fn from_raw(raw: u8) -> Self {
+        let ratio = raw as f32 / u8::MAX as f32;
+        Self {
+            raw,
+            percent: ratio * 100.,
+            db: if raw == 0 {
+                None
+            } else {
+                Some(40. * ratio.log10())
+            },
+        }
+    }
+
+    // Rounds down, so whole percentages map to the same values they always have (50% is 127).
+    pub fn from_percent(percent: f32) -> Self {
+        let raw = (percent.clamp(0., 100.) * u8::MAX as f32 / 100.).floor() as u8;
+        Self::from_raw(raw)
+    }
+
+    // The inverse of the curve used in from_raw, anything above 0dB is treated as full volume.
+    pub fn from_db(db: f32) -> Self {
+        let ratio = 10_f32.powf(db.min(0.) / 40.);
+        Self::from_raw((ratio * u8::MAX as f32).round() as u8)
+    }
+}
+
+impl std::fmt::Display for VolumeLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.db {
+            Some(db) => write!(f, "{:.0}% ({:.1}dB)", self.percent, db),
+            None => write!(f, "{:.0}% (-inf dB)", self.percent),
+        }
+    }
+}
+
+// Accepts a percentage ('75' or '75%') or a level in dB ('-12dB'), which must be 0 or lower. '-inf'
+// is accepted as silence, so the printed value of a muted channel can be passed back in.
+impl FromStr for VolumeLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        let lower = value.to_lowercase();
+        if let Some(db) = lower.strip_suffix("db") {
+            let db = f32::from_str(db.trim()).map_err(|_| format!("Invalid dB value: {}", s))?;
+            if db.is_nan() || db > 0. {
+                return Err(String::from("dB values must be 0 or lower"));
+            }
+            return Ok(Self::from_db(db));
+        }
+
+        let percent = value.strip_suffix('%').unwrap_or(value);
+        let percent = f32::from_str(percent.trim())
+            .map_err(|_| String::from("Value must be a percentage (0 - 100) or in dB"))?;
+
+        // This also rejects NaN, which would otherwise end up as 0
+        if !(0.0..=100.0).contains(&percent) {
+            return Err(String::from("Value must be between 0 and 100"));
+        }
+        Ok(Self::from_percent(percent))
+    }
+}
@@ -1,7 +1,20 @@
+use anyhow::{bail, Result};
+use byteorder::{ByteOrder, LittleEndian};
 use enum_map::Enum;
 use enumset::{EnumSet, EnumSetType};
+use goxlr_types::{EncoderName, FaderName};
 use strum::EnumIter;
 
+/*
+The status report returned by GetButtonStates, which the daemon polls to keep in sync with the
+hardware. It's laid out as:
+
+0..4   - A bitfield of the currently pressed buttons (bit positions are the Buttons values)
+4..8   - The Encoder positions (Pitch, Gender, Reverb, Echo), these are signed
+8..12  - The Fader positions (A, B, C, D), 0 - 255
+ */
+pub const STATUS_REPORT_LENGTH: usize = 12;
+
 #[derive(Debug, Copy, Clone)]
 pub enum ButtonStates {
     Colour1 = 0x01,
@@ -18,6 +31,68 @@ pub struct CurrentButtonStates {
     pub encoders: [i8; 4],
 }
 
+impl CurrentButtonStates {
+    pub fn from_report(report: &[u8]) -> Result<Self> {
+        if report.len() < STATUS_REPORT_LENGTH {
+            bail!(
+                "Status Report too short, expected {} bytes, received {}",
+                STATUS_REPORT_LENGTH,
+                report.len()
+            );
+        }
+
+        let button_states = LittleEndian::read_u32(&report[0..4]);
+        let mut pressed = EnumSet::empty();
+        for button in EnumSet::<Buttons>::all() {
+            if button_states & (1 << button as u8) != 0 {
+                pressed.insert(button);
+            }
+        }
+
+        // The encoders can technically be negative, so cast straight to i8
+        let mut encoders = [0; 4];
+        for (encoder, value) in encoders.iter_mut().zip(&report[4..8]) {
+            *encoder = *value as i8;
+        }
+
+        let mut volumes = [0; 4];
+        volumes.copy_from_slice(&report[8..12]);
+
+        Ok(Self {
+            pressed,
+            volumes,
+            encoders,
+        })
+    }
+
+    pub fn to_report(&self) -> Vec<u8> {
+        let mut button_states = 0;
+        for button in self.pressed {
+            button_states |= 1 << button as u8;
+        }
+
+        let mut report = vec![0; STATUS_REPORT_LENGTH];
+        LittleEndian::write_u32(&mut report[0..4], button_states);
+        for (index, value) in self.encoders.iter().enumerate() {
+            report[4 + index] = *value as u8;
+        }
+        report[8..12].copy_from_slice(&self.volumes);
+        report
+    }
+
+    pub fn is_pressed(&self, button: Buttons) -> bool {
+        self.pressed.contains(button)
+    }
+
+    pub fn fader_position(&self, fader: FaderName) -> u8 {
+        self.volumes[fader as usize]
+    }
+
+    pub fn encoder_position(&self, encoder: EncoderName) -> i8 {
+        self.encoders[encoder as usize]
+    }
+}
+
 #[derive(EnumSetType, Enum, EnumIter, Debug)]
 pub enum Buttons {
     // These are all the buttons from the GoXLR Mini.
@@ -1,18 +1,10 @@
-#[derive(Copy, Clone, Debug)]
-pub enum DCPCategory {
-    Peaks,
-    Router,
-    Mixer,
-    NVM,
-}
+pub use goxlr_types::DCPCategory;
 
-impl DCPCategory {
-    pub fn id(&self) -> u16 {
-        match self {
-            DCPCategory::Peaks => 1,
-            DCPCategory::Router => 2,
-            DCPCategory::Mixer => 3,
-            DCPCategory::NVM => 4,
-        }
+pub(crate) fn category_id(category: DCPCategory) -> u16 {
+    match category {
+        DCPCategory::Peaks => 1,
+        DCPCategory::Router => 2,
+        DCPCategory::Mixer => 3,
+        DCPCategory::NVM => 4,
     }
 }
@@ -0,0 +1,36 @@
+/*
+Requests are sent to the GoXLR one at a time, each carrying an index which the device echoes back
+in its response. Normally the next packet read is the response to the request we just sent, but
+occasionally it isn't:
+
+- A response to an earlier request which timed out arrives late (Stale)
+- The device sends a packet which nobody asked for (Unsolicited), these appear when something
+  changes on the device
+
+Neither of these should be treated as the response to the current request, stale responses are
+simply discarded, and unsolicited packets are passed to the event system so the device's state is
+polled.
+ */
+
+// How far behind the current index a response can be and still be considered stale.
+const STALE_WINDOW: u16 = 16;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResponseKind {
+    Matched,
+    Stale,
+    Unsolicited,
+}
+
+pub fn classify_response(expected: u16, received: u16) -> ResponseKind {
+    if expected == received {
+        return ResponseKind::Matched;
+    }
+
+    // The index wraps around, so measure the distance backwards from what we expected..
+    if expected.wrapping_sub(received) <= STALE_WINDOW {
+        ResponseKind::Stale
+    } else {
+        ResponseKind::Unsolicited
+    }
+}
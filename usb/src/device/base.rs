@@ -1,28 +1,39 @@
 use crate::animation::{AnimationMode, WaterFallDir};
-use crate::buttonstate::{ButtonStates, Buttons, CurrentButtonStates};
+use crate::buttonstate::{ButtonStates, CurrentButtonStates};
 use crate::channelstate::ChannelState;
 use crate::commands::Command::ExecuteFirmwareUpdateAction;
 use crate::commands::SystemInfoCommand::SupportsDCPCategory;
 use crate::commands::{
     Command, FirmwareAction, FirmwareCommand, HardwareInfoCommand, SystemInfoCommand,
 };
-use crate::dcp::DCPCategory;
+use crate::dcp::{category_id, DCPCategory};
 use crate::routing::InputDevice;
+use crate::transfer_log;
 use anyhow::{bail, Result};
+use async_trait::async_trait;
 use byteorder::{ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
-use enumset::EnumSet;
 use goxlr_types::{
     ChannelName, EffectKey, EncoderName, FaderName, FirmwareVersions, MicrophoneParamKey,
     MicrophoneType, Mix, SubMixChannelName, VersionNumber,
 };
 use log::debug;
 use std::io::{Cursor, Write};
+use std::time::Instant;
+use strum::IntoEnumIterator;
 use tokio::sync::mpsc::Sender;
 
+// A request (16 byte header, plus payload) can be at most 1040 bytes, anything larger has to be
+// split across multiple requests.
+pub const MAX_PAYLOAD_SIZE: usize = 1024;
+
+// Firmware packets have a 12 byte header of their own, this matches the Official App.
+pub const FIRMWARE_CHUNK_SIZE: usize = MAX_PAYLOAD_SIZE - 12;
+
 // This is a basic SuperTrait which defines all the 'Parts' of the GoXLR for use.
 pub trait FullGoXLRDevice: AttachGoXLR + GoXLRCommands + Sync + Send {}
 
-pub trait AttachGoXLR {
+#[async_trait]
+pub trait AttachGoXLR: Send {
     fn from_device(
         device: GoXLRDevice,
         disconnect_sender: Sender<String>,
@@ -32,40 +43,90 @@ pub trait AttachGoXLR {
         Self: Sized;
 
     fn set_unique_identifier(&mut self, identifier: String);
-    fn is_connected(&mut self) -> bool;
+    async fn is_connected(&mut self) -> bool;
+
+    // Pauses polling, the device is still in use but shouldn't be sending events (for example
+    // during a firmware update).
     fn stop_polling(&mut self);
+
+    // Stops polling for good, the handle won't be used again once this has been called.
+    fn shutdown(&mut self);
 }
 
-pub trait ExecutableGoXLR {
-    fn request_data(&mut self, command: Command, body: &[u8]) -> Result<Vec<u8>> {
-        self.perform_request(command, body, false)
+// Requests are asynchronous, backends should avoid blocking the runtime while waiting on the
+// device, so that a slow (or dead) GoXLR doesn't stall anything else in the daemon.
+#[async_trait]
+pub trait ExecutableGoXLR: Send {
+    async fn request_data(&mut self, command: Command, body: &[u8]) -> Result<Vec<u8>> {
+        if body.len() > MAX_PAYLOAD_SIZE {
+            bail!(
+                "Payload for {:?} is {} bytes, the maximum for a single request is {}",
+                command,
+                body.len(),
+                MAX_PAYLOAD_SIZE
+            );
+        }
+
+        if !transfer_log::is_enabled() {
+            return self.perform_request(command, body, false).await;
+        }
+
+        let start = Instant::now();
+        let result = self.perform_request(command, body, false).await;
+        transfer_log::log_transfer(command, body, &result, start.elapsed());
+        result
     }
 
-    fn perform_request(&mut self, command: Command, body: &[u8], retry: bool) -> Result<Vec<u8>>;
+    async fn perform_request(
+        &mut self,
+        command: Command,
+        body: &[u8],
+        retry: bool,
+    ) -> Result<Vec<u8>>;
     fn get_descriptor(&self) -> Result<UsbData>;
 }
 
 // These are commands that can be executed, but perform_request must be implemented..
+#[async_trait]
 pub trait GoXLRCommands: ExecutableGoXLR {
-    fn supports_dcp_category(&mut self, category: DCPCategory) -> Result<bool> {
+    async fn supports_dcp_category(&mut self, category: DCPCategory) -> Result<bool> {
         let mut out = [0; 2];
-        LittleEndian::write_u16(&mut out, category.id());
-        let result = self.request_data(Command::SystemInfo(SupportsDCPCategory), &out)?;
+        LittleEndian::write_u16(&mut out, category_id(category));
+        let result = self
+            .request_data(Command::SystemInfo(SupportsDCPCategory), &out)
+            .await?;
         Ok(LittleEndian::read_u16(&result) == 1)
     }
 
-    fn get_system_info(&mut self) -> Result<()> {
-        let _result =
-            self.request_data(Command::SystemInfo(SystemInfoCommand::FirmwareVersion), &[])?;
+    // Probes every known category, older firmware may reject the request for categories it
+    // doesn't know about, so failures are treated as unsupported.
+    async fn get_supported_dcp_categories(&mut self) -> Vec<DCPCategory> {
+        let mut supported = vec![];
+        for category in DCPCategory::iter() {
+            match self.supports_dcp_category(category).await {
+                Ok(true) => supported.push(category),
+                Ok(false) => {}
+                Err(error) => debug!("Unable to probe DCP Category {:?}: {}", category, error),
+            }
+        }
+        supported
+    }
+
+    async fn get_system_info(&mut self) -> Result<()> {
+        let _result = self
+            .request_data(Command::SystemInfo(SystemInfoCommand::FirmwareVersion), &[])
+            .await?;
         // TODO: parse that?
         Ok(())
     }
 
-    fn get_firmware_version(&mut self) -> Result<FirmwareVersions> {
-        let result = self.request_data(
-            Command::GetHardwareInfo(HardwareInfoCommand::FirmwareVersion),
-            &[],
-        )?;
+    async fn get_firmware_version(&mut self) -> Result<FirmwareVersions> {
+        let result = self
+            .request_data(
+                Command::GetHardwareInfo(HardwareInfoCommand::FirmwareVersion),
+                &[],
+            )
+            .await?;
         debug!("{:x?}", result);
         let mut cursor = Cursor::new(result);
         let firmware_packed = cursor.read_u32::<LittleEndian>()?;
@@ -96,11 +157,13 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         })
     }
 
-    fn get_serial_number(&mut self) -> Result<(String, String)> {
-        let result = self.request_data(
-            Command::GetHardwareInfo(HardwareInfoCommand::SerialNumber),
-            &[],
-        )?;
+    async fn get_serial_number(&mut self) -> Result<(String, String)> {
+        let result = self
+            .request_data(
+                Command::GetHardwareInfo(HardwareInfoCommand::SerialNumber),
+                &[],
+            )
+            .await?;
 
         let serial_slice = &result[..24];
         let serial_len = serial_slice
@@ -119,48 +182,59 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         Ok((serial_number, manufacture_date))
     }
 
-    fn set_fader(&mut self, fader: FaderName, channel: ChannelName) -> Result<()> {
+    async fn set_fader(&mut self, fader: FaderName, channel: ChannelName) -> Result<()> {
         // Channel ID, unknown, unknown, unknown
-        self.request_data(Command::SetFader(fader), &[channel as u8, 0x00, 0x00, 0x00])?;
+        self.request_data(Command::SetFader(fader), &[channel as u8, 0x00, 0x00, 0x00])
+            .await?;
         Ok(())
     }
 
-    fn set_volume(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
-        self.request_data(Command::SetChannelVolume(channel), &[volume])?;
+    async fn set_volume(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
+        self.request_data(Command::SetChannelVolume(channel), &[volume])
+            .await?;
         Ok(())
     }
 
-    fn set_encoder_value(&mut self, encoder: EncoderName, value: i8) -> Result<()> {
-        self.request_data(Command::SetEncoderValue(encoder), &[value as u8])?;
+    async fn set_encoder_value(&mut self, encoder: EncoderName, value: i8) -> Result<()> {
+        self.request_data(Command::SetEncoderValue(encoder), &[value as u8])
+            .await?;
         Ok(())
     }
 
-    fn set_encoder_mode(&mut self, encoder: EncoderName, mode: u8, resolution: u8) -> Result<()> {
-        self.request_data(Command::SetEncoderMode(encoder), &[mode, resolution])?;
+    async fn set_encoder_mode(
+        &mut self,
+        encoder: EncoderName,
+        mode: u8,
+        resolution: u8,
+    ) -> Result<()> {
+        self.request_data(Command::SetEncoderMode(encoder), &[mode, resolution])
+            .await?;
         Ok(())
     }
 
-    fn set_channel_state(&mut self, channel: ChannelName, state: ChannelState) -> Result<()> {
-        self.request_data(Command::SetChannelState(channel), &[state.id()])?;
+    async fn set_channel_state(&mut self, channel: ChannelName, state: ChannelState) -> Result<()> {
+        self.request_data(Command::SetChannelState(channel), &[state.id()])
+            .await?;
         Ok(())
     }
 
-    fn set_button_states(&mut self, data: [ButtonStates; 24]) -> Result<()> {
-        self.request_data(Command::SetButtonStates(), &data.map(|state| state as u8))?;
+    async fn set_button_states(&mut self, data: [ButtonStates; 24]) -> Result<()> {
+        self.request_data(Command::SetButtonStates(), &data.map(|state| state as u8))
+            .await?;
         Ok(())
     }
 
-    fn set_button_colours(&mut self, data: [u8; 328]) -> Result<()> {
-        self.request_data(Command::SetColourMap(), &data)?;
+    async fn set_button_colours(&mut self, data: [u8; 328]) -> Result<()> {
+        self.request_data(Command::SetColourMap(), &data).await?;
         Ok(())
     }
 
-    fn set_button_colours_1_3_40(&mut self, data: [u8; 520]) -> Result<()> {
-        self.request_data(Command::SetColourMap(), &data)?;
+    async fn set_button_colours_1_3_40(&mut self, data: [u8; 520]) -> Result<()> {
+        self.request_data(Command::SetColourMap(), &data).await?;
         Ok(())
     }
 
-    fn set_fader_display_mode(
+    async fn set_fader_display_mode(
         &mut self,
         fader: FaderName,
         gradient: bool,
@@ -174,39 +248,48 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         self.request_data(
             Command::SetFaderDisplayMode(fader),
             &[gradient_byte, meter_byte],
-        )?;
+        )
+        .await?;
         Ok(())
     }
 
-    fn set_fader_scribble(&mut self, fader: FaderName, data: [u8; 1024]) -> Result<()> {
+    async fn set_fader_scribble(&mut self, fader: FaderName, data: [u8; 1024]) -> Result<()> {
         // Dump it, see what happens..
-        self.request_data(Command::SetScribble(fader), &data)?;
+        self.request_data(Command::SetScribble(fader), &data)
+            .await?;
         Ok(())
     }
 
-    fn set_routing(&mut self, input_device: InputDevice, data: [u8; 22]) -> Result<()> {
-        self.request_data(Command::SetRouting(input_device), &data)?;
+    async fn set_routing(&mut self, input_device: InputDevice, data: [u8; 22]) -> Result<()> {
+        self.request_data(Command::SetRouting(input_device), &data)
+            .await?;
         Ok(())
     }
 
     // Submix Stuff
-    fn set_sub_volume(&mut self, channel: SubMixChannelName, volume: u8) -> Result<()> {
-        self.request_data(Command::SetSubChannelVolume(channel), &[volume])?;
+    async fn set_sub_volume(&mut self, channel: SubMixChannelName, volume: u8) -> Result<()> {
+        self.request_data(Command::SetSubChannelVolume(channel), &[volume])
+            .await?;
         Ok(())
     }
 
     // TODO: Potentially for later, abstract out the 'data' section into a couple of Vec<>s
-    fn set_channel_mixes(&mut self, data: [u8; 8]) -> Result<()> {
-        self.request_data(Command::SetChannelMixes, &data)?;
+    async fn set_channel_mixes(&mut self, data: [u8; 8]) -> Result<()> {
+        self.request_data(Command::SetChannelMixes, &data).await?;
         Ok(())
     }
 
-    fn set_monitored_mix(&mut self, mix: Mix) -> Result<()> {
-        self.request_data(Command::SetMonitoredMix, &[mix as u8])?;
+    async fn set_monitored_mix(&mut self, mix: Mix) -> Result<()> {
+        self.request_data(Command::SetMonitoredMix, &[mix as u8])
+            .await?;
         Ok(())
     }
 
-    fn set_microphone_gain(&mut self, microphone_type: MicrophoneType, gain: u16) -> Result<()> {
+    async fn set_microphone_gain(
+        &mut self,
+        microphone_type: MicrophoneType,
+        gain: u16,
+    ) -> Result<()> {
         let mut gain_value = [0; 4];
         LittleEndian::write_u16(&mut gain_value[2..], gain);
         self.set_mic_param(&[
@@ -218,71 +301,48 @@ pub trait GoXLRCommands: ExecutableGoXLR {
                 },
             ),
             (microphone_type.get_gain_param(), gain_value),
-        ])?;
+        ])
+        .await?;
         Ok(())
     }
 
-    fn get_microphone_level(&mut self) -> Result<u16> {
-        let result = self.request_data(Command::GetMicrophoneLevel, &[])?;
+    async fn get_microphone_level(&mut self) -> Result<u16> {
+        let result = self.request_data(Command::GetMicrophoneLevel, &[]).await?;
         Ok(LittleEndian::read_u16(&result))
     }
 
-    fn set_effect_values(&mut self, effects: &[(EffectKey, i32)]) -> Result<()> {
+    async fn set_effect_values(&mut self, effects: &[(EffectKey, i32)]) -> Result<()> {
         let mut data = Vec::with_capacity(effects.len() * 8);
         let mut cursor = Cursor::new(&mut data);
         for (key, value) in effects {
             cursor.write_u32::<LittleEndian>(*key as u32)?;
             cursor.write_i32::<LittleEndian>(*value)?;
         }
-        self.request_data(Command::SetEffectParameters, &data)?;
+        self.request_data(Command::SetEffectParameters, &data)
+            .await?;
 
         Ok(())
     }
 
-    fn set_mic_param(&mut self, params: &[(MicrophoneParamKey, [u8; 4])]) -> Result<()> {
+    async fn set_mic_param(&mut self, params: &[(MicrophoneParamKey, [u8; 4])]) -> Result<()> {
         let mut data = Vec::with_capacity(params.len() * 8);
         let mut cursor = Cursor::new(&mut data);
         for (key, value) in params {
             cursor.write_u32::<LittleEndian>(*key as u32)?;
             cursor.write_all(value)?;
         }
-        self.request_data(Command::SetMicrophoneParameters, &data)?;
+        self.request_data(Command::SetMicrophoneParameters, &data)
+            .await?;
 
         Ok(())
     }
 
-    fn get_button_states(&mut self) -> Result<CurrentButtonStates> {
-        let result = self.request_data(Command::GetButtonStates, &[])?;
-        let mut pressed = EnumSet::empty();
-        let mut mixers = [0; 4];
-        let mut encoders = [0; 4];
-        let button_states = LittleEndian::read_u32(&result[0..4]);
-
-        mixers[0] = result[8];
-        mixers[1] = result[9];
-        mixers[2] = result[10];
-        mixers[3] = result[11];
-
-        // These can technically be negative, cast straight to i8
-        encoders[0] = result[4] as i8; // Pitch
-        encoders[1] = result[5] as i8; // Gender
-        encoders[2] = result[6] as i8; // Reverb
-        encoders[3] = result[7] as i8; // Echo
-
-        for button in EnumSet::<Buttons>::all() {
-            if button_states & (1 << button as u8) != 0 {
-                pressed.insert(button);
-            }
-        }
-
-        Ok(CurrentButtonStates {
-            pressed,
-            volumes: mixers,
-            encoders,
-        })
+    async fn get_button_states(&mut self) -> Result<CurrentButtonStates> {
+        let result = self.request_data(Command::GetButtonStates, &[]).await?;
+        CurrentButtonStates::from_report(&result)
     }
 
-    fn set_animation_mode(
+    async fn set_animation_mode(
         &mut self,
         enabled: bool,
         mode: AnimationMode,
@@ -302,16 +362,18 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         command[3] = modifier2;
         command[4] = waterfall as u8;
 
-        let _ = self.request_data(Command::SetAnimationMode, &command);
+        let _ = self.request_data(Command::SetAnimationMode, &command).await;
         Ok(())
     }
 
     // DO NOT EXECUTE ANY OF THESE, SERIOUSLY!
-    fn begin_firmware_upload(&mut self) -> Result<()> {
-        let result = self.request_data(
-            Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::START),
-            &[],
-        )?;
+    async fn begin_firmware_upload(&mut self) -> Result<()> {
+        let result = self
+            .request_data(
+                Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::START),
+                &[],
+            )
+            .await?;
         let code = LittleEndian::read_u32(&result[0..4]);
         if code != 0 {
             bail!("Invalid Response Received!");
@@ -319,31 +381,34 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         Ok(())
     }
 
-    fn begin_erase_nvr(&mut self) -> Result<()> {
+    async fn begin_erase_nvr(&mut self) -> Result<()> {
         let mut header = [0; 8];
         LittleEndian::write_u32(&mut header[0..4], 7);
         LittleEndian::write_u32(&mut header[4..8], 0);
 
-        self.request_data(ExecuteFirmwareUpdateAction(FirmwareAction::ERASE), &header)?;
+        self.request_data(ExecuteFirmwareUpdateAction(FirmwareAction::ERASE), &header)
+            .await?;
         Ok(())
     }
 
-    fn poll_erase_nvr(&mut self) -> Result<u8> {
+    async fn poll_erase_nvr(&mut self) -> Result<u8> {
         let mut header = [0; 8];
         LittleEndian::write_u32(&mut header[0..4], 7);
         LittleEndian::write_u32(&mut header[4..8], 0);
 
-        let result = self.request_data(
-            Command::ExecuteFirmwareUpdateAction(FirmwareAction::POLL),
-            &header,
-        )?;
+        let result = self
+            .request_data(
+                Command::ExecuteFirmwareUpdateAction(FirmwareAction::POLL),
+                &header,
+            )
+            .await?;
         if result.len() != 1 {
             bail!("Unexpected Result from NVRam Firmware Erase!");
         }
         Ok(result[0])
     }
 
-    fn send_firmware_packet(&mut self, bytes_sent: u64, data: &[u8]) -> Result<()> {
+    async fn send_firmware_packet(&mut self, bytes_sent: u64, data: &[u8]) -> Result<()> {
         let mut header = [0; 12];
         LittleEndian::write_u32(&mut header[0..4], 7);
         LittleEndian::write_u64(&mut header[4..], bytes_sent);
@@ -355,11 +420,29 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         self.request_data(
             Command::ExecuteFirmwareUpdateAction(FirmwareAction::SEND),
             &packet,
-        )?;
+        )
+        .await?;
         Ok(())
     }
 
-    fn validate_firmware_packet(
+    // Sends a block of firmware (starting at 'offset') split into packets, calling progress with
+    // the total number of bytes sent after each one. Returns the new offset.
+    async fn send_firmware_data(
+        &mut self,
+        offset: u64,
+        data: &[u8],
+        progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<u64> {
+        let mut sent = offset;
+        for chunk in data.chunks(FIRMWARE_CHUNK_SIZE) {
+            self.send_firmware_packet(sent, chunk).await?;
+            sent += chunk.len() as u64;
+            progress(sent);
+        }
+        Ok(sent)
+    }
+
+    async fn validate_firmware_packet(
         &mut self,
         verified: u32,
         hash: u32,
@@ -371,10 +454,12 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         LittleEndian::write_u32(&mut packet[8..12], hash);
         LittleEndian::write_u32(&mut packet[12..16], remaining);
 
-        let result = self.request_data(
-            Command::ExecuteFirmwareUpdateAction(FirmwareAction::VALIDATE),
-            &packet,
-        )?;
+        let result = self
+            .request_data(
+                Command::ExecuteFirmwareUpdateAction(FirmwareAction::VALIDATE),
+                &packet,
+            )
+            .await?;
 
         // Grab the Hash and Count from the result..
         let hash = LittleEndian::read_u32(&result[0..4]);
@@ -383,11 +468,13 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         Ok((hash, count))
     }
 
-    fn verify_firmware_status(&mut self) -> Result<()> {
-        let result = self.request_data(
-            Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::VERIFY),
-            &[],
-        )?;
+    async fn verify_firmware_status(&mut self) -> Result<()> {
+        let result = self
+            .request_data(
+                Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::VERIFY),
+                &[],
+            )
+            .await?;
 
         let output = LittleEndian::read_u32(&result);
         if output != 0 {
@@ -396,11 +483,13 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         Ok(())
     }
 
-    fn poll_verify_firmware_status(&mut self) -> Result<(bool, u32, u32)> {
-        let result = self.request_data(
-            Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::POLL),
-            &[],
-        )?;
+    async fn poll_verify_firmware_status(&mut self) -> Result<(bool, u32, u32)> {
+        let result = self
+            .request_data(
+                Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::POLL),
+                &[],
+            )
+            .await?;
 
         let mut cursor = Cursor::new(result);
         let op = cursor.read_u32::<LittleEndian>()?;
@@ -455,11 +544,13 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         );
     }
 
-    fn finalise_firmware_upload(&mut self) -> Result<()> {
-        let result = self.request_data(
-            Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::FINALISE),
-            &[],
-        )?;
+    async fn finalise_firmware_upload(&mut self) -> Result<()> {
+        let result = self
+            .request_data(
+                Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::FINALISE),
+                &[],
+            )
+            .await?;
 
         let output = LittleEndian::read_u32(&result);
         if output != 0 {
@@ -468,11 +559,13 @@ pub trait GoXLRCommands: ExecutableGoXLR {
         Ok(())
     }
 
-    fn poll_finalise_firmware_upload(&mut self) -> Result<(bool, u32, u32)> {
-        let result = self.request_data(
-            Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::POLL),
-            &[],
-        )?;
+    async fn poll_finalise_firmware_upload(&mut self) -> Result<(bool, u32, u32)> {
+        let result = self
+            .request_data(
+                Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::POLL),
+                &[],
+            )
+            .await?;
 
         let mut cursor = Cursor::new(result);
         let op = cursor.read_u32::<LittleEndian>()?;
@@ -518,20 +611,24 @@ pub trait GoXLRCommands: ExecutableGoXLR {
          */
     }
 
-    fn abort_firmware_update(&mut self) -> Result<u32> {
-        let result = self.request_data(
-            Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::ABORT),
-            &[],
-        )?;
+    async fn abort_firmware_update(&mut self) -> Result<u32> {
+        let result = self
+            .request_data(
+                Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::ABORT),
+                &[],
+            )
+            .await?;
         let value = LittleEndian::read_u32(&result);
         Ok(value)
     }
 
-    fn reboot_after_firmware_upload(&mut self) -> Result<()> {
-        let result = self.request_data(
-            Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::REBOOT),
-            &[],
-        )?;
+    async fn reboot_after_firmware_upload(&mut self) -> Result<()> {
+        let result = self
+            .request_data(
+                Command::ExecuteFirmwareUpdateCommand(FirmwareCommand::REBOOT),
+                &[],
+            )
+            .await?;
 
         let output = LittleEndian::read_u32(&result);
         if output != 0 {
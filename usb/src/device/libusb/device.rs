@@ -1,9 +1,13 @@
 use crate::commands::Command;
+use crate::demux::{classify_response, ResponseKind};
 use crate::device::base::{
     AttachGoXLR, ExecutableGoXLR, FullGoXLRDevice, GoXLRCommands, GoXLRDevice, UsbData,
 };
+use crate::error::ProtocolError;
+use crate::timing;
 use crate::{PID_GOXLR_FULL, PID_GOXLR_MINI, VID_GOXLR};
-use anyhow::{anyhow, bail, Error, Result};
+use anyhow::{anyhow, bail, Context, Error, Result};
+use async_trait::async_trait;
 use byteorder::{ByteOrder, LittleEndian};
 use log::{debug, error, info, warn};
 use rusb::Error::Pipe;
@@ -18,8 +22,12 @@ use std::time::Duration;
 use tokio::sync::mpsc::Sender;
 use tokio::task;
 
+// The most stale responses we'll discard when resyncing, the device should never have more than
+// a couple waiting.
+const MAX_DRAIN_READS: usize = 8;
+
 pub struct GoXLRUSB {
-    handle: DeviceHandle<GlobalContext>,
+    handle: Arc<DeviceHandle<GlobalContext>>,
     device: Device<GlobalContext>,
     descriptor: DeviceDescriptor,
 
@@ -54,7 +62,7 @@ impl GoXLRUSB {
         bail!("Specified Device not Found!")
     }
 
-    fn trigger_disconnect(&mut self) -> Result<()> {
+    async fn trigger_disconnect(&mut self) -> Result<()> {
         // If this function has already been called further up the stack, don't run it.
         if self.disconnecting {
             return Ok(());
@@ -64,7 +72,7 @@ impl GoXLRUSB {
         self.disconnecting = true;
 
         // Perform a connection Check, and reset if needed..
-        if self.is_connected() {
+        if self.is_connected().await {
             // We're still connected, reset the disconnecting flag
             self.disconnecting = false;
             return Ok(());
@@ -78,65 +86,179 @@ impl GoXLRUSB {
         bail!("Unable to Disconnect, Identifier not Found!");
     }
 
-    pub(crate) fn write_class_control(
-        &mut self,
+    // libusb transfers are blocking, so they're handed off to tokio's blocking thread pool, which
+    // allows the runtime (and things like interrupt polling) to continue while we wait.
+    async fn write_control(
+        &self,
         request: u8,
         value: u16,
         index: u16,
-        data: &[u8],
+        data: Vec<u8>,
     ) -> Result<(), rusb::Error> {
-        self.handle.write_control(
-            rusb::request_type(Direction::Out, RequestType::Class, Recipient::Interface),
-            request,
-            value,
-            index,
-            data,
-            self.timeout,
-        )?;
-
-        Ok(())
+        let handle = self.handle.clone();
+        let timeout = self.timeout;
+        task::spawn_blocking(move || write_control(&handle, timeout, request, value, index, &data))
+            .await
+            .unwrap_or(Err(rusb::Error::Other))
     }
 
-    pub(crate) fn write_control(
-        &mut self,
+    async fn read_control(
+        &self,
         request: u8,
         value: u16,
         index: u16,
-        data: &[u8],
-    ) -> Result<(), rusb::Error> {
-        self.handle.write_control(
-            rusb::request_type(Direction::Out, RequestType::Vendor, Recipient::Interface),
-            request,
-            value,
-            index,
-            data,
-            self.timeout,
-        )?;
+        length: usize,
+    ) -> Result<Vec<u8>, rusb::Error> {
+        let handle = self.handle.clone();
+        let timeout = self.timeout;
+        task::spawn_blocking(move || read_control(&handle, timeout, request, value, index, length))
+            .await
+            .unwrap_or(Err(rusb::Error::Other))
+    }
+
+    // Unsolicited packets indicate something has changed, so have the daemon poll the device.
+    fn notify_unsolicited(&self) {
+        if let Some(identifier) = &self.identifier {
+            let _ = self.event_sender.try_send(identifier.clone());
+        }
+    }
 
-        Ok(())
+    // Reads (and discards) any responses still waiting on the device, so that the next request
+    // doesn't pick up a response meant for something else.
+    async fn drain_responses(&self) {
+        for _ in 0..MAX_DRAIN_READS {
+            match self.read_control(3, 0, 0, 1040).await {
+                Ok(response) => debug!("Discarded pending response ({} bytes)", response.len()),
+                Err(_) => break,
+            }
+        }
     }
+}
 
-    pub(crate) fn read_control(
-        &mut self,
-        request: u8,
-        value: u16,
-        index: u16,
-        length: usize,
-    ) -> Result<Vec<u8>, rusb::Error> {
-        let mut buf = vec![0; length];
-        let response_length = self.handle.read_control(
-            rusb::request_type(Direction::In, RequestType::Vendor, Recipient::Interface),
-            request,
-            value,
-            index,
-            &mut buf,
-            self.timeout,
-        )?;
-        buf.truncate(response_length);
-        Ok(buf)
+fn write_class_control(
+    handle: &DeviceHandle<GlobalContext>,
+    timeout: Duration,
+    request: u8,
+    value: u16,
+    index: u16,
+    data: &[u8],
+) -> Result<(), rusb::Error> {
+    handle.write_control(
+        rusb::request_type(Direction::Out, RequestType::Class, Recipient::Interface),
+        request,
+        value,
+        index,
+        data,
+        timeout,
+    )?;
+
+    Ok(())
+}
+
+fn write_control(
+    handle: &DeviceHandle<GlobalContext>,
+    timeout: Duration,
+    request: u8,
+    value: u16,
+    index: u16,
+    data: &[u8],
+) -> Result<(), rusb::Error> {
+    handle.write_control(
+        rusb::request_type(Direction::Out, RequestType::Vendor, Recipient::Interface),
+        request,
+        value,
+        index,
+        data,
+        timeout,
+    )?;
+
+    Ok(())
+}
+
+fn read_control(
+    handle: &DeviceHandle<GlobalContext>,
+    timeout: Duration,
+    request: u8,
+    value: u16,
+    index: u16,
+    length: usize,
+) -> Result<Vec<u8>, rusb::Error> {
+    let mut buf = vec![0; length];
+    let response_length = handle.read_control(
+        rusb::request_type(Direction::In, RequestType::Vendor, Recipient::Interface),
+        request,
+        value,
+        index,
+        &mut buf,
+        timeout,
+    )?;
+    buf.truncate(response_length);
+    Ok(buf)
+}
+
+// Performs the initialisation handshake for a GoXLR which hasn't been set up since it was
+// powered on (normally the Windows driver would do this).
+fn initialise_device(
+    handle: &mut DeviceHandle<GlobalContext>,
+    timeout: Duration,
+    device_is_claimed: bool,
+) -> Result<()> {
+    info!("Found uninitialised GoXLR, attempting initialisation..");
+    if device_is_claimed {
+        handle
+            .release_interface(0)
+            .context("Unable to release interface")?;
+    }
+
+    // Not all platforms support detaching the kernel driver, that's fine if we can still claim.
+    if rusb::supports_detach_kernel_driver() {
+        handle
+            .set_auto_detach_kernel_driver(true)
+            .context("Unable to enable kernel driver detach")?;
     }
+    handle
+        .claim_interface(0)
+        .context("Unable to Claim Device")?;
+
+    debug!("Activating Vendor Interface...");
+    let response = read_control(handle, timeout, 0, 0, 0, 24)
+        .context("Unable to activate the vendor interface")?;
+    if response.is_empty() {
+        bail!("Vendor interface activation returned no data, is this a GoXLR?");
+    }
+
+    // Now activate audio..
+    debug!("Activating Audio...");
+    write_class_control(
+        handle,
+        timeout,
+        1,
+        0x0100,
+        0x2900,
+        &[0x80, 0xbb, 0x00, 0x00],
+    )
+    .context("Unable to activate audio")?;
+    handle
+        .release_interface(0)
+        .context("Unable to release interface")?;
+
+    // Reset the device, so ALSA can pick it up again..
+    handle.reset().context("Unable to reset the device")?;
+
+    // Reattempt the reset, if this still fails the initialisation didn't take..
+    write_control(handle, timeout, 1, 0, 0, &[])
+        .context("GoXLR did not respond after initialisation")?;
+
+    warn!(
+        "Initialisation complete. If you are using the JACK script, you may need to reboot for audio to work."
+    );
+
+    // Pause for a second, as we can grab devices a little too quickly!
+    sleep(Duration::from_secs(2));
+    Ok(())
 }
 
+#[async_trait]
 impl AttachGoXLR for GoXLRUSB {
     fn from_device(
         device: GoXLRDevice,
@@ -145,9 +267,9 @@ impl AttachGoXLR for GoXLRUSB {
     ) -> Result<Box<(dyn FullGoXLRDevice)>> {
         // Firstly, we need to locate the USB device based on the location..
         let (device, descriptor) = GoXLRUSB::find_device(device)?;
-        let mut handle = device.open()?;
+        let mut handle = device.open().context("Unable to open the USB device")?;
 
-        let timeout = Duration::from_secs(1);
+        let timeout = timing::get_timings().timeout();
 
         let languages = handle.read_languages(timeout)?;
         let language = languages
@@ -158,11 +280,43 @@ impl AttachGoXLR for GoXLRUSB {
         let device = handle.device();
         info!("Connected to possible GoXLR device at {:?}", device);
 
+        // Make sure the device is configured before we try to talk to it, an unconfigured device
+        // reports a configuration of 0.
+        let configuration = handle
+            .active_configuration()
+            .context("Unable to read the active configuration")?;
+        if configuration != 1 {
+            debug!("Device Configuration is {}, setting to 1", configuration);
+            handle
+                .set_active_configuration(1)
+                .context("Unable to set the active configuration")?;
+        }
+
+        // On Linux the audio driver may already hold this interface, which is fine for normal
+        // operation, so failing to claim here isn't an error.
         let device_is_claimed = handle.claim_interface(0).is_ok();
 
-        let mut goxlr = Self {
+        // Resets the state of the device (unconfirmed - Might just be the command id counter)
+        match write_control(&handle, timeout, 1, 0, 0, &[]) {
+            Ok(()) => {}
+            Err(Pipe) => {
+                initialise_device(&mut handle, timeout, device_is_claimed)?;
+            }
+            Err(error) => {
+                return Err(error).context("Unable to reset the GoXLR command index");
+            }
+        }
+
+        // Force command pipe activation in all cases, this drains any response left over from
+        // a previous session, so the content isn't important.
+        debug!("Handling initial request");
+        let response = read_control(&handle, timeout, 3, 0, 0, 1040)
+            .context("Unable to activate the GoXLR command pipe")?;
+        debug!("Initial request returned {} bytes", response.len());
+
+        Ok(Box::new(Self {
             device: handle.device(),
-            handle,
+            handle: Arc::new(handle),
             descriptor,
             language,
             disconnect_sender,
@@ -174,51 +328,7 @@ impl AttachGoXLR for GoXLRUSB {
             timeout,
             pause_polling: Arc::new(AtomicBool::new(false)),
             stop_polling: Arc::new(AtomicBool::new(false)),
-        };
-
-        // Resets the state of the device (unconfirmed - Might just be the command id counter)
-        let result = goxlr.write_control(1, 0, 0, &[]);
-
-        if result == Err(Pipe) {
-            // The GoXLR is not initialised, we need to fix that..
-            info!("Found uninitialised GoXLR, attempting initialisation..");
-            if device_is_claimed {
-                goxlr.handle.release_interface(0)?;
-            }
-            goxlr.handle.set_auto_detach_kernel_driver(true)?;
-
-            if goxlr.handle.claim_interface(0).is_err() {
-                return Err(anyhow!("Unable to Claim Device"));
-            }
-
-            debug!("Activating Vendor Interface...");
-            goxlr.read_control(0, 0, 0, 24)?;
-
-            // Now activate audio..
-            debug!("Activating Audio...");
-            goxlr.write_class_control(1, 0x0100, 0x2900, &[0x80, 0xbb, 0x00, 0x00])?;
-            goxlr.handle.release_interface(0)?;
-
-            // Reset the device, so ALSA can pick it up again..
-            goxlr.handle.reset()?;
-
-            // Reattempt the reset..
-            goxlr.write_control(1, 0, 0, &[])?;
-
-            warn!(
-                "Initialisation complete. If you are using the JACK script, you may need to reboot for audio to work."
-            );
-
-            // Pause for a second, as we can grab devices a little too quickly!
-            sleep(Duration::from_secs(2));
-        }
-
-        // Force command pipe activation in all cases.
-        debug!("Handling initial request");
-        goxlr.read_control(3, 0, 0, 1040)?;
-
-        // Set the local serial number..
-        Ok(Box::new(goxlr))
+        }))
     }
 
     fn set_unique_identifier(&mut self, identifier: String) {
@@ -259,11 +369,11 @@ impl AttachGoXLR for GoXLRUSB {
         });
     }
 
-    fn is_connected(&mut self) -> bool {
+    async fn is_connected(&mut self) -> bool {
         debug!("Checking Disconnect for device: {:?}", self.device);
         let active_configuration = self.handle.active_configuration();
         if active_configuration.is_ok() {
-            let result = self.request_data(Command::ResetCommandIndex, &[]);
+            let result = self.request_data(Command::ResetCommandIndex, &[]).await;
             return if result.is_ok() {
                 debug!("Device {:?} is still connected", self.device);
                 true
@@ -279,17 +389,28 @@ impl AttachGoXLR for GoXLRUSB {
         warn!("Disabling GoXLR Value Polling");
         self.stop_polling.store(true, Ordering::Relaxed);
     }
+
+    fn shutdown(&mut self) {
+        // This ends the polling task created in set_unique_identifier
+        self.stopping.store(true, Ordering::Relaxed);
+    }
 }
 
+#[async_trait]
 impl ExecutableGoXLR for GoXLRUSB {
-    fn perform_request(&mut self, command: Command, body: &[u8], retry: bool) -> Result<Vec<u8>> {
+    async fn perform_request(
+        &mut self,
+        command: Command,
+        body: &[u8],
+        retry: bool,
+    ) -> Result<Vec<u8>> {
         self.pause_polling.store(true, Ordering::Relaxed);
 
         if command == Command::ResetCommandIndex {
             self.command_count = 0;
         } else {
             if self.command_count == u16::MAX {
-                let result = self.request_data(Command::ResetCommandIndex, &[]);
+                let result = self.request_data(Command::ResetCommandIndex, &[]).await;
                 if result.is_err() {
                     self.pause_polling.store(false, Ordering::Relaxed);
                     return result;
@@ -298,6 +419,10 @@ impl ExecutableGoXLR for GoXLRUSB {
             self.command_count += 1;
         }
 
+        // Pick up any changes to the timings..
+        let timings = timing::get_timings();
+        self.timeout = timings.timeout();
+
         let command_index = self.command_count;
         let mut full_request = vec![0; 16];
         LittleEndian::write_u32(&mut full_request[0..4], command.command_id());
@@ -305,34 +430,37 @@ impl ExecutableGoXLR for GoXLRUSB {
         LittleEndian::write_u16(&mut full_request[6..8], command_index);
         full_request.extend(body);
 
-        if let Err(error) = self.write_control(2, 0, 0, &full_request) {
+        if let Err(error) = self.write_control(2, 0, 0, full_request.clone()).await {
             debug!("Error when attempting to write control.");
             self.pause_polling.store(false, Ordering::Relaxed);
-            self.trigger_disconnect()?;
+            self.trigger_disconnect().await?;
             bail!(error);
         }
 
-        // The full fat GoXLR can handle requests incredibly quickly..
-        let mut sleep_time = Duration::from_millis(3);
-        if self.descriptor.product_id() == PID_GOXLR_MINI {
-            // The mini, however, cannot.
-            sleep_time = Duration::from_millis(10);
-        }
-        sleep(sleep_time);
+        let sleep_time = timings.command_delay(self.descriptor.product_id() == PID_GOXLR_MINI);
+        tokio::time::sleep(sleep_time).await;
 
+        let attempts = timings.read_retries.max(1);
         let mut response = vec![];
-        for i in 0..20 {
-            let response_value = self.read_control(3, 0, 0, 1040);
+        let mut mismatched = None;
+        for i in 0..attempts {
+            let response_value = self.read_control(3, 0, 0, 1040).await;
             if response_value == Err(Pipe) {
-                if i < 19 {
-                    debug!("Response not arrived yet for {:?}, sleeping and retrying (Attempt {} of 20)", command, i + 1);
-                    sleep(sleep_time);
+                if i < attempts - 1 {
+                    debug!("Response not arrived yet for {:?}, sleeping and retrying (Attempt {} of {})", command, i + 1, attempts);
+                    tokio::time::sleep(sleep_time).await;
                     continue;
+                } else if mismatched.is_some() {
+                    // The device is responding, just not to us, so try to resync below.
+                    break;
                 } else {
                     // We can't read from this GoXLR, flag as disconnected.
                     self.pause_polling.store(false, Ordering::Relaxed);
-                    self.trigger_disconnect()?;
-                    warn!("Failed to receive response (Attempt 20 of 20), possible Dead GoXLR?");
+                    self.trigger_disconnect().await?;
+                    warn!(
+                        "Failed to receive response (Attempt {} of {}), possible Dead GoXLR?",
+                        attempts, attempts
+                    );
                     return Err(Error::from(response_value.err().unwrap()));
                 }
             }
@@ -341,7 +469,7 @@ impl ExecutableGoXLR for GoXLRUSB {
                 debug!("Error Occurred during packet read: {}", err);
 
                 self.pause_polling.store(false, Ordering::Relaxed);
-                self.trigger_disconnect()?;
+                self.trigger_disconnect().await?;
                 return Err(Error::from(err));
             }
 
@@ -352,50 +480,84 @@ impl ExecutableGoXLR for GoXLRUSB {
                     response_header.len()
                 );
                 self.pause_polling.store(false, Ordering::Relaxed);
-                self.trigger_disconnect()?;
-                return Err(Error::from(Pipe));
+                self.trigger_disconnect().await?;
+                return Err(ProtocolError::InvalidHeader(response_header.len()).into());
             }
 
             response = response_header.split_off(16);
             let response_length = LittleEndian::read_u16(&response_header[4..6]);
             let response_command_index = LittleEndian::read_u16(&response_header[6..8]);
 
-            if response_command_index != command_index {
-                debug!("Mismatched Command Indexes..");
-                debug!(
-                    "Expected {}, received: {}",
-                    command_index, response_command_index
-                );
-                debug!("Full Request: {:?}", full_request);
-                debug!("Response Header: {:?}", response_header);
-                debug!("Response Body: {:?}", response);
-
-                return if !retry {
-                    debug!("Attempting Resync and Retry");
-                    let result = self.perform_request(Command::ResetCommandIndex, &[], true);
-                    if result.is_err() {
-                        self.pause_polling.store(false, Ordering::Relaxed);
-                        return result;
+            match classify_response(command_index, response_command_index) {
+                ResponseKind::Matched => mismatched = None,
+                kind => {
+                    debug!(
+                        "{:?} packet received while waiting for {:?}, Expected {}, received: {}",
+                        kind, command, command_index, response_command_index
+                    );
+                    debug!("Response Header: {:?}", response_header);
+                    debug!("Response Body: {:?}", response);
+                    if kind == ResponseKind::Unsolicited {
+                        self.notify_unsolicited();
                     }
 
-                    debug!("Resync complete, retrying Command..");
-                    let result = self.perform_request(command, body, true);
-                    if result.is_err() {
-                        self.pause_polling.store(false, Ordering::Relaxed);
-                    }
-                    return result;
-                } else {
-                    debug!("Resync Failed, Throwing Error..");
-                    self.pause_polling.store(false, Ordering::Relaxed);
-                    self.trigger_disconnect()?;
-                    Err(Error::from(rusb::Error::Other))
-                };
+                    // Our response may still be on its way, so keep reading..
+                    mismatched = Some(response_command_index);
+                    continue;
+                }
             }
 
-            debug_assert!(response.len() == response_length as usize);
+            if response.len() != response_length as usize {
+                warn!(
+                    "Response to {:?} has the wrong length, expected {}, received {}",
+                    command,
+                    response_length,
+                    response.len()
+                );
+                self.drain_responses().await;
+                self.pause_polling.store(false, Ordering::Relaxed);
+                return Err(ProtocolError::MismatchedLength {
+                    expected: response_length as usize,
+                    received: response.len(),
+                }
+                .into());
+            }
             break;
         }
 
+        if let Some(response_command_index) = mismatched {
+            debug!("Mismatched Command Indexes..");
+            debug!("Full Request: {:?}", full_request);
+
+            self.drain_responses().await;
+            return if !retry {
+                debug!("Attempting Resync and Retry");
+                let result = self
+                    .perform_request(Command::ResetCommandIndex, &[], true)
+                    .await;
+                if result.is_err() {
+                    self.pause_polling.store(false, Ordering::Relaxed);
+                    return result;
+                }
+
+                debug!("Resync complete, retrying Command..");
+                let result = self.perform_request(command, body, true).await;
+                if result.is_err() {
+                    self.pause_polling.store(false, Ordering::Relaxed);
+                }
+                result
+            } else {
+                debug!("Resync Failed, Throwing Error..");
+                self.pause_polling.store(false, Ordering::Relaxed);
+                self.trigger_disconnect().await?;
+                Err(ProtocolError::MismatchedIndex {
+                    expected: command_index,
+                    received: response_command_index,
+                }
+                .into())
+            };
+        }
+
         self.pause_polling.store(false, Ordering::Relaxed);
         Ok(response)
     }
@@ -426,6 +588,7 @@ impl ExecutableGoXLR for GoXLRUSB {
     }
 }
 
+#[async_trait]
 impl GoXLRCommands for GoXLRUSB {}
 impl FullGoXLRDevice for GoXLRUSB {}
 
@@ -1,10 +1,12 @@
 use crate::device::base::AttachGoXLR;
 use crate::device::base::FullGoXLRDevice;
 use crate::device::base::GoXLRDevice;
+use crate::device::simulator::device::{is_simulated, SimulatedGoXLR};
 use anyhow::Result;
 use tokio::sync::mpsc::Sender;
 
 pub mod base;
+pub mod simulator;
 
 cfg_if::cfg_if! {
     if #[cfg(target_os = "windows")] {
@@ -12,11 +14,11 @@ cfg_if::cfg_if! {
         mod tusb;
         use crate::device::tusb::device;
 
-        pub fn find_devices() -> Vec<GoXLRDevice> {
+        fn find_platform_devices() -> Vec<GoXLRDevice> {
             device::find_devices()
         }
 
-        pub fn from_device(
+        fn from_platform_device(
             device: GoXLRDevice,
             disconnect_sender: Sender<String>,
             event_sender: Sender<String>,
@@ -28,11 +30,11 @@ cfg_if::cfg_if! {
         mod libusb;
         use crate::device::libusb::device;
 
-        pub fn find_devices() -> Vec<GoXLRDevice> {
+        fn find_platform_devices() -> Vec<GoXLRDevice> {
             device::find_devices()
         }
 
-        pub fn from_device(
+        fn from_platform_device(
             device: GoXLRDevice,
             disconnect_sender: Sender<String>,
             event_sender: Sender<String>,
@@ -41,3 +43,20 @@ cfg_if::cfg_if! {
         }
     }
 }
+
+pub fn find_devices() -> Vec<GoXLRDevice> {
+    let mut devices = find_platform_devices();
+    devices.extend(simulator::device::find_devices());
+    devices
+}
+
+pub fn from_device(
+    device: GoXLRDevice,
+    disconnect_sender: Sender<String>,
+    event_sender: Sender<String>,
+) -> Result<Box<dyn FullGoXLRDevice>> {
+    if is_simulated(&device) {
+        return SimulatedGoXLR::from_device(device, disconnect_sender, event_sender);
+    }
+    from_platform_device(device, disconnect_sender, event_sender)
+}
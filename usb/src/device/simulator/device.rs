@@ -0,0 +1,346 @@
+use crate::buttonstate::{Buttons, CurrentButtonStates};
+use crate::commands::{Command, HardwareInfoCommand, SystemInfoCommand};
+use crate::device::base::{
+    AttachGoXLR, ExecutableGoXLR, FullGoXLRDevice, GoXLRCommands, GoXLRDevice, UsbData,
+};
+use crate::{PID_GOXLR_FULL, PID_GOXLR_MINI, VID_GOXLR};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use byteorder::{LittleEndian, WriteBytesExt};
+use enumset::EnumSet;
+use goxlr_types::{EncoderName, FaderName};
+use log::{debug, info};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::Sender;
+
+/*
+A fake GoXLR, which can be attached to the daemon in place of real hardware. It responds to all
+commands in the same way a device would (although obviously, nothing changes), and allows button
+presses, fader movements, encoder turns and microphone levels to be triggered via a
+SimulatorHandle, which sends an event to the daemon in the same way the hardware interrupt does.
+
+Devices are added with add_simulated_device, and will be picked up by the next device scan. Every
+write request made to the device is recorded, and can be retrieved (for comparison in tests, for
+example) with SimulatorHandle::take_history.
+ */
+
+// Simulated devices are reported on bus 0, which isn't used by real USB hardware.
+const SIMULATED_BUS: u8 = 0;
+const SIMULATED_PREFIX: &str = "simulated-";
+const MAX_HISTORY: usize = 1000;
+
+static SIMULATED_DEVICES: Mutex<Vec<Arc<Mutex<SimulatorState>>>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SimulatedDeviceType {
+    Full,
+    Mini,
+}
+
+struct SimulatorState {
+    address: u8,
+    device_type: SimulatedDeviceType,
+    serial: String,
+
+    attached: bool,
+    identifier: Option<String>,
+    event_sender: Option<Sender<String>>,
+    disconnect_sender: Option<Sender<String>>,
+
+    pressed: EnumSet<Buttons>,
+    volumes: [u8; 4],
+    encoders: [i8; 4],
+    microphone_level: u16,
+
+    history: Vec<(Command, Vec<u8>)>,
+}
+
+impl SimulatorState {
+    fn notify(&self) {
+        if let (Some(sender), Some(identifier)) = (&self.event_sender, &self.identifier) {
+            // If the channel is full, an update is already pending, so we can skip this one.
+            let _ = sender.try_send(identifier.clone());
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SimulatorHandle {
+    state: Arc<Mutex<SimulatorState>>,
+}
+
+impl SimulatorHandle {
+    pub fn serial(&self) -> String {
+        self.state.lock().unwrap().serial.clone()
+    }
+
+    pub fn press_button(&self, button: Buttons) {
+        let mut state = self.state.lock().unwrap();
+        state.pressed.insert(button);
+        state.notify();
+    }
+
+    pub fn release_button(&self, button: Buttons) {
+        let mut state = self.state.lock().unwrap();
+        state.pressed.remove(button);
+        state.notify();
+    }
+
+    pub fn set_fader_volume(&self, fader: FaderName, volume: u8) {
+        let mut state = self.state.lock().unwrap();
+        state.volumes[fader as usize] = volume;
+        state.notify();
+    }
+
+    pub fn set_encoder_value(&self, encoder: EncoderName, value: i8) {
+        let mut state = self.state.lock().unwrap();
+        state.encoders[encoder as usize] = value;
+        state.notify();
+    }
+
+    pub fn set_microphone_level(&self, level: u16) {
+        self.state.lock().unwrap().microphone_level = level;
+    }
+
+    /// Returns all write requests sent to the device since the last call.
+    pub fn take_history(&self) -> Vec<(Command, Vec<u8>)> {
+        std::mem::take(&mut self.state.lock().unwrap().history)
+    }
+
+    /// Simulates the device being unplugged, it will no longer be returned by a device scan.
+    pub fn disconnect(&self) {
+        SIMULATED_DEVICES
+            .lock()
+            .unwrap()
+            .retain(|device| !Arc::ptr_eq(device, &self.state));
+
+        let mut state = self.state.lock().unwrap();
+        state.attached = false;
+        if let (Some(sender), Some(identifier)) = (&state.disconnect_sender, &state.identifier) {
+            let _ = sender.try_send(identifier.clone());
+        }
+    }
+}
+
+pub fn add_simulated_device(device_type: SimulatedDeviceType) -> SimulatorHandle {
+    let mut devices = SIMULATED_DEVICES.lock().unwrap();
+
+    // Find the first address not currently in use..
+    let mut address = 1;
+    while devices
+        .iter()
+        .any(|device| device.lock().unwrap().address == address)
+    {
+        address += 1;
+    }
+
+    let prefix = match device_type {
+        SimulatedDeviceType::Full => "SIMFULL",
+        SimulatedDeviceType::Mini => "SIMMINI",
+    };
+
+    let state = Arc::new(Mutex::new(SimulatorState {
+        address,
+        device_type,
+        serial: format!("{}{:06}", prefix, address),
+
+        attached: false,
+        identifier: None,
+        event_sender: None,
+        disconnect_sender: None,
+
+        pressed: EnumSet::empty(),
+        volumes: [0; 4],
+        encoders: [0; 4],
+        microphone_level: 0,
+
+        history: vec![],
+    }));
+
+    info!(
+        "Added Simulated GoXLR {:?} at address {}",
+        device_type, address
+    );
+    devices.push(state.clone());
+    SimulatorHandle { state }
+}
+
+pub struct SimulatedGoXLR {
+    state: Arc<Mutex<SimulatorState>>,
+}
+
+// A simulated device which is already attached, but never returned by a device scan. This can be
+// swapped in for real hardware when a command's effects need to be worked out without sending
+// anything to the device.
+pub fn create_detached_device(device_type: SimulatedDeviceType) -> Box<dyn FullGoXLRDevice> {
+    let state = Arc::new(Mutex::new(SimulatorState {
+        address: 0,
+        device_type,
+        serial: String::from("DETACHED"),
+
+        attached: true,
+        identifier: None,
+        event_sender: None,
+        disconnect_sender: None,
+
+        pressed: EnumSet::empty(),
+        volumes: [0; 4],
+        encoders: [0; 4],
+        microphone_level: 0,
+
+        history: vec![],
+    }));
+    Box::new(SimulatedGoXLR { state })
+}
+
+#[async_trait]
+impl AttachGoXLR for SimulatedGoXLR {
+    fn from_device(
+        device: GoXLRDevice,
+        disconnect_sender: Sender<String>,
+        event_sender: Sender<String>,
+    ) -> Result<Box<dyn FullGoXLRDevice>> {
+        let state = SIMULATED_DEVICES
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|state| state.lock().unwrap().address == device.address)
+            .cloned()
+            .ok_or_else(|| anyhow!("Simulated Device not Found!"))?;
+
+        {
+            let mut locked = state.lock().unwrap();
+            locked.attached = true;
+            locked.event_sender.replace(event_sender);
+            locked.disconnect_sender.replace(disconnect_sender);
+        }
+
+        info!("Connected to Simulated GoXLR at address {}", device.address);
+        Ok(Box::new(Self { state }))
+    }
+
+    fn set_unique_identifier(&mut self, identifier: String) {
+        let mut state = self.state.lock().unwrap();
+        state.identifier = Some(identifier);
+
+        // Trigger an initial read of the 'hardware' state..
+        state.notify();
+    }
+
+    async fn is_connected(&mut self) -> bool {
+        self.state.lock().unwrap().attached
+    }
+
+    fn stop_polling(&mut self) {
+        // Simulated devices only send events when something changes, so there's nothing to stop.
+    }
+
+    fn shutdown(&mut self) {}
+}
+
+#[async_trait]
+impl ExecutableGoXLR for SimulatedGoXLR {
+    async fn perform_request(
+        &mut self,
+        command: Command,
+        body: &[u8],
+        _retry: bool,
+    ) -> Result<Vec<u8>> {
+        let mut state = self.state.lock().unwrap();
+        if !state.attached {
+            bail!(rusb::Error::NoDevice);
+        }
+
+        let mut response = vec![];
+        match command {
+            Command::SystemInfo(SystemInfoCommand::SupportsDCPCategory) => {
+                response.write_u16::<LittleEndian>(1)?;
+            }
+            Command::GetHardwareInfo(HardwareInfoCommand::FirmwareVersion) => {
+                // Firmware 1.4.2.107, 1 FPGA, DICE 1.0.20.700
+                response.write_u32::<LittleEndian>((1 << 12) | (4 << 8) | 2)?;
+                response.write_u32::<LittleEndian>(107)?;
+                response.write_u32::<LittleEndian>(0)?;
+                response.write_u32::<LittleEndian>(1)?;
+                response.write_u32::<LittleEndian>(700)?;
+                response.write_u32::<LittleEndian>((1 << 20) | 20)?;
+            }
+            Command::GetHardwareInfo(HardwareInfoCommand::SerialNumber) => {
+                let mut serial = state.serial.clone().into_bytes();
+                serial.resize(24, 0);
+
+                let mut date = b"2023-01-01".to_vec();
+                date.resize(16, 0);
+
+                response.extend(serial);
+                response.extend(date);
+            }
+            Command::GetButtonStates => {
+                let report = CurrentButtonStates {
+                    pressed: state.pressed,
+                    volumes: state.volumes,
+                    encoders: state.encoders,
+                };
+                response.extend(report.to_report());
+            }
+            Command::GetMicrophoneLevel => {
+                response.write_u16::<LittleEndian>(state.microphone_level)?;
+            }
+            Command::ExecuteFirmwareUpdateCommand(_) | Command::ExecuteFirmwareUpdateAction(_) => {
+                bail!("Firmware Updates are not supported on Simulated Devices");
+            }
+            _ => {
+                debug!("Simulated GoXLR received {:?}", command);
+                if state.history.len() >= MAX_HISTORY {
+                    state.history.remove(0);
+                }
+                state.history.push((command, body.to_vec()));
+            }
+        }
+        Ok(response)
+    }
+
+    fn get_descriptor(&self) -> Result<UsbData> {
+        let state = self.state.lock().unwrap();
+        let (product_id, product_name) = match state.device_type {
+            SimulatedDeviceType::Full => (PID_GOXLR_FULL, "GoXLR (Simulated)"),
+            SimulatedDeviceType::Mini => (PID_GOXLR_MINI, "GoXLR Mini (Simulated)"),
+        };
+
+        Ok(UsbData {
+            vendor_id: VID_GOXLR,
+            product_id,
+            device_version: (1, 0, 0),
+            device_manufacturer: String::from("TC-Helicon"),
+            product_name: String::from(product_name),
+        })
+    }
+}
+
+#[async_trait]
+impl GoXLRCommands for SimulatedGoXLR {}
+impl FullGoXLRDevice for SimulatedGoXLR {}
+
+pub fn find_devices() -> Vec<GoXLRDevice> {
+    SIMULATED_DEVICES
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|state| {
+            let address = state.lock().unwrap().address;
+            GoXLRDevice {
+                bus_number: SIMULATED_BUS,
+                address,
+                identifier: Some(format!("{}{}", SIMULATED_PREFIX, address)),
+            }
+        })
+        .collect()
+}
+
+pub fn is_simulated(device: &GoXLRDevice) -> bool {
+    device
+        .identifier
+        .as_ref()
+        .map(|identifier| identifier.starts_with(SIMULATED_PREFIX))
+        .unwrap_or(false)
+}
@@ -1,11 +1,15 @@
 use crate::commands::Command;
+use crate::demux::{classify_response, ResponseKind};
 use crate::device::base::{
     AttachGoXLR, ExecutableGoXLR, FullGoXLRDevice, GoXLRCommands, GoXLRDevice, UsbData,
 };
 use crate::device::tusb::tusbaudio::{
     get_devices, DeviceHandle, EventChannelReceiver, EventChannelSender, TUSB_INTERFACE,
 };
+use crate::error::ProtocolError;
+use crate::timing;
 use anyhow::{bail, Result};
+use async_trait::async_trait;
 use byteorder::{ByteOrder, LittleEndian};
 use log::{debug, error, warn};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -65,7 +69,7 @@ impl TUSBAudioGoXLR {
         // know a read event will return incredibly quickly, we can slap a loop in to wait for the
         // data.
 
-        let timeout = Instant::now() + Duration::from_secs(1);
+        let timeout = Instant::now() + timing::get_timings().timeout();
         loop {
             if Instant::now() > timeout {
                 // We've hit a timeout, don't infinite loop, instead throw as error.
@@ -81,6 +85,30 @@ impl TUSBAudioGoXLR {
         }
     }
 
+    // Unsolicited packets indicate something has changed, so have the daemon poll the device.
+    fn notify_unsolicited(&self) {
+        if let Some(identifier) = &*self.daemon_identifier.lock().unwrap() {
+            let _ = self.event_sender.try_send(identifier.clone());
+        }
+    }
+
+    // Discards any responses still waiting on the device, so that the next request doesn't pick up
+    // a response meant for something else.
+    fn drain_responses(&mut self) {
+        while let Ok(true) = self.event_receivers.data_read.try_recv() {
+            if let Ok(response) = self.read_control(3, 0, 0, 1040) {
+                debug!("Discarded pending response ({} bytes)", response.len());
+            }
+        }
+    }
+
+    async fn await_data_async(&mut self) -> bool {
+        // Now that requests are asynchronous, we can simply wait on the channel here.
+        let timeout = timing::get_timings().timeout();
+        let result = tokio::time::timeout(timeout, self.event_receivers.data_read.recv()).await;
+        matches!(result, Ok(Some(true)))
+    }
+
     pub fn await_ready(mut receiver: tokio::sync::oneshot::Receiver<bool>) -> bool {
         let timeout = Instant::now() + Duration::from_secs(1);
         loop {
@@ -100,6 +128,7 @@ impl TUSBAudioGoXLR {
     }
 }
 
+#[async_trait]
 impl AttachGoXLR for TUSBAudioGoXLR {
     fn from_device(
         device: GoXLRDevice,
@@ -207,7 +236,7 @@ impl AttachGoXLR for TUSBAudioGoXLR {
         *local_identifier = Some(identifier);
     }
 
-    fn is_connected(&mut self) -> bool {
+    async fn is_connected(&mut self) -> bool {
         // We need to verify and restore our handle if it's broken..
         if let Err(error) = self.handle.get_device_id_string() {
             debug!(
@@ -234,15 +263,25 @@ impl AttachGoXLR for TUSBAudioGoXLR {
     fn stop_polling(&mut self) {
         // The TUSB implementation is event driven, so there's no polling to stop.
     }
+
+    fn shutdown(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
 }
 
+#[async_trait]
 impl ExecutableGoXLR for TUSBAudioGoXLR {
-    fn perform_request(&mut self, command: Command, body: &[u8], retry: bool) -> Result<Vec<u8>> {
+    async fn perform_request(
+        &mut self,
+        command: Command,
+        body: &[u8],
+        retry: bool,
+    ) -> Result<Vec<u8>> {
         if command == Command::ResetCommandIndex {
             self.command_count = 0;
         } else {
             if self.command_count == u16::MAX {
-                let _ = self.request_data(Command::ResetCommandIndex, &[])?;
+                let _ = self.request_data(Command::ResetCommandIndex, &[]).await?;
             }
             self.command_count += 1;
         }
@@ -256,7 +295,7 @@ impl ExecutableGoXLR for TUSBAudioGoXLR {
 
         if let Err(error) = self.write_control(2, 0, 0, &full_request) {
             if error.to_string() == "TSTATUS_INVALID_HANDLE" {
-                if self.is_connected() {
+                if self.is_connected().await {
                     // Try again..
                     if let Err(error) = self.write_control(2, 0, 0, &full_request) {
                         self.trigger_disconnect();
@@ -276,71 +315,115 @@ impl ExecutableGoXLR for TUSBAudioGoXLR {
             }
         }
 
-        // We will sit here, and wait for a response.. this may take a few cycles..
-        if !self.await_data() {
-            self.trigger_disconnect();
-            bail!("Event handler has ended, Disconnecting.");
-        }
+        let attempts = timing::get_timings().read_retries.max(1);
+        let mut response = vec![];
+        let mut response_length = 0;
+        let mut mismatched = None;
+        for _ in 0..attempts {
+            // We will sit here, and wait for a response.. this may take a few cycles..
+            if !self.await_data_async().await {
+                if mismatched.is_some() {
+                    // The device is responding, just not to us, so try to resync below.
+                    break;
+                }
+                self.trigger_disconnect();
+                bail!("Event handler has ended, Disconnecting.");
+            }
 
-        let mut response_value = self.read_control(3, 0, 0, 1040);
-        if let Err(error) = response_value {
-            if error.to_string() == "TSTATUS_INVALID_HANDLE" {
-                if self.is_connected() {
-                    response_value = self.read_control(3, 0, 0, 1040);
-                    if let Err(error) = response_value {
+            let mut response_value = self.read_control(3, 0, 0, 1040);
+            if let Err(error) = response_value {
+                if error.to_string() == "TSTATUS_INVALID_HANDLE" {
+                    if self.is_connected().await {
+                        response_value = self.read_control(3, 0, 0, 1040);
+                        if let Err(error) = response_value {
+                            self.trigger_disconnect();
+                            bail!(
+                                "Recovered Handle, but still unable to read command response: {}",
+                                error
+                            );
+                        }
+                    } else {
                         self.trigger_disconnect();
-                        bail!(
-                            "Recovered Handle, but still unable to read command response: {}",
-                            error
-                        );
+                        bail!("GoXLR has been Disconnected while Reading Response");
                     }
                 } else {
                     self.trigger_disconnect();
-                    bail!("GoXLR has been Disconnected while Reading Response");
+                    bail!("Unknown Error while Reading, Disconnecting: {}", error);
                 }
-            } else {
-                self.trigger_disconnect();
-                bail!("Unknown Error while Reading, Disconnecting: {}", error);
             }
-        }
 
-        let mut response_header = response_value?;
-        if response_header.len() < 16 {
-            error!(
-                "Invalid Response received from the GoXLR, Expected: 16, Received: {}",
-                response_header.len()
-            );
-            bail!("Invalid Response");
-        }
+            let mut response_header = response_value?;
+            if response_header.len() < 16 {
+                error!(
+                    "Invalid Response received from the GoXLR, Expected: 16, Received: {}",
+                    response_header.len()
+                );
+                return Err(ProtocolError::InvalidHeader(response_header.len()).into());
+            }
+
+            response = response_header.split_off(16);
+            response_length = LittleEndian::read_u16(&response_header[4..6]);
+            let response_command_index = LittleEndian::read_u16(&response_header[6..8]);
 
-        let response = response_header.split_off(16);
-        let response_length = LittleEndian::read_u16(&response_header[4..6]);
-        let response_command_index = LittleEndian::read_u16(&response_header[6..8]);
+            match classify_response(command_index, response_command_index) {
+                ResponseKind::Matched => {
+                    mismatched = None;
+                    break;
+                }
+                kind => {
+                    debug!(
+                        "{:?} packet received while waiting for {:?}, Expected {}, received: {}",
+                        kind, command, command_index, response_command_index
+                    );
+                    debug!("Response Header: {:?}", response_header);
+                    debug!("Response Body: {:?}", response);
+                    if kind == ResponseKind::Unsolicited {
+                        self.notify_unsolicited();
+                    }
 
-        if response_command_index != command_index {
+                    // Our response may still be on its way, so keep waiting..
+                    mismatched = Some(response_command_index);
+                }
+            }
+        }
+
+        if let Some(response_command_index) = mismatched {
             debug!("Mismatched Command Indexes..");
-            debug!(
-                "Expected {}, received: {}",
-                command_index, response_command_index
-            );
             debug!("Full Request: {:?}", full_request);
-            debug!("Response Header: {:?}", response_header);
-            debug!("Response Body: {:?}", response);
 
+            self.drain_responses();
             return if !retry {
                 debug!("Attempting Resync and Retry");
-                self.perform_request(Command::ResetCommandIndex, &[], true)?;
+                self.perform_request(Command::ResetCommandIndex, &[], true)
+                    .await?;
 
                 debug!("Resync complete, retrying Command..");
-                self.perform_request(command, body, true)
+                self.perform_request(command, body, true).await
             } else {
                 debug!("Resync Failed, Throwing Error..");
                 self.trigger_disconnect();
-                bail!("Invalid Response received from GoXLR, disconnecting!");
+                Err(ProtocolError::MismatchedIndex {
+                    expected: command_index,
+                    received: response_command_index,
+                }
+                .into())
             };
         }
 
-        debug_assert!(response.len() == response_length as usize);
+        if response.len() != response_length as usize {
+            warn!(
+                "Response to {:?} has the wrong length, expected {}, received {}",
+                command,
+                response_length,
+                response.len()
+            );
+            self.drain_responses();
+            return Err(ProtocolError::MismatchedLength {
+                expected: response_length as usize,
+                received: response.len(),
+            }
+            .into());
+        }
         Ok(response)
     }
 
@@ -357,6 +440,7 @@ impl ExecutableGoXLR for TUSBAudioGoXLR {
     }
 }
 
+#[async_trait]
 impl GoXLRCommands for TUSBAudioGoXLR {}
 impl FullGoXLRDevice for TUSBAudioGoXLR {}
 
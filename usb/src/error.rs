@@ -21,3 +21,44 @@ pub enum CommandError {
     #[error("Malformed response from GoXLR")]
     MalformedResponse(#[from] std::io::Error),
 }
+
+/// Searches an error chain for an error raised by the USB backend, returning its description.
+/// This allows callers to categorise errors without depending on the backend in use.
+pub fn find_usb_error(error: &anyhow::Error) -> Option<String> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<rusb::Error>())
+        .map(|error| error.to_string())
+}
+
+/// Returns true if the error indicates the device has gone away (for example, been unplugged).
+pub fn is_disconnect_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<rusb::Error>(),
+            Some(rusb::Error::NoDevice) | Some(rusb::Error::Io) | Some(rusb::Error::NotFound)
+        )
+    })
+}
+
+/// Raised when a response from the GoXLR doesn't match the request it should belong to, the
+/// pending responses are drained before this is returned, so the next request starts clean.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolError {
+    #[error("Invalid response header, expected 16 bytes, received {0}")]
+    InvalidHeader(usize),
+
+    #[error("Mismatched command index, expected {expected}, received {received}")]
+    MismatchedIndex { expected: u16, received: u16 },
+
+    #[error("Mismatched response length, expected {expected}, received {received}")]
+    MismatchedLength { expected: usize, received: usize },
+}
+
+/// Returns the ProtocolError in the error chain, if there is one.
+pub fn find_protocol_error(error: &anyhow::Error) -> Option<ProtocolError> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<ProtocolError>())
+        .cloned()
+}
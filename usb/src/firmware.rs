@@ -0,0 +1,42 @@
+use std::io::Cursor;
+
+use anyhow::{bail, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use goxlr_types::VersionNumber;
+
+/*
+Firmware files start with a 32 byte header, the first 16 bytes hold the (null terminated) name of
+the device the firmware is for, and bytes 24 to 32 hold the packed version and build number in
+the same format the device reports its own firmware version.
+ */
+const HEADER_SIZE: usize = 32;
+
+#[derive(Debug, Clone)]
+pub struct FirmwareHeader {
+    pub name: String,
+    pub version: VersionNumber,
+}
+
+impl FirmwareHeader {
+    pub fn parse(firmware: &[u8]) -> Result<Self> {
+        if firmware.len() < HEADER_SIZE {
+            bail!("Firmware File is too small");
+        }
+
+        let name = &firmware[0..16];
+        let end_index = name.iter().position(|byte| *byte == 0x00).unwrap_or(16);
+        let name = String::from_utf8_lossy(&name[0..end_index]).to_string();
+
+        let mut cursor = Cursor::new(&firmware[24..32]);
+        let firmware_packed = cursor.read_u32::<LittleEndian>()?;
+        let firmware_build = cursor.read_u32::<LittleEndian>()?;
+        let version = VersionNumber(
+            firmware_packed >> 12,
+            (firmware_packed >> 8) & 0xF,
+            firmware_packed & 0xFF,
+            firmware_build,
+        );
+
+        Ok(Self { name, version })
+    }
+}
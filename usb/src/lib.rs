@@ -1,13 +1,16 @@
-pub use rusb;
 pub mod buttonstate;
 pub mod channelstate;
 pub mod colouring;
 pub mod commands;
 pub mod dcp;
+pub mod demux;
 pub mod devices;
 pub mod error;
+pub mod firmware;
 pub mod microphone;
 pub mod routing;
+pub mod timing;
+pub mod transfer_log;
 
 pub mod animation;
 pub mod device;
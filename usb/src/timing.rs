@@ -0,0 +1,52 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+/*
+The timings used when talking to the GoXLR. The defaults work for most setups, but some USB hubs
+(and some firmware versions) respond slowly enough that commands are treated as failed and the
+device is flagged as disconnected, so these can be overridden in the daemon's settings.
+
+The command delay is how long we wait between sending a command and reading its response (and
+between each read attempt), if not set the Mini uses a longer delay than the Full device.
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UsbTimings {
+    pub timeout_ms: u64,
+    pub read_retries: u8,
+    pub command_delay_ms: Option<u64>,
+}
+
+impl Default for UsbTimings {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 1000,
+            read_retries: 20,
+            command_delay_ms: None,
+        }
+    }
+}
+
+impl UsbTimings {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms)
+    }
+
+    pub fn command_delay(&self, is_mini: bool) -> Duration {
+        match self.command_delay_ms {
+            Some(delay) => Duration::from_millis(delay),
+            // The full fat GoXLR can handle requests incredibly quickly, the mini cannot.
+            None if is_mini => Duration::from_millis(10),
+            None => Duration::from_millis(3),
+        }
+    }
+}
+
+static TIMINGS: Mutex<Option<UsbTimings>> = Mutex::new(None);
+
+pub fn set_timings(timings: UsbTimings) {
+    TIMINGS.lock().unwrap().replace(timings);
+}
+
+pub fn get_timings() -> UsbTimings {
+    TIMINGS.lock().unwrap().unwrap_or_default()
+}
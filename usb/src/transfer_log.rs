@@ -0,0 +1,75 @@
+use anyhow::Result;
+use serde_json::json;
+use std::fmt::Write as FmtWrite;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::commands::Command;
+
+/*
+This is a debugging aid, when enabled every request sent to the GoXLR is written to a file as a
+single line of JSON, containing the command, its ID, the payload sent, the response (or error)
+and how long it took. This is primarily useful for working out what unknown commands do, and for
+diagnosing protocol issues reported by users.
+
+Payloads can be large (the colour map is 520 bytes), so this should only be turned on while
+actively debugging.
+ */
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static LOG_FILE: Mutex<Option<File>> = Mutex::new(None);
+
+pub fn enable(path: &Path) -> Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    LOG_FILE.lock().unwrap().replace(file);
+    ENABLED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+pub fn disable() {
+    ENABLED.store(false, Ordering::Relaxed);
+    LOG_FILE.lock().unwrap().take();
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub(crate) fn log_transfer(
+    command: Command,
+    body: &[u8],
+    result: &Result<Vec<u8>>,
+    duration: Duration,
+) {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis() as u64)
+        .unwrap_or(0);
+
+    let mut line = json!({
+        "timestamp": timestamp,
+        "command": format!("{:?}", command),
+        "command_id": format!("{:#x}", command.command_id()),
+        "payload": to_hex(body),
+        "duration_us": duration.as_micros() as u64,
+    });
+    match result {
+        Ok(response) => line["response"] = json!(to_hex(response)),
+        Err(error) => line["error"] = json!(error.to_string()),
+    }
+
+    if let Some(file) = LOG_FILE.lock().unwrap().as_mut() {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+fn to_hex(data: &[u8]) -> String {
+    let mut output = String::with_capacity(data.len() * 2);
+    for byte in data {
+        let _ = write!(output, "{:02x}", byte);
+    }
+    output
+}